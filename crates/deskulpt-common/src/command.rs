@@ -0,0 +1,80 @@
+//! Common utilities for Deskulpt commands.
+
+use std::time::Instant;
+
+/// Instrument a Tauri command.
+///
+/// Every invocation of the command runs within a `command` span recording the
+/// command name, the label of the calling window, and on completion the
+/// duration and the result status. The invocation is also counted in the
+/// `command.calls` and `command.errors` counters, and its duration is observed
+/// in a per-command histogram named `command.<plugin>.<command>_ms`.
+///
+/// This macro must be placed above `#[tauri::command]` because it adds a
+/// parameter for the calling window.
+pub use deskulpt_macros::instrument;
+use tracing::Span;
+use tracing::instrument::Instrumented;
+
+use crate::metrics::{self, Histogram};
+
+/// An in-flight invocation of a Tauri command.
+///
+/// This is meant to be used by the [`macro@instrument`] macro only.
+#[doc(hidden)]
+pub struct Invocation {
+    /// The span of the invocation.
+    span: Span,
+    /// The time when the invocation started.
+    start: Instant,
+    /// The histogram of durations of the command.
+    duration_ms: &'static Histogram,
+}
+
+impl Invocation {
+    /// Start an invocation of a command called from a window.
+    pub fn start(name: &'static str, metric: &'static str, window: &str) -> Self {
+        let span = tracing::info_span!(
+            "command",
+            command = name,
+            window,
+            duration_ms = tracing::field::Empty,
+            ok = tracing::field::Empty,
+        );
+        Self {
+            span,
+            start: Instant::now(),
+            duration_ms: metrics::histogram(metric),
+        }
+    }
+
+    /// Run a future within the span of the invocation.
+    pub fn instrument<F: Future>(&self, future: F) -> Instrumented<F> {
+        tracing::Instrument::instrument(future, self.span.clone())
+    }
+
+    /// Run a closure within the span of the invocation.
+    pub fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.span.in_scope(f)
+    }
+
+    /// Finish the invocation with whether it succeeded.
+    pub fn finish(self, ok: bool) {
+        let duration_ms = self.start.elapsed().as_secs_f64() * 1000.0;
+        self.duration_ms.observe(duration_ms);
+        metrics::counter("command.calls").increment();
+        if !ok {
+            metrics::counter("command.errors").increment();
+        }
+
+        self.span.record("duration_ms", duration_ms);
+        self.span.record("ok", ok);
+        self.span.in_scope(|| {
+            if ok {
+                tracing::trace!(duration_ms, "Command succeeded");
+            } else {
+                tracing::debug!(duration_ms, "Command failed");
+            }
+        });
+    }
+}
@@ -32,6 +32,19 @@ pub trait Event: Serialize {
         emitter.emit_to(window, Self::NAME, self)?;
         Ok(())
     }
+
+    /// Emit the event to the window with the specified label.
+    ///
+    /// This is for windows other than [`DeskulptWindow`], e.g., floating
+    /// windows of detached widgets. It is a no-op if no such window exists.
+    fn emit_to_label<R, E>(&self, emitter: &E, label: &str) -> Result<()>
+    where
+        R: Runtime,
+        E: Emitter<R>,
+    {
+        emitter.emit_to(label, Self::NAME, self)?;
+        Ok(())
+    }
 }
 
 /// Derive the [`Event`] trait for a struct.
@@ -0,0 +1,29 @@
+//! Fuzzy matching of text.
+
+/// Score how well a candidate matches the query text.
+///
+/// Matching is case-insensitive. Exact matches score highest, followed by
+/// prefix matches, substring matches, and finally subsequence matches. This
+/// returns `None` if the candidate does not match at all.
+pub fn score(text: &str, candidate: &str) -> Option<f64> {
+    let text = text.trim().to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if text.is_empty() {
+        return None;
+    }
+
+    // Shorter candidates are slightly preferred among matches of the same kind
+    let tiebreak = text.len() as f64 / candidate.len().max(text.len()) as f64 * 0.1;
+    if candidate == text {
+        Some(1.0)
+    } else if candidate.starts_with(&text) {
+        Some(0.8 + tiebreak)
+    } else if candidate.contains(&text) {
+        Some(0.6 + tiebreak)
+    } else {
+        let mut chars = candidate.chars();
+        text.chars()
+            .all(|c| chars.any(|d| d == c))
+            .then_some(0.3 + tiebreak)
+    }
+}
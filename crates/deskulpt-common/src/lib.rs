@@ -5,8 +5,11 @@
 )]
 
 pub mod bindings;
+pub mod command;
 pub mod event;
+pub mod fuzzy;
 pub mod init;
+pub mod metrics;
 pub mod outcome;
 mod ser_error;
 pub mod window;
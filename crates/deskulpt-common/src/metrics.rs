@@ -0,0 +1,234 @@
+//! Lightweight process-wide metrics registry.
+//!
+//! Metrics are identified by static dotted names (e.g., `render.total_ms`),
+//! with the unit as a suffix where applicable. Handles are obtained via
+//! [`counter`], [`gauge`], and [`histogram`], which register the metric on
+//! first use. Recording into a handle never blocks, so hot paths should obtain
+//! their handles once upfront and reuse them.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use parking_lot::RwLock;
+use serde::Serialize;
+
+/// The upper bounds of histogram buckets.
+///
+/// Observations above the last bound fall into an overflow bucket.
+const BUCKETS: [f64; 16] = [
+    1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0, 20000.0,
+    50000.0, 100000.0,
+];
+
+/// A monotonically increasing counter.
+#[derive(Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    /// Increment the counter by one.
+    pub fn increment(&self) {
+        self.add(1);
+    }
+
+    /// Increment the counter by the given amount.
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+}
+
+/// A gauge holding the latest value.
+#[derive(Default)]
+pub struct Gauge(AtomicU64);
+
+impl Gauge {
+    /// Set the value of the gauge.
+    pub fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// A histogram of observed values with fixed buckets.
+pub struct Histogram {
+    /// The number of observations in each bucket, see [`BUCKETS`].
+    buckets: [AtomicU64; BUCKETS.len() + 1],
+    /// The sum of observed values, as bits of an `f64`.
+    sum: AtomicU64,
+    /// The maximum observed value, as bits of an `f64`.
+    max: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            sum: AtomicU64::new(0f64.to_bits()),
+            max: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+}
+
+impl Histogram {
+    /// Record an observed value.
+    pub fn observe(&self, value: f64) {
+        let idx = BUCKETS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(BUCKETS.len());
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sum| {
+                Some((f64::from_bits(sum) + value).to_bits())
+            });
+        let _ = self
+            .max
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |max| {
+                (value > f64::from_bits(max)).then_some(value.to_bits())
+            });
+    }
+
+    /// Take a snapshot of the histogram.
+    fn snapshot(&self) -> HistogramSnapshot {
+        let counts = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect::<Vec<_>>();
+        let count = counts.iter().sum::<u64>();
+        let max = f64::from_bits(self.max.load(Ordering::Relaxed));
+
+        // Estimate a quantile by the upper bound of the bucket containing it,
+        // capped by the maximum observed value
+        let quantile = |q: f64| {
+            let rank = (q * count as f64).ceil().max(1.0) as u64;
+            let mut seen = 0;
+            for (idx, n) in counts.iter().enumerate() {
+                seen += n;
+                if seen >= rank {
+                    return BUCKETS.get(idx).map_or(max, |bound| bound.min(max));
+                }
+            }
+            max
+        };
+
+        HistogramSnapshot {
+            count,
+            sum: f64::from_bits(self.sum.load(Ordering::Relaxed)),
+            max,
+            p50: quantile(0.5),
+            p95: quantile(0.95),
+            p99: quantile(0.99),
+        }
+    }
+}
+
+/// A snapshot of a [`Histogram`].
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramSnapshot {
+    /// The number of observations.
+    pub count: u64,
+    /// The sum of observed values.
+    pub sum: f64,
+    /// The maximum observed value.
+    pub max: f64,
+    /// The estimated median.
+    pub p50: f64,
+    /// The estimated 95th percentile.
+    pub p95: f64,
+    /// The estimated 99th percentile.
+    pub p99: f64,
+}
+
+/// A snapshot of all registered metrics.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsSnapshot {
+    /// The values of counters, keyed by name.
+    pub counters: BTreeMap<String, u64>,
+    /// The values of gauges, keyed by name.
+    pub gauges: BTreeMap<String, f64>,
+    /// The snapshots of histograms, keyed by name.
+    pub histograms: BTreeMap<String, HistogramSnapshot>,
+}
+
+/// The registered metrics.
+///
+/// Metrics live for the rest of the process once registered, so their handles
+/// are leaked to be shared as static references.
+#[derive(Default)]
+struct Registry {
+    counters: BTreeMap<&'static str, &'static Counter>,
+    gauges: BTreeMap<&'static str, &'static Gauge>,
+    histograms: BTreeMap<&'static str, &'static Histogram>,
+}
+
+static REGISTRY: LazyLock<RwLock<Registry>> = LazyLock::new(Default::default);
+
+/// Get or register a metric in one of the maps of the [`Registry`].
+fn get_or_register<T: Default>(
+    name: &'static str,
+    map: impl Fn(&mut Registry) -> &mut BTreeMap<&'static str, &'static T>,
+    get: impl Fn(&Registry) -> Option<&'static T>,
+) -> &'static T {
+    if let Some(metric) = get(&REGISTRY.read()) {
+        return metric;
+    }
+    let mut registry = REGISTRY.write();
+    map(&mut registry)
+        .entry(name)
+        .or_insert_with(|| Box::leak(Box::default()))
+}
+
+/// Get the counter with the given name, registering it if needed.
+pub fn counter(name: &'static str) -> &'static Counter {
+    get_or_register(
+        name,
+        |registry| &mut registry.counters,
+        |registry| registry.counters.get(name).copied(),
+    )
+}
+
+/// Get the gauge with the given name, registering it if needed.
+pub fn gauge(name: &'static str) -> &'static Gauge {
+    get_or_register(
+        name,
+        |registry| &mut registry.gauges,
+        |registry| registry.gauges.get(name).copied(),
+    )
+}
+
+/// Get the histogram with the given name, registering it if needed.
+pub fn histogram(name: &'static str) -> &'static Histogram {
+    get_or_register(
+        name,
+        |registry| &mut registry.histograms,
+        |registry| registry.histograms.get(name).copied(),
+    )
+}
+
+/// Take a snapshot of all registered metrics.
+pub fn snapshot() -> MetricsSnapshot {
+    let registry = REGISTRY.read();
+    MetricsSnapshot {
+        counters: registry
+            .counters
+            .iter()
+            .map(|(name, counter)| (name.to_string(), counter.0.load(Ordering::Relaxed)))
+            .collect(),
+        gauges: registry
+            .gauges
+            .iter()
+            .map(|(name, gauge)| {
+                let value = f64::from_bits(gauge.0.load(Ordering::Relaxed));
+                (name.to_string(), value)
+            })
+            .collect(),
+        histograms: registry
+            .histograms
+            .iter()
+            .map(|(name, histogram)| (name.to_string(), histogram.snapshot()))
+            .collect(),
+    }
+}
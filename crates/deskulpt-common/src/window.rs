@@ -26,6 +26,22 @@ impl DeskulptWindow {
     }
 }
 
+/// Get the label of the floating window of a detached widget.
+///
+/// Window labels may only contain alphanumeric characters and `-/:_`, so the
+/// widget ID is hex-encoded to support arbitrary IDs.
+pub fn detached_widget_label(id: &str) -> String {
+    let hex = id.bytes().map(|b| format!("{b:02x}")).collect::<String>();
+    format!("widget-{hex}")
+}
+
+/// Check if a window label is of the floating window of a detached widget.
+///
+/// See [`detached_widget_label`] for the format.
+pub fn is_detached_widget_label(label: &str) -> bool {
+    label.starts_with("widget-")
+}
+
 impl AsRef<str> for DeskulptWindow {
     fn as_ref(&self) -> &str {
         match self {
@@ -0,0 +1,78 @@
+//! Internals of the `#[instrument]` macro.
+
+use proc_macro::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{GenericParam, ItemFn, LitStr, ReturnType, Type, parse_macro_input, parse_quote};
+
+/// Token stream processor for the `#[instrument]` macro.
+///
+/// This clones the AST of the original command function and performs the
+/// following modifications:
+///
+/// - Add a parameter for the calling window. The runtime of the window is the
+///   first type parameter of the function if any, or the default runtime.
+/// - Wrap the original function body in a block that starts an invocation via
+///   `deskulpt_common::command::Invocation`, runs the original body within its
+///   span, and finishes the invocation with the status of the result.
+///
+/// The status is derived from the result if the return type is named `Result`
+/// or `SerResult`; otherwise the command is considered infallible. The macro
+/// must be placed above `#[tauri::command]` so that the added parameter is
+/// visible to it.
+pub fn proc_instrument(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut func = parse_macro_input!(item as ItemFn);
+
+    let name = LitStr::new(&func.sig.ident.to_string(), func.sig.ident.span());
+
+    let runtime = func
+        .sig
+        .generics
+        .params
+        .iter()
+        .find_map(|param| match param {
+            GenericParam::Type(param) => Some(param.ident.clone()),
+            _ => None,
+        });
+    let window_type: Type = match runtime {
+        Some(runtime) => parse_quote!(::tauri::WebviewWindow<#runtime>),
+        None => parse_quote!(::tauri::WebviewWindow),
+    };
+    func.sig
+        .inputs
+        .insert(0, parse_quote!(__deskulpt_window: #window_type));
+
+    let output_type: Type = match &func.sig.output {
+        ReturnType::Type(_, ty) => (**ty).clone(),
+        ReturnType::Default => parse_quote!(()),
+    };
+    let is_fallible = matches!(
+        &output_type,
+        Type::Path(path) if path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "Result" || segment.ident == "SerResult"
+        })
+    );
+    let status = if is_fallible {
+        quote!(__deskulpt_result.is_ok())
+    } else {
+        quote!(true)
+    };
+
+    let original_body = func.block.clone();
+    let run = if func.sig.asyncness.is_some() {
+        quote!(__deskulpt_invocation.instrument(async move #original_body).await)
+    } else {
+        quote!(__deskulpt_invocation.in_scope(move || #original_body))
+    };
+    func.block = Box::new(parse_quote!({
+        let __deskulpt_invocation = ::deskulpt_common::command::Invocation::start(
+            concat!(env!("DESKULPT_TAURI_PLUGIN_NAME"), "|", #name),
+            concat!("command.", env!("DESKULPT_TAURI_PLUGIN_NAME"), ".", #name, "_ms"),
+            __deskulpt_window.label(),
+        );
+        let __deskulpt_result: #output_type = #run;
+        __deskulpt_invocation.finish(#status);
+        __deskulpt_result
+    }));
+
+    func.into_token_stream().into()
+}
@@ -5,6 +5,7 @@
 )]
 
 mod event;
+mod instrument;
 
 use proc_macro::TokenStream;
 
@@ -12,3 +13,8 @@ use proc_macro::TokenStream;
 pub fn derive_event(input: TokenStream) -> TokenStream {
     event::proc_derive_event(input)
 }
+
+#[proc_macro_attribute]
+pub fn instrument(attr: TokenStream, item: TokenStream) -> TokenStream {
+    instrument::proc_instrument(attr, item)
+}
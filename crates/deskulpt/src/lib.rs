@@ -5,26 +5,47 @@
 )]
 
 use tauri::{Builder, generate_context};
+use tauri_plugin_deskulpt_core::custom_css::CustomCssExt;
+use tauri_plugin_deskulpt_core::plugins::PluginsExt;
+use tauri_plugin_deskulpt_core::remote::RemoteExt;
+use tauri_plugin_deskulpt_core::safe_mode::SafeModeExt;
+use tauri_plugin_deskulpt_core::search::SearchExt;
 use tauri_plugin_deskulpt_core::shortcuts::ShortcutsExt;
 use tauri_plugin_deskulpt_core::states::CanvasImodeStateExt;
+use tauri_plugin_deskulpt_core::timeline::TimelineExt;
 use tauri_plugin_deskulpt_core::tray::TrayExt;
+use tauri_plugin_deskulpt_core::watchdog::WatchdogExt;
 use tauri_plugin_deskulpt_core::window::WindowExt;
+use tauri_plugin_deskulpt_settings::overrides::Overrides;
 use tauri_plugin_deskulpt_widgets::WidgetsExt;
 
 /// Entry point for the Deskulpt backend.
+///
+/// Settings can be overridden for the launch via environment variables and
+/// command line flags; see [`Overrides`].
 pub fn run() {
     Builder::default()
+        .manage(Overrides::from_env())
         .setup(move |app| {
             // Hide the application from the dock on macOS because skipping
             // taskbar is not applicable for macOS
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
+            app.init_state_timeline();
             app.init_shortcuts();
+            app.init_custom_css();
+            app.init_search();
+            app.init_safe_mode()?;
+            if !app.is_safe_mode() {
+                app.init_plugins()?;
+            }
             app.create_canvas()?;
             app.create_tray()?;
 
             app.manage_canvas_imode()?;
+            app.start_remote_control();
+            app.start_watchdog();
 
             app.widgets().maybe_add_starter()?;
 
@@ -1,6 +1,29 @@
 fn main() {
     tauri_deskulpt_build::Builder::default()
-        .commands(&["call_plugin", "open"])
-        .events(&["ShowToastEvent"])
+        .commands(&[
+            "attach_widget",
+            "call_plugin",
+            "capture_canvas",
+            "detach_widget",
+            "diagnose_compositing",
+            "dump_state_timeline",
+            "exit_safe_mode",
+            "export_diagnostics",
+            "get_metrics",
+            "health",
+            "is_layout_editing_supported",
+            "open",
+            "query",
+            "set_layout_editing",
+            "validate_shortcut",
+        ])
+        .events(&[
+            "DisplayChangedEvent",
+            "ImodeChangedEvent",
+            "ShowToastEvent",
+            "SwitchPortalTabEvent",
+            "UpdateCustomCssEvent",
+            "WidgetHoverEvent",
+        ])
         .build();
 }
@@ -0,0 +1,21 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::window::WindowExt;
+
+/// Attach a detached widget back to the canvas.
+///
+/// This command is a wrapper of [`WindowExt::attach_widget`].
+///
+/// ### Errors
+///
+/// - The widget does not exist.
+/// - Error closing the floating window.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn attach_widget<R: Runtime>(app_handle: AppHandle<R>, id: String) -> SerResult<()> {
+    app_handle.attach_widget(&id)?;
+    Ok(())
+}
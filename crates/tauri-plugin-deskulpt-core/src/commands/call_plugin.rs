@@ -1,9 +1,14 @@
-use deskulpt_common::{SerResult, ser_bail};
+use std::time::Instant;
+
+use deskulpt_common::command::instrument;
+use deskulpt_common::{SerResult, metrics, ser_bail};
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use tauri::{AppHandle, Runtime, command};
 use tauri_plugin_deskulpt_widgets::WidgetsExt;
 
+use crate::safe_mode::SafeModeExt;
+
 // TODO: Remove this temporary implementation
 static FS_PLUGIN: Lazy<Mutex<deskulpt_plugin_fs::FsPlugin>> =
     Lazy::new(|| Mutex::new(deskulpt_plugin_fs::FsPlugin));
@@ -24,6 +29,7 @@ static SYS_PLUGIN: Lazy<Mutex<deskulpt_plugin_sys::SysPlugin>> =
 /// Also, in order to simplify the engine API for the plugin (because it is
 /// a temporary implementation), `app_handle` is using the default runtime but
 /// it should be a generic `R: Runtime` parameter in the final implementation.
+#[instrument]
 #[command]
 #[specta::specta]
 pub async fn call_plugin<R: Runtime>(
@@ -33,31 +39,30 @@ pub async fn call_plugin<R: Runtime>(
     id: String,
     payload: Option<serde_json::Value>,
 ) -> SerResult<serde_json::Value> {
-    let widget_dir_fn = move |id: &str| app_handle.widgets().dir().join(id);
+    if app_handle.is_safe_mode() {
+        ser_bail!("Plugins are not loaded in safe mode");
+    }
+    app_handle
+        .widgets()
+        .authorize_plugin_call(&id, &plugin, &command, payload.as_ref())?;
+    let widget_dir_fn = move |id: &str| app_handle.widgets().widget_dir(id);
 
-    match plugin.as_str() {
+    metrics::counter("plugin.calls").increment();
+    let start = Instant::now();
+    let result = match plugin.as_str() {
         "fs" => {
             let plugin = FS_PLUGIN.lock();
-            let result = deskulpt_plugin::call_plugin(
-                widget_dir_fn,
-                &*plugin,
-                command.as_str(),
-                id,
-                payload,
-            )?;
-            Ok(result)
+            deskulpt_plugin::call_plugin(widget_dir_fn, &*plugin, command.as_str(), id, payload)
         },
         "sys" => {
             let plugin = SYS_PLUGIN.lock();
-            let result = deskulpt_plugin::call_plugin(
-                widget_dir_fn,
-                &*plugin,
-                command.as_str(),
-                id,
-                payload,
-            )?;
-            Ok(result)
+            deskulpt_plugin::call_plugin(widget_dir_fn, &*plugin, command.as_str(), id, payload)
         },
         _ => ser_bail!("Unknown plugin: {}", plugin),
+    };
+    metrics::histogram("plugin.call_ms").observe(start.elapsed().as_secs_f64() * 1000.0);
+    if result.is_err() {
+        metrics::counter("plugin.errors").increment();
     }
+    Ok(result?)
 }
@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::window::{CaptureRegion, WindowExt};
+
+/// Capture the canvas contents to a PNG file.
+///
+/// This command is a wrapper of [`WindowExt::capture_canvas`].
+///
+/// ### Errors
+///
+/// - Capturing the canvas is unsupported on the platform.
+/// - The region is outside of the canvas.
+/// - Error writing the PNG file.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn capture_canvas<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: PathBuf,
+    region: Option<CaptureRegion>,
+) -> SerResult<()> {
+    app_handle.capture_canvas(&path, region)?;
+    Ok(())
+}
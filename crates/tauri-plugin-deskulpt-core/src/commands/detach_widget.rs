@@ -0,0 +1,21 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::window::WindowExt;
+
+/// Detach a widget into its own floating window.
+///
+/// This command is a wrapper of [`WindowExt::detach_widget`].
+///
+/// ### Errors
+///
+/// - The widget does not exist.
+/// - Error creating the floating window.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn detach_widget<R: Runtime>(app_handle: AppHandle<R>, id: String) -> SerResult<()> {
+    app_handle.detach_widget(&id)?;
+    Ok(())
+}
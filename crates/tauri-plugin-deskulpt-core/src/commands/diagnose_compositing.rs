@@ -0,0 +1,22 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+
+use crate::window::CompositingDiagnostics;
+
+/// Diagnose the compositing setup of Deskulpt windows.
+///
+/// This takes the WebGL renderer probed by the calling webview, if available.
+/// The diagnostics are logged for bug triage and also returned.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn diagnose_compositing<R: Runtime>(
+    app_handle: AppHandle<R>,
+    renderer: Option<String>,
+) -> SerResult<CompositingDiagnostics> {
+    let diagnostics = CompositingDiagnostics::new(&app_handle.settings().read(), renderer);
+    tracing::info!(?diagnostics, "Compositing diagnostics");
+    Ok(diagnostics)
+}
@@ -0,0 +1,22 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::timeline::{TimelineEntry, TimelineExt};
+
+/// Dump the recorded state timeline, oldest first.
+///
+/// This command is a wrapper of [`TimelineExt::dump_state_timeline`].
+///
+/// ### Errors
+///
+/// - Not in dev mode, where the state timeline is not recorded.
+/// - Error reading the timeline files.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn dump_state_timeline<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> SerResult<Vec<TimelineEntry>> {
+    Ok(app_handle.dump_state_timeline()?)
+}
@@ -0,0 +1,21 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::safe_mode::SafeModeExt;
+
+/// Exit safe mode by restarting the app normally.
+///
+/// This command is a wrapper of [`SafeModeExt::exit_safe_mode`].
+///
+/// ### Errors
+///
+/// - Error resetting the startup crash count.
+/// - Error persisting settings or widgets.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn exit_safe_mode<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<()> {
+    app_handle.exit_safe_mode()?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::diagnostics::DiagnosticsExt;
+
+/// Export a diagnostic bundle for bug reports to a zip archive.
+///
+/// This command is a wrapper of [`DiagnosticsExt::export_diagnostics`].
+///
+/// ### Errors
+///
+/// - Error reading the logs or the widget catalog.
+/// - Error writing the archive.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn export_diagnostics<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: PathBuf,
+) -> SerResult<()> {
+    app_handle.export_diagnostics(&path)?;
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use deskulpt_common::metrics::{self, MetricsSnapshot};
+use tauri::command;
+
+/// Get a snapshot of the runtime metrics.
+///
+/// This includes counters, gauges, and histograms recorded by the bundler,
+/// the render worker, plugin calls, and the mouse interaction loop.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn get_metrics() -> SerResult<MetricsSnapshot> {
+    Ok(metrics::snapshot())
+}
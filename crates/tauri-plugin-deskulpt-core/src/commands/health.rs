@@ -0,0 +1,14 @@
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::health::{HealthExt, HealthReport};
+
+/// Check the health of all subsystems.
+///
+/// This command is a wrapper of [`HealthExt::health`].
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn health<R: Runtime>(app_handle: AppHandle<R>) -> HealthReport {
+    app_handle.health().await
+}
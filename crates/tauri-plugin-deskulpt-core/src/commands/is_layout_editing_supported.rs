@@ -0,0 +1,16 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::states::CanvasImodeStateExt;
+
+/// Check whether layout editing of widgets is supported on the platform.
+///
+/// This command is a wrapper of
+/// [`CanvasImodeStateExt::is_layout_editing_supported`].
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn is_layout_editing_supported<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<bool> {
+    Ok(app_handle.is_layout_editing_supported())
+}
@@ -1,9 +1,48 @@
 //! Deskulpt core commands to be invoked by the frontend.
 
+#[doc(hidden)]
+mod attach_widget;
 #[doc(hidden)]
 mod call_plugin;
 #[doc(hidden)]
+mod capture_canvas;
+#[doc(hidden)]
+mod detach_widget;
+#[doc(hidden)]
+mod diagnose_compositing;
+#[doc(hidden)]
+mod dump_state_timeline;
+#[doc(hidden)]
+mod exit_safe_mode;
+#[doc(hidden)]
+mod export_diagnostics;
+#[doc(hidden)]
+mod get_metrics;
+#[doc(hidden)]
+mod health;
+#[doc(hidden)]
+mod is_layout_editing_supported;
+#[doc(hidden)]
 mod open;
+#[doc(hidden)]
+mod query;
+#[doc(hidden)]
+mod set_layout_editing;
+#[doc(hidden)]
+mod validate_shortcut;
 
+pub use attach_widget::*;
 pub use call_plugin::*;
+pub use capture_canvas::*;
+pub use detach_widget::*;
+pub use diagnose_compositing::*;
+pub use dump_state_timeline::*;
+pub use exit_safe_mode::*;
+pub use export_diagnostics::*;
+pub use get_metrics::*;
+pub use health::*;
+pub use is_layout_editing_supported::*;
 pub use open::*;
+pub use query::*;
+pub use set_layout_editing::*;
+pub use validate_shortcut::*;
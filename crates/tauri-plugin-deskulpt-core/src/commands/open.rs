@@ -1,4 +1,5 @@
 use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
 use serde::Deserialize;
 use tauri::{AppHandle, Runtime, command};
 use tauri_plugin_deskulpt_logs::LogsExt;
@@ -27,13 +28,14 @@ pub enum OpenTarget {
 ///
 /// - Error accessing the specified target.
 /// - Error opening the target.
+#[instrument]
 #[command]
 #[specta::specta]
 pub async fn open<R: Runtime>(app_handle: AppHandle<R>, target: OpenTarget) -> SerResult<()> {
     let path = match target {
         OpenTarget::Widgets => app_handle.widgets().dir(),
-        OpenTarget::Widget(id) => &app_handle.widgets().dir().join(id),
-        OpenTarget::Settings => app_handle.settings().persist_path(),
+        OpenTarget::Widget(id) => &app_handle.widgets().widget_dir(&id),
+        OpenTarget::Settings => &app_handle.settings().persist_path(),
         OpenTarget::Logs => app_handle.logs().dir(),
     };
 
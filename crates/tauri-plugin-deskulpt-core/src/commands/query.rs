@@ -0,0 +1,28 @@
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+
+use crate::search::{SearchExt, SearchResult};
+
+/// Query search providers for results matching the text on behalf of a widget.
+///
+/// This command is a wrapper of [`SearchExt::search`]. The `files` provider
+/// exposes file names in user directories, so it is only queried if the
+/// widget is allowed to call `search.files` as a plugin command, see
+/// [`tauri_plugin_deskulpt_widgets::WidgetsManager::authorize_plugin_call`].
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn query<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    text: String,
+) -> Vec<SearchResult> {
+    let allow_files = app_handle
+        .widgets()
+        .authorize_plugin_call(&id, "search", "files", None)
+        .is_ok();
+    app_handle
+        .search(&text, |provider| allow_files || provider != "files")
+        .await
+}
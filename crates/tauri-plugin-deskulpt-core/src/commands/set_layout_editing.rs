@@ -0,0 +1,24 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+
+use crate::states::CanvasImodeStateExt;
+
+/// Start or stop layout editing of widgets.
+///
+/// This command is a wrapper of [`CanvasImodeStateExt::set_layout_editing`].
+///
+/// ### Errors
+///
+/// - The canvas window does not exist.
+/// - Layout editing is not supported on the platform.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn set_layout_editing<R: Runtime>(
+    app_handle: AppHandle<R>,
+    enabled: bool,
+) -> SerResult<()> {
+    app_handle.set_layout_editing(enabled)?;
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use tauri::{AppHandle, Runtime, command};
+use tauri_plugin_deskulpt_settings::model::ShortcutAction;
+
+use crate::shortcuts::{ShortcutValidation, ShortcutsExt};
+
+/// Validate a keyboard shortcut before binding it to an action.
+///
+/// This command is a wrapper of [`ShortcutsExt::validate_shortcut`]. See
+/// [`ShortcutValidation`] for the possible results.
+#[instrument]
+#[command]
+#[specta::specta]
+pub async fn validate_shortcut<R: Runtime>(
+    app_handle: AppHandle<R>,
+    accelerator: String,
+    action: Option<ShortcutAction>,
+) -> SerResult<ShortcutValidation> {
+    Ok(app_handle.validate_shortcut(&accelerator, action.as_ref()))
+}
@@ -0,0 +1,180 @@
+//! User-defined CSS theme overrides.
+
+use std::path::Path;
+use std::time::SystemTime;
+
+use anyhow::{Result, bail};
+use deskulpt_common::event::Event;
+use deskulpt_common::outcome::Outcome;
+use deskulpt_common::window::DeskulptWindow;
+use parking_lot::{Mutex, RwLock};
+use tauri::{App, AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+
+use crate::events::UpdateCustomCssEvent;
+
+/// The maximum allowed size of a custom CSS file in bytes.
+const MAX_CUSTOM_CSS_SIZE: u64 = 1 << 20;
+
+/// Managed state for user-defined CSS theme overrides.
+#[derive(Default)]
+struct CustomCssState {
+    /// The currently active custom CSS.
+    ///
+    /// This is an empty string if no custom CSS is configured or if the
+    /// configured file is invalid.
+    css: RwLock<String>,
+    /// The fingerprint of the custom CSS file when last checked.
+    ///
+    /// See [`fingerprint`] and [`check_custom_css`].
+    last_seen: Mutex<Option<(SystemTime, u64)>>,
+}
+
+/// Load and validate a custom CSS file.
+///
+/// The file must have a `.css` extension, must not exceed
+/// [`MAX_CUSTOM_CSS_SIZE`] bytes, and must be valid UTF-8.
+fn load_custom_css(path: &Path) -> Result<String> {
+    if path.extension().is_none_or(|ext| ext != "css") {
+        bail!(
+            "Custom CSS file must have a .css extension: {}",
+            path.display()
+        );
+    }
+    let size = std::fs::metadata(path)?.len();
+    if size > MAX_CUSTOM_CSS_SIZE {
+        bail!("Custom CSS file exceeds the maximum size of {MAX_CUSTOM_CSS_SIZE} bytes: {size}");
+    }
+    Ok(std::fs::read_to_string(path)?)
+}
+
+/// Get the modification fingerprint of a file.
+///
+/// This is used for detecting changes to the custom CSS file. `None` is
+/// returned if the metadata of the file cannot be accessed.
+fn fingerprint(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Generate JavaScript code that injects the custom CSS into a window.
+///
+/// The CSS is placed in a dedicated `<style>` element so that it can be
+/// replaced on subsequent injections. Injecting an empty string effectively
+/// removes the overrides.
+pub(crate) fn injection_script(css: &str) -> Result<String> {
+    let css = serde_json::to_string(css)?;
+    Ok(format!(
+        r#"(function (css) {{
+  const apply = () => {{
+    let style = document.getElementById("__deskulpt-custom-css");
+    if (!style) {{
+      style = document.createElement("style");
+      style.id = "__deskulpt-custom-css";
+      document.head.appendChild(style);
+    }}
+    style.textContent = css;
+  }};
+  if (document.head) {{
+    apply();
+  }} else {{
+    document.addEventListener("DOMContentLoaded", apply);
+  }}
+}})({css});"#
+    ))
+}
+
+/// Extension trait for operations on user-defined CSS theme overrides.
+pub trait CustomCssExt<R: Runtime>: Manager<R> + SettingsExt<R> {
+    /// Initialize management of user-defined CSS theme overrides.
+    ///
+    /// This loads the custom CSS file configured in the settings, injects it
+    /// into all Deskulpt windows, and watches the file for changes on the tick
+    /// of the settings worker. It also reloads the custom CSS when the
+    /// configured path changes.
+    fn init_custom_css(&self) {
+        self.manage(CustomCssState::default());
+
+        let path = self.settings().read().custom_css.clone();
+        reload_custom_css(self.app_handle(), path.as_deref());
+
+        let app_handle = self.app_handle().clone();
+        self.settings().on_custom_css_change(move |_, new| {
+            reload_custom_css(&app_handle, new.map(|s| s.as_str()));
+        });
+
+        let app_handle = self.app_handle().clone();
+        self.settings().on_watch_tick(move || {
+            check_custom_css(&app_handle);
+        });
+    }
+
+    /// Get the currently active custom CSS.
+    ///
+    /// This is an empty string if no custom CSS is active or if custom CSS
+    /// management has not been initialized.
+    fn custom_css(&self) -> String {
+        self.try_state::<CustomCssState>()
+            .map(|state| state.css.read().clone())
+            .unwrap_or_default()
+    }
+}
+
+impl<R: Runtime> CustomCssExt<R> for App<R> {}
+impl<R: Runtime> CustomCssExt<R> for AppHandle<R> {}
+
+/// Reload the custom CSS if the configured file has changed since last checked.
+fn check_custom_css<R: Runtime>(app_handle: &AppHandle<R>) {
+    let state = app_handle.state::<CustomCssState>();
+    let mut last_seen = state.last_seen.lock();
+
+    let Some(path) = app_handle.settings().read().custom_css.clone() else {
+        *last_seen = None;
+        return;
+    };
+
+    let current = fingerprint(Path::new(&path));
+    if last_seen.is_some() && current != *last_seen {
+        tracing::debug!(%path, "Custom CSS file changed, reloading");
+        reload_custom_css(app_handle, Some(&path));
+    }
+    *last_seen = current;
+}
+
+/// Reload the custom CSS from the given path and apply it.
+///
+/// Upon failure to load the file, the overrides are removed so that windows
+/// fall back to the built-in theme. In either case, an
+/// [`UpdateCustomCssEvent`] is emitted for live preview.
+fn reload_custom_css<R: Runtime>(app_handle: &AppHandle<R>, path: Option<&str>) {
+    let outcome: Outcome<String> = match path {
+        Some(path) => load_custom_css(Path::new(path)).into(),
+        None => Outcome::Ok(String::new()),
+    };
+
+    let css = match &outcome {
+        Outcome::Ok(css) => css.clone(),
+        Outcome::Err(e) => {
+            tracing::error!(error = %e, ?path, "Failed to load custom CSS");
+            String::new()
+        },
+    };
+    *app_handle.state::<CustomCssState>().css.write() = css.clone();
+
+    match injection_script(&css) {
+        Ok(script) => {
+            for window in [DeskulptWindow::Canvas, DeskulptWindow::Portal] {
+                if let Ok(webview) = window.webview_window(app_handle)
+                    && let Err(e) = webview.eval(&script)
+                {
+                    tracing::error!(error = ?e, %window, "Failed to inject custom CSS");
+                }
+            }
+        },
+        Err(e) => tracing::error!(error = ?e, "Failed to generate custom CSS injection script"),
+    }
+
+    if let Err(e) = UpdateCustomCssEvent(&outcome).emit(app_handle) {
+        tracing::error!("Failed to emit UpdateCustomCssEvent: {e:?}");
+    }
+}
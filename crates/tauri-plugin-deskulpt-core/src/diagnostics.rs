@@ -0,0 +1,193 @@
+//! Diagnostic bundles for bug reports.
+//!
+//! A diagnostic bundle is a zip archive with the recent logs (including the
+//! tail of the logs dumped on the last crash, if any), the settings file, the
+//! widget catalog report, the available plugins, and some system information.
+//! Personally identifiable information is scrubbed from every file before
+//! packaging, so that users can attach the bundle to public bug reports.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{App, AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_logs::LogsExt;
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use crate::window::CompositingDiagnostics;
+
+/// The maximum number of most recent log files to include in a bundle.
+const MAX_LOG_FILES: usize = 3;
+
+/// The placeholder for redacted values.
+const REDACTED: &str = "<redacted>";
+
+/// System information included in a diagnostic bundle.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemInfo {
+    /// The Deskulpt version.
+    app_version: String,
+    /// The Tauri version.
+    tauri_version: &'static str,
+    /// The operating system.
+    os: &'static str,
+    /// The operating system version, if available.
+    os_version: Option<String>,
+    /// The CPU architecture.
+    arch: &'static str,
+    /// The compositing setup of Deskulpt windows.
+    compositing: CompositingDiagnostics,
+}
+
+/// Scrubber of personally identifiable information.
+///
+/// This replaces the home directory with `~` and the user name with a
+/// placeholder. User names that are too short are left alone since replacing
+/// them would mangle unrelated text.
+struct Scrubber {
+    /// Pairs of patterns and their replacements, applied in order.
+    replacements: Vec<(String, String)>,
+}
+
+impl Scrubber {
+    /// Create a scrubber for the current user.
+    fn new<R: Runtime>(app_handle: &AppHandle<R>) -> Self {
+        let mut replacements = vec![];
+
+        if let Ok(home) = app_handle.path().home_dir() {
+            let home = home.to_string_lossy().into_owned();
+            if !home.is_empty() {
+                // Paths may be written with either separator, and may also be
+                // escaped in JSON strings on Windows
+                replacements.push((home.replace('\\', "\\\\"), "~".to_string()));
+                replacements.push((home.replace('\\', "/"), "~".to_string()));
+                replacements.push((home, "~".to_string()));
+            }
+        }
+
+        if let Some(user) = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok()
+            .filter(|user| user.len() >= 3)
+        {
+            replacements.push((user, "<user>".to_string()));
+        }
+
+        Self { replacements }
+    }
+
+    /// Scrub a piece of text.
+    fn scrub(&self, text: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(text.to_string(), |text, (pattern, replacement)| {
+                text.replace(pattern, replacement)
+            })
+    }
+}
+
+/// Redact the secrets in the settings file content.
+///
+/// Secret names are kept since they can be useful for triage, but their
+/// encrypted values are replaced. If the content is not valid JSON, it is
+/// returned as is since there is no way to locate the secrets.
+fn redact_settings(content: String) -> String {
+    let Ok(mut settings) = serde_json::from_str::<Value>(&content) else {
+        return content;
+    };
+    if let Some(secrets) = settings.get_mut("secrets").and_then(Value::as_object_mut) {
+        for value in secrets.values_mut() {
+            *value = Value::String(REDACTED.to_string());
+        }
+    }
+    serde_json::to_string_pretty(&settings).unwrap_or(content)
+}
+
+/// Extension trait for diagnostic bundle operations.
+pub trait DiagnosticsExt<R: Runtime>:
+    Manager<R> + LogsExt<R> + SettingsExt<R> + WidgetsExt<R>
+{
+    /// Export a diagnostic bundle to a zip archive at the given path.
+    ///
+    /// Tauri command: [`crate::commands::export_diagnostics`].
+    fn export_diagnostics(&self, path: &Path) -> Result<()> {
+        let app_handle = self.app_handle();
+        let scrubber = Scrubber::new(app_handle);
+
+        let system_info = SystemInfo {
+            app_version: app_handle.package_info().version.to_string(),
+            tauri_version: tauri::VERSION,
+            os: std::env::consts::OS,
+            os_version: sysinfo::System::long_os_version(),
+            arch: std::env::consts::ARCH,
+            compositing: CompositingDiagnostics::new(&self.settings().read(), None),
+        };
+
+        let settings = match std::fs::read_to_string(self.settings().persist_path()) {
+            Ok(content) => redact_settings(content),
+            Err(e) => format!("Failed to read settings file: {e}"),
+        };
+
+        let mut files = vec![
+            (
+                "system.json".to_string(),
+                serde_json::to_string_pretty(&system_info)?,
+            ),
+            ("settings.json".to_string(), settings),
+            (
+                "catalog-report.json".to_string(),
+                serde_json::to_string_pretty(&self.widgets().catalog_report()?)?,
+            ),
+            (
+                "plugins.json".to_string(),
+                serde_json::to_string_pretty(&self.widgets().plugins())?,
+            ),
+        ];
+
+        for log_file in self.logs().collect()?.into_iter().take(MAX_LOG_FILES) {
+            let Some(name) = log_file.file_name() else {
+                continue;
+            };
+            let content = std::fs::read(&log_file)
+                .with_context(|| format!("Failed to read log file: {}", log_file.display()))?;
+            files.push((
+                format!("logs/{}", name.to_string_lossy()),
+                String::from_utf8_lossy(&content).into_owned(),
+            ));
+        }
+
+        let crash_tail = self.logs().crash_tail();
+        if crash_tail.exists() {
+            let content = std::fs::read(&crash_tail)
+                .with_context(|| format!("Failed to read crash tail: {}", crash_tail.display()))?;
+            files.push((
+                "logs/crash-tail.ndjson".to_string(),
+                String::from_utf8_lossy(&content).into_owned(),
+            ));
+        }
+
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create bundle: {}", path.display()))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+        for (name, content) in files {
+            zip.start_file(name, options)?;
+            zip.write_all(scrubber.scrub(&content).as_bytes())?;
+        }
+        zip.finish()?;
+
+        let path = scrubber.scrub(&path.display().to_string());
+        tracing::info!(path, "Exported diagnostic bundle");
+        Ok(())
+    }
+}
+
+impl<R: Runtime> DiagnosticsExt<R> for App<R> {}
+impl<R: Runtime> DiagnosticsExt<R> for AppHandle<R> {}
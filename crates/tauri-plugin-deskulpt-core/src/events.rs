@@ -1,7 +1,11 @@
 //! Deskulpt core events.
 
 use deskulpt_common::event::Event;
+use deskulpt_common::outcome::Outcome;
 use serde::Serialize;
+use tauri_plugin_deskulpt_settings::model::CanvasImode;
+
+use crate::window::PortalTab;
 
 /// Event for showing a toast notification.
 ///
@@ -15,3 +19,66 @@ pub enum ShowToastEvent {
     /// Show an [error](https://sonner.emilkowal.ski/toast#error) toast.
     Error(String),
 }
+
+/// Event for notifying the canvas of a display configuration change.
+///
+/// This event is emitted from the backend to the canvas when monitors are
+/// added or removed, or when the canvas is moved, resized, or rescaled, after
+/// widget positions have been re-resolved against the new monitor layout.
+#[derive(Debug, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayChangedEvent {
+    /// The number of available monitors.
+    pub monitor_count: u32,
+    /// The scale factor of the canvas.
+    pub scale_factor: f64,
+}
+
+/// Event for notifying the canvas of an interaction state change.
+///
+/// This event is emitted from the backend to the canvas when the canvas
+/// interaction mode changes, when layout editing starts or stops, and whenever
+/// the canvas switches between accepting and ignoring cursor events, e.g., as
+/// the cursor moves on and off widgets in auto mode.
+#[derive(Debug, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ImodeChangedEvent {
+    /// The canvas interaction mode.
+    pub mode: CanvasImode,
+    /// Whether the canvas is accepting cursor events.
+    pub is_interactive: bool,
+    /// Whether widgets are being laid out by dragging them on the canvas.
+    ///
+    /// While this is the case, the canvas should keep widgets from handling
+    /// cursor events, since dragging is handled by the backend.
+    pub is_editing_layout: bool,
+}
+
+/// Event for notifying the canvas of the cursor entering or leaving a widget.
+///
+/// This event is emitted from the backend to the canvas as the cursor moves
+/// across widgets, regardless of whether the canvas is accepting cursor events,
+/// so that widgets can show hover affordances even when click-through. Only the
+/// topmost widget under the cursor is considered hovered.
+#[derive(Debug, Serialize, specta::Type, Event)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum WidgetHoverEvent {
+    /// The cursor entered the widget with the given ID.
+    Enter(String),
+    /// The cursor left the widget with the given ID.
+    Leave(String),
+}
+
+/// Event for notifying frontend windows of a custom CSS update.
+///
+/// This carries either the new custom CSS (empty if removed) or an error
+/// message loading it, which can be used for live preview.
+#[derive(Debug, Serialize, specta::Type, Event)]
+pub struct UpdateCustomCssEvent<'a>(pub &'a Outcome<String>);
+
+/// Event for switching Deskulpt portal to a tab.
+///
+/// This event is emitted from the backend to the portal when it is asked to
+/// show a specific tab while already open.
+#[derive(Debug, Serialize, specta::Type, Event)]
+pub struct SwitchPortalTabEvent(pub PortalTab);
@@ -0,0 +1,282 @@
+//! Health checks of Deskulpt subsystems.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use deskulpt_common::metrics;
+use serde::Serialize;
+use tauri::{App, AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_logs::LogsExt;
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+
+use crate::safe_mode::SafeModeExt;
+use crate::states::{CanvasImodeStateExt, MouseHookBackend};
+
+/// The minimum available disk space for logs in bytes to be considered
+/// healthy.
+const MIN_AVAILABLE_SPACE: u64 = 100 * 1024 * 1024;
+
+/// The status of a subsystem.
+///
+/// Statuses are ordered by severity, so that the overall status is the most
+/// severe among the subsystems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    /// The subsystem is working as expected.
+    Ok,
+    /// The subsystem is working with reduced functionality.
+    Degraded,
+    /// The subsystem is not working.
+    Down,
+}
+
+/// The health of the widgets subsystem.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetsHealth {
+    /// The status of the subsystem.
+    pub status: HealthStatus,
+    /// Whether each background worker is still running.
+    pub workers: BTreeMap<String, bool>,
+    /// The number of renders in flight.
+    pub renders_in_flight: u64,
+    /// The number of renders queued behind those in flight.
+    pub renders_queued: u64,
+}
+
+/// The health of the settings subsystem.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsHealth {
+    /// The status of the subsystem.
+    pub status: HealthStatus,
+    /// The path where settings are persisted.
+    pub persist_path: PathBuf,
+    /// The error making the persist path unwritable, if any.
+    pub error: Option<String>,
+}
+
+/// The health of the plugins subsystem.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginsHealth {
+    /// The status of the subsystem.
+    pub status: HealthStatus,
+    /// The loaded plugins, mapping plugin names to versions.
+    pub loaded: BTreeMap<String, String>,
+    /// The plugins required by widgets that failed to load or whose loaded
+    /// versions do not satisfy the requirements.
+    pub failed: BTreeSet<String>,
+    /// Whether plugin loading is skipped in safe mode.
+    pub safe_mode: bool,
+}
+
+/// The health of the logging subsystem.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsHealth {
+    /// The status of the subsystem.
+    pub status: HealthStatus,
+    /// The directory where log files are stored.
+    pub dir: PathBuf,
+    /// The number of log lines dropped so far.
+    pub dropped_lines: u64,
+    /// The available disk space for log files in bytes, if known.
+    pub available_space: Option<u64>,
+}
+
+/// The health of the connectivity to the widgets registry.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryHealth {
+    /// The status of the subsystem.
+    pub status: HealthStatus,
+    /// The round-trip time to the registry in milliseconds, if reachable.
+    pub latency_ms: Option<u64>,
+    /// The error reaching the registry, if any.
+    pub error: Option<String>,
+}
+
+/// The health of the global mouse hook.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MouseHealth {
+    /// The status of the subsystem.
+    pub status: HealthStatus,
+    /// The backend of the running mouse hook, if any.
+    pub backend: Option<MouseHookBackend>,
+}
+
+/// A health report of all subsystems.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    /// The overall status, i.e., the most severe status of all subsystems.
+    pub status: HealthStatus,
+    /// The health of the widgets subsystem.
+    pub widgets: WidgetsHealth,
+    /// The health of the settings subsystem.
+    pub settings: SettingsHealth,
+    /// The health of the plugins subsystem.
+    pub plugins: PluginsHealth,
+    /// The health of the logging subsystem.
+    pub logs: LogsHealth,
+    /// The health of the connectivity to the widgets registry.
+    pub registry: RegistryHealth,
+    /// The health of the global mouse hook.
+    pub mouse: MouseHealth,
+}
+
+/// Check whether a file can be written without modifying it.
+///
+/// If the file does not exist, this checks whether it can be created instead
+/// by creating and removing a probe file next to it.
+fn check_writable(path: &Path) -> std::io::Result<()> {
+    if path.exists() {
+        OpenOptions::new().append(true).open(path)?;
+        return Ok(());
+    }
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".deskulpt-write-probe");
+    OpenOptions::new().create(true).append(true).open(&probe)?;
+    std::fs::remove_file(&probe)
+}
+
+/// Get the available disk space at a path in bytes.
+///
+/// This uses the disk with the longest mount point containing the path.
+fn available_space(path: &Path) -> Option<u64> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Extension trait for health checks.
+pub trait HealthExt<R: Runtime>:
+    Manager<R> + CanvasImodeStateExt<R> + LogsExt<R> + SafeModeExt<R> + SettingsExt<R> + WidgetsExt<R>
+{
+    /// Check the health of all subsystems.
+    ///
+    /// This never fails; failures of individual checks are reflected in the
+    /// report instead. Checking the registry connectivity involves a network
+    /// request, so this may take a few seconds.
+    ///
+    /// Tauri command: [`crate::commands::health`].
+    fn health(&self) -> impl Future<Output = HealthReport> + Send {
+        let workers = self.widgets().workers_alive();
+        let gauges = metrics::snapshot().gauges;
+        let gauge = |name: &str| gauges.get(name).copied().unwrap_or_default() as u64;
+        let widgets = WidgetsHealth {
+            status: if workers.values().all(|alive| *alive) {
+                HealthStatus::Ok
+            } else {
+                HealthStatus::Down
+            },
+            workers,
+            renders_in_flight: gauge("render.in_flight"),
+            renders_queued: gauge("render.queued"),
+        };
+
+        let persist_path = self.settings().persist_path();
+        let error = check_writable(&persist_path).err().map(|e| e.to_string());
+        let settings = SettingsHealth {
+            status: if error.is_none() {
+                HealthStatus::Ok
+            } else {
+                HealthStatus::Degraded
+            },
+            persist_path,
+            error,
+        };
+
+        let safe_mode = self.is_safe_mode();
+        let failed = self.widgets().missing_plugins();
+        let plugins = PluginsHealth {
+            status: if safe_mode || !failed.is_empty() {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Ok
+            },
+            loaded: self.widgets().plugins(),
+            failed,
+            safe_mode,
+        };
+
+        let dir = self.logs().dir().to_path_buf();
+        let dropped_lines = self.logs().dropped_lines() as u64;
+        let available_space = available_space(&dir);
+        let logs = LogsHealth {
+            status: if dropped_lines > 0
+                || available_space.is_some_and(|space| space < MIN_AVAILABLE_SPACE)
+            {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Ok
+            },
+            dir,
+            dropped_lines,
+            available_space,
+        };
+
+        // Polling works but lags behind the cursor and keeps waking up
+        let backend = self.mouse_hook_backend();
+        let mouse = MouseHealth {
+            status: match backend {
+                Some(MouseHookBackend::Native) => HealthStatus::Ok,
+                Some(MouseHookBackend::Polling) => HealthStatus::Degraded,
+                None => HealthStatus::Down,
+            },
+            backend,
+        };
+
+        let app_handle = self.app_handle().clone();
+        async move {
+            let start = Instant::now();
+            let registry = match app_handle.widgets().ping_registry().await {
+                Ok(()) => RegistryHealth {
+                    status: HealthStatus::Ok,
+                    latency_ms: Some(start.elapsed().as_millis() as u64),
+                    error: None,
+                },
+                Err(e) => RegistryHealth {
+                    status: HealthStatus::Degraded,
+                    latency_ms: None,
+                    error: Some(format!("{e:?}")),
+                },
+            };
+
+            let status = [
+                widgets.status,
+                settings.status,
+                plugins.status,
+                logs.status,
+                registry.status,
+                mouse.status,
+            ]
+            .into_iter()
+            .max()
+            .unwrap_or(HealthStatus::Ok);
+
+            HealthReport {
+                status,
+                widgets,
+                settings,
+                plugins,
+                logs,
+                registry,
+                mouse,
+            }
+        }
+    }
+}
+
+impl<R: Runtime> HealthExt<R> for App<R> {}
+impl<R: Runtime> HealthExt<R> for AppHandle<R> {}
@@ -8,15 +8,36 @@ use tauri::Runtime;
 use tauri::plugin::TauriPlugin;
 
 mod commands;
+pub mod custom_css;
+pub mod diagnostics;
 pub mod events;
+pub mod health;
+pub mod migrate;
+pub mod plugins;
+pub mod remote;
+pub mod safe_mode;
+pub mod search;
 pub mod shortcuts;
 pub mod states;
+pub mod timeline;
 pub mod tray;
+pub mod watchdog;
 pub mod window;
 
 deskulpt_common::bindings::build_bindings!();
 
 /// Initialize the plugin.
+///
+/// Startup crashes are tracked and legacy persisted data is migrated on setup.
+/// This plugin must be registered before the settings, widgets, and logs
+/// plugins so that they load the migrated data, and so that crashes in their
+/// setup are tracked.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    deskulpt_common::init::init_builder!().build()
+    deskulpt_common::init::init_builder!()
+        .setup(|app_handle, _| {
+            safe_mode::track_startup_crashes(app_handle)?;
+            migrate::migrate(app_handle)?;
+            Ok(())
+        })
+        .build()
 }
@@ -0,0 +1,269 @@
+//! Migration of legacy persisted data.
+//!
+//! Older versions of Deskulpt persisted data in formats that the current
+//! version no longer understands, which would otherwise be silently discarded
+//! as corrupted. This module detects such legacy data on startup and converts
+//! it to the current formats:
+//!
+//! - The monolithic settings file, which nested application settings under an
+//!   `app` key and kept widget settings under a `widgets` key, is split into
+//!   the current settings file and the widgets file. Legacy shortcut keys are
+//!   renamed as well.
+//! - Widget manifests under the legacy name `deskulpt.conf.json` are renamed to
+//!   `deskulpt.widget.json`.
+//! - Plain-text log files, which cannot be read as structured logs, are moved
+//!   into a `legacy` subdirectory of the logs directory.
+//!
+//! Migration is idempotent: legacy data is converted in place or moved away,
+//! so it is detected only once. Files that are overwritten are backed up, and
+//! whenever anything is migrated, a report is appended to the migration report
+//! file in the app local data directory.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_widgets::WidgetsManager;
+
+/// The name of the migration report file.
+const REPORT_FILE: &str = "migration-report.json";
+
+/// Legacy shortcut keys and their current counterparts.
+const LEGACY_SHORTCUT_KEYS: &[(&str, &str)] = &[
+    ("toggleCanvas", "toggleCanvasImode"),
+    ("openManager", "openPortal"),
+    ("showManager", "openPortal"),
+];
+
+/// A report of a migration run.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationReport {
+    /// The Unix timestamp in milliseconds when the migration ran.
+    timestamp: u64,
+    /// The version of Deskulpt that ran the migration.
+    version: String,
+    /// The migration steps that succeeded.
+    steps: Vec<String>,
+    /// The migration steps that failed.
+    errors: Vec<String>,
+}
+
+/// Read a JSON file.
+fn read_json(path: &Path) -> Result<Value> {
+    let file = File::open(path)?;
+    let value = serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(value)
+}
+
+/// Write a JSON file in a human-readable format.
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Rename legacy shortcut keys in a shortcuts object.
+///
+/// Current keys take precedence over legacy ones mapping to them. This returns
+/// whether any key is renamed.
+fn rename_shortcut_keys(shortcuts: &mut Map<String, Value>) -> bool {
+    let mut renamed = false;
+    for (legacy, current) in LEGACY_SHORTCUT_KEYS {
+        if let Some(value) = shortcuts.remove(*legacy) {
+            shortcuts.entry(*current).or_insert(value);
+            renamed = true;
+        }
+    }
+    renamed
+}
+
+/// Migrate the legacy settings file.
+fn migrate_settings(data_dir: &Path, steps: &mut Vec<String>) -> Result<()> {
+    let settings_path = data_dir.join("settings.json");
+    if !settings_path.exists() {
+        return Ok(());
+    }
+    let Value::Object(mut settings) = read_json(&settings_path)? else {
+        return Ok(());
+    };
+
+    let mut changed = false;
+
+    // The monolithic format nests application settings under `app`
+    if let Some(Value::Object(app)) = settings.remove("app") {
+        for (key, value) in app {
+            settings.entry(key).or_insert(value);
+        }
+        steps.push("Flattened legacy application settings".to_string());
+        changed = true;
+    }
+
+    // The monolithic format keeps widget settings under `widgets`, which are
+    // now persisted separately; existing widgets in the widgets file win
+    if let Some(Value::Object(legacy_widgets)) = settings.remove("widgets") {
+        let widgets_path = data_dir.join("widgets.json");
+        let mut widgets = Map::new();
+        if widgets_path.exists()
+            && let Value::Object(existing) = read_json(&widgets_path)?
+        {
+            widgets = existing;
+        }
+        let count = legacy_widgets.len();
+        for (id, widget_settings) in legacy_widgets {
+            widgets
+                .entry(id)
+                .or_insert_with(|| serde_json::json!({ "settings": widget_settings }));
+        }
+        write_json(&widgets_path, &widgets)?;
+        steps.push(format!(
+            "Moved settings of {count} widget(s) from the legacy settings file to {}",
+            widgets_path.display()
+        ));
+        changed = true;
+    }
+
+    if let Some(Value::Object(shortcuts)) = settings.get_mut("shortcuts")
+        && rename_shortcut_keys(shortcuts)
+    {
+        steps.push("Renamed legacy shortcut keys".to_string());
+        changed = true;
+    }
+
+    if changed {
+        let backup_path = data_dir.join("settings.legacy.json");
+        std::fs::copy(&settings_path, &backup_path)?;
+        write_json(&settings_path, &settings)?;
+        steps.push(format!(
+            "Converted the settings file, with the original backed up to {}",
+            backup_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Migrate legacy widget manifests.
+fn migrate_widget_manifests(widgets_dir: &Path, steps: &mut Vec<String>) -> Result<()> {
+    if !widgets_dir.exists() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(widgets_dir)? {
+        let dir = entry?.path();
+        let legacy_path = dir.join("deskulpt.conf.json");
+        let path = dir.join("deskulpt.widget.json");
+        if legacy_path.is_file() && !path.exists() && !dir.join("deskulpt.widget.toml").exists() {
+            std::fs::rename(&legacy_path, &path)?;
+            steps.push(format!(
+                "Renamed legacy widget manifest in {}",
+                dir.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Whether a log file name is in the current format.
+///
+/// Current log files are named `deskulpt.YYYY-MM-DD.NNNN.log`, where `NNNN` is
+/// the size-based rotation index within the date. Files named
+/// `deskulpt.YYYY-MM-DD.log` by the former daily-only rotation are also
+/// considered current since they can still be read.
+fn is_current_log_file(name: &str) -> bool {
+    let Some(stem) = name
+        .strip_prefix("deskulpt.")
+        .and_then(|s| s.strip_suffix(".log"))
+    else {
+        return false;
+    };
+    let (date, index) = match stem.split_once('.') {
+        Some((date, index)) => (date, Some(index)),
+        None => (stem, None),
+    };
+    let is_date = date.len() == 10
+        && date.char_indices().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+    let is_index =
+        index.is_none_or(|index| index.len() == 4 && index.chars().all(|c| c.is_ascii_digit()));
+    is_date && is_index
+}
+
+/// Migrate legacy log files.
+fn migrate_logs(logs_dir: &Path, steps: &mut Vec<String>) -> Result<()> {
+    if !logs_dir.exists() {
+        return Ok(());
+    }
+    let legacy_dir = logs_dir.join("legacy");
+    for entry in std::fs::read_dir(logs_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().map(|name| name.to_string_lossy()) else {
+            continue;
+        };
+        if path.is_file() && name.ends_with(".log") && !is_current_log_file(&name) {
+            std::fs::create_dir_all(&legacy_dir)?;
+            std::fs::rename(&path, legacy_dir.join(name.as_ref()))?;
+            steps.push(format!(
+                "Moved legacy log file {name} to {}",
+                legacy_dir.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Migrate legacy persisted data to the current formats.
+///
+/// This must run before the settings, widgets, and logs are loaded, i.e., in
+/// the setup of this plugin, which is registered before the others. Since
+/// logging is not yet available at that point, failures of individual steps
+/// are recorded in the migration report instead of failing the startup.
+pub fn migrate<R: Runtime>(app_handle: &AppHandle<R>) -> Result<()> {
+    let data_dir = app_handle.path().app_local_data_dir()?;
+    let widgets_dir = WidgetsManager::resolve_dir(app_handle)?;
+    let logs_dir = app_handle.path().app_log_dir()?;
+
+    let mut steps = vec![];
+    let mut errors = vec![];
+    let results = [
+        ("settings", migrate_settings(&data_dir, &mut steps)),
+        (
+            "widget manifests",
+            migrate_widget_manifests(&widgets_dir, &mut steps),
+        ),
+        ("logs", migrate_logs(&logs_dir, &mut steps)),
+    ];
+    for (name, result) in results {
+        if let Err(e) = result {
+            errors.push(format!("Failed to migrate {name}: {e:?}"));
+        }
+    }
+    if steps.is_empty() && errors.is_empty() {
+        return Ok(());
+    }
+
+    let report_path = data_dir.join(REPORT_FILE);
+    let mut reports = vec![];
+    if report_path.exists()
+        && let Ok(Value::Array(existing)) = read_json(&report_path)
+    {
+        reports = existing;
+    }
+    reports.push(serde_json::to_value(MigrationReport {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        version: app_handle.package_info().version.to_string(),
+        steps,
+        errors,
+    })?);
+    std::fs::create_dir_all(&data_dir)?;
+    write_json(&report_path, &reports)?;
+    Ok(())
+}
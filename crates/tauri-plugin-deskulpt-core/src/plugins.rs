@@ -0,0 +1,29 @@
+//! Deskulpt plugins available to widgets.
+
+use anyhow::Result;
+use deskulpt_plugin::Plugin;
+use tauri::{App, AppHandle, Runtime};
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+
+/// Extension trait for plugin operations.
+pub trait PluginsExt<R: Runtime>: WidgetsExt<R> {
+    /// Register the built-in plugins with the widgets manager.
+    ///
+    /// This lets the widgets manager check the plugins required by widgets
+    /// against the available ones. It must be called after the widgets plugin
+    /// is set up.
+    fn init_plugins(&self) -> Result<()> {
+        // TODO: Register plugins dynamically once plugins are no longer built
+        // into the Deskulpt core, see `call_plugin`
+        self.widgets().register_plugins([
+            ("fs".to_string(), deskulpt_plugin_fs::FsPlugin.version()),
+            (
+                "sys".to_string(),
+                deskulpt_plugin_sys::SysPlugin::default().version(),
+            ),
+        ])
+    }
+}
+
+impl<R: Runtime> PluginsExt<R> for App<R> {}
+impl<R: Runtime> PluginsExt<R> for AppHandle<R> {}
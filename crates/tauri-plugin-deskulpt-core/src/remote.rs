@@ -0,0 +1,217 @@
+//! Remote control of Deskulpt actions for hotkey devices.
+//!
+//! This exposes Deskulpt actions over a WebSocket server on the loopback
+//! interface, so that macro devices such as Stream Deck (via their plugins)
+//! can discover and trigger them. Each text message from a client is a JSON
+//! [`RemoteRequest`], answered by a JSON [`Outcome`].
+//!
+//! Since any local process, including web pages in a browser, can reach the
+//! loopback interface, clients must present the shared token kept in the
+//! [`TOKEN_SECRET`] secret on every connection, either as a bearer token in the
+//! `Authorization` header or as the percent-encoded `token` query parameter.
+//! Handshakes from browsers, i.e., those carrying an `Origin` header, are
+//! always rejected.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use deskulpt_common::outcome::Outcome;
+use futures_util::{SinkExt, StreamExt};
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+use tauri::{App, AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_settings::model::ShortcutAction;
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+
+use crate::shortcuts::run_action;
+
+/// The name of the secret holding the shared token of remote clients.
+///
+/// See [`SettingsManager::secret`](tauri_plugin_deskulpt_settings::SettingsManager::secret).
+pub const TOKEN_SECRET: &str = "remote-control-token";
+
+/// A request from a remote client.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+enum RemoteRequest {
+    /// List all available actions.
+    ListActions,
+    /// Trigger an action.
+    Trigger(ShortcutAction),
+    /// Refresh a specific widget by its ID.
+    RefreshWidget(String),
+    /// Refresh all widgets.
+    RefreshAllWidgets,
+    /// Switch to a settings profile by its name.
+    SwitchProfile(String),
+}
+
+/// Handle a request from a remote client.
+fn handle_request<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    request: RemoteRequest,
+) -> Result<serde_json::Value> {
+    match request {
+        RemoteRequest::ListActions => Ok(serde_json::to_value(ShortcutAction::ALL)?),
+        RemoteRequest::Trigger(action) => {
+            run_action(app_handle, &action)?;
+            Ok(serde_json::Value::Null)
+        },
+        RemoteRequest::RefreshWidget(id) => {
+            app_handle.widgets().refresh(&id)?;
+            Ok(serde_json::Value::Null)
+        },
+        RemoteRequest::RefreshAllWidgets => {
+            app_handle.widgets().refresh_all()?;
+            Ok(serde_json::Value::Null)
+        },
+        RemoteRequest::SwitchProfile(name) => {
+            app_handle.settings().switch_profile(&name)?;
+            Ok(serde_json::Value::Null)
+        },
+    }
+}
+
+/// Compare two strings in time independent of where they differ.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// Authorize the WebSocket handshake of a remote client.
+///
+/// The handshake is rejected if it comes from a browser or does not carry the
+/// shared token, see the [module-level documentation](self).
+fn authorize(request: &Request, token: &str) -> Result<(), ErrorResponse> {
+    let reject = |status: StatusCode, reason: &str| {
+        let mut response = ErrorResponse::new(Some(reason.to_string()));
+        *response.status_mut() = status;
+        response
+    };
+
+    if request.headers().contains_key("origin") {
+        return Err(reject(
+            StatusCode::FORBIDDEN,
+            "Browser clients are not allowed",
+        ));
+    }
+
+    let bearer = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = || {
+        let value = request
+            .uri()
+            .query()?
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))?;
+        percent_decode_str(value).decode_utf8().ok()
+    };
+    match bearer.map(Cow::Borrowed).or_else(query) {
+        Some(presented) if constant_time_eq(&presented, token) => Ok(()),
+        _ => Err(reject(StatusCode::UNAUTHORIZED, "Invalid or missing token")),
+    }
+}
+
+/// Serve a single remote client connection until it is closed.
+async fn serve_connection<R: Runtime>(
+    app_handle: AppHandle<R>,
+    stream: TcpStream,
+    token: Arc<str>,
+) -> Result<()> {
+    let callback =
+        |request: &Request, response: Response| authorize(request, &token).map(|()| response);
+    let mut ws = tokio_tungstenite::accept_hdr_async(stream, callback)
+        .await
+        .context("WebSocket handshake failed")?;
+
+    while let Some(message) = ws.next().await {
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let outcome: Outcome<serde_json::Value> = serde_json::from_str(&text)
+            .context("Invalid remote request")
+            .and_then(|request| handle_request(&app_handle, request))
+            .into();
+        ws.send(Message::Text(serde_json::to_string(&outcome)?.into()))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Extension trait for remote control by hotkey devices.
+pub trait RemoteExt<R: Runtime>: Manager<R> + SettingsExt<R> {
+    /// Start the remote control server if enabled in the settings.
+    ///
+    /// The server is bound to the loopback interface only and runs on Tauri's
+    /// singleton async runtime. It is not started if the shared token in the
+    /// [`TOKEN_SECRET`] secret is not set. Failure to start the server is
+    /// logged but not fatal.
+    fn start_remote_control(&self) {
+        let Some(port) = self.settings().read().remote_control_port else {
+            return;
+        };
+        let token: Arc<str> = match self.settings().secret(TOKEN_SECRET) {
+            Ok(Some(token)) if !token.is_empty() => token.into(),
+            Ok(_) => {
+                tracing::error!(
+                    "Remote control server not started: secret {TOKEN_SECRET:?} is not set"
+                );
+                return;
+            },
+            Err(e) => {
+                tracing::error!(error = ?e, "Remote control server not started");
+                return;
+            },
+        };
+
+        let app_handle = self.app_handle().clone();
+        tauri::async_runtime::spawn(async move {
+            let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::error!(error = ?e, port, "Failed to start remote control server");
+                    return;
+                },
+            };
+            tracing::info!(port, "Remote control server started");
+
+            loop {
+                let (stream, addr) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::warn!(error = ?e, "Failed to accept remote connection");
+                        continue;
+                    },
+                };
+                tracing::debug!(%addr, "Accepted remote connection");
+
+                let app_handle = app_handle.clone();
+                let token = token.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = serve_connection(app_handle, stream, token).await {
+                        tracing::warn!(error = ?e, %addr, "Remote connection closed with error");
+                    }
+                });
+            }
+        });
+    }
+}
+
+impl<R: Runtime> RemoteExt<R> for App<R> {}
+impl<R: Runtime> RemoteExt<R> for AppHandle<R> {}
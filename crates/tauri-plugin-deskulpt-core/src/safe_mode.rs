@@ -0,0 +1,141 @@
+//! Safe mode after repeated startup crashes.
+//!
+//! A panic within [`STARTUP_GRACE_PERIOD`] after launch counts as a startup
+//! crash and is recorded in a marker file. Once [`SAFE_MODE_THRESHOLD`]
+//! consecutive startup crashes are recorded, the next launch boots into safe
+//! mode, where widgets are not rendered and plugins are not loaded, so that a
+//! broken widget or plugin cannot make the app unusable. The marker file is
+//! removed once a launch survives the grace period.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use tauri::{App, AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+
+use crate::window::{PortalTab, WindowExt, mark_exiting};
+
+/// The name of the marker file recording consecutive startup crashes.
+const MARKER_FILE: &str = "startup-crashes";
+
+/// The number of consecutive startup crashes that triggers safe mode.
+const SAFE_MODE_THRESHOLD: u32 = 2;
+
+/// The period after launch within which a panic counts as a startup crash.
+const STARTUP_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Managed state for safe mode.
+struct SafeModeState {
+    /// The number of consecutive startup crashes before this launch.
+    crashes: u32,
+    /// The path of the marker file.
+    marker: PathBuf,
+}
+
+/// Read the number of consecutive startup crashes from the marker file.
+///
+/// A missing or malformed marker file counts as no crashes.
+fn read_crashes(marker: &Path) -> u32 {
+    std::fs::read_to_string(marker)
+        .ok()
+        .and_then(|content| content.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Track startup crashes.
+///
+/// This installs a panic hook that records startup crashes in the marker file,
+/// and removes the marker file once the startup grace period passes. It must
+/// be called as early as possible during setup so that crashes in the setup of
+/// other plugins are also recorded.
+pub(crate) fn track_startup_crashes<R: Runtime>(app_handle: &AppHandle<R>) -> Result<()> {
+    let dir = app_handle.path().app_local_data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let marker = dir.join(MARKER_FILE);
+    let crashes = read_crashes(&marker);
+
+    let started = Arc::new(AtomicBool::new(false));
+
+    let previous_hook = std::panic::take_hook();
+    let (hook_started, hook_marker) = (started.clone(), marker.clone());
+    std::panic::set_hook(Box::new(move |panic_info| {
+        if !hook_started.load(Ordering::Acquire) {
+            let crashes = read_crashes(&hook_marker) + 1;
+            let _ = std::fs::write(&hook_marker, crashes.to_string());
+        }
+        previous_hook(panic_info);
+    }));
+
+    let grace_marker = marker.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(STARTUP_GRACE_PERIOD);
+        started.store(true, Ordering::Release);
+        if let Err(e) = std::fs::remove_file(&grace_marker)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::error!("Failed to remove startup crash marker: {e:?}");
+        }
+    });
+
+    app_handle.manage(SafeModeState { crashes, marker });
+    Ok(())
+}
+
+/// Extension trait for safe mode operations.
+pub trait SafeModeExt<R: Runtime>: WindowExt<R> + WidgetsExt<R> {
+    /// Whether the app is running in safe mode.
+    fn is_safe_mode(&self) -> bool {
+        self.state::<SafeModeState>().crashes >= SAFE_MODE_THRESHOLD
+    }
+
+    /// Enter safe mode if there have been too many consecutive startup
+    /// crashes.
+    ///
+    /// In safe mode, widget rendering is suspended for the rest of the session
+    /// and the portal is opened at the recovery tab. Plugins should not be
+    /// loaded when [`Self::is_safe_mode`] holds.
+    fn init_safe_mode(&self) -> Result<()>
+    where
+        Self: Sized,
+    {
+        if !self.is_safe_mode() {
+            return Ok(());
+        }
+
+        let crashes = self.state::<SafeModeState>().crashes;
+        tracing::warn!(
+            crashes,
+            "Starting in safe mode after repeated startup crashes"
+        );
+        self.widgets().suspend_rendering();
+        self.open_portal_at(Some(PortalTab::Recovery))?;
+        Ok(())
+    }
+
+    /// Exit safe mode by restarting the app normally.
+    ///
+    /// The startup crash count is reset so that the next launch does not boot
+    /// into safe mode again, and settings and widgets are persisted. This does
+    /// not return unless persisting fails.
+    ///
+    /// Tauri command: [`crate::commands::exit_safe_mode`].
+    fn exit_safe_mode(&self) -> Result<()> {
+        let state = self.state::<SafeModeState>();
+        if let Err(e) = std::fs::remove_file(&state.marker)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            return Err(e.into());
+        }
+        self.settings().persist()?;
+        self.widgets().persist()?;
+        mark_exiting();
+        self.app_handle().restart();
+    }
+}
+
+impl<R: Runtime> SafeModeExt<R> for App<R> {}
+impl<R: Runtime> SafeModeExt<R> for AppHandle<R> {}
@@ -0,0 +1,179 @@
+//! Pluggable search providers for launcher-style widgets.
+//!
+//! A query is fanned out to all registered [`SearchProvider`]s concurrently,
+//! each bounded by a timeout, and their results are merged by relevance. This
+//! lets widgets implement Spotlight-style launchers without each implementing
+//! OS-specific indexing. Built-in providers search files, installed apps, and
+//! the widgets registry; more can be registered with
+//! [`SearchExt::register_search_provider`].
+
+mod apps;
+mod files;
+mod registry;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+pub use deskulpt_common::fuzzy::score;
+use futures_util::future::{BoxFuture, join_all};
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use tauri::{App, AppHandle, Manager, Runtime};
+
+use crate::search::apps::AppsProvider;
+use crate::search::files::FilesProvider;
+use crate::search::registry::RegistryProvider;
+
+/// The timeout for each provider to answer a query.
+const PROVIDER_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// The maximum number of results returned for a query.
+const MAX_RESULTS: usize = 50;
+
+/// A search result.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    /// The name of the provider that produced the result.
+    pub provider: String,
+    /// The title of the result.
+    pub title: String,
+    /// Additional information about the result, e.g., its location.
+    pub subtitle: Option<String>,
+    /// What the result refers to.
+    ///
+    /// The meaning depends on the provider, e.g., a file path for files and a
+    /// `handle/id` widget reference for the widgets registry.
+    pub target: String,
+    /// The relevance score between 0 and 1, higher being more relevant.
+    pub score: f64,
+}
+
+/// A provider of search results.
+pub trait SearchProvider: Send + Sync {
+    /// The name of the provider.
+    fn name(&self) -> &str;
+
+    /// Query the provider for results matching the text.
+    ///
+    /// Results need not be sorted or truncated. Providers should use [`score`]
+    /// for consistent ranking across providers.
+    fn query<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<SearchResult>>>;
+}
+
+/// A list that is cached for a period of time.
+///
+/// This is useful for providers that enumerate candidates expensively, e.g.,
+/// by walking directories, and do not need to be perfectly up-to-date.
+struct CachedList<T> {
+    /// How long the cached list stays fresh.
+    ttl: Duration,
+    /// The cached list and when it was produced.
+    inner: Mutex<Option<(Instant, Arc<Vec<T>>)>>,
+}
+
+impl<T: Send + Sync + 'static> CachedList<T> {
+    /// Create a new [`CachedList`] with the given time-to-live.
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            inner: Default::default(),
+        }
+    }
+
+    /// Get the cached list, producing it on a blocking thread if stale.
+    async fn get(&self, produce: impl FnOnce() -> Vec<T> + Send + 'static) -> Result<Arc<Vec<T>>> {
+        if let Some((at, list)) = &*self.inner.lock()
+            && at.elapsed() < self.ttl
+        {
+            return Ok(list.clone());
+        }
+        let list = Arc::new(tokio::task::spawn_blocking(produce).await?);
+        *self.inner.lock() = Some((Instant::now(), list.clone()));
+        Ok(list)
+    }
+}
+
+/// Managed state for search.
+#[derive(Default)]
+struct SearchState(RwLock<Vec<Arc<dyn SearchProvider>>>);
+
+/// Extension trait for search operations.
+pub trait SearchExt<R: Runtime>: Manager<R> {
+    /// Initialize search with the built-in providers.
+    fn init_search(&self) {
+        let path = self.path();
+        let roots = [path.desktop_dir(), path.document_dir(), path.download_dir()]
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect();
+
+        let state = SearchState::default();
+        state.0.write().extend([
+            Arc::new(FilesProvider::new(roots)) as Arc<dyn SearchProvider>,
+            Arc::new(AppsProvider::new()),
+            Arc::new(RegistryProvider(self.app_handle().clone())),
+        ]);
+        self.manage(state);
+    }
+
+    /// Register a custom search provider.
+    ///
+    /// The provider will be queried along with the built-in ones.
+    fn register_search_provider(&self, provider: impl SearchProvider + 'static) {
+        self.state::<SearchState>()
+            .0
+            .write()
+            .push(Arc::new(provider));
+    }
+
+    /// Query search providers for results matching the text.
+    ///
+    /// Only providers whose names pass the filter are queried. Providers are
+    /// queried concurrently. Those that fail or do not answer within a timeout
+    /// are skipped. The results are merged and sorted by relevance, keeping
+    /// only the most relevant ones.
+    fn search(
+        &self,
+        text: &str,
+        filter: impl Fn(&str) -> bool,
+    ) -> impl Future<Output = Vec<SearchResult>> + Send {
+        let providers = self
+            .state::<SearchState>()
+            .0
+            .read()
+            .iter()
+            .filter(|provider| filter(provider.name()))
+            .cloned()
+            .collect::<Vec<_>>();
+        let text = text.to_string();
+        async move {
+            let queries = providers.iter().map(|provider| async {
+                match tokio::time::timeout(PROVIDER_TIMEOUT, provider.query(&text)).await {
+                    Ok(Ok(results)) => results,
+                    Ok(Err(e)) => {
+                        tracing::warn!(provider = provider.name(), error = ?e, "Search failed");
+                        vec![]
+                    },
+                    Err(_) => {
+                        tracing::debug!(provider = provider.name(), "Search timed out");
+                        vec![]
+                    },
+                }
+            });
+
+            let mut results = join_all(queries)
+                .await
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            results.truncate(MAX_RESULTS);
+            results
+        }
+    }
+}
+
+impl<R: Runtime> SearchExt<R> for App<R> {}
+impl<R: Runtime> SearchExt<R> for AppHandle<R> {}
@@ -0,0 +1,169 @@
+//! Search provider for installed applications.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+
+use super::{CachedList, SearchProvider, SearchResult, score};
+
+/// How long the application list stays fresh.
+const CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// An installed application.
+struct InstalledApp {
+    /// The display name of the application.
+    name: String,
+    /// The path to open the application with.
+    path: PathBuf,
+}
+
+/// Search provider for installed applications.
+///
+/// Applications are discovered from desktop entries on Linux, application
+/// bundles on macOS, and Start Menu shortcuts on Windows. Result targets are
+/// paths that can be opened with the system's default handler.
+pub struct AppsProvider {
+    /// The cached list of installed applications.
+    apps: CachedList<InstalledApp>,
+}
+
+impl AppsProvider {
+    /// Create a new [`AppsProvider`].
+    pub fn new() -> Self {
+        Self {
+            apps: CachedList::new(CACHE_TTL),
+        }
+    }
+}
+
+/// Collect files with the given extension in a directory recursively.
+fn collect_files(dir: &Path, extension: &str, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == extension) {
+            files.push(path);
+        } else if depth > 0 && entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_files(&path, extension, depth - 1, files);
+        }
+    }
+}
+
+/// Discover installed applications from desktop entries.
+#[cfg(target_os = "linux")]
+fn discover() -> Vec<InstalledApp> {
+    let mut dirs = vec![
+        PathBuf::from("/usr/share/applications"),
+        PathBuf::from("/usr/local/share/applications"),
+        PathBuf::from("/var/lib/flatpak/exports/share/applications"),
+    ];
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".local/share/applications"));
+    }
+
+    let mut files = vec![];
+    for dir in &dirs {
+        collect_files(dir, "desktop", 2, &mut files);
+    }
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(&path).ok()?;
+            let mut name = None;
+            let mut in_main_group = false;
+            for line in content.lines() {
+                let line = line.trim();
+                if line.starts_with('[') {
+                    in_main_group = line == "[Desktop Entry]";
+                } else if !in_main_group {
+                    continue;
+                } else if line == "NoDisplay=true" || line == "Hidden=true" {
+                    return None;
+                } else if let Some(value) = line.strip_prefix("Name=") {
+                    name.get_or_insert_with(|| value.to_string());
+                }
+            }
+            Some(InstalledApp { name: name?, path })
+        })
+        .collect()
+}
+
+/// Discover installed applications from application bundles.
+#[cfg(target_os = "macos")]
+fn discover() -> Vec<InstalledApp> {
+    let mut dirs = vec![
+        PathBuf::from("/Applications"),
+        PathBuf::from("/System/Applications"),
+    ];
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join("Applications"));
+    }
+
+    let mut files = vec![];
+    for dir in &dirs {
+        collect_files(dir, "app", 1, &mut files);
+    }
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some(InstalledApp { name, path })
+        })
+        .collect()
+}
+
+/// Discover installed applications from Start Menu shortcuts.
+#[cfg(target_os = "windows")]
+fn discover() -> Vec<InstalledApp> {
+    let dirs = ["ProgramData", "APPDATA"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .map(|dir| PathBuf::from(dir).join(r"Microsoft\Windows\Start Menu\Programs"));
+
+    let mut files = vec![];
+    for dir in dirs {
+        collect_files(&dir, "lnk", 3, &mut files);
+    }
+
+    files
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some(InstalledApp { name, path })
+        })
+        .collect()
+}
+
+impl SearchProvider for AppsProvider {
+    fn name(&self) -> &str {
+        "apps"
+    }
+
+    fn query<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<SearchResult>>> {
+        async move {
+            let apps = self.apps.get(discover).await?;
+            let results = apps
+                .iter()
+                .filter_map(|app| {
+                    let score = score(text, &app.name)?;
+                    Some(SearchResult {
+                        provider: self.name().to_string(),
+                        title: app.name.clone(),
+                        subtitle: None,
+                        target: app.path.display().to_string(),
+                        score,
+                    })
+                })
+                .collect();
+            Ok(results)
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,100 @@
+//! Search provider for files in common user directories.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+
+use super::{CachedList, SearchProvider, SearchResult, score};
+
+/// The maximum directory depth to walk below each root.
+const MAX_DEPTH: usize = 4;
+
+/// The maximum number of entries to index across all roots.
+const MAX_ENTRIES: usize = 20_000;
+
+/// How long the file index stays fresh.
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Search provider for files and directories.
+///
+/// This indexes the desktop, documents, and downloads directories of the
+/// user, skipping hidden entries and bounded in depth and size.
+pub struct FilesProvider {
+    /// The root directories to index.
+    roots: Vec<PathBuf>,
+    /// The cached index of paths.
+    index: CachedList<PathBuf>,
+}
+
+impl FilesProvider {
+    /// Create a new [`FilesProvider`] indexing the given root directories.
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            index: CachedList::new(CACHE_TTL),
+        }
+    }
+}
+
+/// Walk a directory recursively, collecting non-hidden entries.
+fn walk(dir: &Path, depth: usize, entries: &mut Vec<PathBuf>) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.flatten() {
+        if entries.len() >= MAX_ENTRIES {
+            return;
+        }
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+        entries.push(path.clone());
+        if is_dir && depth < MAX_DEPTH {
+            walk(&path, depth + 1, entries);
+        }
+    }
+}
+
+impl SearchProvider for FilesProvider {
+    fn name(&self) -> &str {
+        "files"
+    }
+
+    fn query<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<SearchResult>>> {
+        async move {
+            let roots = self.roots.clone();
+            let index = self
+                .index
+                .get(move || {
+                    let mut entries = vec![];
+                    for root in &roots {
+                        walk(root, 0, &mut entries);
+                    }
+                    entries
+                })
+                .await?;
+
+            let results = index
+                .iter()
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_string_lossy();
+                    let score = score(text, &name)?;
+                    Some(SearchResult {
+                        provider: self.name().to_string(),
+                        title: name.to_string(),
+                        subtitle: path.parent().map(|p| p.display().to_string()),
+                        target: path.display().to_string(),
+                        score,
+                    })
+                })
+                .collect();
+            Ok(results)
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,50 @@
+//! Search provider for widgets in the widgets registry.
+
+use anyhow::Result;
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+
+use super::{SearchProvider, SearchResult, score};
+
+/// Search provider for widgets in the widgets registry.
+///
+/// This searches the locally cached registry index by widget name and ID, so
+/// it never hits the network. The cache is populated when the registry is
+/// browsed in the portal. Result targets are `handle/id` widget references.
+pub struct RegistryProvider<R: Runtime>(pub AppHandle<R>);
+
+impl<R: Runtime> SearchProvider for RegistryProvider<R> {
+    fn name(&self) -> &str {
+        "registry"
+    }
+
+    fn query<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<SearchResult>>> {
+        async move {
+            // The index is not cached until the registry is first browsed
+            let Ok(index) = self.0.widgets().cached_registry_index().await else {
+                return Ok(vec![]);
+            };
+            let results = index
+                .widgets()
+                .iter()
+                .filter_map(|entry| {
+                    let score = score(text, &entry.name)
+                        .into_iter()
+                        .chain(score(text, &entry.id))
+                        .reduce(f64::max)?;
+                    Some(SearchResult {
+                        provider: self.name().to_string(),
+                        title: entry.name.clone(),
+                        subtitle: Some(entry.description.clone()),
+                        target: format!("{}/{}", entry.handle, entry.id),
+                        score,
+                    })
+                })
+                .collect();
+            Ok(results)
+        }
+        .boxed()
+    }
+}
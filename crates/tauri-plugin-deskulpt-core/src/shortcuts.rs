@@ -1,13 +1,90 @@
 //! Keyboard shortcut management.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use serde::Serialize;
 use tauri::{App, AppHandle, Manager, Runtime};
 use tauri_plugin_deskulpt_settings::SettingsExt;
 use tauri_plugin_deskulpt_settings::model::ShortcutAction;
-use tauri_plugin_global_shortcut::{GlobalShortcut, GlobalShortcutExt, ShortcutState};
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+use tauri_plugin_global_shortcut::{GlobalShortcut, GlobalShortcutExt, Shortcut, ShortcutState};
 
 use crate::states::CanvasImodeStateExt;
-use crate::window::WindowExt;
+use crate::window::{PortalTab, WindowExt};
+
+/// Run the handler of an action.
+///
+/// This is the single place that maps [`ShortcutAction`]s to their behaviors,
+/// shared by keyboard shortcuts and other means of triggering actions. Actions
+/// meant to last while a shortcut is held, i.e.,
+/// [`ShortcutAction::PeekCanvas`], are toggled instead when triggered this way,
+/// since there is no release.
+pub fn run_action<R: Runtime>(app_handle: &AppHandle<R>, action: &ShortcutAction) -> Result<()> {
+    match action {
+        ShortcutAction::ToggleCanvasImode => app_handle
+            .toggle_canvas_imode()
+            .context("Failed to toggle canvas interaction mode"),
+        ShortcutAction::OpenPortal => app_handle
+            .open_portal()
+            .context("Failed to open Deskulpt portal"),
+        ShortcutAction::UndoLayoutChange => app_handle
+            .widgets()
+            .undo_layout_change()
+            .map(|_| ())
+            .context("Failed to undo layout change"),
+        ShortcutAction::RedoLayoutChange => app_handle
+            .widgets()
+            .redo_layout_change()
+            .map(|_| ())
+            .context("Failed to redo layout change"),
+        ShortcutAction::RefreshAllWidgets => app_handle
+            .widgets()
+            .refresh_all()
+            .context("Failed to refresh all widgets"),
+        ShortcutAction::ToggleAllWidgets => app_handle
+            .toggle_canvas_visibility()
+            .context("Failed to toggle all widgets"),
+        ShortcutAction::CycleProfile => {
+            cycle_profile(app_handle).context("Failed to cycle profile")
+        },
+        ShortcutAction::OpenLogsViewer => app_handle
+            .open_portal_at(Some(PortalTab::Logs))
+            .context("Failed to open logs viewer"),
+        ShortcutAction::PeekCanvas => app_handle
+            .set_canvas_peeking(!app_handle.is_canvas_peeking())
+            .context("Failed to toggle canvas peeking"),
+    }
+}
+
+/// Handle a key-down or key-up event of the shortcut of an action.
+///
+/// Most actions run on key-down only. [`ShortcutAction::PeekCanvas`] starts
+/// peeking on key-down and stops on key-up, so that the canvas is in float mode
+/// only while the shortcut is held.
+fn on_shortcut_event<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    action: &ShortcutAction,
+    state: ShortcutState,
+) -> Result<()> {
+    match action {
+        ShortcutAction::PeekCanvas => app_handle
+            .set_canvas_peeking(state == ShortcutState::Pressed)
+            .context("Failed to peek at canvas"),
+        _ if state == ShortcutState::Pressed => run_action(app_handle, action),
+        _ => Ok(()),
+    }
+}
+
+/// Switch to the next settings profile, wrapping around.
+fn cycle_profile<R: Runtime>(app_handle: &AppHandle<R>) -> Result<()> {
+    let settings = app_handle.settings();
+    let profiles = settings.list_profiles()?;
+    let active = settings.active_profile();
+    let next = profiles
+        .iter()
+        .position(|profile| *profile == active)
+        .map_or(0, |index| (index + 1) % profiles.len());
+    settings.switch_profile(&profiles[next])
+}
 
 /// Re-register a shortcut.
 ///
@@ -23,23 +100,11 @@ fn reregister_shortcut<R: Runtime>(
         gs.unregister(shortcut.as_str())?;
     }
 
-    let handler: fn(&AppHandle<R>) = match action {
-        ShortcutAction::ToggleCanvasImode => |app_handle| {
-            if let Err(e) = app_handle.toggle_canvas_imode() {
-                tracing::error!("Failed to toggle canvas interaction mode: {e}");
-            }
-        },
-        ShortcutAction::OpenPortal => |app_handle| {
-            if let Err(e) = app_handle.open_portal() {
-                tracing::error!("Failed to open Deskulpt portal: {e}");
-            }
-        },
-    };
-
     if let Some(shortcut) = new {
+        let action = action.clone();
         gs.on_shortcut(shortcut.as_str(), move |app_handle, _, event| {
-            if event.state == ShortcutState::Pressed {
-                handler(app_handle);
+            if let Err(e) = on_shortcut_event(app_handle, &action, event.state) {
+                tracing::error!("{e:?}");
             }
         })?;
     }
@@ -47,6 +112,21 @@ fn reregister_shortcut<R: Runtime>(
     Ok(())
 }
 
+/// The result of validating a keyboard shortcut.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum ShortcutValidation {
+    /// The shortcut can be bound.
+    Valid,
+    /// The shortcut cannot be parsed, with the error message.
+    Invalid(String),
+    /// The shortcut is already bound to another action.
+    Conflict(ShortcutAction),
+    /// The shortcut cannot be registered, e.g., because it is taken by
+    /// another application, with the error message.
+    Unavailable(String),
+}
+
 /// Extension trait for keyboard shortcut operations.
 pub trait ShortcutsExt<R: Runtime>: Manager<R> + SettingsExt<R> + GlobalShortcutExt<R> {
     /// Initialize keyboard shortcuts management.
@@ -77,6 +157,47 @@ pub trait ShortcutsExt<R: Runtime>: Manager<R> + SettingsExt<R> + GlobalShortcut
             }
         });
     }
+
+    /// Validate a keyboard shortcut before binding it to an action.
+    ///
+    /// The shortcut is parsed, checked against shortcuts of other actions in
+    /// the settings, and tentatively registered to check that it is available
+    /// system-wide. If `action` is given, its own current binding is not
+    /// considered a conflict.
+    fn validate_shortcut(
+        &self,
+        accelerator: &str,
+        action: Option<&ShortcutAction>,
+    ) -> ShortcutValidation {
+        let shortcut = match accelerator.parse::<Shortcut>() {
+            Ok(shortcut) => shortcut,
+            Err(e) => return ShortcutValidation::Invalid(e.to_string()),
+        };
+
+        let settings = self.settings().read();
+        for (other, bound) in &settings.shortcuts {
+            if Some(other) != action && bound.parse::<Shortcut>().ok() == Some(shortcut) {
+                return ShortcutValidation::Conflict(other.clone());
+            }
+        }
+        drop(settings);
+
+        let gs = self.global_shortcut();
+        if gs.is_registered(shortcut) {
+            // Registered by us, but not for any other action per the check
+            // above, so it must be the current binding of the given action
+            return ShortcutValidation::Valid;
+        }
+        if let Err(e) = gs.register(shortcut) {
+            return ShortcutValidation::Unavailable(e.to_string());
+        }
+        if let Err(e) = gs.unregister(shortcut) {
+            tracing::error!(
+                "Failed to unregister shortcut {accelerator:?} after validation: {e:?}"
+            );
+        }
+        ShortcutValidation::Valid
+    }
 }
 
 impl<R: Runtime> ShortcutsExt<R> for App<R> {}
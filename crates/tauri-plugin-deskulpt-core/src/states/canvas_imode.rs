@@ -1,19 +1,50 @@
 //! State management for canvas interaction mode.
 
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use deskulpt_common::event::Event;
+use deskulpt_common::metrics;
 use deskulpt_common::window::DeskulptWindow;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use seqlock::SeqLock;
-use tauri::{App, AppHandle, Manager, PhysicalPosition, Runtime, WebviewWindow};
+use tauri::{App, AppHandle, Manager, PhysicalPosition, Runtime, WebviewWindow, WindowEvent};
 use tauri_plugin_deskulpt_settings::SettingsExt;
 use tauri_plugin_deskulpt_settings::model::{CanvasImode, SettingsPatch};
-use tauri_plugin_deskulpt_widgets::WidgetsExt;
+use tauri_plugin_deskulpt_widgets::{HitTest, WidgetsExt};
 
-use crate::events::ShowToastEvent;
+#[cfg(windows)]
+use super::fullscreen;
+use super::layout_edit::{self, LayoutEditor};
+use super::mouse_hook::{self, MouseHook, MouseHookBackend};
+use crate::events::{ImodeChangedEvent, ShowToastEvent, WidgetHoverEvent};
+
+/// The interval at which the foreground application is polled for fullscreen.
+#[cfg(windows)]
+const FULLSCREEN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The minimum interval between hit tests in the mousemove listener.
+///
+/// This caps hit testing at 125 evaluations per second, which is above the
+/// refresh rate of most displays while coalescing bursts of raw mouse events.
+const MIN_HIT_TEST_INTERVAL: Duration = Duration::from_millis(8);
+
+/// The minimum interval between hit tests when no widget can be hit.
+///
+/// The result does not depend on the cursor position without widgets, so hit
+/// testing backs off to this interval until a widget becomes hittable again.
+const IDLE_HIT_TEST_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The interval at which the listener checks whether any widget can be hit.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Movements within this distance from the last hit test are skipped.
+///
+/// The distance is in the coordinates of mousemove events, i.e., physical
+/// pixels on most platforms and logical pixels on macOS.
+const HIT_TEST_DEAD_ZONE: f64 = 2.0;
 
 /// Layout information of the canvas.
 #[derive(Copy, Clone)]
@@ -40,8 +71,50 @@ struct CanvasImodeState {
     layout: SeqLock<CanvasLayout>,
 }
 
-/// Whether the global mousemove listener is enabled.
-static LISTENING_MOUSEMOVE: AtomicBool = AtomicBool::new(false);
+/// The current canvas interaction mode, encoded via [`encode_imode`].
+///
+/// This is mirrored from the settings so that the global mousemove listener
+/// can read it without locking.
+static IMODE: AtomicU8 = AtomicU8::new(0);
+
+/// Encode a canvas interaction mode for storing in [`IMODE`].
+fn encode_imode(mode: &CanvasImode) -> u8 {
+    match mode {
+        CanvasImode::Auto => 0,
+        CanvasImode::Sink => 1,
+        CanvasImode::Float => 2,
+    }
+}
+
+/// Decode a canvas interaction mode stored in [`IMODE`].
+fn decode_imode(value: u8) -> CanvasImode {
+    match value {
+        1 => CanvasImode::Sink,
+        2 => CanvasImode::Float,
+        _ => CanvasImode::Auto,
+    }
+}
+
+/// Whether the canvas is currently ignoring cursor events.
+///
+/// This is shared between the mode handler and the mousemove listener, so that
+/// the listener does not skip updates based on a stale state after a mode
+/// switch. It must only be written with the lock of [`CanvasImodeState`].
+static IS_CURSOR_IGNORED: AtomicBool = AtomicBool::new(true);
+
+/// Whether canvas interaction is paused for a fullscreen application.
+///
+/// While paused, the canvas ignores cursor events regardless of the mode, and
+/// the mousemove listener skips hit testing, so that fullscreen games and
+/// videos neither waste CPU on it nor have their clicks stolen. It must only be
+/// written with the lock of [`CanvasImodeState`].
+static IS_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the canvas is peeking, i.e., temporarily in float mode.
+///
+/// While peeking, changes to the mode in the settings are not applied until
+/// peeking ends, when the mode in the settings is restored.
+static IS_PEEKING: AtomicBool = AtomicBool::new(false);
 
 /// Extension trait for operations on canvas interaction mode.
 pub trait CanvasImodeStateExt<R: Runtime>: Manager<R> + SettingsExt<R> {
@@ -62,6 +135,19 @@ pub trait CanvasImodeStateExt<R: Runtime>: Manager<R> + SettingsExt<R> {
             layout: SeqLock::new(canvas_layout),
         });
 
+        // Track the canvas layout from window events on every platform, so that
+        // the mousemove listener never hit tests against stale coordinates,
+        // e.g., after a resolution change; the canvas is undecorated, so the
+        // outer position reported on moves is also its inner position
+        let app_handle = self.app_handle().clone();
+        canvas.on_window_event(move |event| match event {
+            WindowEvent::Moved(position) => app_handle.set_canvas_position(position),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                app_handle.set_canvas_scale_factor(*scale_factor);
+            },
+            _ => {},
+        });
+
         let canvas_cloned = canvas.clone();
         std::thread::spawn(move || {
             // Delay the start of mousemove listener to avoid interfering with
@@ -72,15 +158,23 @@ pub trait CanvasImodeStateExt<R: Runtime>: Manager<R> + SettingsExt<R> {
             std::thread::sleep(Duration::from_secs(1));
 
             if let Err(e) = listen_to_mousemove(canvas_cloned) {
-                eprintln!("Failed to listen to global mousemove events: {}", e);
+                tracing::error!("Failed to listen to global mousemove events: {e:?}");
             }
         });
 
-        if self.settings().read().canvas_imode == CanvasImode::Auto {
-            LISTENING_MOUSEMOVE.store(true, Ordering::Release);
-        }
+        let mode = encode_imode(&self.settings().read().canvas_imode);
+        IMODE.store(mode, Ordering::Release);
+
+        // Fullscreen applications are only detected on Windows, so there is
+        // nothing to watch elsewhere
+        #[cfg(windows)]
+        watch_fullscreen(canvas.clone());
 
         self.settings().on_canvas_imode_change(move |_, new| {
+            // The new mode is restored when peeking ends
+            if IS_PEEKING.load(Ordering::Acquire) {
+                return;
+            }
             if let Err(e) = on_new_canvas_imode(&canvas, new) {
                 tracing::error!("Failed to update canvas interaction mode: {}", e);
             }
@@ -91,9 +185,13 @@ pub trait CanvasImodeStateExt<R: Runtime>: Manager<R> + SettingsExt<R> {
 
     /// Set the position of the canvas.
     ///
-    /// This should be called whenever the canvas is moved.
+    /// This is called automatically whenever the canvas is moved, but can also
+    /// be called to sync the position on display changes that do not come with
+    /// window events. It is a no-op if the state is not managed yet.
     fn set_canvas_position(&self, position: &PhysicalPosition<i32>) {
-        let state = self.state::<CanvasImodeState>();
+        let Some(state) = self.try_state::<CanvasImodeState>() else {
+            return;
+        };
         let mut layout = state.layout.lock_write();
         layout.x = position.x as f64;
         layout.y = position.y as f64;
@@ -101,13 +199,79 @@ pub trait CanvasImodeStateExt<R: Runtime>: Manager<R> + SettingsExt<R> {
 
     /// Set the scale factor of the canvas.
     ///
-    /// This should be called whenever the canvas scale factor changes.
+    /// This is called automatically whenever the canvas scale factor changes,
+    /// but can also be called to sync the scale factor on display changes that
+    /// do not come with window events. It is a no-op if the state is not
+    /// managed yet.
     fn set_canvas_scale_factor(&self, scale_factor: f64) {
-        let state = self.state::<CanvasImodeState>();
+        let Some(state) = self.try_state::<CanvasImodeState>() else {
+            return;
+        };
         let mut layout = state.layout.lock_write();
         layout.inv_scale = 1.0 / scale_factor;
     }
 
+    /// Get the backend of the global mouse hook.
+    ///
+    /// This is `None` until the mouse hook starts running, which is delayed
+    /// shortly after the canvas interaction mode is managed.
+    fn mouse_hook_backend(&self) -> Option<MouseHookBackend> {
+        mouse_hook::running_backend()
+    }
+
+    /// Check whether layout editing of widgets is supported on the platform.
+    ///
+    /// This is only the case on Windows. Frontends should hide layout editing
+    /// where it is not supported, since it would always fail to start.
+    ///
+    /// Tauri command: [`crate::commands::is_layout_editing_supported`].
+    fn is_layout_editing_supported(&self) -> bool {
+        layout_edit::is_supported()
+    }
+
+    /// Start or stop layout editing of widgets.
+    ///
+    /// While layout editing is active, the canvas accepts cursor events over
+    /// all widgets regardless of the interaction mode, and dragging a widget
+    /// moves it, or resizes it if dragged near its edges. Dragging is tracked
+    /// by the backend, so that it works even if widgets misbehave. This is
+    /// only supported on Windows and always fails to start on other platforms.
+    ///
+    /// Tauri command: [`crate::commands::set_layout_editing`].
+    fn set_layout_editing(&self, enabled: bool) -> Result<()> {
+        let canvas = DeskulptWindow::Canvas.webview_window(self)?;
+        layout_edit::set_active(enabled)?;
+        emit_imode_changed(&canvas);
+        Ok(())
+    }
+
+    /// Check whether the canvas is peeking.
+    fn is_canvas_peeking(&self) -> bool {
+        IS_PEEKING.load(Ordering::Acquire)
+    }
+
+    /// Start or stop peeking at the canvas.
+    ///
+    /// Peeking temporarily switches the canvas to float mode without touching
+    /// the settings, so that widgets can be interacted with quickly. Stopping
+    /// restores the mode in the settings. This is a no-op if the canvas is
+    /// already in the requested peeking state.
+    fn set_canvas_peeking(&self, peeking: bool) -> Result<()> {
+        if IS_PEEKING.swap(peeking, Ordering::AcqRel) == peeking {
+            return Ok(());
+        }
+        let canvas = DeskulptWindow::Canvas.webview_window(self)?;
+        let mode = if peeking {
+            CanvasImode::Float
+        } else {
+            self.settings().read().canvas_imode.clone()
+        };
+        apply_canvas_imode(&canvas, &mode)?;
+        emit_imode_changed(&canvas);
+        tracing::debug!(peeking, "Canvas peeking toggled");
+        Ok(())
+    }
+
     /// Toggle the interaction mode of the canvas.
     ///
     /// If the current mode is float or sink, it switches to the other mode. If
@@ -130,53 +294,291 @@ impl<R: Runtime> CanvasImodeStateExt<R> for AppHandle<R> {}
 
 /// Handler for canvas interaction mode changes.
 ///
-/// This updates the canvas click-through state and the mousemove event
-/// listener's behavior according to the given mode. It also emits a toast
-/// notification to the canvas, but failure to do so is non-fatal and will not
-/// result in an error.
+/// This applies the given mode via [`apply_canvas_imode`]. It also emits a
+/// toast notification and an [`ImodeChangedEvent`] to the canvas, but failure
+/// to do so is non-fatal and will not result in an error.
 fn on_new_canvas_imode<R: Runtime>(canvas: &WebviewWindow<R>, mode: &CanvasImode) -> Result<()> {
+    apply_canvas_imode(canvas, mode)?;
+
+    if let Err(e) = ShowToastEvent::Success(format!("Canvas interaction mode: {mode:?}"))
+        .emit_to(canvas, DeskulptWindow::Canvas)
+    {
+        tracing::error!("Failed to emit ShowToastEvent to canvas: {}", e);
+    }
+    emit_imode_changed(canvas);
+
+    Ok(())
+}
+
+/// Apply a canvas interaction mode.
+///
+/// This updates the canvas click-through state and the mousemove event
+/// listener's behavior according to the given mode, without notifying the
+/// canvas.
+fn apply_canvas_imode<R: Runtime>(canvas: &WebviewWindow<R>, mode: &CanvasImode) -> Result<()> {
     match mode {
-        CanvasImode::Auto => {
-            LISTENING_MOUSEMOVE.store(true, Ordering::Release);
-        },
+        CanvasImode::Auto => IMODE.store(encode_imode(mode), Ordering::Release),
         CanvasImode::Sink | CanvasImode::Float => {
-            // Set the flag with write lock acquired to avoid racing with the
-            // mousemove hook on setting `ignore_cursor_events`
+            // Set the flags with write lock acquired to avoid racing with the
+            // mousemove hook on setting `ignore_cursor_events`; the hook keeps
+            // running for widgets whose interactivity overrides the mode
             let state = canvas.state::<CanvasImodeState>();
             let _guard = state.lock.write();
-            LISTENING_MOUSEMOVE.store(false, Ordering::Release);
-            canvas.set_ignore_cursor_events(*mode == CanvasImode::Sink)?;
+            let is_sink = *mode == CanvasImode::Sink;
+            IMODE.store(encode_imode(mode), Ordering::Release);
+            // The mode takes effect once the fullscreen application exits
+            if !IS_PAUSED.load(Ordering::Acquire) {
+                canvas.set_ignore_cursor_events(is_sink)?;
+                IS_CURSOR_IGNORED.store(is_sink, Ordering::Release);
+            }
         },
     }
+    Ok(())
+}
 
-    if let Err(e) = ShowToastEvent::Success(format!("Canvas interaction mode: {mode:?}"))
-        .emit_to(canvas, DeskulptWindow::Canvas)
-    {
-        tracing::error!("Failed to emit ShowToastEvent to canvas: {}", e);
+/// Emit an [`ImodeChangedEvent`] to the canvas with the current state.
+///
+/// Failure to do so is non-fatal and only logged.
+fn emit_imode_changed<R: Runtime>(canvas: &WebviewWindow<R>) {
+    let event = ImodeChangedEvent {
+        mode: decode_imode(IMODE.load(Ordering::Acquire)),
+        is_interactive: !IS_CURSOR_IGNORED.load(Ordering::Acquire),
+        is_editing_layout: layout_edit::is_active(),
+    };
+    if let Err(e) = event.emit_to(canvas, DeskulptWindow::Canvas) {
+        tracing::error!("Failed to emit ImodeChangedEvent to canvas: {e}");
     }
+}
 
+/// Update the widget hovered by the cursor.
+///
+/// If the hovered widget changes, [`WidgetHoverEvent`]s are emitted to the
+/// canvas for leaving the previous one and entering the new one. Failure to do
+/// so is non-fatal and only logged.
+fn update_hovered<R: Runtime>(
+    canvas: &WebviewWindow<R>,
+    hovered: &mut Option<String>,
+    widget: Option<String>,
+) {
+    if *hovered == widget {
+        return;
+    }
+    let previous = std::mem::replace(hovered, widget);
+    let events = previous
+        .map(WidgetHoverEvent::Leave)
+        .into_iter()
+        .chain(hovered.clone().map(WidgetHoverEvent::Enter));
+    for event in events {
+        if let Err(e) = event.emit_to(canvas, DeskulptWindow::Canvas) {
+            tracing::error!("Failed to emit WidgetHoverEvent to canvas: {e}");
+        }
+    }
+}
+
+/// Watch for fullscreen applications in the foreground.
+///
+/// The foreground application is polled in a background thread, and canvas
+/// interaction is paused while it is fullscreen, see [`IS_PAUSED`]. This is
+/// only supported on Windows.
+#[cfg(windows)]
+fn watch_fullscreen<R: Runtime>(canvas: WebviewWindow<R>) {
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(FULLSCREEN_POLL_INTERVAL);
+            let is_fullscreen = match fullscreen::is_fullscreen_app_active(&canvas) {
+                Ok(is_fullscreen) => is_fullscreen,
+                Err(e) => {
+                    tracing::warn!("Failed to detect fullscreen applications: {e:?}");
+                    continue;
+                },
+            };
+            if is_fullscreen == IS_PAUSED.load(Ordering::Acquire) {
+                continue;
+            }
+            if let Err(e) = set_paused(&canvas, is_fullscreen) {
+                tracing::error!("Failed to pause canvas interaction: {e:?}");
+            }
+        }
+    });
+}
+
+/// Pause or resume canvas interaction for a fullscreen application.
+///
+/// Pausing makes the canvas ignore cursor events. Resuming restores the state
+/// of the current mode, which the mousemove listener then keeps up to date as
+/// usual.
+#[cfg(windows)]
+fn set_paused<R: Runtime>(canvas: &WebviewWindow<R>, paused: bool) -> Result<()> {
+    let state = canvas.state::<CanvasImodeState>();
+    let guard = state.lock.write();
+    let should_ignore_cursor =
+        paused || decode_imode(IMODE.load(Ordering::Acquire)) != CanvasImode::Float;
+    canvas.set_ignore_cursor_events(should_ignore_cursor)?;
+    IS_CURSOR_IGNORED.store(should_ignore_cursor, Ordering::Release);
+    IS_PAUSED.store(paused, Ordering::Release);
+    drop(guard);
+    emit_imode_changed(canvas);
+    if paused {
+        tracing::info!("Fullscreen application detected; paused canvas interaction");
+    } else {
+        tracing::info!("Fullscreen application exited; resumed canvas interaction");
+    }
     Ok(())
 }
 
+/// Throttling state of the global mousemove listener.
+struct MousemoveThrottle {
+    /// The time of the last hit test, if any.
+    last_hit_test: Option<Instant>,
+    /// The cursor position of the last hit test.
+    last_position: (f64, f64),
+    /// The time of the last check for hittable widgets, if any.
+    last_idle_check: Option<Instant>,
+    /// Whether no widget could be hit as of the last check.
+    is_idle: bool,
+    /// The cursor position of the last event skipped for coming too soon, if
+    /// it has not been superseded by a hit test since.
+    pending: Option<(f64, f64)>,
+    /// The time at which the pending position can be hit tested.
+    pending_deadline: Option<Instant>,
+    /// Whether a trailing hit test of the pending position is scheduled.
+    is_trailing_scheduled: bool,
+}
+
+impl MousemoveThrottle {
+    /// Create a new throttle that lets the first event through.
+    fn new() -> Self {
+        Self {
+            last_hit_test: None,
+            last_position: (f64::NAN, f64::NAN),
+            last_idle_check: None,
+            is_idle: false,
+            pending: None,
+            pending_deadline: None,
+            is_trailing_scheduled: false,
+        }
+    }
+
+    /// Check whether a hit test should be skipped for the cursor position.
+    ///
+    /// Hit tests are skipped if they come too soon after the last one, see
+    /// [`MIN_HIT_TEST_INTERVAL`] and [`IDLE_HIT_TEST_INTERVAL`], or if the
+    /// cursor has barely moved since then, see [`HIT_TEST_DEAD_ZONE`]. In the
+    /// former case, the position is kept as pending to be hit tested once the
+    /// interval expires, see [`Self::schedule_trailing`].
+    fn should_skip<R: Runtime>(&mut self, canvas: &WebviewWindow<R>, x: f64, y: f64) -> bool {
+        let now = Instant::now();
+        if self
+            .last_idle_check
+            .is_none_or(|last| now.duration_since(last) >= IDLE_CHECK_INTERVAL)
+            && let Some(has_hittable) = canvas.widgets().try_has_hit_testable()
+        {
+            self.is_idle = !has_hittable;
+            self.last_idle_check = Some(now);
+        }
+
+        let interval = if self.is_idle {
+            IDLE_HIT_TEST_INTERVAL
+        } else {
+            MIN_HIT_TEST_INTERVAL
+        };
+        let (last_x, last_y) = self.last_position;
+        if (x - last_x).hypot(y - last_y) < HIT_TEST_DEAD_ZONE {
+            // The last hit test still holds for this position
+            self.pending = None;
+            return true;
+        }
+        if let Some(last) = self.last_hit_test
+            && now.duration_since(last) < interval
+        {
+            self.pending = Some((x, y));
+            self.pending_deadline = Some(last + interval);
+            return true;
+        }
+        false
+    }
+
+    /// Schedule a trailing hit test of the pending position, if any.
+    ///
+    /// This returns the time at which to hit test the pending position, unless
+    /// there is none or a trailing hit test is already scheduled.
+    fn schedule_trailing(&mut self) -> Option<Instant> {
+        if self.pending.is_none() || self.is_trailing_scheduled {
+            return None;
+        }
+        self.is_trailing_scheduled = true;
+        self.pending_deadline
+    }
+
+    /// Take the pending position for its scheduled trailing hit test.
+    fn take_trailing(&mut self) -> Option<(f64, f64)> {
+        self.is_trailing_scheduled = false;
+        self.pending.take()
+    }
+
+    /// Record a completed hit test at the cursor position.
+    fn record(&mut self, x: f64, y: f64) {
+        self.last_hit_test = Some(Instant::now());
+        self.last_position = (x, y);
+        self.pending = None;
+    }
+}
+
 /// Global mousemove event listener.
 ///
-/// If the cheap check on [`LISTENING_MOUSEMOVE`] gives false, the hook will
-/// short-circuit immediately, effectively disabling the listener. Otherwise,
-/// it will check whether the mouse is over any widget in the canvas. If so, the
-/// canvas will accept cursor events; otherwise, it will ignore them.
+/// Mousemove events come from a [`MouseHook`] with whichever backend is
+/// available on the platform. For each event, it will check whether the canvas
+/// should be interactive at the mouse position under the current mode in
+/// [`IMODE`], taking the interactivity of widgets into account. If so, the
+/// canvas will accept cursor events; otherwise, it will ignore them. Events are
+/// skipped while paused for a fullscreen application, see [`IS_PAUSED`].
+///
+/// The canvas is notified of the interaction state switching with an
+/// [`ImodeChangedEvent`], and of the cursor moving across widgets with
+/// [`WidgetHoverEvent`]s. While layout editing is active, widgets are dragged
+/// with a [`LayoutEditor`] as well.
+///
+/// Hit tests are throttled by [`MousemoveThrottle`], so the result may lag
+/// behind the cursor by a few pixels at widget edges in exchange for much less
+/// background CPU usage. The last skipped position is hit tested once the
+/// throttling interval expires by a background thread that sleeps otherwise,
+/// so that the result settles where the cursor stops.
 fn listen_to_mousemove<R: Runtime>(canvas: WebviewWindow<R>) -> Result<()> {
-    let mut is_cursor_ignored = true;
+    // Obtain metric handles upfront to avoid locking the registry per event
+    let events = metrics::counter("mouse.events");
+    let hit_test_us = metrics::histogram("mouse.hit_test_us");
+    let cursor_toggles = metrics::counter("mouse.cursor_toggles");
+    let throttled = metrics::counter("mouse.throttled");
+    let throttle = Arc::new(Mutex::new(MousemoveThrottle::new()));
+    let hovered = Mutex::new(None);
+    let editor = Mutex::new(LayoutEditor::default());
+    let (trailing_tx, trailing_rx) = std::sync::mpsc::channel::<Instant>();
+
+    let hook_canvas = canvas.clone();
+    let listener_throttle = throttle.clone();
+    let on_mousemove = Arc::new(move |x: f64, y: f64| {
+        if IS_PAUSED.load(Ordering::Acquire) {
+            return;
+        }
 
-    global_mousemove::listen(move |event| {
-        if !LISTENING_MOUSEMOVE.load(Ordering::Acquire) {
+        // The listener is called from the hook thread and, for trailing hit
+        // tests, the trailing thread, so the lock is rarely contended; either
+        // way the other caller is handling a newer position
+        let Some(mut throttle) = listener_throttle.try_lock() else {
+            return;
+        };
+        if throttle.should_skip(&canvas, x, y) {
+            throttled.increment();
+            if let Some(deadline) = throttle.schedule_trailing() {
+                let _ = trailing_tx.send(deadline);
+            }
             return;
         }
 
+        let mode = IMODE.load(Ordering::Acquire);
         let state = canvas.state::<CanvasImodeState>();
         let canvas_layout = state.layout.read();
 
-        let global_mousemove::MouseMoveEvent { x, y } = event;
-
         // For macOS, mousemove coordinates are in logical coordinates, so
         // only canvas physical position needs to be scaled
         #[cfg(target_os = "macos")]
@@ -191,29 +593,69 @@ fn listen_to_mousemove<R: Runtime>(canvas: WebviewWindow<R>) -> Result<()> {
         #[cfg(not(target_os = "macos"))]
         let scaled_y = (y - canvas_layout.y) * canvas_layout.inv_scale;
 
-        let Some(mouse_over_widget) = canvas.widgets().try_covers_point(scaled_x, scaled_y) else {
+        let hit_test_start = Instant::now();
+        let Some(HitTest {
+            widget,
+            mut is_interactive,
+        }) = canvas
+            .widgets()
+            .try_hit_test(scaled_x, scaled_y, &decode_imode(mode))
+        else {
             return; // Avoid blocking
         };
+        hit_test_us.observe(hit_test_start.elapsed().as_micros() as f64);
+        throttle.record(x, y);
+
+        // Dragging widgets needs the canvas to capture the cursor over them
+        // regardless of their interactivity, so that clicks and releases do
+        // not reach the desktop underneath
+        let mut editor = editor.lock();
+        editor.on_mousemove(&canvas, scaled_x, scaled_y, widget.as_deref());
+        if layout_edit::is_active() {
+            is_interactive |= widget.is_some() || editor.is_dragging();
+        }
+        drop(editor);
+        update_hovered(&canvas, &mut hovered.lock(), widget);
 
         // Avoid redundant calls by checking if the state has really changed
-        let should_ignore_cursor = !mouse_over_widget;
-        if should_ignore_cursor != is_cursor_ignored {
+        let should_ignore_cursor = !is_interactive;
+        if should_ignore_cursor != IS_CURSOR_IGNORED.load(Ordering::Acquire) {
             // Check the flag with read lock acquired to avoid racing with the
             // writers on setting `ignore_cursor_events`
             let state = canvas.state::<CanvasImodeState>();
-            let _guard = match state.lock.try_read() {
+            let guard = match state.lock.try_read() {
                 Some(guard) => guard,
                 None => return, // Avoid blocking
             };
 
-            if !LISTENING_MOUSEMOVE.load(Ordering::Acquire) {
+            // The mode or pause state may have changed since the checks above
+            if IMODE.load(Ordering::Acquire) != mode || IS_PAUSED.load(Ordering::Acquire) {
                 return;
             }
-            is_cursor_ignored = should_ignore_cursor;
             if let Err(e) = canvas.set_ignore_cursor_events(should_ignore_cursor) {
                 eprintln!("Failed to set cursor events state: {e}");
             }
+            IS_CURSOR_IGNORED.store(should_ignore_cursor, Ordering::Release);
+            cursor_toggles.increment();
+            drop(guard);
+            emit_imode_changed(&canvas);
         }
+    });
+
+    let trailing = on_mousemove.clone();
+    std::thread::spawn(move || {
+        while let Ok(deadline) = trailing_rx.recv() {
+            std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            let pending = throttle.lock().take_trailing();
+            if let Some((x, y)) = pending {
+                trailing(x, y);
+            }
+        }
+    });
+
+    MouseHook::new(hook_canvas).run(move |x, y| {
+        events.increment();
+        on_mousemove(x, y);
     })?;
 
     Ok(())
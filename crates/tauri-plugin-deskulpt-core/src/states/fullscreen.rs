@@ -0,0 +1,78 @@
+//! Detection of fullscreen applications in the foreground.
+//!
+//! This is only supported on Windows.
+
+use anyhow::Result;
+use tauri::{Runtime, WebviewWindow};
+
+/// Check if a fullscreen application is in the foreground.
+///
+/// This covers exclusive fullscreen Direct3D applications and presentations as
+/// reported by the shell, as well as borderless windows covering their whole
+/// monitor, e.g., games and videos in fullscreen. The desktop and the canvas
+/// itself are never considered fullscreen applications.
+pub(super) fn is_fullscreen_app_active<R: Runtime>(canvas: &WebviewWindow<R>) -> Result<bool> {
+    use windows_sys::Win32::Foundation::RECT;
+    use windows_sys::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MONITOR_DEFAULTTONULL, MONITORINFO, MonitorFromWindow,
+    };
+    use windows_sys::Win32::UI::Shell::{
+        QUNS_PRESENTATION_MODE, QUNS_RUNNING_D3D_FULL_SCREEN, SHQueryUserNotificationState,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetClassNameW, GetForegroundWindow, GetShellWindow, GetWindowRect,
+    };
+
+    let canvas_hwnd = canvas.hwnd()?.0;
+
+    // SAFETY: The pointers passed point to initialized local variables that
+    // outlive the calls, and the buffer length matches the buffer
+    unsafe {
+        let mut state = 0;
+        if SHQueryUserNotificationState(&mut state) == 0
+            && matches!(state, QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE)
+        {
+            return Ok(true);
+        }
+
+        let foreground = GetForegroundWindow();
+        if foreground.is_null() || foreground == canvas_hwnd || foreground == GetShellWindow() {
+            return Ok(false);
+        }
+
+        // The desktop windows span the whole screen when focused
+        let mut class = [0u16; 16];
+        let len = GetClassNameW(foreground, class.as_mut_ptr(), class.len() as i32);
+        let class = String::from_utf16_lossy(&class[..len.max(0) as usize]);
+        if class == "Progman" || class == "WorkerW" {
+            return Ok(false);
+        }
+
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        if GetWindowRect(foreground, &mut rect) == 0 {
+            return Ok(false);
+        }
+        let monitor = MonitorFromWindow(foreground, MONITOR_DEFAULTTONULL);
+        if monitor.is_null() {
+            return Ok(false);
+        }
+        let mut info = MONITORINFO {
+            cbSize: size_of::<MONITORINFO>() as u32,
+            ..std::mem::zeroed()
+        };
+        if GetMonitorInfoW(monitor, &mut info) == 0 {
+            return Ok(false);
+        }
+
+        let bounds = info.rcMonitor;
+        Ok(rect.left <= bounds.left
+            && rect.top <= bounds.top
+            && rect.right >= bounds.right
+            && rect.bottom >= bounds.bottom)
+    }
+}
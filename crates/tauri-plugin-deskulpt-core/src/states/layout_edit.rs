@@ -0,0 +1,214 @@
+//! Layout editing of widgets by the backend.
+//!
+//! While layout editing is active, widgets are moved and resized by dragging
+//! them on the canvas, tracked with the global mouse hook instead of the DOM
+//! events of the canvas. Dragging close to the edges of a widget resizes it,
+//! and dragging anywhere else moves it. This keeps layout editing working even
+//! if a widget swallows cursor events or is stuck, since the canvas frontend
+//! only needs to render the updated settings. Dragged layouts are snapped as
+//! configured in the settings like any other layout change.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Result, bail};
+use tauri::{Runtime, WebviewWindow};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_widgets::{WidgetSettingsPatch, WidgetsExt};
+
+use super::mouse_hook;
+
+/// The distance from widget edges within which dragging resizes the widget.
+const RESIZE_MARGIN: f64 = 8.0;
+
+/// The minimum width and height of widgets when resizing.
+const MIN_SIZE: i64 = 20;
+
+/// Whether layout editing is active.
+static IS_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Check whether layout editing is active.
+pub(super) fn is_active() -> bool {
+    IS_ACTIVE.load(Ordering::Acquire)
+}
+
+/// Check whether layout editing is supported on the platform.
+///
+/// Dragging is tracked with the global button state, which can only be queried
+/// on some platforms.
+pub(super) fn is_supported() -> bool {
+    mouse_hook::is_primary_button_down().is_some()
+}
+
+/// Start or stop layout editing.
+///
+/// An error is returned if layout editing is not supported on the platform,
+/// see [`is_supported`].
+pub(super) fn set_active(active: bool) -> Result<()> {
+    if active && !is_supported() {
+        bail!("Layout editing is only supported on Windows");
+    }
+    IS_ACTIVE.store(active, Ordering::Release);
+    tracing::info!(active, "Layout editing toggled");
+    Ok(())
+}
+
+/// The edges of a widget being dragged for resizing.
+///
+/// If no edge is dragged, the widget is being moved instead.
+#[derive(Debug, Clone, Copy, Default)]
+struct Edges {
+    left: bool,
+    top: bool,
+    right: bool,
+    bottom: bool,
+}
+
+impl Edges {
+    /// The edges within [`RESIZE_MARGIN`] of a point inside a rectangle.
+    ///
+    /// Right and bottom edges win over left and top ones if the rectangle is
+    /// too small to tell them apart.
+    fn near(x: f64, y: f64, rect: (i64, i64, i64, i64)) -> Self {
+        let (left, top, width, height) = rect;
+        let right = (left + width) as f64 - x <= RESIZE_MARGIN;
+        let bottom = (top + height) as f64 - y <= RESIZE_MARGIN;
+        Self {
+            left: !right && x - left as f64 <= RESIZE_MARGIN,
+            top: !bottom && y - top as f64 <= RESIZE_MARGIN,
+            right,
+            bottom,
+        }
+    }
+
+    /// Whether any edge is dragged.
+    fn any(&self) -> bool {
+        self.left || self.top || self.right || self.bottom
+    }
+}
+
+/// An ongoing drag of a widget.
+#[derive(Debug)]
+struct Drag {
+    /// The ID of the widget.
+    id: String,
+    /// The edges being dragged.
+    edges: Edges,
+    /// The cursor position where the drag started, in canvas coordinates.
+    start: (f64, f64),
+    /// The position and size of the widget when the drag started.
+    rect: (i64, i64, i64, i64),
+}
+
+impl Drag {
+    /// The patch moving or resizing the widget for the cursor position.
+    fn patch(&self, x: f64, y: f64) -> WidgetSettingsPatch {
+        let dx = (x - self.start.0).round() as i64;
+        let dy = (y - self.start.1).round() as i64;
+        let (left, top, width, height) = self.rect;
+        let (right, bottom) = (left + width, top + height);
+
+        if !self.edges.any() {
+            return WidgetSettingsPatch {
+                x: Some((left + dx) as i32),
+                y: Some((top + dy) as i32),
+                ..Default::default()
+            };
+        }
+
+        // The opposite edges of those dragged stay in place
+        let (mut new_left, mut new_right) = (left, right);
+        if self.edges.left {
+            new_left = (left + dx).min(right - MIN_SIZE);
+        } else if self.edges.right {
+            new_right = (right + dx).max(left + MIN_SIZE);
+        }
+        let (mut new_top, mut new_bottom) = (top, bottom);
+        if self.edges.top {
+            new_top = (top + dy).min(bottom - MIN_SIZE);
+        } else if self.edges.bottom {
+            new_bottom = (bottom + dy).max(top + MIN_SIZE);
+        }
+        WidgetSettingsPatch {
+            x: Some(new_left as i32),
+            y: Some(new_top as i32),
+            width: Some((new_right - new_left) as u32),
+            height: Some((new_bottom - new_top) as u32),
+            ..Default::default()
+        }
+    }
+}
+
+/// Layout editing state of the global mousemove listener.
+#[derive(Debug, Default)]
+pub(super) struct LayoutEditor {
+    /// The ongoing drag, if any.
+    drag: Option<Drag>,
+    /// Whether the primary button was down as of the last movement.
+    was_down: bool,
+}
+
+impl LayoutEditor {
+    /// Whether a widget is being dragged.
+    pub(super) fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Handle a cursor movement in canvas coordinates.
+    ///
+    /// The hit widget is the topmost one at the cursor position, if any. A
+    /// drag starts when the primary button is pressed over a widget, and the
+    /// widget settings are updated on every movement until it is released.
+    /// Errors updating the settings are logged and end the drag. This is a
+    /// no-op unless layout editing is active.
+    pub(super) fn on_mousemove<R: Runtime>(
+        &mut self,
+        canvas: &WebviewWindow<R>,
+        x: f64,
+        y: f64,
+        hit: Option<&str>,
+    ) {
+        let is_down = is_active() && mouse_hook::is_primary_button_down().unwrap_or(false);
+        let was_down = std::mem::replace(&mut self.was_down, is_down);
+        if !is_down {
+            self.drag = None;
+            return;
+        }
+
+        let Some(drag) = &self.drag else {
+            // Only presses over a widget start a drag, not moving onto a
+            // widget with the button already held
+            if !was_down && let Some(id) = hit {
+                self.drag = start_drag(canvas, id, x, y);
+            }
+            return;
+        };
+
+        let patch = drag.patch(x, y);
+        if let Err(e) = canvas.widgets().update_settings(&drag.id, patch) {
+            tracing::error!(id = %drag.id, "Failed to drag widget: {e:?}");
+            self.drag = None;
+        }
+    }
+}
+
+/// Start dragging a widget at the cursor position.
+///
+/// No drag is started if the widget does not exist or settings are locked.
+fn start_drag<R: Runtime>(canvas: &WebviewWindow<R>, id: &str, x: f64, y: f64) -> Option<Drag> {
+    if canvas.settings().is_locked() {
+        return None;
+    }
+    let settings = canvas.widgets().widget_settings(id).ok()?;
+    let rect = (
+        settings.x as i64,
+        settings.y as i64,
+        settings.width as i64,
+        settings.height as i64,
+    );
+    Some(Drag {
+        id: id.to_string(),
+        edges: Edges::near(x, y, rect),
+        start: (x, y),
+        rect,
+    })
+}
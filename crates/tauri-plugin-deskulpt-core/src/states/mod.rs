@@ -1,6 +1,11 @@
 //! Deskulpt runtime state management.
 
 mod canvas_imode;
+#[cfg(windows)]
+mod fullscreen;
+mod layout_edit;
+mod mouse_hook;
 
 #[doc(hidden)]
 pub use canvas_imode::CanvasImodeStateExt;
+pub use mouse_hook::MouseHookBackend;
@@ -0,0 +1,173 @@
+//! Global mouse hook with per-platform backends.
+//!
+//! The canvas needs the global cursor position to decide whether it should
+//! accept cursor events, even while it is ignoring them. Native global hooks
+//! provide this with the least overhead, but they are not available everywhere,
+//! e.g., Wayland compositors do not expose the cursor position outside of the
+//! focused client. In that case, the cursor position is polled instead, which
+//! works anywhere the windowing library can query it at the cost of some
+//! latency and background wakeups.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+use tauri::{Runtime, WebviewWindow};
+
+/// The interval at which the cursor position is polled by the polling backend.
+///
+/// This is roughly one frame on a 60Hz display, which is the best the canvas
+/// can react anyway without costing noticeable CPU in the background.
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// The backend of the global mouse hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseHookBackend {
+    /// The native global mousemove hook of the platform.
+    Native,
+    /// Polling of the cursor position, used when no native hook is available.
+    Polling,
+}
+
+/// The backend of the running mouse hook, encoded via [`encode_backend`].
+///
+/// This is zero until a mouse hook starts running.
+static BACKEND: AtomicU8 = AtomicU8::new(0);
+
+/// Encode a mouse hook backend for storing in [`BACKEND`].
+fn encode_backend(backend: MouseHookBackend) -> u8 {
+    match backend {
+        MouseHookBackend::Native => 1,
+        MouseHookBackend::Polling => 2,
+    }
+}
+
+/// Get the backend of the running mouse hook, if any.
+pub(super) fn running_backend() -> Option<MouseHookBackend> {
+    match BACKEND.load(Ordering::Acquire) {
+        1 => Some(MouseHookBackend::Native),
+        2 => Some(MouseHookBackend::Polling),
+        _ => None,
+    }
+}
+
+/// A global mouse hook reporting cursor movements anywhere on screen.
+///
+/// Cursor positions are reported in the coordinates of the native hook, i.e.,
+/// logical pixels on macOS and physical pixels on other platforms, regardless
+/// of the backend in use.
+pub(super) struct MouseHook<R: Runtime> {
+    /// The canvas window used to query the cursor position for polling.
+    canvas: WebviewWindow<R>,
+}
+
+impl<R: Runtime> MouseHook<R> {
+    /// Create a new mouse hook.
+    pub(super) fn new(canvas: WebviewWindow<R>) -> Self {
+        Self { canvas }
+    }
+
+    /// Select the preferred backend for the current session.
+    ///
+    /// The native backend is preferred, except on Wayland where it would only
+    /// observe the cursor over XWayland windows.
+    fn preferred_backend() -> MouseHookBackend {
+        #[cfg(target_os = "linux")]
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            return MouseHookBackend::Polling;
+        }
+        MouseHookBackend::Native
+    }
+
+    /// Run the mouse hook, calling the callback with the cursor position on
+    /// every movement.
+    ///
+    /// This blocks the current thread. The preferred backend is tried first,
+    /// and if the native hook fails to start, it falls back to polling. The
+    /// backend in use can be queried with [`running_backend`].
+    pub(super) fn run<F>(self, callback: F) -> Result<()>
+    where
+        F: Fn(f64, f64) + Send + Sync + 'static,
+    {
+        let callback = Arc::new(callback);
+        if Self::preferred_backend() == MouseHookBackend::Native {
+            set_running_backend(MouseHookBackend::Native);
+            let native_callback = callback.clone();
+            match global_mousemove::listen(move |event| native_callback(event.x, event.y)) {
+                Ok(()) => return Ok(()),
+                Err(e) => tracing::warn!("Native mouse hook unavailable, falling back: {e}"),
+            }
+        }
+        set_running_backend(MouseHookBackend::Polling);
+        self.poll(&*callback)
+    }
+
+    /// Poll the cursor position.
+    ///
+    /// The callback is only called when the position has changed since the
+    /// last poll.
+    fn poll(&self, callback: &dyn Fn(f64, f64)) -> Result<()> {
+        let mut last_position = None;
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let position = match self.canvas.cursor_position() {
+                Ok(position) => position,
+                Err(e) => {
+                    tracing::trace!("Failed to poll cursor position: {e}");
+                    continue;
+                },
+            };
+            if last_position == Some(position) {
+                continue;
+            }
+            last_position = Some(position);
+
+            // The native hook reports logical coordinates on macOS
+            #[cfg(target_os = "macos")]
+            let position = position.to_logical::<f64>(self.canvas.scale_factor()?);
+
+            callback(position.x, position.y);
+        }
+    }
+}
+
+/// Check whether the primary mouse button is held down.
+///
+/// This queries the global button state regardless of the window under the
+/// cursor, taking swapped buttons into account.
+#[cfg(windows)]
+pub(super) fn is_primary_button_down() -> Option<bool> {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VK_LBUTTON, VK_RBUTTON,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_SWAPBUTTON};
+
+    // SAFETY: These functions have no preconditions
+    let state = unsafe {
+        let button = if GetSystemMetrics(SM_SWAPBUTTON) != 0 {
+            VK_RBUTTON
+        } else {
+            VK_LBUTTON
+        };
+        GetAsyncKeyState(button as i32)
+    };
+    Some(state as u16 & 0x8000 != 0)
+}
+
+/// Check whether the primary mouse button is held down.
+///
+/// This is only supported on Windows and always returns `None` on other
+/// platforms.
+#[cfg(not(windows))]
+pub(super) fn is_primary_button_down() -> Option<bool> {
+    None
+}
+
+/// Record the backend of the running mouse hook.
+fn set_running_backend(backend: MouseHookBackend) {
+    tracing::info!(?backend, "Starting global mouse hook");
+    BACKEND.store(encode_backend(backend), Ordering::Release);
+}
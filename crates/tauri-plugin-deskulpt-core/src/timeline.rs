@@ -0,0 +1,282 @@
+//! Time-travel debug recording of app state.
+//!
+//! In dev mode, catalog revisions and settings diffs are recorded with
+//! timestamps into a bounded on-disk ring in the logs directory. This allows
+//! maintainers to reconstruct what the app believed around the time of a
+//! reported bug. The ring consists of two newline-delimited JSON files: when
+//! the current file is full, it replaces the previous one and a fresh file is
+//! started.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use deskulpt_common::event::Event;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{App, AppHandle, Listener, Manager, Runtime};
+use tauri_plugin_deskulpt_logs::LogsExt;
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_settings::events::UpdateEvent as SettingsUpdateEvent;
+use tauri_plugin_deskulpt_widgets::events::{DiffEvent, UpdateEvent as CatalogUpdateEvent};
+
+/// The maximum number of entries in each timeline file.
+const MAX_ENTRIES_PER_FILE: usize = 500;
+
+/// The name of the current timeline file.
+const TIMELINE_FILE: &str = "state-timeline.jsonl";
+
+/// The name of the previous timeline file.
+const PREVIOUS_TIMELINE_FILE: &str = "state-timeline.1.jsonl";
+
+/// A record of an app state change.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum TimelineRecord {
+    /// A full snapshot of the widget catalog.
+    Catalog {
+        /// The catalog revision, increasing with each catalog change.
+        revision: u64,
+        /// The catalog snapshot.
+        catalog: Value,
+    },
+    /// An incremental change to the widget catalog.
+    CatalogDiff {
+        /// The catalog revision, increasing with each catalog change.
+        revision: u64,
+        /// The catalog change.
+        diff: Value,
+    },
+    /// A change to the settings.
+    ///
+    /// This maps the changed top-level settings to their new values. Removed
+    /// settings are mapped to `null`.
+    Settings(Value),
+}
+
+/// An entry in the state timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct TimelineEntry {
+    /// The time of the record in milliseconds since the UNIX epoch.
+    pub timestamp: u64,
+    /// The record.
+    pub record: TimelineRecord,
+}
+
+/// Mutable part of the [`TimelineState`].
+struct TimelineInner {
+    /// The number of entries in the current timeline file.
+    len: usize,
+    /// The latest catalog revision.
+    catalog_revision: u64,
+    /// The latest settings snapshot, for computing diffs.
+    settings: Value,
+}
+
+/// Managed state for the state timeline recorder.
+struct TimelineState {
+    /// The directory where timeline files are stored.
+    dir: PathBuf,
+    /// The mutable part of the state.
+    inner: Mutex<TimelineInner>,
+}
+
+impl TimelineState {
+    /// Create a new [`TimelineState`] that continues the ring in `dir`.
+    fn new(dir: PathBuf) -> Self {
+        let len = std::fs::read_to_string(dir.join(TIMELINE_FILE))
+            .map(|content| content.lines().count())
+            .unwrap_or_default();
+        Self {
+            dir,
+            inner: Mutex::new(TimelineInner {
+                len,
+                catalog_revision: 0,
+                settings: Value::Null,
+            }),
+        }
+    }
+
+    /// Record a catalog snapshot.
+    fn record_catalog(&self, catalog: Value) -> Result<()> {
+        let mut inner = self.inner.lock();
+        inner.catalog_revision += 1;
+        let record = TimelineRecord::Catalog {
+            revision: inner.catalog_revision,
+            catalog,
+        };
+        self.append(&mut inner, record)
+    }
+
+    /// Record an incremental catalog change.
+    fn record_catalog_diff(&self, diff: Value) -> Result<()> {
+        let mut inner = self.inner.lock();
+        inner.catalog_revision += 1;
+        let record = TimelineRecord::CatalogDiff {
+            revision: inner.catalog_revision,
+            diff,
+        };
+        self.append(&mut inner, record)
+    }
+
+    /// Record the diff against the previous settings snapshot.
+    ///
+    /// Nothing is recorded if the settings did not change.
+    fn record_settings(&self, settings: Value) -> Result<()> {
+        let mut inner = self.inner.lock();
+        let diff = diff_objects(&inner.settings, &settings);
+        inner.settings = settings;
+        if diff.is_empty() {
+            return Ok(());
+        }
+        self.append(&mut inner, TimelineRecord::Settings(Value::Object(diff)))
+    }
+
+    /// Append a record to the ring, rotating the files if necessary.
+    fn append(&self, inner: &mut TimelineInner, record: TimelineRecord) -> Result<()> {
+        let path = self.dir.join(TIMELINE_FILE);
+        if inner.len >= MAX_ENTRIES_PER_FILE {
+            std::fs::rename(&path, self.dir.join(PREVIOUS_TIMELINE_FILE))?;
+            inner.len = 0;
+        }
+
+        let entry = TimelineEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            record,
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        inner.len += 1;
+        Ok(())
+    }
+
+    /// Read all entries in the ring, oldest first.
+    fn dump(&self) -> Result<Vec<TimelineEntry>> {
+        let _inner = self.inner.lock(); // Prevent rotation while reading
+        let mut entries = vec![];
+        for name in [PREVIOUS_TIMELINE_FILE, TIMELINE_FILE] {
+            read_entries(&self.dir.join(name), &mut entries)?;
+        }
+        Ok(entries)
+    }
+}
+
+/// Read timeline entries from a file into `entries`.
+///
+/// Missing files are treated as empty. Malformed lines, e.g., one truncated
+/// by a crash, are skipped.
+fn read_entries(path: &Path, entries: &mut Vec<TimelineEntry>) -> Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+        },
+    };
+    entries.extend(
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok()),
+    );
+    Ok(())
+}
+
+/// Compute the top-level differences between two JSON objects.
+///
+/// Non-object values are treated as empty objects.
+fn diff_objects(old: &Value, new: &Value) -> serde_json::Map<String, Value> {
+    let empty = serde_json::Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+
+    let mut diff = serde_json::Map::new();
+    for (key, value) in new {
+        if old.get(key) != Some(value) {
+            diff.insert(key.clone(), value.clone());
+        }
+    }
+    for key in old.keys() {
+        if !new.contains_key(key) {
+            diff.insert(key.clone(), Value::Null);
+        }
+    }
+    diff
+}
+
+/// Register a listener that records the payloads of an event.
+fn listen<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    event: &str,
+    record: fn(&TimelineState, Value) -> Result<()>,
+) {
+    let app_handle_cloned = app_handle.clone();
+    let event_name = event.to_string();
+    app_handle.listen_any(event, move |event| {
+        let state = app_handle_cloned.state::<TimelineState>();
+        let result = serde_json::from_str(event.payload())
+            .map_err(Into::into)
+            .and_then(|payload| record(&state, payload));
+        if let Err(e) = result {
+            tracing::warn!(error = ?e, event = %event_name, "Failed to record state timeline");
+        }
+    });
+}
+
+/// Extension trait for time-travel debug recording of app state.
+pub trait TimelineExt<R: Runtime>: Manager<R> + LogsExt<R> + SettingsExt<R> {
+    /// Start recording the state timeline if in dev mode.
+    ///
+    /// This records the current settings as the baseline, then catalog and
+    /// settings updates as they are emitted. It is a no-op in release builds.
+    fn init_state_timeline(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        let state = TimelineState::new(self.logs().dir().to_path_buf());
+        let baseline = serde_json::to_value(&*self.settings().read())
+            .map_err(Into::into)
+            .and_then(|settings| state.record_settings(settings));
+        if let Err(e) = baseline {
+            tracing::warn!(error = ?e, "Failed to record baseline settings");
+        }
+        self.manage(state);
+
+        let app_handle = self.app_handle();
+        listen(
+            app_handle,
+            CatalogUpdateEvent::NAME,
+            TimelineState::record_catalog,
+        );
+        listen(
+            app_handle,
+            DiffEvent::NAME,
+            TimelineState::record_catalog_diff,
+        );
+        listen(
+            app_handle,
+            SettingsUpdateEvent::NAME,
+            TimelineState::record_settings,
+        );
+        tracing::debug!("State timeline recording started");
+    }
+
+    /// Dump the recorded state timeline, oldest first.
+    ///
+    /// Tauri command: [`crate::commands::dump_state_timeline`].
+    fn dump_state_timeline(&self) -> Result<Vec<TimelineEntry>> {
+        let state = self
+            .try_state::<TimelineState>()
+            .context("State timeline recording is only available in dev mode")?;
+        state.dump()
+    }
+}
+
+impl<R: Runtime> TimelineExt<R> for App<R> {}
+impl<R: Runtime> TimelineExt<R> for AppHandle<R> {}
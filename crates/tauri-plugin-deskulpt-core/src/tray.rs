@@ -7,7 +7,7 @@ use tauri::{App, AppHandle, Manager, Runtime};
 use tauri_plugin_deskulpt_settings::SettingsExt;
 use tauri_plugin_deskulpt_widgets::WidgetsExt;
 
-use crate::window::WindowExt;
+use crate::window::{WindowExt, mark_exiting};
 
 /// Extention trait for system tray-related operations.
 pub trait TrayExt<R: Runtime>: Manager<R> {
@@ -57,6 +57,7 @@ fn on_menu_event<R: Runtime>(app_handle: &AppHandle<R>, event: MenuEvent) {
             }
         },
         "tray-exit" => {
+            mark_exiting();
             if let Err(e) = app_handle.settings().persist() {
                 tracing::error!("Failed to persist settings before exit: {e}");
                 app_handle.exit(1);
@@ -0,0 +1,92 @@
+//! Watchdog for stalled background workers.
+//!
+//! Background workers that hang do so silently: their queues keep accepting
+//! work that is never processed. The watchdog periodically pings each worker
+//! and restarts those that do not respond in time.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use tauri::{App, AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_logs::LogsExt;
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+use tokio::sync::oneshot;
+
+/// The interval between two rounds of pings.
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The time within which a worker must respond to a ping.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Wait for a worker to respond to a ping.
+///
+/// An error is returned if the ping cannot be sent, if the worker drops it, or
+/// if the worker does not respond within [`PING_TIMEOUT`].
+async fn check(ping: Result<oneshot::Receiver<()>>) -> Result<()> {
+    tokio::time::timeout(PING_TIMEOUT, ping?).await??;
+    Ok(())
+}
+
+/// Extension trait for the watchdog of background workers.
+pub trait WatchdogExt<R: Runtime>:
+    Manager<R> + LogsExt<R> + SettingsExt<R> + WidgetsExt<R>
+{
+    /// Start the watchdog of background workers.
+    ///
+    /// The settings worker, the render worker, and the log writer thread are
+    /// pinged every 10 seconds. A worker that does not respond within 5
+    /// seconds is reported as an error and restarted. The watchdog runs on
+    /// Tauri's singleton async runtime for the lifetime of the app.
+    fn start_watchdog(&self) {
+        let app_handle = self.app_handle().clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(PING_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = check(app_handle.settings().ping_worker()).await {
+                    tracing::error!(
+                        error = ?e,
+                        timeout = ?PING_TIMEOUT,
+                        "Settings worker stopped responding; restarting",
+                    );
+                    if let Err(e) = app_handle.settings().restart_worker() {
+                        tracing::error!(error = ?e, "Failed to restart settings worker");
+                    }
+                }
+
+                if let Err(e) = check(app_handle.widgets().ping_render_worker()).await {
+                    tracing::error!(
+                        error = ?e,
+                        timeout = ?PING_TIMEOUT,
+                        "Render worker stopped responding; restarting",
+                    );
+                    if let Err(e) = app_handle.widgets().restart_render_worker() {
+                        tracing::error!(error = ?e, "Failed to restart render worker");
+                    }
+                }
+
+                if let Err(e) = check(Ok(app_handle.logs().ping_writer())).await {
+                    // This still reaches the in-memory logs even if the log
+                    // files are not written
+                    tracing::error!(
+                        error = ?e,
+                        timeout = ?PING_TIMEOUT,
+                        "Log writer stopped responding; restarting",
+                    );
+                    // Restarting waits for the old writer thread to flush
+                    let app_handle = app_handle.clone();
+                    let _ = tauri::async_runtime::spawn_blocking(move || {
+                        app_handle.logs().restart_writer();
+                    })
+                    .await;
+                }
+            }
+        });
+    }
+}
+
+impl<R: Runtime> WatchdogExt<R> for App<R> {}
+impl<R: Runtime> WatchdogExt<R> for AppHandle<R> {}
@@ -2,6 +2,7 @@ Object.defineProperty(window, "__DESKULPT_INTERNALS__", {
   value: {
     apisWrapper: __TEMPLATE_apis_wrapper__,
     initialSettings: __TEMPLATE_initial_settings__,
+    detachedWidget: __TEMPLATE_detached_widget__,
   },
   writable: false,
   configurable: false,
@@ -0,0 +1,213 @@
+//! Capture of the canvas contents.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use tauri::{Runtime, WebviewWindow};
+
+/// A region of the canvas to capture.
+///
+/// The coordinates are in canvas pixels, the same as widget positions and
+/// sizes, so that a region can be derived from widget settings directly.
+#[derive(Debug, Clone, Copy, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRegion {
+    /// The leftmost x-coordinate in pixels.
+    pub x: i32,
+    /// The topmost y-coordinate in pixels.
+    pub y: i32,
+    /// The width in pixels.
+    pub width: u32,
+    /// The height in pixels.
+    pub height: u32,
+}
+
+/// A captured frame of 8-bit RGBA pixels in row-major order.
+#[cfg_attr(not(windows), allow(dead_code))]
+struct Frame {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Frame {
+    /// Crop the frame to a region in physical pixels.
+    ///
+    /// The region is clamped to the frame bounds. An error is returned if
+    /// nothing is left after clamping.
+    fn crop(self, x: i64, y: i64, width: i64, height: i64) -> Result<Self> {
+        let left = x.clamp(0, self.width as i64) as usize;
+        let top = y.clamp(0, self.height as i64) as usize;
+        let right = (x + width).clamp(0, self.width as i64) as usize;
+        let bottom = (y + height).clamp(0, self.height as i64) as usize;
+        if left >= right || top >= bottom {
+            bail!("Capture region is outside of the canvas");
+        }
+
+        let stride = self.width as usize * 4;
+        let mut rgba = Vec::with_capacity((right - left) * (bottom - top) * 4);
+        for row in top..bottom {
+            rgba.extend_from_slice(&self.rgba[row * stride + left * 4..row * stride + right * 4]);
+        }
+        Ok(Self {
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+            rgba,
+        })
+    }
+
+    /// Encode the frame as a PNG file.
+    fn save_png(&self, path: &Path) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgba)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+/// Capture the canvas contents to a PNG file.
+///
+/// Only the contents rendered by the canvas webview are captured, not what is
+/// behind the transparent canvas, e.g., the wallpaper or other windows. If a
+/// region is given, the capture is cropped to it.
+pub(super) fn capture<R: Runtime>(
+    canvas: &WebviewWindow<R>,
+    path: &Path,
+    region: Option<CaptureRegion>,
+) -> Result<()> {
+    let mut frame = grab(canvas)?;
+    if let Some(region) = region {
+        let scale_factor = canvas.scale_factor()?;
+        let scale = |value: f64| (value * scale_factor).round() as i64;
+        frame = frame.crop(
+            scale(region.x as f64),
+            scale(region.y as f64),
+            scale(region.width as f64),
+            scale(region.height as f64),
+        )?;
+    }
+    frame.save_png(path)
+}
+
+/// Grab the client area of the canvas window.
+///
+/// `PrintWindow` with `PW_RENDERFULLCONTENT` asks the window to render itself
+/// into a bitmap, which includes DirectComposition content like WebView2 but
+/// not the windows behind it. Some compositing setups leave the alpha channel
+/// empty, in which case the pixels are treated as opaque instead.
+#[cfg(windows)]
+fn grab<R: Runtime>(canvas: &WebviewWindow<R>) -> Result<Frame> {
+    use windows_sys::Win32::Foundation::RECT;
+    use windows_sys::Win32::Graphics::Gdi::{
+        BI_RGB, BITMAPINFO, BITMAPINFOHEADER, CreateCompatibleDC, CreateDIBSection, DIB_RGB_COLORS,
+        DeleteDC, DeleteObject, GetDC, ReleaseDC, SelectObject,
+    };
+    use windows_sys::Win32::Storage::Xps::{PW_CLIENTONLY, PrintWindow};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{GetClientRect, PW_RENDERFULLCONTENT};
+
+    let hwnd = canvas.hwnd()?.0;
+
+    // SAFETY: The window handle is valid, the pointers passed point to local
+    // variables that outlive the calls, and every GDI object created here is
+    // released before returning
+    unsafe {
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        if GetClientRect(hwnd, &mut rect) == 0 {
+            bail!("Failed to get the canvas client rectangle");
+        }
+        let width = (rect.right - rect.left).max(0);
+        let height = (rect.bottom - rect.top).max(0);
+        if width == 0 || height == 0 {
+            bail!("Canvas has an empty client area");
+        }
+
+        let info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative height for a top-down bitmap
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB,
+                ..std::mem::zeroed()
+            },
+            ..std::mem::zeroed()
+        };
+
+        let window_dc = GetDC(hwnd);
+        let memory_dc = CreateCompatibleDC(window_dc);
+        let mut bits = std::ptr::null_mut();
+        let bitmap = CreateDIBSection(
+            window_dc,
+            &info,
+            DIB_RGB_COLORS,
+            &mut bits,
+            std::ptr::null_mut(),
+            0,
+        );
+        let mut rgba = None;
+        if !bitmap.is_null() {
+            let previous = SelectObject(memory_dc, bitmap);
+            if PrintWindow(hwnd, memory_dc, PW_CLIENTONLY | PW_RENDERFULLCONTENT) != 0 {
+                let len = width as usize * height as usize * 4;
+                let bgra = std::slice::from_raw_parts(bits as *const u8, len);
+                rgba = Some(bgra_to_rgba(bgra));
+            }
+            SelectObject(memory_dc, previous);
+            DeleteObject(bitmap);
+        }
+        DeleteDC(memory_dc);
+        ReleaseDC(hwnd, window_dc);
+
+        let Some(rgba) = rgba else {
+            bail!("Failed to render the canvas into a bitmap");
+        };
+        Ok(Frame {
+            width: width as u32,
+            height: height as u32,
+            rgba,
+        })
+    }
+}
+
+/// Grab the client area of the canvas window.
+///
+/// This is only supported on Windows and always fails on other platforms.
+#[cfg(not(windows))]
+fn grab<R: Runtime>(_canvas: &WebviewWindow<R>) -> Result<Frame> {
+    bail!("Capturing the canvas is only supported on Windows")
+}
+
+/// Convert premultiplied BGRA pixels as returned by GDI to RGBA pixels.
+///
+/// If the alpha channel is empty, the pixels are made opaque.
+#[cfg(windows)]
+fn bgra_to_rgba(bgra: &[u8]) -> Vec<u8> {
+    let has_alpha = bgra.chunks_exact(4).any(|pixel| pixel[3] != 0);
+    bgra.chunks_exact(4)
+        .flat_map(|pixel| {
+            let [b, g, r, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            if !has_alpha {
+                return [r, g, b, u8::MAX];
+            }
+            if a == 0 {
+                return [0, 0, 0, 0];
+            }
+            let unmultiply = |c: u8| ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8;
+            [unmultiply(r), unmultiply(g), unmultiply(b), a]
+        })
+        .collect()
+}
@@ -0,0 +1,124 @@
+//! Compositing workarounds and diagnostics for Deskulpt windows.
+
+use serde::Serialize;
+use tauri::{Manager, Runtime, WebviewWindowBuilder};
+use tauri_plugin_deskulpt_settings::model::Settings;
+
+/// The default additional browser arguments that Tauri passes to WebView2.
+///
+/// Setting additional browser arguments overrides these defaults, so they
+/// need to be retained manually.
+#[cfg(windows)]
+const DEFAULT_WEBVIEW2_ARGS: &str =
+    "--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection";
+
+/// Apply process-wide compositing workarounds according to the settings.
+///
+/// This must be called before any webview is created, because some platforms
+/// only read these configurations when spawning the web process.
+#[cfg_attr(windows, allow(unused_variables))]
+pub(super) fn apply_process_workarounds(settings: &Settings) {
+    #[cfg(target_os = "linux")]
+    if settings.disable_hardware_acceleration {
+        // SAFETY: This is called during app setup before any webview is
+        // created, so no other thread is reading these variables
+        unsafe {
+            std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
+            std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    if settings.disable_hardware_acceleration {
+        tracing::warn!("Disabling hardware acceleration is unsupported on macOS");
+    }
+}
+
+/// Apply per-webview compositing workarounds according to the settings.
+///
+/// On Windows, all webviews must share the same browser arguments, so this
+/// must be applied to every Deskulpt window.
+#[cfg_attr(not(windows), allow(unused_variables))]
+pub(super) fn configure_builder<'a, R, M>(
+    builder: WebviewWindowBuilder<'a, R, M>,
+    settings: &Settings,
+) -> WebviewWindowBuilder<'a, R, M>
+where
+    R: Runtime,
+    M: Manager<R>,
+{
+    #[cfg(windows)]
+    if settings.disable_hardware_acceleration {
+        return builder.additional_browser_args(&format!("{DEFAULT_WEBVIEW2_ARGS} --disable-gpu"));
+    }
+    builder
+}
+
+/// The compositing path that a webview is rendered with.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum CompositingPath {
+    /// Rendered with GPU acceleration.
+    Hardware,
+    /// Rendered with a software rasterizer.
+    Software,
+    /// The compositing path cannot be determined.
+    Unknown,
+}
+
+impl CompositingPath {
+    /// Infer the compositing path from a WebGL renderer string.
+    fn from_renderer(renderer: Option<&str>) -> Self {
+        const SOFTWARE_RENDERERS: &[&str] = &[
+            "swiftshader",
+            "llvmpipe",
+            "softpipe",
+            "software",
+            "microsoft basic render",
+        ];
+
+        match renderer {
+            Some(renderer) => {
+                let renderer = renderer.to_lowercase();
+                if SOFTWARE_RENDERERS.iter().any(|s| renderer.contains(s)) {
+                    Self::Software
+                } else {
+                    Self::Hardware
+                }
+            },
+            None => Self::Unknown,
+        }
+    }
+}
+
+/// Diagnostics of the compositing setup, for bug triage.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositingDiagnostics {
+    /// The operating system.
+    pub platform: String,
+    /// Whether hardware acceleration is requested.
+    pub hardware_acceleration: bool,
+    /// Whether the canvas is transparent.
+    pub transparent_canvas: bool,
+    /// The WebGL renderer reported by the webview, if available.
+    pub renderer: Option<String>,
+    /// The active compositing path inferred from the renderer.
+    pub path: CompositingPath,
+}
+
+impl CompositingDiagnostics {
+    /// Diagnose the compositing setup.
+    ///
+    /// The renderer is probed by the frontend since it is only available from
+    /// within the webview.
+    pub fn new(settings: &Settings, renderer: Option<String>) -> Self {
+        Self {
+            platform: std::env::consts::OS.to_string(),
+            hardware_acceleration: !settings.disable_hardware_acceleration,
+            transparent_canvas: !settings.opaque_canvas,
+            path: CompositingPath::from_renderer(renderer.as_deref()),
+            renderer,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Handling of display configuration changes.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use deskulpt_common::event::Event;
+use deskulpt_common::window::DeskulptWindow;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+use tauri_plugin_deskulpt_widgets::WidgetsExt;
+
+use crate::events::DisplayChangedEvent;
+use crate::states::CanvasImodeStateExt;
+
+/// The interval at which the monitor layout is polled for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A snapshot of the monitor layout for detecting changes.
+///
+/// Each monitor is recorded by its name, physical position and size, and the
+/// bits of its scale factor.
+#[derive(PartialEq)]
+struct MonitorLayout(Vec<(Option<String>, i32, i32, u32, u32, u64)>);
+
+impl MonitorLayout {
+    /// Take a snapshot of the current monitor layout.
+    fn current<R: Runtime>(canvas: &WebviewWindow<R>) -> Result<Self> {
+        let monitors = canvas
+            .available_monitors()?
+            .into_iter()
+            .map(|monitor| {
+                let position = monitor.position();
+                let size = monitor.size();
+                (
+                    monitor.name().cloned(),
+                    position.x,
+                    position.y,
+                    size.width,
+                    size.height,
+                    monitor.scale_factor().to_bits(),
+                )
+            })
+            .collect();
+        Ok(Self(monitors))
+    }
+}
+
+/// Handle a change in the display configuration.
+///
+/// This syncs the canvas layout used for mouse interaction with the current
+/// position and scale factor of the canvas, re-resolves widget positions
+/// against the new monitor layout so that they stay on visible screen area,
+/// and notifies the canvas with a [`DisplayChangedEvent`] so that it can
+/// re-layout.
+pub fn on_display_change<R: Runtime>(app_handle: &AppHandle<R>) -> Result<()> {
+    let canvas = DeskulptWindow::Canvas.webview_window(app_handle)?;
+    let scale_factor = canvas.scale_factor()?;
+    app_handle.set_canvas_position(&canvas.inner_position()?);
+    app_handle.set_canvas_scale_factor(scale_factor);
+
+    app_handle.widgets().resolve_monitor_positions()?;
+
+    let monitor_count = canvas.available_monitors()?.len() as u32;
+    DisplayChangedEvent {
+        monitor_count,
+        scale_factor,
+    }
+    .emit_to(app_handle, DeskulptWindow::Canvas)?;
+    Ok(())
+}
+
+/// Watch the monitor layout for changes.
+///
+/// Monitors being added or removed, or their DPI changing, do not necessarily
+/// move, resize, or rescale the canvas, so there may be no window event for
+/// them. The monitor layout is thus polled in a background thread, and
+/// [`on_display_change`] is called whenever it changes.
+pub fn watch_monitors<R: Runtime>(canvas: WebviewWindow<R>) {
+    std::thread::spawn(move || {
+        let mut layout = MonitorLayout::current(&canvas).ok();
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let current = match MonitorLayout::current(&canvas) {
+                Ok(current) => current,
+                Err(e) => {
+                    tracing::warn!("Failed to query monitor layout: {e:?}");
+                    continue;
+                },
+            };
+            if layout.as_ref() == Some(&current) {
+                continue;
+            }
+            if layout.is_some() {
+                tracing::info!(monitors = current.0.len(), "Monitor layout changed");
+                if let Err(e) = on_display_change(canvas.app_handle()) {
+                    tracing::error!("Failed to handle display change: {e:?}");
+                }
+            }
+            layout = Some(current);
+        }
+    });
+}
@@ -1,15 +1,75 @@
 //! Deskulpt windows.
 
+mod capture;
+mod compositing;
+mod display;
 mod script;
+mod wallpaper;
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::Result;
-use deskulpt_common::window::DeskulptWindow;
+pub use capture::CaptureRegion;
+pub use compositing::{CompositingDiagnostics, CompositingPath};
+use deskulpt_common::event::Event;
+use deskulpt_common::window::{DeskulptWindow, detached_widget_label};
 use script::{CanvasInitJS, PortalInitJS};
-use tauri::{App, AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use serde::Serialize;
+use tauri::{
+    App, AppHandle, Manager, PhysicalPosition, Runtime, WebviewUrl, WebviewWindowBuilder,
+    WindowEvent,
+};
 use tauri_plugin_deskulpt_settings::SettingsExt;
 use tauri_plugin_deskulpt_settings::model::{CanvasImode, Theme};
+use tauri_plugin_deskulpt_widgets::{DetachedPosition, WidgetsExt};
+
+use crate::custom_css::{CustomCssExt, injection_script};
+use crate::events::SwitchPortalTabEvent;
+use crate::safe_mode::SafeModeExt;
+
+/// Whether the application is exiting.
+///
+/// Floating windows of detached widgets are destroyed on exit as well, which
+/// must not attach the widgets back, so that they are detached again on the
+/// next launch. Likewise, the canvas being destroyed on exit must not be
+/// recovered as if Explorer had restarted, see [`wallpaper::attach`].
+static IS_EXITING: AtomicBool = AtomicBool::new(false);
 
-use crate::states::CanvasImodeStateExt;
+/// Mark the application as exiting.
+///
+/// This should be called right before exiting the application, see
+/// [`IS_EXITING`].
+pub(crate) fn mark_exiting() {
+    IS_EXITING.store(true, Ordering::Release);
+}
+
+/// Get the background color of Deskulpt windows for a theme.
+fn theme_background_color(theme: &Theme) -> (u8, u8, u8) {
+    // https://www.radix-ui.com/colors: "Slate 1" colors
+    match theme {
+        Theme::Light => (252, 252, 253), // #FCFCFD
+        Theme::Dark => (17, 17, 19),     // #111113
+    }
+}
+
+/// The tabs of Deskulpt portal.
+#[derive(Debug, Clone, Copy, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum PortalTab {
+    /// The widgets tab.
+    Widgets,
+    /// The settings tab.
+    Settings,
+    /// The gallery tab.
+    Gallery,
+    /// The logs tab.
+    Logs,
+    /// The about tab.
+    About,
+    /// The recovery tab, available in safe mode only.
+    Recovery,
+}
 
 /// Extention trait for window-related operations.
 pub trait WindowExt<R: Runtime>: Manager<R> + SettingsExt<R> {
@@ -18,88 +78,289 @@ pub trait WindowExt<R: Runtime>: Manager<R> + SettingsExt<R> {
     /// If the portal already exists, it will be focused. Otherwise it will be
     /// created first.
     fn open_portal(&self) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.open_portal_at(None)
+    }
+
+    /// Open Deskulpt portal at a specific tab.
+    ///
+    /// Same as [`WindowExt::open_portal`], but additionally switches the
+    /// portal to the given tab if any.
+    fn open_portal_at(&self, tab: Option<PortalTab>) -> Result<()>
     where
         Self: Sized,
     {
         if let Ok(portal) = DeskulptWindow::Portal.webview_window(self) {
+            if let Some(tab) = tab {
+                SwitchPortalTabEvent(tab).emit_to(self.app_handle(), DeskulptWindow::Portal)?;
+            }
             portal.set_focus()?;
             return Ok(());
         }
 
         let settings = self.settings().read();
-        let init_js = PortalInitJS::generate(&settings)?;
+        let init_js = PortalInitJS::generate(&settings, tab, self.app_handle().is_safe_mode())?;
+        let custom_css_js = injection_script(&self.app_handle().custom_css())?;
 
-        // https://www.radix-ui.com/colors: "Slate 1" colors
-        let background_color = match settings.theme {
-            Theme::Light => (252, 252, 253), // #FCFCFD
-            Theme::Dark => (17, 17, 19),     // #111113
-        };
+        let background_color = theme_background_color(&settings.theme);
 
-        let portal = WebviewWindowBuilder::new(
+        let builder = WebviewWindowBuilder::new(
             self,
             DeskulptWindow::Portal,
             WebviewUrl::App("packages/deskulpt-portal/index.html".into()),
-        )
-        .title("Deskulpt Portal")
-        .background_color(background_color.into())
-        .inner_size(800.0, 500.0)
-        .center()
-        .resizable(false)
-        .maximizable(false)
-        .minimizable(false)
-        .initialization_script(&init_js)
-        .build()?;
+        );
+        let portal = compositing::configure_builder(builder, &settings)
+            .title("Deskulpt Portal")
+            .background_color(background_color.into())
+            .inner_size(800.0, 500.0)
+            .center()
+            .resizable(false)
+            .maximizable(false)
+            .minimizable(false)
+            .initialization_script(&init_js)
+            .initialization_script(&custom_css_js)
+            .build()?;
+
+        // Flush pending settings changes once the portal is out of sight, so
+        // that they survive a crash or a forced shutdown
+        let app_handle = self.app_handle().clone();
+        portal.on_window_event(move |event| {
+            if matches!(event, WindowEvent::Focused(false) | WindowEvent::Destroyed)
+                && let Err(e) = app_handle.settings().flush()
+            {
+                tracing::error!("Failed to flush settings: {e:?}");
+            }
+        });
 
         portal.set_focus()?;
 
         Ok(())
     }
 
+    /// Toggle the visibility of Deskulpt canvas.
+    ///
+    /// Hiding the canvas hides all widgets at once without unloading them.
+    fn toggle_canvas_visibility(&self) -> Result<()>
+    where
+        Self: Sized,
+    {
+        let canvas = DeskulptWindow::Canvas.webview_window(self)?;
+        if canvas.is_visible()? {
+            canvas.hide()?;
+        } else {
+            canvas.show()?;
+        }
+        Ok(())
+    }
+
+    /// Capture the canvas contents to a PNG file.
+    ///
+    /// Only widgets are captured, not the wallpaper or other windows behind
+    /// the canvas. If a region is given, the capture is cropped to it. This is
+    /// only supported on Windows for now.
+    ///
+    /// Tauri command: [`crate::commands::capture_canvas`].
+    fn capture_canvas(&self, path: &Path, region: Option<CaptureRegion>) -> Result<()>
+    where
+        Self: Sized,
+    {
+        let canvas = DeskulptWindow::Canvas.webview_window(self)?;
+        capture::capture(&canvas, path, region)
+    }
+
     /// Create Deskulpt canvas.
+    ///
+    /// This should be the first Deskulpt window to create, because it also
+    /// applies process-wide compositing workarounds from the settings.
     fn create_canvas(&self) -> Result<()>
     where
         Self: Sized,
     {
         let settings = self.settings().read();
-        let init_js = CanvasInitJS::generate(&settings)?;
-        let canvas = WebviewWindowBuilder::new(
+        compositing::apply_process_workarounds(&settings);
+
+        let init_js = CanvasInitJS::generate(&settings, None)?;
+        let custom_css_js = injection_script(&self.app_handle().custom_css())?;
+        let builder = WebviewWindowBuilder::new(
             self,
             DeskulptWindow::Canvas,
             WebviewUrl::App("packages/deskulpt-canvas/index.html".into()),
-        )
-        .title("Deskulpt Canvas")
-        .maximized(true)
-        .transparent(true)
-        .decorations(false)
-        .always_on_bottom(true)
-        // TODO: Remove when the following issue is fixed:
-        // https://github.com/tauri-apps/tauri/issues/9597
-        .visible(false)
-        // Unsupported on macOS; see below for activation policy
-        .skip_taskbar(true)
-        .initialization_script(&init_js)
-        .shadow(false)
-        .build()?;
+        );
+        let mut builder = compositing::configure_builder(builder, &settings)
+            .title("Deskulpt Canvas")
+            .maximized(true)
+            .transparent(!settings.opaque_canvas)
+            .decorations(false)
+            .always_on_bottom(true)
+            // TODO: Remove when the following issue is fixed:
+            // https://github.com/tauri-apps/tauri/issues/9597
+            .visible(false)
+            // Unsupported on macOS; see below for activation policy
+            .skip_taskbar(true)
+            .initialization_script(&init_js)
+            .initialization_script(&custom_css_js)
+            .shadow(false);
+
+        if settings.opaque_canvas {
+            builder = builder.background_color(theme_background_color(&settings.theme).into());
+        }
+        let canvas = builder.build()?;
 
         // TODO: Remove when the following issue is fixed:
         // https://github.com/tauri-apps/tauri/issues/9597
         canvas.show()?;
 
+        if settings.attach_to_wallpaper
+            && let Err(e) = wallpaper::attach(&canvas)
+        {
+            tracing::warn!("Failed to attach canvas to wallpaper; keeping it on bottom: {e:?}");
+        }
+
+        if let Err(e) = self.app_handle().widgets().resolve_monitor_positions() {
+            tracing::error!("Failed to resolve widget positions: {e:?}");
+        }
+
+        // The canvas is moved, resized, or rescaled when the display
+        // configuration changes, and monitors may be added or removed without
+        // affecting the canvas at all, see the display module for details
+        let app_handle = self.app_handle().clone();
+        canvas.on_window_event(move |event| {
+            if !matches!(
+                event,
+                WindowEvent::Moved(_)
+                    | WindowEvent::Resized(_)
+                    | WindowEvent::ScaleFactorChanged { .. }
+            ) {
+                return;
+            }
+            if let Err(e) = display::on_display_change(&app_handle) {
+                tracing::error!("Failed to handle display change: {e:?}");
+            }
+        });
+        display::watch_monitors(canvas.clone());
+
+        if settings.canvas_imode == CanvasImode::Sink {
+            canvas.set_ignore_cursor_events(true)?;
+        }
+        drop(settings);
+
+        for (id, _) in self.app_handle().widgets().detached_widgets() {
+            if let Err(e) = self.detach_widget(&id) {
+                tracing::error!("Failed to restore detached widget {id}: {e:?}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Detach a widget into its own floating window.
+    ///
+    /// The floating window is a small transparent always-on-top window that
+    /// runs the canvas for that widget only, so the widget is rendered by the
+    /// same pipeline as on the canvas. It is opened at its persisted position
+    /// if any, or otherwise where the widget is on the canvas. Moving the
+    /// window updates the persisted position, and closing it, e.g., with
+    /// Alt+F4, attaches the widget back to the canvas. If the window already
+    /// exists, it will be focused.
+    fn detach_widget(&self, id: &str) -> Result<()>
+    where
+        Self: Sized,
+    {
+        let label = detached_widget_label(id);
+        if let Some(window) = self.get_webview_window(&label) {
+            window.set_focus()?;
+            return Ok(());
+        }
+
+        let widgets = self.app_handle().widgets();
+        let widget_settings = widgets.widget_settings(id)?;
+        let position = match widget_settings.detached {
+            Some(position) => position,
+            None => {
+                let canvas = DeskulptWindow::Canvas.webview_window(self)?;
+                let origin = canvas.inner_position()?;
+                let scale_factor = canvas.scale_factor()?;
+                DetachedPosition {
+                    x: origin.x + (widget_settings.x as f64 * scale_factor).round() as i32,
+                    y: origin.y + (widget_settings.y as f64 * scale_factor).round() as i32,
+                }
+            },
+        };
+
+        let custom_css_js = injection_script(&self.app_handle().custom_css())?;
+        let builder = WebviewWindowBuilder::new(
+            self,
+            &label,
+            WebviewUrl::App("packages/deskulpt-canvas/index.html".into()),
+        );
+        // The settings guard must be dropped before building the window, which
+        // dispatches to the main thread
+        let settings = self.settings().read();
+        let init_js = CanvasInitJS::generate(&settings, Some(id))?;
+        let builder = compositing::configure_builder(builder, &settings);
+        drop(settings);
+        let window = builder
+            .title(format!("Deskulpt Widget: {id}"))
+            .inner_size(widget_settings.width as f64, widget_settings.height as f64)
+            .transparent(true)
+            .decorations(false)
+            .always_on_top(true)
+            .resizable(false)
+            .maximizable(false)
+            .minimizable(false)
+            // TODO: Remove when the following issue is fixed:
+            // https://github.com/tauri-apps/tauri/issues/9597
+            .visible(false)
+            .skip_taskbar(true)
+            .initialization_script(&init_js)
+            .initialization_script(&custom_css_js)
+            .shadow(false)
+            .build()?;
+
+        window.set_position(PhysicalPosition::new(position.x, position.y))?;
+        window.show()?;
+        widgets.set_detached(id, Some(position))?;
+
         let app_handle = self.app_handle().clone();
-        canvas.on_window_event(move |event| match event {
+        let id = id.to_string();
+        window.on_window_event(move |event| match event {
             WindowEvent::Moved(position) => {
-                app_handle.set_canvas_position(position);
+                let position = DetachedPosition {
+                    x: position.x,
+                    y: position.y,
+                };
+                if let Err(e) = app_handle.widgets().set_detached(&id, Some(position)) {
+                    tracing::error!("Failed to record position of detached widget {id}: {e:?}");
+                }
             },
-            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
-                app_handle.set_canvas_scale_factor(*scale_factor);
+            // Otherwise the widget would be on neither the canvas nor the
+            // screen until the next launch
+            WindowEvent::Destroyed if !IS_EXITING.load(Ordering::Acquire) => {
+                if let Err(e) = app_handle.widgets().set_detached(&id, None) {
+                    tracing::error!("Failed to attach closed widget {id} back: {e:?}");
+                }
             },
             _ => {},
         });
 
-        if settings.canvas_imode == CanvasImode::Sink {
-            canvas.set_ignore_cursor_events(true)?;
-        }
+        Ok(())
+    }
 
+    /// Attach a detached widget back to the canvas.
+    ///
+    /// This closes the floating window of the widget if any, see
+    /// [`WindowExt::detach_widget`]. The widget returns to its original place
+    /// on the canvas.
+    fn attach_widget(&self, id: &str) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.app_handle().widgets().set_detached(id, None)?;
+        if let Some(window) = self.get_webview_window(&detached_widget_label(id)) {
+            window.close()?;
+        }
         Ok(())
     }
 }
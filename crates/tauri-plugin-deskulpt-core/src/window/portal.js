@@ -1,6 +1,8 @@
 Object.defineProperty(window, "__DESKULPT_INTERNALS__", {
   value: {
     initialSettings: __TEMPLATE_initial_settings__,
+    initialTab: __TEMPLATE_initial_tab__,
+    safeMode: __TEMPLATE_safe_mode__,
   },
   writable: false,
   configurable: false,
@@ -4,12 +4,18 @@ use anyhow::Result;
 use serialize_to_javascript::{DefaultTemplate, Template, default_template};
 use tauri_plugin_deskulpt_settings::model::Settings;
 
+use super::PortalTab;
+
 /// Template for Deskulpt portal initialization script.
 #[derive(Template)]
 #[default_template("portal.js")]
 pub struct PortalInitJS<'a> {
     /// `window.__DESKULPT_INTERNALS__.initialSettings`
     initial_settings: &'a Settings,
+    /// `window.__DESKULPT_INTERNALS__.initialTab`
+    initial_tab: Option<PortalTab>,
+    /// `window.__DESKULPT_INTERNALS__.safeMode`
+    safe_mode: bool,
 }
 
 /// Template for Deskulpt canvas initialization script.
@@ -20,12 +26,22 @@ pub struct CanvasInitJS<'a> {
     apis_wrapper: &'static str,
     /// `window.__DESKULPT_INTERNALS__.initialSettings`
     initial_settings: &'a Settings,
+    /// `window.__DESKULPT_INTERNALS__.detachedWidget`
+    detached_widget: Option<&'a str>,
 }
 
 impl<'a> PortalInitJS<'a> {
     /// Generate JavaScript code for initializing Deskulpt portal.
-    pub fn generate(initial_settings: &'a Settings) -> Result<String> {
-        let template = Self { initial_settings };
+    pub fn generate(
+        initial_settings: &'a Settings,
+        initial_tab: Option<PortalTab>,
+        safe_mode: bool,
+    ) -> Result<String> {
+        let template = Self {
+            initial_settings,
+            initial_tab,
+            safe_mode,
+        };
         let serialized = template.render_default(&Default::default())?;
         Ok(serialized.into_string())
     }
@@ -33,10 +49,17 @@ impl<'a> PortalInitJS<'a> {
 
 impl<'a> CanvasInitJS<'a> {
     /// Generate JavaScript code for initializing Deskulpt canvas.
-    pub fn generate(initial_settings: &'a Settings) -> Result<String> {
+    ///
+    /// The floating window of a detached widget runs the canvas as well, in
+    /// which case the ID of that widget should be given.
+    pub fn generate(
+        initial_settings: &'a Settings,
+        detached_widget: Option<&'a str>,
+    ) -> Result<String> {
         let template = Self {
             apis_wrapper: include_str!("../../gen/apis.wrapper.js"),
             initial_settings,
+            detached_widget,
         };
         let serialized = template.render_default(&Default::default())?;
         Ok(serialized.into_string())
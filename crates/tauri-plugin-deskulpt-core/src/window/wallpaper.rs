@@ -0,0 +1,201 @@
+//! Attachment of the canvas to the desktop wallpaper.
+//!
+//! On Windows, the desktop icons are hosted by a `SHELLDLL_DefView` window.
+//! Sending the undocumented `0x052C` message to the `Progman` window makes
+//! Explorer spawn a `WorkerW` window between the icons and the wallpaper, which
+//! is what wallpaper engines draw into. Parenting the canvas to that window
+//! makes widgets appear as part of the wallpaper, and they are not hidden by
+//! "show desktop" which minimizes all top-level windows.
+//!
+//! The canvas is then destroyed along with the `WorkerW` window whenever
+//! Explorer restarts. The canvas cannot be re-created in place since other
+//! subsystems hold on to it, so the app is restarted instead.
+
+use anyhow::Result;
+use tauri::{Runtime, WebviewWindow};
+
+/// The maximum time to wait for Explorer to come back when recovering.
+#[cfg(windows)]
+const RECOVERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// The interval at which Explorer is checked for when recovering.
+#[cfg(windows)]
+const RECOVERY_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Attach the canvas to the desktop wallpaper.
+///
+/// An error is returned if the wallpaper window cannot be found or the canvas
+/// cannot be reparented, in which case the canvas is left as is. Once attached,
+/// the app is restarted if the canvas is destroyed with the wallpaper window,
+/// see [`recover`]. This is only supported on Windows and always fails on other
+/// platforms.
+#[cfg(windows)]
+pub(super) fn attach<R: Runtime>(canvas: &WebviewWindow<R>) -> Result<()> {
+    use std::sync::atomic::Ordering;
+
+    use anyhow::bail;
+    use tauri::{Manager, WindowEvent};
+    use windows_sys::Win32::Foundation::{POINT, RECT};
+    use windows_sys::Win32::Graphics::Gdi::ScreenToClient;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetWindowRect, SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER, SetParent, SetWindowPos,
+    };
+
+    let canvas_hwnd = canvas.hwnd()?.0;
+    let Some(worker) = find_wallpaper_worker() else {
+        bail!("Failed to find the desktop wallpaper window");
+    };
+
+    // SAFETY: Both window handles are valid, and the pointers passed point to
+    // initialized local variables that outlive the calls
+    unsafe {
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        if GetWindowRect(canvas_hwnd, &mut rect) == 0 {
+            bail!("Failed to get the canvas window rectangle");
+        }
+        if SetParent(canvas_hwnd, worker).is_null() {
+            bail!("Failed to reparent the canvas to the desktop wallpaper window");
+        }
+
+        // Child windows are positioned relative to their parent, which spans
+        // the whole virtual screen whose origin may not be at (0, 0)
+        let mut origin = POINT {
+            x: rect.left,
+            y: rect.top,
+        };
+        ScreenToClient(worker, &mut origin);
+        SetWindowPos(
+            canvas_hwnd,
+            std::ptr::null_mut(),
+            origin.x,
+            origin.y,
+            0,
+            0,
+            SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+        );
+    }
+
+    let app_handle = canvas.app_handle().clone();
+    canvas.on_window_event(move |event| {
+        if matches!(event, WindowEvent::Destroyed) && !super::IS_EXITING.load(Ordering::Acquire) {
+            recover(app_handle.clone());
+        }
+    });
+    Ok(())
+}
+
+/// Recover from the canvas being destroyed along with the wallpaper window.
+///
+/// This waits in a background thread for Explorer to come back, for at most
+/// [`RECOVERY_TIMEOUT`], and then persists settings and widgets and restarts
+/// the app. The canvas is attached again on launch, or stays on the bottom of
+/// other windows if Explorer is still not back.
+#[cfg(windows)]
+fn recover<R: Runtime>(app_handle: tauri::AppHandle<R>) {
+    use tauri_plugin_deskulpt_settings::SettingsExt;
+    use tauri_plugin_deskulpt_widgets::WidgetsExt;
+
+    tracing::warn!("Canvas destroyed with the desktop wallpaper window; restarting");
+    std::thread::spawn(move || {
+        let start = std::time::Instant::now();
+        while find_wallpaper_worker().is_none() && start.elapsed() < RECOVERY_TIMEOUT {
+            std::thread::sleep(RECOVERY_POLL_INTERVAL);
+        }
+        if let Err(e) = app_handle.settings().persist() {
+            tracing::error!("Failed to persist settings before restarting: {e:?}");
+        }
+        if let Err(e) = app_handle.widgets().persist() {
+            tracing::error!("Failed to persist widgets before restarting: {e:?}");
+        }
+        super::mark_exiting();
+        app_handle.restart();
+    });
+}
+
+/// Attach the canvas to the desktop wallpaper.
+///
+/// This is only supported on Windows and always fails on other platforms.
+#[cfg(not(windows))]
+pub(super) fn attach<R: Runtime>(_canvas: &WebviewWindow<R>) -> Result<()> {
+    anyhow::bail!("Attaching to the desktop wallpaper is only supported on Windows")
+}
+
+/// Find the `WorkerW` window behind the desktop icons.
+///
+/// Explorer is asked to spawn the window first, which is a no-op if it already
+/// exists. Before Windows 11 24H2, it is the top-level `WorkerW` window right
+/// after the one hosting `SHELLDLL_DefView`; since then, it is a child of the
+/// `Progman` window instead.
+#[cfg(windows)]
+fn find_wallpaper_worker() -> Option<windows_sys::Win32::Foundation::HWND> {
+    use windows_sys::Win32::Foundation::{HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, FindWindowExW, FindWindowW, SMTO_NORMAL, SendMessageTimeoutW,
+    };
+    use windows_sys::core::BOOL;
+
+    /// Encode a string as a null-terminated UTF-16 string.
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// Check a top-level window for hosting the desktop icons.
+    ///
+    /// If it does, the `WorkerW` window right after it is written to the
+    /// handle pointed to by `lparam` and enumeration stops.
+    unsafe extern "system" fn find_worker(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let def_view = wide("SHELLDLL_DefView");
+        let worker_w = wide("WorkerW");
+        // SAFETY: The class names are null-terminated, and `lparam` is the
+        // pointer to the handle passed to `EnumWindows` below
+        unsafe {
+            let shell = FindWindowExW(
+                hwnd,
+                std::ptr::null_mut(),
+                def_view.as_ptr(),
+                std::ptr::null(),
+            );
+            if shell.is_null() {
+                return 1;
+            }
+            *(lparam as *mut HWND) = FindWindowExW(
+                std::ptr::null_mut(),
+                hwnd,
+                worker_w.as_ptr(),
+                std::ptr::null(),
+            );
+        }
+        0
+    }
+
+    let progman_class = wide("Progman");
+    let worker_w = wide("WorkerW");
+
+    // SAFETY: The class names are null-terminated, and the pointers passed
+    // point to local variables that outlive the calls
+    unsafe {
+        let progman = FindWindowW(progman_class.as_ptr(), std::ptr::null());
+        if progman.is_null() {
+            return None;
+        }
+        let mut result = 0;
+        SendMessageTimeoutW(progman, 0x052C, 0, 0, SMTO_NORMAL, 1000, &mut result);
+
+        let mut worker: HWND = std::ptr::null_mut();
+        EnumWindows(Some(find_worker), &mut worker as *mut HWND as LPARAM);
+        if worker.is_null() {
+            worker = FindWindowExW(
+                progman,
+                std::ptr::null_mut(),
+                worker_w.as_ptr(),
+                std::ptr::null(),
+            );
+        }
+        (!worker.is_null()).then_some(worker)
+    }
+}
@@ -1,5 +1,13 @@
 fn main() {
     tauri_deskulpt_build::Builder::default()
-        .commands(&["clear", "read", "log"])
+        .commands(&[
+            "clear",
+            "clear_for_widget",
+            "read",
+            "read_for_widget",
+            "log",
+            "log_widget",
+            "set_log_level",
+        ])
         .build();
 }
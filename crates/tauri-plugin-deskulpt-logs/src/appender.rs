@@ -0,0 +1,213 @@
+//! Rolling log file appender with size-based rotation and retention.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use time::{Date, OffsetDateTime};
+
+/// The prefix of log file names.
+const FILENAME_PREFIX: &str = "deskulpt.";
+
+/// The suffix of log file names.
+const FILENAME_SUFFIX: &str = ".log";
+
+/// Collect log files in a directory in most recent first order.
+///
+/// Log files are named `deskulpt.<date>.<index>.log`, where the date is in UTC
+/// and the index counts the size-based rotations within that date. Since both
+/// parts have fixed widths, sorting by filename in descending order corresponds
+/// to most recent first.
+pub fn collect(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = std::fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            let name = path.file_name()?.to_string_lossy();
+            if name.starts_with(FILENAME_PREFIX) && name.ends_with(FILENAME_SUFFIX) {
+                Some(path)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    files.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    Ok(files)
+}
+
+/// The log file being written to.
+struct ActiveFile {
+    /// The file handle, opened in append mode.
+    file: File,
+    /// The date of the file.
+    date: Date,
+    /// The rotation index of the file within its date.
+    index: u32,
+    /// The known size of the file in bytes.
+    size: u64,
+}
+
+/// A log file appender that rotates daily and when the active file exceeds a
+/// maximum size.
+///
+/// On each rotation, older log files are pruned so that at most
+/// [`Self::max_files`] files are retained and their total size is within
+/// [`Self::max_total_size`]. The active file is never pruned.
+pub struct RollingAppender {
+    /// The directory where log files are stored.
+    dir: PathBuf,
+    /// The maximum size of a log file in bytes before it is rotated.
+    max_file_size: u64,
+    /// The maximum total size of log files in bytes.
+    max_total_size: u64,
+    /// The maximum number of log files.
+    max_files: usize,
+    /// The log file being written to, opened lazily on first write.
+    active: Option<ActiveFile>,
+}
+
+impl RollingAppender {
+    /// Create a new appender writing to the given directory.
+    pub fn new(dir: PathBuf, max_file_size: u64, max_total_size: u64, max_files: usize) -> Self {
+        Self {
+            dir,
+            max_file_size,
+            max_total_size,
+            max_files,
+            active: None,
+        }
+    }
+
+    /// Get the path of the log file with the given date and index.
+    fn path_of(&self, date: Date, index: u32) -> PathBuf {
+        self.dir.join(format!(
+            "{FILENAME_PREFIX}{date}.{index:04}{FILENAME_SUFFIX}"
+        ))
+    }
+
+    /// Check whether the active file needs to be rotated before writing `len`
+    /// more bytes on the given date.
+    fn should_rotate(&mut self, date: Date, len: u64) -> bool {
+        let Some(active) = &mut self.active else {
+            return true;
+        };
+        if active.date != date {
+            return true;
+        }
+        if active.size == 0 || active.size + len <= self.max_file_size {
+            return false;
+        }
+
+        // The file may have been truncated externally, e.g., when logs are
+        // cleared, so check the actual size before rotating
+        if let Ok(metadata) = active.file.metadata() {
+            active.size = metadata.len();
+        }
+        active.size > 0 && active.size + len > self.max_file_size
+    }
+
+    /// Open a new active file for the given date.
+    ///
+    /// On startup, this continues with the latest existing file of the date if
+    /// it is not yet full.
+    fn rotate(&mut self, date: Date) -> io::Result<()> {
+        let mut index = match &self.active {
+            Some(active) if active.date == date => active.index + 1,
+            Some(_) => 0,
+            None => {
+                let prefix = format!("{FILENAME_PREFIX}{date}.");
+                let latest = collect(&self.dir)?.into_iter().find_map(|path| {
+                    let name = path.file_name()?.to_string_lossy().into_owned();
+                    let index = name
+                        .strip_prefix(&prefix)?
+                        .strip_suffix(FILENAME_SUFFIX)?
+                        .parse::<u32>()
+                        .ok()?;
+                    Some((index, path.metadata().map_or(0, |m| m.len())))
+                });
+                match latest {
+                    Some((index, size)) if size < self.max_file_size => index,
+                    Some((index, _)) => index + 1,
+                    None => 0,
+                }
+            },
+        };
+
+        // Skip indices whose files are already full, which may happen if the
+        // clock went backwards
+        let (file, size) = loop {
+            let path = self.path_of(date, index);
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            let size = file.metadata()?.len();
+            if size == 0 || size < self.max_file_size {
+                break (file, size);
+            }
+            index += 1;
+        };
+
+        self.active = Some(ActiveFile {
+            file,
+            date,
+            index,
+            size,
+        });
+        self.prune();
+        Ok(())
+    }
+
+    /// Prune older log files beyond the retention limits.
+    ///
+    /// Failures are ignored since pruning is best-effort and should not affect
+    /// logging.
+    fn prune(&self) {
+        let Ok(files) = collect(&self.dir) else {
+            return;
+        };
+        let active_path = self
+            .active
+            .as_ref()
+            .map(|active| self.path_of(active.date, active.index));
+
+        let mut count = 0;
+        let mut total_size = 0;
+        for file in files {
+            let size = file.metadata().map_or(0, |m| m.len());
+            if Some(&file) == active_path.as_ref() {
+                count += 1;
+                total_size += size;
+                continue;
+            }
+            if count + 1 > self.max_files || total_size + size > self.max_total_size {
+                let _ = std::fs::remove_file(&file);
+            } else {
+                count += 1;
+                total_size += size;
+            }
+        }
+    }
+}
+
+impl Write for RollingAppender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let date = OffsetDateTime::now_utc().date();
+        if self.should_rotate(date, buf.len() as u64) {
+            self.rotate(date)?;
+        }
+
+        let active = self
+            .active
+            .as_mut()
+            .expect("active file is opened on rotation");
+        active.file.write_all(buf)?;
+        active.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.active {
+            Some(active) => active.file.flush(),
+            None => Ok(()),
+        }
+    }
+}
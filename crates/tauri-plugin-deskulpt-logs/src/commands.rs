@@ -2,11 +2,15 @@
 #![doc = include_str!("../permissions/autogenerated/reference.md")]
 
 use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use deskulpt_common::window::is_detached_widget_label;
 use serde::Deserialize;
 use tauri::{AppHandle, Runtime, WebviewWindow};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_settings::model::SettingsPatch;
 
 use crate::LogsExt;
-use crate::reader::{Cursor, Page};
+use crate::reader::{Cursor, Filter, Page};
 
 /// Level of severity for logging.
 #[derive(Debug, Deserialize, specta::Type)]
@@ -43,6 +47,7 @@ impl From<Level> for tracing::Level {
 ///
 /// The `meta` parameter accepts any JSON-serializable value to include extra
 /// metadata along with the log message. Pass `null` if no metadata is needed.
+// Not instrumented since every log entry would be accompanied by another
 #[tauri::command]
 #[specta::specta]
 pub async fn log<R: Runtime>(
@@ -51,8 +56,9 @@ pub async fn log<R: Runtime>(
     message: String,
     meta: serde_json::Value,
 ) -> SerResult<()> {
+    // Floating windows of detached widgets run the canvas as well
     match window.label() {
-        "canvas" => match level {
+        label if label == "canvas" || is_detached_widget_label(label) => match level {
             Level::Trace => tracing::trace!(target: "frontend::canvas", %meta, message),
             Level::Debug => tracing::debug!(target: "frontend::canvas", %meta, message),
             Level::Info => tracing::info!(target: "frontend::canvas", %meta, message),
@@ -71,33 +77,125 @@ pub async fn log<R: Runtime>(
     Ok(())
 }
 
+/// Emit a log message on behalf of a widget at the specified level.
+///
+/// The log entry is recorded under the `widget` target and tagged with the
+/// widget ID in the `widget` field, so that it is properly attributed to the
+/// widget instead of the canvas. Tracing targets must be static, so the ID is
+/// not part of the target itself; use the `widget_id` filter of [`read`] to
+/// isolate logs of one widget.
+///
+/// The `meta` parameter is the same as in [`log`].
+// Not instrumented for the same reason as `log`
+#[tauri::command]
+#[specta::specta]
+pub async fn log_widget(
+    widget_id: String,
+    level: Level,
+    message: String,
+    meta: serde_json::Value,
+) -> SerResult<()> {
+    match level {
+        Level::Trace => tracing::trace!(target: "widget", widget = %widget_id, %meta, message),
+        Level::Debug => tracing::debug!(target: "widget", widget = %widget_id, %meta, message),
+        Level::Info => tracing::info!(target: "widget", widget = %widget_id, %meta, message),
+        Level::Warn => tracing::warn!(target: "widget", widget = %widget_id, %meta, message),
+        Level::Error => tracing::error!(target: "widget", widget = %widget_id, %meta, message),
+    }
+    Ok(())
+}
+
 /// Read a page of log entries.
 ///
 /// This retrieves log entries from the log files, from newest to oldest. At
 /// most `limit` log entries will be returned. Only log entries with at least
-/// the severity of `min_level` will be included.
+/// the severity of `min_level` and passing `filter` will be included, see
+/// [`Filter`] for the available filters.
 ///
 /// An optional `cursor` can be provided. Pass `null` to start from the latest
 /// log entry. Pass a cursor returned from a previous call to continue reading
 /// from where you left off. An invalid cursor will be ignored.
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn read<R: Runtime>(
     app_handle: AppHandle<R>,
     limit: usize,
     min_level: Level,
+    filter: Filter,
+    cursor: Option<Cursor>,
+) -> SerResult<Page> {
+    let page = app_handle
+        .logs()
+        .read(limit, min_level.into(), filter, cursor)?;
+    Ok(page)
+}
+
+/// Read a page of log entries attributed to a widget.
+///
+/// Wrapper of [`crate::LogsManager::read_for_widget`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn read_for_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    widget_id: String,
+    limit: usize,
+    min_level: Level,
     cursor: Option<Cursor>,
 ) -> SerResult<Page> {
-    let page = app_handle.logs().read(limit, min_level.into(), cursor)?;
+    let page = app_handle
+        .logs()
+        .read_for_widget(&widget_id, limit, min_level.into(), cursor)?;
     Ok(page)
 }
 
+/// Clear the log files of a widget.
+///
+/// This returns the amount of freed space in bytes.
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_for_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    widget_id: String,
+) -> SerResult<u64> {
+    Ok(app_handle.logs().clear_for_widget(&widget_id))
+}
+
 /// Clear all log files.
 ///
 /// This returns the amount of freed space in bytes.
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn clear<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<u64> {
     let size = app_handle.logs().clear()?;
     Ok(size)
 }
+
+/// Set the filter of recorded logs.
+///
+/// The filter takes effect immediately and is persisted in the settings. It is
+/// a comma-separated list of directives, each being either a default level
+/// (e.g., `info`) or a level for a target and its children (e.g.,
+/// `deskulpt::widgets=debug`). `None` means recording all severities.
+///
+/// ### Errors
+///
+/// - The filter is invalid.
+/// - Error updating the settings.
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn set_log_level<R: Runtime>(
+    app_handle: AppHandle<R>,
+    filter: Option<String>,
+) -> SerResult<()> {
+    app_handle.logs().set_filter(filter.as_deref())?;
+    app_handle.settings().update(SettingsPatch {
+        log_filter: Some(filter),
+        ..Default::default()
+    })?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Liveness checks of the log writer thread.
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+/// Pending liveness checks of the log writer thread.
+///
+/// The non-blocking log writer processes log lines on a dedicated thread, and
+/// a stuck thread silently drops logs once its queue is full. A liveness check
+/// is answered as soon as that thread performs any write or flush.
+#[derive(Default)]
+pub struct Heartbeat {
+    /// The senders of pending liveness checks.
+    pending: Mutex<Vec<oneshot::Sender<()>>>,
+}
+
+impl Heartbeat {
+    /// Register a liveness check.
+    ///
+    /// The returned receiver resolves on the next write or flush of the log
+    /// writer thread.
+    pub fn register(&self) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().push(tx);
+        rx
+    }
+
+    /// Answer all pending liveness checks.
+    fn beat(&self) {
+        let mut pending = self.pending.lock();
+        for tx in pending.drain(..) {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// A writer that answers liveness checks of [`Heartbeat`] on every write or
+/// flush.
+pub struct HeartbeatWriter<W: Write> {
+    /// The wrapped writer.
+    inner: W,
+    /// The pending liveness checks.
+    heartbeat: Arc<Heartbeat>,
+}
+
+impl<W: Write> HeartbeatWriter<W> {
+    /// Create a new [`HeartbeatWriter`] instance.
+    pub fn new(inner: W, heartbeat: Arc<Heartbeat>) -> Self {
+        Self { inner, heartbeat }
+    }
+}
+
+impl<W: Write> Write for HeartbeatWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.heartbeat.beat();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()?;
+        self.heartbeat.beat();
+        Ok(())
+    }
+}
@@ -4,12 +4,16 @@
     html_favicon_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/public/deskulpt.svg"
 )]
 
+mod appender;
 mod commands;
+mod heartbeat;
 mod manager;
 mod reader;
+mod ring;
+mod widget_logs;
 
 pub use manager::LogsManager;
-pub use reader::{Cursor, Entry, Page};
+pub use reader::{Cursor, Entry, FileCursor, Filter, Page};
 use tauri::plugin::TauriPlugin;
 use tauri::{Manager, Runtime};
 
@@ -1,47 +1,143 @@
 //! Deskulpt logs manager and its APIs.
 
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
+use parking_lot::{Mutex, RwLock};
 use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tauri_plugin_deskulpt_settings::overrides::Overrides;
+use tokio::sync::oneshot;
 use tracing::Level;
-use tracing_appender::non_blocking::{NonBlockingBuilder, WorkerGuard};
-use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::{NonBlocking, NonBlockingBuilder, WorkerGuard};
 use tracing_subscriber::filter::Targets;
 use tracing_subscriber::fmt::time::UtcTime;
 use tracing_subscriber::layer::SubscriberExt;
-use tracing_subscriber::{Layer, Registry, fmt};
+use tracing_subscriber::{Layer, Registry, fmt, reload};
 
-use crate::reader::{Cursor, Page, RollingTailReader};
+use crate::appender::{self, RollingAppender};
+use crate::heartbeat::{Heartbeat, HeartbeatWriter};
+use crate::reader::{Cursor, Filter, Page, RollingTailReader};
+use crate::ring::{RingBuffer, TeeMakeWriter};
+use crate::widget_logs::{RoutingWriter, WidgetLogs};
+
+/// The default maximum size of a log file in bytes before it is rotated.
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The maximum total size of log files in bytes.
+const MAX_TOTAL_SIZE: u64 = 50 * 1024 * 1024;
+
+/// The maximum number of log files.
+const MAX_FILES: usize = 10;
+
+/// The maximum number of recent log entries kept in memory.
+const RING_CAPACITY: usize = 1000;
+
+/// The name of the file where the tail of the logs is dumped on crash.
+const CRASH_TAIL_FILE: &str = "crash-tail.ndjson";
+
+/// The name of the subdirectory where per-widget log files are stored.
+const WIDGET_LOGS_DIR: &str = "widgets";
+
+/// Handle for reloading the filter of recorded logs.
+type FilterHandle = reload::Handle<Targets, Registry>;
+
+/// Parse a filter of recorded logs.
+///
+/// See [`Settings::log_filter`] for the syntax. `None` or an empty filter
+/// records all severities.
+///
+/// [`Settings::log_filter`]: tauri_plugin_deskulpt_settings::model::Settings::log_filter
+fn parse_filter(filter: Option<&str>) -> Result<Targets> {
+    match filter.map(str::trim).filter(|filter| !filter.is_empty()) {
+        Some(filter) => Ok(filter.parse()?),
+        None => Ok(Targets::new().with_default(LevelFilter::TRACE)),
+    }
+}
+
+/// Parse a filter of recorded logs and apply it via the reload handle.
+fn reload_filter(handle: &FilterHandle, filter: Option<&str>) -> Result<()> {
+    handle.reload(parse_filter(filter)?)?;
+    Ok(())
+}
+
+/// Create a non-blocking writer of log files.
+///
+/// Entries attributed to widgets are also routed to their own log files, and
+/// liveness checks of the writer thread are answered via the heartbeat.
+fn file_writer(
+    dir: &Path,
+    max_file_size: u64,
+    widgets: Arc<WidgetLogs>,
+    heartbeat: Arc<Heartbeat>,
+) -> (NonBlocking, WorkerGuard) {
+    let appender =
+        RollingAppender::new(dir.to_path_buf(), max_file_size, MAX_TOTAL_SIZE, MAX_FILES);
+    let writer = HeartbeatWriter::new(RoutingWriter::new(appender, widgets), heartbeat);
+    NonBlockingBuilder::default().finish(writer)
+}
 
 /// Manager for Deskulpt logs.
 pub struct LogsManager<R: Runtime> {
     /// The Tauri app handle.
     _app_handle: AppHandle<R>,
+    /// Handle for reloading the filter of recorded logs.
+    filter: FilterHandle,
+    /// The most recent log entries.
+    ring: Arc<RingBuffer>,
+    /// The per-widget log files.
+    widgets: Arc<WidgetLogs>,
     /// The directory where log files are stored.
     dir: PathBuf,
+    /// The maximum size of a log file in bytes before it is rotated.
+    max_file_size: u64,
+    /// The non-blocking writer of log files.
+    writer: Arc<RwLock<NonBlocking>>,
+    /// The pending liveness checks of the writer thread.
+    heartbeat: Arc<Heartbeat>,
     /// A guard that flushes pending logs when dropped.
-    _guard: WorkerGuard,
+    guard: Mutex<WorkerGuard>,
 }
 
 impl<R: Runtime> LogsManager<R> {
     /// Initialize the logging system.
     ///
-    /// This will set up structured logging in newline-delimited JSON format
-    /// with daily rotation, retaining up to 10 log files. The logging system
-    /// remains active for the lifetime of the manager.
+    /// This will set up structured logging in newline-delimited JSON format.
+    /// Log files are rotated daily and whenever they exceed 10 MB (or the size
+    /// specified by [`Overrides::log_max_file_size`]). Older log files are
+    /// pruned to retain at most 10 files and 50 MB in total. The most recent
+    /// 1000 entries are also kept in memory, and they are dumped to
+    /// [`Self::crash_tail`] on panic. Entries attributed to widgets are
+    /// additionally written to per-widget log files.
+    ///
+    /// Recorded logs are filtered by [`Settings::log_filter`], which can be
+    /// changed at runtime, and are further capped by [`Overrides::log_level`]
+    /// if specified. The logging system remains active for the lifetime of the
+    /// manager.
+    ///
+    /// [`Settings::log_filter`]: tauri_plugin_deskulpt_settings::model::Settings::log_filter
     pub fn new(app_handle: AppHandle<R>) -> Result<Self> {
         let dir = app_handle.path().app_log_dir()?;
         std::fs::create_dir_all(&dir)?;
 
-        let appender = RollingFileAppender::builder()
-            .rotation(Rotation::DAILY)
-            .max_log_files(10)
-            .filename_prefix("deskulpt")
-            .filename_suffix("log")
-            .build(&dir)?;
+        let overrides = Overrides::of(&app_handle);
+        let max_file_size = overrides.log_max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+        let widgets = Arc::new(WidgetLogs::new(dir.join(WIDGET_LOGS_DIR)));
+        let heartbeat = Arc::new(Heartbeat::default());
+        let (writer, guard) = file_writer(&dir, max_file_size, widgets.clone(), heartbeat.clone());
+        let writer = Arc::new(RwLock::new(writer));
+        let ring = Arc::new(RingBuffer::new(RING_CAPACITY));
+        let level = overrides.log_level.unwrap_or(Level::TRACE);
 
-        let (writer, guard) = NonBlockingBuilder::default().finish(appender);
+        let log_filter = app_handle.settings().read().log_filter.clone();
+        let (initial_filter, filter_error) = match parse_filter(log_filter.as_deref()) {
+            Ok(targets) => (targets, None),
+            Err(e) => (parse_filter(None)?, Some(e)),
+        };
+        let (reload_layer, filter) = reload::Layer::new(initial_filter);
 
         let file_layer = fmt::layer()
             .json()
@@ -52,28 +148,52 @@ impl<R: Runtime> LogsManager<R> {
             .with_current_span(false)
             .with_span_list(true)
             .flatten_event(true)
-            .with_writer(writer)
+            .with_writer(TeeMakeWriter::new(ring.clone(), writer.clone()))
             .with_filter(
                 Targets::new()
-                    .with_target("deskulpt", Level::TRACE)
-                    .with_target("frontend::canvas", Level::TRACE)
-                    .with_target("frontend::manager", Level::TRACE),
-            );
+                    .with_target("deskulpt", level)
+                    .with_target("frontend::canvas", level)
+                    .with_target("frontend::manager", level)
+                    .with_target("widget", level),
+            )
+            .with_filter(reload_layer);
 
         let subscriber = Registry::default().with(file_layer);
         tracing::subscriber::set_global_default(subscriber)?;
 
-        // Set up panic hook to log uncaught panics
+        if let Some(e) = filter_error {
+            tracing::warn!("Invalid log filter {log_filter:?}, recording all severities: {e:?}");
+        }
+
+        let handle = filter.clone();
+        app_handle.settings().on_log_filter_change(move |_, new| {
+            if let Err(e) = reload_filter(&handle, new.map(String::as_str)) {
+                tracing::error!("Failed to apply log filter {new:?}: {e:?}");
+            }
+        });
+
+        // Set up panic hook to log uncaught panics and dump the tail of the
+        // logs, which may not have been flushed to the log files yet
         let previous_hook = std::panic::take_hook();
+        let (crash_ring, crash_tail) = (ring.clone(), dir.join(CRASH_TAIL_FILE));
         std::panic::set_hook(Box::new(move |panic_info| {
             tracing_panic::panic_hook(panic_info);
+            if let Some(tail) = crash_ring.try_dump() {
+                let _ = std::fs::write(&crash_tail, tail);
+            }
             previous_hook(panic_info);
         }));
 
         Ok(Self {
             dir,
+            max_file_size,
+            writer,
+            heartbeat,
             _app_handle: app_handle,
-            _guard: guard,
+            filter,
+            ring,
+            widgets,
+            guard: Mutex::new(guard),
         })
     }
 
@@ -82,54 +202,182 @@ impl<R: Runtime> LogsManager<R> {
         &self.dir
     }
 
-    /// Collect log files in most recent first order.
-    fn collect(&self) -> Result<Vec<PathBuf>> {
-        let mut files = std::fs::read_dir(&self.dir)?
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                let path = entry.path();
-                let name = path.file_name()?.to_string_lossy();
-                if name.starts_with("deskulpt.") && name.ends_with(".log") {
-                    Some(path)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
+    /// Get the number of log lines dropped so far.
+    ///
+    /// Lines are dropped when the non-blocking writer falls behind or its
+    /// worker thread has stopped, so a growing count indicates that logs are
+    /// not reliably recorded.
+    pub fn dropped_lines(&self) -> usize {
+        self.writer.read().error_counter().dropped_lines()
+    }
+
+    /// Check whether the writer thread is responsive.
+    ///
+    /// The returned receiver resolves once the writer thread processes the
+    /// ping, so a caller should wait on it with a timeout.
+    pub fn ping_writer(&self) -> oneshot::Receiver<()> {
+        let rx = self.heartbeat.register();
+        // An empty write is not written out, but the writer thread still
+        // flushes after processing it
+        let _ = self.writer.read().clone().write(&[]);
+        rx
+    }
+
+    /// Replace the writer with a fresh one, e.g., if its thread stops
+    /// responding.
+    ///
+    /// Log lines queued to the old writer are lost. Dropping the old guard
+    /// waits briefly for the old writer thread to flush, so this may block.
+    pub fn restart_writer(&self) {
+        let (writer, guard) = file_writer(
+            &self.dir,
+            self.max_file_size,
+            self.widgets.clone(),
+            self.heartbeat.clone(),
+        );
+        *self.writer.write() = writer;
+        let old_guard = std::mem::replace(&mut *self.guard.lock(), guard);
+        drop(old_guard);
+    }
+
+    /// Set the filter of recorded logs.
+    ///
+    /// This takes effect immediately but is not persisted. See
+    /// [`Settings::log_filter`] for the syntax. `None` records all severities.
+    ///
+    /// [`Settings::log_filter`]: tauri_plugin_deskulpt_settings::model::Settings::log_filter
+    pub fn set_filter(&self, filter: Option<&str>) -> Result<()> {
+        reload_filter(&self.filter, filter)
+    }
 
-        // Here we assume that the filenames are timestamps, so sorting by
-        // filename in descending order should correspond to most recent first
-        files.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
-        Ok(files)
+    /// Get the path of the file where the tail of the logs is dumped on crash.
+    ///
+    /// The file contains the most recent log entries at the time of the last
+    /// panic in newline-delimited JSON format. It may not exist.
+    pub fn crash_tail(&self) -> PathBuf {
+        self.dir.join(CRASH_TAIL_FILE)
+    }
+
+    /// Collect log files in most recent first order.
+    pub fn collect(&self) -> Result<Vec<PathBuf>> {
+        Ok(appender::collect(&self.dir)?)
     }
 
     /// Read a page of log entries.
     ///
     /// This will read up to `limit` log entries with severity at or above
-    /// `min_level`. If `cursor` is `None`, this method starts reading from the
-    /// newest entries. Otherwise, it continues reading from the provided
-    /// cursor, which should have been obtained from a previous call to this
-    /// method.
-    pub fn read(&self, limit: usize, min_level: Level, cursor: Option<Cursor>) -> Result<Page> {
+    /// `min_level` and passing `filter`. If `cursor` is `None`, this method
+    /// starts reading from the newest entries. Otherwise, it continues reading
+    /// from the provided cursor, which should have been obtained from a
+    /// previous call to this method with the same filters.
+    ///
+    /// The newest entries are served from memory as long as possible, after
+    /// which reading continues from the log files with entries older than the
+    /// oldest one examined in memory.
+    pub fn read(
+        &self,
+        limit: usize,
+        min_level: Level,
+        filter: Filter,
+        cursor: Option<Cursor>,
+    ) -> Result<Page> {
         let files = self.collect()?;
-        let mut reader = RollingTailReader::new(files, min_level);
+        let mut reader = RollingTailReader::new(files, min_level, filter)?;
+
+        let (before_seq, mut boundary) = match cursor {
+            None => (None, None),
+            Some(Cursor::Memory { seq, timestamp }) => (Some(seq), Some(timestamp)),
+            Some(Cursor::File(cursor)) => return reader.read(limit, Some(cursor)),
+        };
+
+        let mut entries = vec![];
+        for (seq, line) in self.ring.snapshot(before_seq) {
+            let Ok(raw) = serde_json::from_slice::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let Some(timestamp) = raw.get("timestamp").and_then(|t| t.as_str()) else {
+                continue;
+            };
+            let timestamp = timestamp.to_string();
+            boundary = Some(timestamp.clone());
+
+            if let Some(entry) = reader.filter_entry(raw) {
+                entries.push(entry);
+                if entries.len() >= limit {
+                    return Ok(Page {
+                        entries,
+                        cursor: Some(Cursor::Memory { seq, timestamp }),
+                    });
+                }
+            }
+        }
+
+        // The buffer is exhausted, so continue from the log files with entries
+        // older than those examined in memory
+        if let Some(boundary) = boundary {
+            reader = reader.before(&boundary)?;
+        }
+        let mut page = reader.read(limit - entries.len(), None)?;
+        entries.append(&mut page.entries);
+        Ok(Page {
+            entries,
+            cursor: page.cursor,
+        })
+    }
+
+    /// Read a page of log entries attributed to a widget.
+    ///
+    /// This reads from the per-widget log files, which is cheaper than
+    /// filtering the main log files by [`Filter::widget_id`] for a noisy
+    /// widget. See [`Self::read`] for the other arguments. Only file cursors
+    /// are valid here.
+    pub fn read_for_widget(
+        &self,
+        id: &str,
+        limit: usize,
+        min_level: Level,
+        cursor: Option<Cursor>,
+    ) -> Result<Page> {
+        let cursor = match cursor {
+            None => None,
+            Some(Cursor::File(cursor)) => Some(cursor),
+            Some(Cursor::Memory { .. }) => bail!("Invalid cursor for widget logs"),
+        };
+        let files = self
+            .widgets
+            .paths(id)
+            .into_iter()
+            .filter(|path| path.exists())
+            .collect();
+        let mut reader = RollingTailReader::new(files, min_level, Filter::default())?;
         reader.read(limit, cursor)
     }
 
+    /// Clear the log files of a widget.
+    ///
+    /// Entries of the widget in the main log files are kept. The total amount
+    /// of space freed is returned in bytes.
+    pub fn clear_for_widget(&self, id: &str) -> u64 {
+        self.widgets.clear(id)
+    }
+
     /// Clear all log files.
     ///
     /// The latest log file is truncated instead of deleted to ensure that
-    /// logging can continue without interruption. All older log files are
-    /// permanently deleted. The total amount of space freed is returned in
-    /// bytes.
+    /// logging can continue without interruption. All older log files, the
+    /// per-widget log files, and the recent log entries in memory are
+    /// permanently deleted. The total amount
+    /// of space freed is returned in bytes.
     ///
     /// This method returns an error if log file collection fails in the first
     /// place. Individual file deletion or truncation failures are silently
     /// ignored, and they do not contribute to the computed freed space.
     pub fn clear(&self) -> Result<u64> {
         let log_files = self.collect()?;
+        self.ring.clear();
 
-        let mut freed_space: u64 = log_files
+        let mut freed_space = self.widgets.clear_all();
+        freed_space += log_files
             .iter()
             .skip(1)
             .filter_map(|file| {
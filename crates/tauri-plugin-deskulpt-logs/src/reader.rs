@@ -5,8 +5,10 @@ use std::io::{Read, Seek, SeekFrom};
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 use tracing::Level;
 
 /// A page of log entries.
@@ -23,8 +25,30 @@ pub struct Page {
 
 /// Cursor for log pagination.
 #[derive(Debug, Deserialize, Serialize, specta::Type)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum Cursor {
+    /// Resume from the in-memory buffer of recent log entries.
+    Memory {
+        /// The sequence number of the last examined entry.
+        ///
+        /// When continuing from this cursor, reading resumes with entries
+        /// older than this one.
+        seq: u64,
+        /// The timestamp of the last examined entry in RFC 3339 format.
+        ///
+        /// If the buffer has no entries older than the last examined one, e.g.,
+        /// because they have been evicted, reading continues from the log
+        /// files with entries strictly older than this timestamp.
+        timestamp: String,
+    },
+    /// Resume from the log files.
+    File(FileCursor),
+}
+
+/// Cursor for log pagination within the log files.
+#[derive(Debug, Deserialize, Serialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
-pub struct Cursor {
+pub struct FileCursor {
     /// The index of the log file in the files list.
     pub file_idx: usize,
     /// The byte offset within the log file.
@@ -35,6 +59,118 @@ pub struct Cursor {
     pub offset: u64,
 }
 
+/// Filters of log entries in addition to the minimum severity level.
+///
+/// All filters are optional, and an entry must pass all specified filters to
+/// be included.
+#[derive(Debug, Default, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Filter {
+    /// Only include entries whose targets are any of these or their children.
+    ///
+    /// For example, `deskulpt` includes entries with targets `deskulpt` and
+    /// `deskulpt::widgets`, but not `deskulpt_extra`.
+    #[specta(optional, type = Vec<String>)]
+    pub targets: Option<Vec<String>>,
+    /// Only include entries attributed to the widget with this ID.
+    ///
+    /// An entry is attributed to a widget if it or any of its spans has the
+    /// `widget` field set to the widget ID.
+    #[specta(optional, type = String)]
+    pub widget_id: Option<String>,
+    /// Only include entries at or after this time in RFC 3339 format.
+    #[specta(optional, type = String)]
+    pub since: Option<String>,
+    /// Only include entries at or before this time in RFC 3339 format.
+    #[specta(optional, type = String)]
+    pub until: Option<String>,
+}
+
+/// [`Filter`] with the time range parsed.
+#[derive(Debug, Default)]
+struct ParsedFilter {
+    /// See [`Filter::targets`].
+    targets: Option<Vec<String>>,
+    /// See [`Filter::widget_id`].
+    widget_id: Option<String>,
+    /// See [`Filter::since`].
+    since: Option<OffsetDateTime>,
+    /// See [`Filter::until`].
+    until: Option<OffsetDateTime>,
+    /// Only include entries strictly before this time.
+    ///
+    /// This is not part of [`Filter`] but used internally to continue reading
+    /// from the log files after the in-memory buffer is exhausted.
+    before: Option<OffsetDateTime>,
+}
+
+impl TryFrom<Filter> for ParsedFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(filter: Filter) -> Result<Self> {
+        let parse = |time: Option<String>| {
+            time.map(|time| {
+                OffsetDateTime::parse(&time, &Rfc3339)
+                    .with_context(|| format!("Invalid RFC 3339 time: {time:?}"))
+            })
+            .transpose()
+        };
+        Ok(Self {
+            targets: filter.targets,
+            widget_id: filter.widget_id,
+            since: parse(filter.since)?,
+            until: parse(filter.until)?,
+            before: None,
+        })
+    }
+}
+
+impl ParsedFilter {
+    /// Check whether a raw log entry passes the filter.
+    fn matches(&self, raw: &serde_json::Value, timestamp: &str) -> bool {
+        if let Some(targets) = &self.targets {
+            let Some(target) = raw.get("target").and_then(|v| v.as_str()) else {
+                return false;
+            };
+            let matches_target = targets.iter().any(|t| {
+                target
+                    .strip_prefix(t.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            });
+            if !matches_target {
+                return false;
+            }
+        }
+
+        if let Some(widget_id) = &self.widget_id {
+            let is_widget = |v: &serde_json::Value| {
+                v.get("widget").and_then(|w| w.as_str()) == Some(widget_id.as_str())
+            };
+            let in_spans = raw
+                .get("spans")
+                .and_then(|spans| spans.as_array())
+                .is_some_and(|spans| spans.iter().any(is_widget));
+            if !is_widget(raw) && !in_spans {
+                return false;
+            }
+        }
+
+        if self.since.is_some() || self.until.is_some() || self.before.is_some() {
+            let Ok(time) = OffsetDateTime::parse(timestamp, &Rfc3339) else {
+                return false;
+            };
+            if self.since.is_some_and(|since| time < since)
+                || self.until.is_some_and(|until| time > until)
+                || self.before.is_some_and(|before| time >= before)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 /// A single log entry.
 #[derive(Debug, Serialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
@@ -64,6 +200,10 @@ pub struct RollingTailReader {
     ///
     /// Entries with severity lower than this level are skipped when reading.
     min_level: Level,
+    /// Additional filters of entries.
+    ///
+    /// Entries not passing the filter are skipped when reading.
+    filter: ParsedFilter,
     /// Reusable buffer for reading file blocks.
     ///
     /// This is to avoid repeated allocations when reading multiple blocks. The
@@ -80,23 +220,37 @@ impl RollingTailReader {
     const BLOCK_SIZE: u64 = 1 << 14;
 
     /// Create a new [`RollingTailReader`] instance.
-    pub fn new(files: Vec<PathBuf>, min_level: Level) -> Self {
-        Self {
+    ///
+    /// This returns an error if the time range of the filter is invalid.
+    pub fn new(files: Vec<PathBuf>, min_level: Level, filter: Filter) -> Result<Self> {
+        Ok(Self {
             files,
             min_level,
+            filter: filter.try_into()?,
             buf: vec![0u8; Self::BLOCK_SIZE as usize],
-        }
+        })
+    }
+
+    /// Only read entries strictly before the given time in RFC 3339 format.
+    ///
+    /// This returns an error if the time is invalid.
+    pub fn before(mut self, timestamp: &str) -> Result<Self> {
+        let time = OffsetDateTime::parse(timestamp, &Rfc3339)
+            .with_context(|| format!("Invalid RFC 3339 time: {timestamp:?}"))?;
+        self.filter.before = Some(time);
+        Ok(self)
     }
 
     /// Read a page of log entries.
     ///
     /// This returns up to `limit` log entries at or above the configured
-    /// minimum severity level of the reader. Entries are returned in reverse
-    /// chronological order (most recent first). If `cursor` is `None`, reading
-    /// starts from the last log entry in the last log file and proceeds
-    /// backwards. Otherwise, reading resumes from the specified cursor, which
-    /// should have been obtained from a previous call to this method.
-    pub fn read(&mut self, limit: usize, cursor: Option<Cursor>) -> Result<Page> {
+    /// minimum severity level of the reader and passing its filter. Entries are
+    /// returned in reverse chronological order (most recent first). If
+    /// `cursor` is `None`, reading starts from the last log entry in the
+    /// last log file and proceeds backwards. Otherwise, reading resumes
+    /// from the specified cursor, which should have been obtained from a
+    /// previous call to this method.
+    pub fn read(&mut self, limit: usize, cursor: Option<FileCursor>) -> Result<Page> {
         assert!(limit > 0, "Limit must be strictly positive");
 
         if self.files.is_empty() {
@@ -131,10 +285,10 @@ impl RollingTailReader {
             if let Some(next_offset) = cursor_in_file {
                 // We have filled the quota while still within this file, so we
                 // return a cursor pointing to where we left off
-                let next_cursor = Cursor {
+                let next_cursor = Cursor::File(FileCursor {
                     file_idx,
                     offset: next_offset,
-                };
+                });
                 return Ok(Page {
                     entries,
                     cursor: Some(next_cursor),
@@ -155,12 +309,18 @@ impl RollingTailReader {
 
     /// Parse and filter a log entry from a line of bytes.
     ///
-    /// Returns `None` if the line cannot be parsed as valid JSON, is missing
-    /// required fields (`timestamp`, `level`, `message`), or has a severity
-    /// level below the configured minimum.
+    /// Returns `None` if the line cannot be parsed as valid JSON or the entry
+    /// is filtered out by [`Self::filter_entry`].
     fn parse_entry(&self, line: &[u8]) -> Option<Entry> {
-        let raw: serde_json::Value = serde_json::from_slice(line).ok()?;
+        self.filter_entry(serde_json::from_slice(line).ok()?)
+    }
 
+    /// Filter a raw log entry.
+    ///
+    /// Returns `None` if the entry is missing required fields (`timestamp`,
+    /// `level`, `message`), has a severity level below the configured minimum,
+    /// or does not pass the configured filter.
+    pub fn filter_entry(&self, raw: serde_json::Value) -> Option<Entry> {
         // Filter by severity level (note: tracing levels are ordered by
         // verbosity, with TRACE > DEBUG > INFO > WARN > ERROR)
         let level = raw.get("level")?.as_str()?;
@@ -168,8 +328,13 @@ impl RollingTailReader {
             return None;
         }
 
+        let timestamp = raw.get("timestamp")?.as_str()?;
+        if !self.filter.matches(&raw, timestamp) {
+            return None;
+        }
+
         Some(Entry {
-            timestamp: raw.get("timestamp")?.as_str()?.to_string(),
+            timestamp: timestamp.to_string(),
             level: level.to_string(),
             message: raw.get("message")?.as_str()?.to_string(),
             raw,
@@ -206,7 +371,7 @@ impl RollingTailReader {
     /// invalid file index, it is treated as if no cursor is provided.
     ///
     /// This method returns `None` if there are no more files to read.
-    fn start_position(&self, cursor: &Option<Cursor>) -> Option<(usize, u64)> {
+    fn start_position(&self, cursor: &Option<FileCursor>) -> Option<(usize, u64)> {
         match cursor {
             None => self.next_file_position(0),
             Some(c) => {
@@ -0,0 +1,147 @@
+//! In-memory ring buffer of the most recent log entries.
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use parking_lot::{Mutex, RwLock};
+use tracing_appender::non_blocking::NonBlocking;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Mutable part of the [`RingBuffer`].
+#[derive(Default)]
+struct RingInner {
+    /// The buffered log lines with their sequence numbers, oldest first.
+    lines: VecDeque<(u64, Vec<u8>)>,
+    /// The sequence number of the next log line.
+    next_seq: u64,
+}
+
+/// A bounded in-memory buffer of the most recent log lines.
+///
+/// Log lines are captured synchronously as they are formatted, before they are
+/// handed to the non-blocking file writer. This allows the most recent pages
+/// of logs to be served without touching disk, and the tail of the logs to be
+/// dumped on crash even if the file writer is behind.
+///
+/// Each line is assigned an increasing sequence number, which is used by
+/// [`crate::Cursor::Memory`] to paginate within the buffer.
+pub struct RingBuffer {
+    /// The maximum number of buffered lines.
+    capacity: usize,
+    /// The buffered lines.
+    inner: Mutex<RingInner>,
+}
+
+impl RingBuffer {
+    /// Create a new empty buffer with the given capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Default::default(),
+        }
+    }
+
+    /// Push a log line, evicting the oldest line if the buffer is full.
+    fn push(&self, line: &[u8]) {
+        let mut inner = self.inner.lock();
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        if inner.lines.len() >= self.capacity {
+            inner.lines.pop_front();
+        }
+        inner.lines.push_back((seq, line.to_vec()));
+    }
+
+    /// Get the buffered lines older than the given sequence number, most recent
+    /// first.
+    ///
+    /// If `before` is `None`, all buffered lines are returned.
+    pub fn snapshot(&self, before: Option<u64>) -> Vec<(u64, Vec<u8>)> {
+        let inner = self.inner.lock();
+        inner
+            .lines
+            .iter()
+            .rev()
+            .filter(|(seq, _)| before.is_none_or(|before| *seq < before))
+            .cloned()
+            .collect()
+    }
+
+    /// Dump all buffered lines, oldest first.
+    ///
+    /// This does not block, since it is meant to be called from the panic hook
+    /// where the panicking thread may be holding the lock. `None` is returned
+    /// if the lock cannot be acquired.
+    pub fn try_dump(&self) -> Option<Vec<u8>> {
+        let inner = self.inner.try_lock()?;
+        Some(
+            inner
+                .lines
+                .iter()
+                .flat_map(|(_, line)| line)
+                .copied()
+                .collect(),
+        )
+    }
+
+    /// Remove all buffered lines.
+    ///
+    /// Sequence numbers keep increasing so that existing cursors do not point
+    /// to new lines.
+    pub fn clear(&self) {
+        self.inner.lock().lines.clear();
+    }
+}
+
+/// A [`MakeWriter`] that writes log lines to both a [`RingBuffer`] and the
+/// non-blocking file writer.
+pub struct TeeMakeWriter {
+    /// The ring buffer.
+    ring: Arc<RingBuffer>,
+    /// The non-blocking file writer.
+    ///
+    /// This is shared so that the writer can be replaced if its worker thread
+    /// gets stuck.
+    file: Arc<RwLock<NonBlocking>>,
+}
+
+impl TeeMakeWriter {
+    /// Create a new [`TeeMakeWriter`] instance.
+    pub fn new(ring: Arc<RingBuffer>, file: Arc<RwLock<NonBlocking>>) -> Self {
+        Self { ring, file }
+    }
+}
+
+impl<'a> MakeWriter<'a> for TeeMakeWriter {
+    type Writer = TeeWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        TeeWriter {
+            ring: &self.ring,
+            file: self.file.read().make_writer(),
+        }
+    }
+}
+
+/// The writer created by [`TeeMakeWriter`].
+///
+/// The formatting layer writes each log line with a single call, so every
+/// write is captured as a whole line in the ring buffer.
+pub struct TeeWriter<'a> {
+    /// The ring buffer.
+    ring: &'a RingBuffer,
+    /// The non-blocking file writer.
+    file: NonBlocking,
+}
+
+impl Write for TeeWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.ring.push(buf);
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
@@ -0,0 +1,185 @@
+//! Per-widget log files.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use serde_json::Value;
+
+/// The maximum size of a per-widget log file in bytes before it is rotated.
+///
+/// Each widget keeps at most one rotated file in addition to the active one.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Get the ID of the widget that a raw log entry is attributed to.
+///
+/// The `widget` field of the entry itself takes precedence, followed by that
+/// of the innermost span carrying it.
+fn widget_of(raw: &Value) -> Option<&str> {
+    if let Some(id) = raw.get("widget").and_then(Value::as_str) {
+        return Some(id);
+    }
+    raw.get("spans")?
+        .as_array()?
+        .iter()
+        .rev()
+        .find_map(|span| span.get("widget").and_then(Value::as_str))
+}
+
+/// An open per-widget log file.
+struct WidgetFile {
+    /// The file handle, opened in append mode.
+    file: File,
+    /// The known size of the file in bytes.
+    size: u64,
+}
+
+/// Manager of per-widget log files.
+///
+/// Entries attributed to a widget, i.e., carrying a `widget` field themselves
+/// or in any of their spans, are additionally written to a dedicated
+/// newline-delimited JSON file of that widget, so that the logs of a noisy
+/// widget can be inspected or purged in isolation.
+pub struct WidgetLogs {
+    /// The directory where per-widget log files are stored.
+    dir: PathBuf,
+    /// The open log files, keyed by widget ID.
+    files: Mutex<HashMap<String, WidgetFile>>,
+}
+
+impl WidgetLogs {
+    /// Create a new [`WidgetLogs`] instance storing files in the given
+    /// directory.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            files: Default::default(),
+        }
+    }
+
+    /// Get the paths of the log files of a widget, most recent first.
+    ///
+    /// The files may not exist.
+    pub fn paths(&self, id: &str) -> [PathBuf; 2] {
+        let stem = utf8_percent_encode(id, NON_ALPHANUMERIC).to_string();
+        [
+            self.dir.join(format!("{stem}.ndjson")),
+            self.dir.join(format!("{stem}.1.ndjson")),
+        ]
+    }
+
+    /// Route a log line to the log file of the widget it is attributed to.
+    ///
+    /// Lines not attributed to any widget are ignored. Failures are also
+    /// ignored since the line has already been written to the main log files.
+    fn route(&self, line: &[u8]) {
+        // Cheap check to avoid parsing most lines that are unrelated
+        if !line.windows(9).any(|w| w == b"\"widget\":") {
+            return;
+        }
+        let Ok(raw) = serde_json::from_slice::<Value>(line) else {
+            return;
+        };
+        if let Some(id) = widget_of(&raw) {
+            let _ = self.write(id, line);
+        }
+    }
+
+    /// Append a log line to the log file of a widget.
+    fn write(&self, id: &str, line: &[u8]) -> io::Result<()> {
+        let mut files = self.files.lock();
+        let [path, rotated_path] = self.paths(id);
+
+        if let Some(file) = files.get(id)
+            && file.size > 0
+            && file.size + line.len() as u64 > MAX_FILE_SIZE
+        {
+            files.remove(id);
+            std::fs::rename(&path, &rotated_path)?;
+        }
+
+        let file = match files.entry(id.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                std::fs::create_dir_all(&self.dir)?;
+                let file = OpenOptions::new().create(true).append(true).open(&path)?;
+                let size = file.metadata()?.len();
+                entry.insert(WidgetFile { file, size })
+            },
+        };
+        file.file.write_all(line)?;
+        file.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Delete the log files of a widget.
+    ///
+    /// The total amount of space freed is returned in bytes. Individual file
+    /// deletion failures are silently ignored, and they do not contribute to
+    /// the computed freed space.
+    pub fn clear(&self, id: &str) -> u64 {
+        let mut files = self.files.lock();
+        files.remove(id);
+        self.paths(id)
+            .into_iter()
+            .filter_map(|path| remove_file(&path))
+            .sum()
+    }
+
+    /// Delete the log files of all widgets.
+    ///
+    /// See [`Self::clear`] for the returned value.
+    pub fn clear_all(&self) -> u64 {
+        let mut files = self.files.lock();
+        files.clear();
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return 0;
+        };
+        entries
+            .filter_map(|entry| remove_file(&entry.ok()?.path()))
+            .sum()
+    }
+}
+
+/// Delete a file and return its size, or `None` if deletion fails.
+fn remove_file(path: &Path) -> Option<u64> {
+    let size = path.metadata().ok()?.len();
+    std::fs::remove_file(path).ok()?;
+    Some(size)
+}
+
+/// A writer that writes log lines to the main log files and routes those
+/// attributed to widgets to [`WidgetLogs`] as well.
+///
+/// This is meant to run on the worker thread of the non-blocking writer, so
+/// that routing does not slow down logging call sites.
+pub struct RoutingWriter<W: Write> {
+    /// The writer of the main log files.
+    inner: W,
+    /// The per-widget log files.
+    widgets: Arc<WidgetLogs>,
+}
+
+impl<W: Write> RoutingWriter<W> {
+    /// Create a new [`RoutingWriter`] instance.
+    pub fn new(inner: W, widgets: Arc<WidgetLogs>) -> Self {
+        Self { inner, widgets }
+    }
+}
+
+impl<W: Write> Write for RoutingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write_all(buf)?;
+        self.widgets.route(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -1,6 +1,19 @@
 fn main() {
     tauri_deskulpt_build::Builder::default()
-        .commands(&["update"])
-        .events(&["UpdateEvent"])
+        .commands(&[
+            "create_profile",
+            "export_settings",
+            "get_secret",
+            "get_widget_config",
+            "import_settings",
+            "is_locked",
+            "list_profiles",
+            "patch_widget_config",
+            "restore_settings_backup",
+            "set_secret",
+            "switch_profile",
+            "update",
+        ])
+        .events(&["UpdateEvent", "WidgetConfigEvent"])
         .build();
 }
@@ -0,0 +1,46 @@
+//! Rotated backups of the settings file.
+//!
+//! Every time the settings are dumped, the file being replaced is kept as the
+//! most recent backup, shifting older backups down and dropping the oldest
+//! beyond [`MAX_BACKUPS`]. Backups sit next to the settings file, numbered
+//! from 1 for the most recent, e.g., `settings.json.1.bak`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+/// The maximum number of backups kept.
+pub const MAX_BACKUPS: usize = 3;
+
+/// Get the path of a backup of a settings file.
+///
+/// The index starts from 1 for the most recent backup.
+pub fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{index}.bak"));
+    path.with_file_name(name)
+}
+
+/// Rotate the backups of a settings file.
+///
+/// The current settings file, if any, becomes the most recent backup. It is
+/// copied rather than moved, so that the settings file stays in place until
+/// it is atomically replaced.
+pub fn rotate(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let oldest = backup_path(path, MAX_BACKUPS);
+    if oldest.exists() {
+        std::fs::remove_file(&oldest)?;
+    }
+    for index in (1..MAX_BACKUPS).rev() {
+        let backup = backup_path(path, index);
+        if backup.exists() {
+            std::fs::rename(&backup, backup_path(path, index + 1))?;
+        }
+    }
+    std::fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
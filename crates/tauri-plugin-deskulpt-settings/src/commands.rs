@@ -1,18 +1,183 @@
 //! Tauri commands.
 #![doc = include_str!("../permissions/autogenerated/reference.md")]
 
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
 use deskulpt_common::SerResult;
-use tauri::{AppHandle, Runtime};
+use deskulpt_common::command::instrument;
+use deskulpt_common::window::DeskulptWindow;
+use serde_json::Value;
+use tauri::{AppHandle, Runtime, WebviewWindow};
 
 use crate::SettingsExt;
-use crate::model::SettingsPatch;
+use crate::model::{MergeStrategy, ProfileList, SettingsPatch};
 
 /// Update the settings with a patch.
 ///
 /// Wrapper of [`crate::SettingsManager::update`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn update<R: Runtime>(app_handle: AppHandle<R>, patch: SettingsPatch) -> SerResult<()> {
     app_handle.settings().update(patch)?;
     Ok(())
 }
+
+/// Restore the settings from a rotated backup.
+///
+/// Wrapper of [`crate::SettingsManager::restore_backup`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_settings_backup<R: Runtime>(
+    app_handle: AppHandle<R>,
+    index: usize,
+) -> SerResult<()> {
+    app_handle.settings().restore_backup(index)?;
+    Ok(())
+}
+
+/// Export the current settings to a file.
+///
+/// Wrapper of [`crate::SettingsManager::export`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn export_settings<R: Runtime>(app_handle: AppHandle<R>, path: PathBuf) -> SerResult<()> {
+    app_handle.settings().export(&path)?;
+    Ok(())
+}
+
+/// Import settings from a file.
+///
+/// Wrapper of [`crate::SettingsManager::import`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn import_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: PathBuf,
+    merge_strategy: MergeStrategy,
+) -> SerResult<()> {
+    app_handle.settings().import(&path, merge_strategy)?;
+    Ok(())
+}
+
+/// Get the configuration of a widget.
+///
+/// Wrapper of [`crate::SettingsManager::widget_config`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn get_widget_config<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> SerResult<Value> {
+    Ok(app_handle.settings().widget_config(&id))
+}
+
+/// Patch the configuration of a widget.
+///
+/// Wrapper of [`crate::SettingsManager::patch_widget_config`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn patch_widget_config<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    patch: Value,
+) -> SerResult<()> {
+    app_handle.settings().patch_widget_config(&id, patch)?;
+    Ok(())
+}
+
+/// Check whether the settings are locked.
+///
+/// Wrapper of [`crate::SettingsManager::is_locked`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn is_locked<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<bool> {
+    Ok(app_handle.settings().is_locked())
+}
+
+/// List the available settings profiles.
+///
+/// Wrapper of [`crate::SettingsManager::list_profiles`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn list_profiles<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<ProfileList> {
+    let settings = app_handle.settings();
+    Ok(ProfileList {
+        active: settings.active_profile(),
+        profiles: settings.list_profiles()?,
+    })
+}
+
+/// Create a new settings profile as a copy of the current settings.
+///
+/// Wrapper of [`crate::SettingsManager::create_profile`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn create_profile<R: Runtime>(app_handle: AppHandle<R>, name: String) -> SerResult<()> {
+    app_handle.settings().create_profile(&name)?;
+    Ok(())
+}
+
+/// Switch to another settings profile.
+///
+/// Wrapper of [`crate::SettingsManager::switch_profile`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn switch_profile<R: Runtime>(app_handle: AppHandle<R>, name: String) -> SerResult<()> {
+    app_handle.settings().switch_profile(&name)?;
+    Ok(())
+}
+
+/// Ensure that secrets are accessed from the portal.
+///
+/// Secrets are never exposed to the canvas, where arbitrary widget code runs.
+fn ensure_portal<R: Runtime>(window: &WebviewWindow<R>) -> Result<()> {
+    if window.label() != DeskulptWindow::Portal.as_ref() {
+        bail!("Secrets are only accessible from the portal");
+    }
+    Ok(())
+}
+
+/// Get a secret by its name.
+///
+/// Wrapper of [`crate::SettingsManager::secret`]. This is only allowed from
+/// the portal.
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn get_secret<R: Runtime>(
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    name: String,
+) -> SerResult<Option<String>> {
+    ensure_portal(&window)?;
+    Ok(app_handle.settings().secret(&name)?)
+}
+
+/// Set or remove a secret by its name.
+///
+/// Wrapper of [`crate::SettingsManager::set_secret`]. This is only allowed
+/// from the portal.
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn set_secret<R: Runtime>(
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    name: String,
+    secret: Option<String>,
+) -> SerResult<()> {
+    ensure_portal(&window)?;
+    app_handle.settings().set_secret(&name, secret.as_deref())?;
+    Ok(())
+}
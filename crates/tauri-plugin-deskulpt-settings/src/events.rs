@@ -2,9 +2,25 @@
 
 use deskulpt_common::event::Event;
 use serde::Serialize;
+use serde_json::Value;
 
 use crate::model::Settings;
 
 /// Event for notifying frontend windows of a settings update.
 #[derive(Debug, Serialize, specta::Type, Event)]
 pub struct UpdateEvent<'a>(pub &'a Settings);
+
+/// Event for notifying frontend windows of a widget configuration change.
+///
+/// Unlike [`UpdateEvent`], this carries only the configuration of the owning
+/// widget, so that each widget can listen for changes to its own
+/// configuration.
+#[derive(Debug, Serialize, specta::Type, Event)]
+pub struct WidgetConfigEvent<'a> {
+    /// The ID of the widget.
+    pub id: &'a str,
+    /// The new configuration of the widget.
+    ///
+    /// This is `null` if the configuration has been removed.
+    pub config: &'a Value,
+}
@@ -4,10 +4,15 @@
     html_favicon_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/public/deskulpt.svg"
 )]
 
+mod backup;
 mod commands;
-mod events;
+pub mod events;
 mod manager;
+mod migrate;
 pub mod model;
+pub mod overrides;
+pub mod profile;
+mod secrets;
 mod worker;
 
 pub use manager::SettingsManager;
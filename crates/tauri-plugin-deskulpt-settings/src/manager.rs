@@ -1,16 +1,26 @@
 //! Deskulpt settings manager and its APIs.
 
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use anyhow::{Result, anyhow, bail};
 use deskulpt_common::event::Event;
-use parking_lot::{RwLock, RwLockReadGuard};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use serde_json::Value;
 use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::oneshot;
 use url::Url;
 
-use crate::events::UpdateEvent;
-use crate::model::{CanvasImode, Settings, SettingsPatch, ShortcutAction, Theme};
+use crate::events::{UpdateEvent, WidgetConfigEvent};
+use crate::model::{
+    CanvasImode, MergeStrategy, Settings, SettingsPatch, ShortcutAction, Theme, WidgetPermissions,
+    merge_patch,
+};
+use crate::overrides::Overrides;
+use crate::secrets::Cipher;
 use crate::worker::{WorkerHandle, WorkerTask};
+use crate::{backup, profile};
 
 #[doc(hidden)]
 type OnThemeChange = Box<dyn Fn(&Theme, &Theme) + Send + Sync>;
@@ -18,10 +28,25 @@ type OnThemeChange = Box<dyn Fn(&Theme, &Theme) + Send + Sync>;
 #[doc(hidden)]
 type OnCanvasImodeChange = Box<dyn Fn(&CanvasImode, &CanvasImode) + Send + Sync>;
 
+#[doc(hidden)]
+type OnCustomCssChange = Box<dyn Fn(Option<&String>, Option<&String>) + Send + Sync>;
+
+#[doc(hidden)]
+type OnLocaleChange = Box<dyn Fn(Option<&String>, Option<&String>) + Send + Sync>;
+
+#[doc(hidden)]
+type OnLogFilterChange = Box<dyn Fn(Option<&String>, Option<&String>) + Send + Sync>;
+
+#[doc(hidden)]
+type OnProfileChange = Box<dyn Fn(&str, &str) + Send + Sync>;
+
 #[doc(hidden)]
 type OnShortcutChange =
     Box<dyn Fn(&ShortcutAction, Option<&String>, Option<&String>) + Send + Sync>;
 
+#[doc(hidden)]
+type OnWatchTick = Box<dyn Fn() + Send + Sync>;
+
 /// The collection of hooks on settings change.
 #[derive(Default)]
 struct SettingsHooks {
@@ -37,18 +62,69 @@ struct SettingsHooks {
     ///
     /// See [`SettingsManager::on_shortcut_change`] for registration.
     on_shortcut_change: Vec<OnShortcutChange>,
+    /// Hooks triggered on custom CSS change.
+    ///
+    /// See [`SettingsManager::on_custom_css_change`] for registration.
+    on_custom_css_change: Vec<OnCustomCssChange>,
+    /// Hooks triggered on locale change.
+    ///
+    /// See [`SettingsManager::on_locale_change`] for registration.
+    on_locale_change: Vec<OnLocaleChange>,
+    /// Hooks triggered on log filter change.
+    ///
+    /// See [`SettingsManager::on_log_filter_change`] for registration.
+    on_log_filter_change: Vec<OnLogFilterChange>,
+    /// Hooks triggered on profile change.
+    ///
+    /// See [`SettingsManager::on_profile_change`] for registration.
+    on_profile_change: Vec<OnProfileChange>,
+    /// Hooks triggered on each tick of watching for external edits.
+    ///
+    /// See [`SettingsManager::on_watch_tick`] for registration.
+    on_watch_tick: Vec<OnWatchTick>,
+}
+
+/// The stamp of a settings file, i.e., its modification time and size.
+///
+/// This is used to tell whether the settings file has been changed since it was
+/// last loaded or persisted.
+type FileStamp = (SystemTime, u64);
+
+/// Get the stamp of a file, or `None` if it cannot be determined.
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
 }
 
 /// Manager for Deskulpt settings.
 pub struct SettingsManager<R: Runtime> {
     /// The Tauri app handle.
     app_handle: AppHandle<R>,
-    /// The path where settings are persisted.
-    persist_path: PathBuf,
+    /// The directory where settings of all profiles are persisted.
+    dir: PathBuf,
+    /// The active profile.
+    profile: RwLock<String>,
     /// The URL to the settings schema file.
     schema_url: String,
+    /// The stamp of the settings file when last loaded or persisted.
+    ///
+    /// See [`Self::reload_if_changed`] for how this is used to detect external
+    /// edits.
+    persisted_stamp: Mutex<Option<FileStamp>>,
     /// The Deskulpt settings.
     settings: RwLock<Settings>,
+    /// The persisted theme shadowed by [`Overrides::theme`], if overridden.
+    ///
+    /// Theme updates are redirected here while overridden, so that the
+    /// override stays in effect but is never persisted.
+    shadowed_theme: Mutex<Option<Theme>>,
+    /// Whether the settings are locked by [`Overrides::locked`].
+    locked_by_override: bool,
+    /// The cipher for secrets.
+    ///
+    /// This is initialized on first access to secrets, since accessing the OS
+    /// keychain may prompt the user.
+    cipher: Mutex<Option<Cipher>>,
     /// The handle for the worker.
     worker: WorkerHandle,
     /// The collection of hooks on settings change.
@@ -58,19 +134,24 @@ pub struct SettingsManager<R: Runtime> {
 impl<R: Runtime> SettingsManager<R> {
     /// Initialize the [`SettingsManager`].
     ///
-    /// The settings are loaded from disk. If loading fails (which means
-    /// corrupted settings), default settings are used. A worker is started
-    /// immediately.
+    /// The settings of the last active profile are loaded from disk. If
+    /// loading fails (which means corrupted settings), default settings are
+    /// used. [`Overrides`] managed by the app are applied on top as a
+    /// non-persisted overlay. A worker is started immediately.
     pub fn new(app_handle: AppHandle<R>) -> Result<Self> {
-        let persist_path = app_handle
-            .path()
-            .app_local_data_dir()?
-            .join("settings.json");
+        let dir = app_handle.path().app_local_data_dir()?;
+        let profile = profile::read_active(&dir);
+        let persist_path = profile::settings_path(&dir, &profile);
 
-        let settings = Settings::load(&persist_path).unwrap_or_else(|e| {
+        let mut settings = Settings::load(&persist_path).unwrap_or_else(|e| {
             tracing::error!("Failed to load settings: {e:?}");
             Default::default()
         });
+        let overrides = Overrides::of(&app_handle);
+        let shadowed_theme = overrides
+            .theme
+            .map(|theme| std::mem::replace(&mut settings.theme, theme));
+        let persisted_stamp = file_stamp(&persist_path);
 
         let schema_path = app_handle
             .path()
@@ -86,9 +167,14 @@ impl<R: Runtime> SettingsManager<R> {
 
         Ok(Self {
             app_handle,
-            persist_path,
+            dir,
+            profile: RwLock::new(profile),
             schema_url,
+            persisted_stamp: Mutex::new(persisted_stamp),
             settings: RwLock::new(settings),
+            shadowed_theme: Mutex::new(shadowed_theme),
+            locked_by_override: overrides.locked,
+            cipher: Mutex::new(None),
             worker,
             hooks: RwLock::new(Default::default()),
         })
@@ -113,18 +199,101 @@ impl<R: Runtime> SettingsManager<R> {
         self.settings.try_read()
     }
 
-    /// Get the path where settings are persisted.
-    pub fn persist_path(&self) -> &Path {
-        &self.persist_path
+    /// Whether the settings are locked.
+    ///
+    /// This is the case if [`Settings::locked`] is set or Deskulpt is launched
+    /// locked via [`Overrides::locked`]. Layout and shortcut changes are
+    /// rejected when locked.
+    ///
+    /// Tauri command: [`crate::commands::is_locked`].
+    pub fn is_locked(&self) -> bool {
+        self.locked_by_override || self.settings.read().locked
+    }
+
+    /// Get the path where settings of the active profile are persisted.
+    pub fn persist_path(&self) -> PathBuf {
+        profile::settings_path(&self.dir, &self.profile.read())
+    }
+
+    /// Get the settings with overrides reverted, i.e., as they are persisted.
+    fn without_overrides<'a>(&self, settings: &'a Settings) -> Cow<'a, Settings> {
+        match &*self.shadowed_theme.lock() {
+            Some(theme) => {
+                let mut settings = settings.clone();
+                settings.theme = theme.clone();
+                Cow::Owned(settings)
+            },
+            None => Cow::Borrowed(settings),
+        }
     }
 
     /// Persist the current settings to disk.
+    ///
+    /// Overrides are not persisted; see [`Overrides`].
     pub fn persist(&self) -> Result<()> {
+        let mut persisted_stamp = self.persisted_stamp.lock();
         let settings = self.settings.read();
-        settings.dump(&self.persist_path, &self.schema_url)?;
+        let persist_path = self.persist_path();
+        self.without_overrides(&settings)
+            .dump(&persist_path, &self.schema_url)?;
+        *persisted_stamp = file_stamp(&persist_path);
         Ok(())
     }
 
+    /// Reload the settings if the settings file has been edited externally.
+    ///
+    /// The settings file is considered edited if its stamp differs from the
+    /// one when it was last loaded or persisted by Deskulpt. The reloaded
+    /// settings are applied as a patch via [`Self::update_with`], so that only
+    /// actual changes go through the same hooks and events as in-app updates.
+    /// A removed settings file is left alone, so that it is recreated on the
+    /// next persist.
+    ///
+    /// The file is not reloaded again until it changes, even if it fails to
+    /// load, e.g., because it is in the middle of being edited.
+    pub fn reload_if_changed(&self) -> Result<()> {
+        let reloaded = {
+            let mut persisted_stamp = self.persisted_stamp.lock();
+            let persist_path = self.persist_path();
+            let stamp = file_stamp(&persist_path);
+            if stamp.is_none() || stamp == *persisted_stamp {
+                return Ok(());
+            }
+            *persisted_stamp = stamp;
+            Settings::load(&persist_path)?
+        };
+        tracing::info!("Settings file edited externally; reloading");
+        self.update_with(|settings| reloaded.into_patch(settings))
+    }
+
+    /// Flush pending changes to disk.
+    ///
+    /// Changes to the settings are persisted in the background after a short
+    /// debounce. This instructs the worker to persist pending changes right
+    /// away instead, e.g., when a Deskulpt window is hidden and the app might
+    /// be left idle or killed. It does not block and is a no-op if there are no
+    /// pending changes.
+    pub fn flush(&self) -> Result<()> {
+        self.worker.process(WorkerTask::Flush)
+    }
+
+    /// Check whether the background worker is responsive.
+    ///
+    /// The returned receiver resolves once the worker processes the ping, so
+    /// a caller should wait on it with a timeout.
+    pub fn ping_worker(&self) -> Result<oneshot::Receiver<()>> {
+        self.worker.ping()
+    }
+
+    /// Restart the background worker, e.g., if it stops responding.
+    ///
+    /// Tasks queued to the old worker are dropped, so a persist is requested
+    /// again in case one was pending.
+    pub fn restart_worker(&self) -> Result<()> {
+        self.worker.restart(self.app_handle.clone());
+        self.worker.process(WorkerTask::Persist)
+    }
+
     /// Register a hook that will be triggered on theme change.
     ///
     /// The two arguments are respectively the old and new themes.
@@ -191,13 +360,117 @@ impl<R: Runtime> SettingsManager<R> {
         }
     }
 
+    /// Register a hook that will be triggered on custom CSS change.
+    ///
+    /// The two arguments are respectively the old and new paths to the custom
+    /// CSS file. `None` means that no custom CSS was/is configured.
+    pub fn on_custom_css_change<F>(&self, hook: F)
+    where
+        F: Fn(Option<&String>, Option<&String>) + Send + Sync + 'static,
+    {
+        let mut hooks = self.hooks.write();
+        hooks.on_custom_css_change.push(Box::new(hook));
+    }
+
+    /// Trigger all registered custom CSS change hooks.
+    pub(crate) fn trigger_custom_css_hooks(&self, old: Option<&String>, new: Option<&String>) {
+        let hooks = self.hooks.read();
+        for hook in &hooks.on_custom_css_change {
+            hook(old, new);
+        }
+    }
+
+    /// Register a hook that will be triggered on locale change.
+    ///
+    /// The two arguments are respectively the old and new locales. `None`
+    /// means that no locale was/is configured.
+    pub fn on_locale_change<F>(&self, hook: F)
+    where
+        F: Fn(Option<&String>, Option<&String>) + Send + Sync + 'static,
+    {
+        let mut hooks = self.hooks.write();
+        hooks.on_locale_change.push(Box::new(hook));
+    }
+
+    /// Trigger all registered locale change hooks.
+    pub(crate) fn trigger_locale_hooks(&self, old: Option<&String>, new: Option<&String>) {
+        let hooks = self.hooks.read();
+        for hook in &hooks.on_locale_change {
+            hook(old, new);
+        }
+    }
+
+    /// Register a hook that will be triggered on log filter change.
+    ///
+    /// The two arguments are respectively the old and new log filters. `None`
+    /// means that no log filter was/is configured.
+    pub fn on_log_filter_change<F>(&self, hook: F)
+    where
+        F: Fn(Option<&String>, Option<&String>) + Send + Sync + 'static,
+    {
+        let mut hooks = self.hooks.write();
+        hooks.on_log_filter_change.push(Box::new(hook));
+    }
+
+    /// Trigger all registered log filter change hooks.
+    pub(crate) fn trigger_log_filter_hooks(&self, old: Option<&String>, new: Option<&String>) {
+        let hooks = self.hooks.read();
+        for hook in &hooks.on_log_filter_change {
+            hook(old, new);
+        }
+    }
+
+    /// Register a hook that will be triggered on profile change.
+    ///
+    /// The two arguments are respectively the old and new profiles. Data that
+    /// belongs to a profile should be persisted to the old profile and loaded
+    /// from the new profile, see [`profile::file_name`].
+    pub fn on_profile_change<F>(&self, hook: F)
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        let mut hooks = self.hooks.write();
+        hooks.on_profile_change.push(Box::new(hook));
+    }
+
+    /// Trigger all registered profile change hooks.
+    pub(crate) fn trigger_profile_hooks(&self, old: &str, new: &str) {
+        let hooks = self.hooks.read();
+        for hook in &hooks.on_profile_change {
+            hook(old, new);
+        }
+    }
+
+    /// Register a hook that will be triggered on each tick of watching the
+    /// settings file for external edits.
+    ///
+    /// This allows other files referenced by the settings to be watched on the
+    /// same schedule without polling on their own. The hook runs on the
+    /// settings worker, so it should be cheap.
+    pub fn on_watch_tick<F>(&self, hook: F)
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        let mut hooks = self.hooks.write();
+        hooks.on_watch_tick.push(Box::new(hook));
+    }
+
+    /// Trigger all registered watch tick hooks.
+    pub(crate) fn trigger_watch_tick_hooks(&self) {
+        let hooks = self.hooks.read();
+        for hook in &hooks.on_watch_tick {
+            hook();
+        }
+    }
+
     /// Update the settings with a patch generated by a closure.
     ///
     /// The closure is given an immutable reference to the current settings and
     /// must return a [`SettingsPatch`] that describes the changes to be made.
     /// See its documentation for details on how settings patching works. If any
     /// actual changes are made, an [`UpdateEvent`] will be emitted with the
-    /// updated settings.
+    /// updated settings. If the settings are locked, see [`Self::is_locked`],
+    /// patches that change shortcuts are rejected as a whole.
     ///
     /// The registered hooks for changed settings will be triggered by the
     /// worker asynchronously. This is done at best effort, meaning that one
@@ -210,12 +483,36 @@ impl<R: Runtime> SettingsManager<R> {
         F: FnOnce(&Settings) -> SettingsPatch,
     {
         let mut settings = self.settings.write();
-        let patch = patch(&settings);
+        let mut patch = patch(&settings);
+
+        if self.locked_by_override || settings.locked {
+            if let Some(shortcuts) = &patch.shortcuts
+                && shortcuts
+                    .iter()
+                    .any(|(action, shortcut)| settings.shortcuts.get(action) != shortcut.as_ref())
+            {
+                bail!("Shortcuts cannot be changed while settings are locked");
+            }
+            if self.locked_by_override && patch.locked == Some(false) {
+                bail!("Settings are locked for this launch and cannot be unlocked");
+            }
+        }
 
         let mut tasks = vec![];
         let mut should_emit = false; // Should emit; implies should persist
         let mut should_persist = false; // Should persist only
 
+        // Redirect theme updates to the shadowed theme while overridden
+        let mut shadowed_theme = self.shadowed_theme.lock();
+        if let Some(shadowed_theme) = shadowed_theme.as_mut()
+            && let Some(theme) = patch.theme.take()
+            && *shadowed_theme != theme
+        {
+            *shadowed_theme = theme;
+            should_persist = true;
+        }
+        drop(shadowed_theme);
+
         if let Some(theme) = patch.theme
             && settings.theme != theme
         {
@@ -255,6 +552,224 @@ impl<R: Runtime> SettingsManager<R> {
             }
         }
 
+        if let Some(custom_css) = patch.custom_css
+            && settings.custom_css != custom_css
+        {
+            let old_custom_css = std::mem::replace(&mut settings.custom_css, custom_css.clone());
+            tasks.push(WorkerTask::CustomCssChanged {
+                old: old_custom_css,
+                new: custom_css,
+            });
+            should_emit = true;
+        }
+
+        if let Some(locale) = patch.locale
+            && settings.locale != locale
+        {
+            let old_locale = std::mem::replace(&mut settings.locale, locale.clone());
+            tasks.push(WorkerTask::LocaleChanged {
+                old: old_locale,
+                new: locale,
+            });
+            should_emit = true;
+        }
+
+        if let Some(remote_control_port) = patch.remote_control_port
+            && settings.remote_control_port != remote_control_port
+        {
+            settings.remote_control_port = remote_control_port;
+            should_emit = true;
+        }
+
+        if let Some(render_timeout_seconds) = patch.render_timeout_seconds
+            && settings.render_timeout_seconds != render_timeout_seconds
+        {
+            settings.render_timeout_seconds = render_timeout_seconds;
+            should_emit = true;
+        }
+
+        if let Some(render_concurrency) = patch.render_concurrency
+            && settings.render_concurrency != render_concurrency
+        {
+            settings.render_concurrency = render_concurrency;
+            should_emit = true;
+        }
+
+        if let Some(type_check_widgets) = patch.type_check_widgets
+            && settings.type_check_widgets != type_check_widgets
+        {
+            settings.type_check_widgets = type_check_widgets;
+            should_emit = true;
+        }
+
+        if let Some(bundle_size_budget_kib) = patch.bundle_size_budget_kib
+            && settings.bundle_size_budget_kib != bundle_size_budget_kib
+        {
+            settings.bundle_size_budget_kib = bundle_size_budget_kib;
+            should_emit = true;
+        }
+
+        if let Some(strict_widgets) = patch.strict_widgets
+            && settings.strict_widgets != strict_widgets
+        {
+            settings.strict_widgets = strict_widgets;
+            should_emit = true;
+        }
+
+        if let Some(auto_update_widgets) = patch.auto_update_widgets
+            && settings.auto_update_widgets != auto_update_widgets
+        {
+            settings.auto_update_widgets = auto_update_widgets;
+            should_emit = true;
+        }
+
+        if let Some(registries) = patch.registries
+            && settings.registries != registries
+        {
+            settings.registries = registries;
+            should_emit = true;
+        }
+
+        if let Some(registry_mirrors) = patch.registry_mirrors
+            && settings.registry_mirrors != registry_mirrors
+        {
+            settings.registry_mirrors = registry_mirrors;
+            should_emit = true;
+        }
+
+        if let Some(registry_proxy) = patch.registry_proxy
+            && settings.registry_proxy != registry_proxy
+        {
+            settings.registry_proxy = registry_proxy;
+            should_emit = true;
+        }
+
+        if let Some(registry_ca_bundle) = patch.registry_ca_bundle
+            && settings.registry_ca_bundle != registry_ca_bundle
+        {
+            settings.registry_ca_bundle = registry_ca_bundle;
+            should_emit = true;
+        }
+
+        if let Some(trusted_publishers) = patch.trusted_publishers
+            && settings.trusted_publishers != trusted_publishers
+        {
+            settings.trusted_publishers = trusted_publishers;
+            should_emit = true;
+        }
+
+        if let Some(unsigned_widgets) = patch.unsigned_widgets
+            && settings.unsigned_widgets != unsigned_widgets
+        {
+            settings.unsigned_widgets = unsigned_widgets;
+            should_emit = true;
+        }
+
+        if let Some(report_endpoint) = patch.report_endpoint
+            && settings.report_endpoint != report_endpoint
+        {
+            settings.report_endpoint = report_endpoint;
+            should_emit = true;
+        }
+
+        if let Some(missing_widget_retention_days) = patch.missing_widget_retention_days
+            && settings.missing_widget_retention_days != missing_widget_retention_days
+        {
+            settings.missing_widget_retention_days = missing_widget_retention_days;
+            should_emit = true;
+        }
+
+        if let Some(log_filter) = patch.log_filter
+            && settings.log_filter != log_filter
+        {
+            let old_log_filter = std::mem::replace(&mut settings.log_filter, log_filter.clone());
+            tasks.push(WorkerTask::LogFilterChanged {
+                old: old_log_filter,
+                new: log_filter,
+            });
+            should_emit = true;
+        }
+
+        if let Some(disable_hardware_acceleration) = patch.disable_hardware_acceleration
+            && settings.disable_hardware_acceleration != disable_hardware_acceleration
+        {
+            settings.disable_hardware_acceleration = disable_hardware_acceleration;
+            should_emit = true;
+        }
+
+        if let Some(opaque_canvas) = patch.opaque_canvas
+            && settings.opaque_canvas != opaque_canvas
+        {
+            settings.opaque_canvas = opaque_canvas;
+            should_emit = true;
+        }
+
+        if let Some(attach_to_wallpaper) = patch.attach_to_wallpaper
+            && settings.attach_to_wallpaper != attach_to_wallpaper
+        {
+            settings.attach_to_wallpaper = attach_to_wallpaper;
+            should_emit = true;
+        }
+
+        if let Some(grid_size) = patch.grid_size
+            && settings.grid_size != grid_size
+        {
+            settings.grid_size = grid_size;
+            should_emit = true;
+        }
+
+        if let Some(snap_to_edges) = patch.snap_to_edges
+            && settings.snap_to_edges != snap_to_edges
+        {
+            settings.snap_to_edges = snap_to_edges;
+            should_emit = true;
+        }
+
+        if let Some(locked) = patch.locked
+            && settings.locked != locked
+        {
+            settings.locked = locked;
+            should_emit = true;
+        }
+
+        if let Some(secrets) = patch.secrets {
+            for (name, encrypted) in secrets {
+                let old_encrypted = match &encrypted {
+                    Some(encrypted) => settings.secrets.insert(name, encrypted.clone()),
+                    None => settings.secrets.remove(&name),
+                };
+                if old_encrypted != encrypted {
+                    should_persist = true;
+                }
+            }
+        }
+
+        if let Some(widget_grants) = patch.widget_grants {
+            for (id, grant) in widget_grants {
+                let old_grant = match &grant {
+                    Some(grant) => settings.widget_grants.insert(id, grant.clone()),
+                    None => settings.widget_grants.remove(&id),
+                };
+                if old_grant != grant {
+                    should_persist = true;
+                }
+            }
+        }
+
+        let mut changed_widget_configs = vec![];
+        if let Some(widget_config) = patch.widget_config {
+            for (id, config) in widget_config {
+                let old_config = match &config {
+                    Some(config) => settings.widget_config.insert(id.clone(), config.clone()),
+                    None => settings.widget_config.remove(&id),
+                };
+                if old_config != config {
+                    changed_widget_configs.push((id, config));
+                    should_persist = true;
+                }
+            }
+        }
+
         if let Some(starter_widgets_added) = patch.starter_widgets_added
             && settings.starter_widgets_added != starter_widgets_added
         {
@@ -265,6 +780,13 @@ impl<R: Runtime> SettingsManager<R> {
         if should_emit {
             UpdateEvent(&settings).emit(&self.app_handle)?;
         }
+        for (id, config) in &changed_widget_configs {
+            WidgetConfigEvent {
+                id,
+                config: config.as_ref().unwrap_or(&Value::Null),
+            }
+            .emit(&self.app_handle)?;
+        }
         if should_emit || should_persist {
             tasks.push(WorkerTask::Persist);
         }
@@ -292,6 +814,232 @@ impl<R: Runtime> SettingsManager<R> {
         Ok(())
     }
 
+    /// Restore the settings from a rotated backup.
+    ///
+    /// The index starts from 1 for the most recent backup, i.e., the settings
+    /// before the latest persist. The restored settings are applied as a patch
+    /// via [`Self::update_with`], so that hooks are triggered and the settings
+    /// are persisted as usual. An error is returned if the backup does not
+    /// exist or cannot be loaded.
+    ///
+    /// Tauri command: [`crate::commands::restore_settings_backup`].
+    pub fn restore_backup(&self, index: usize) -> Result<()> {
+        if index == 0 || index > backup::MAX_BACKUPS {
+            bail!(
+                "Invalid settings backup index {index}; expected 1 to {}",
+                backup::MAX_BACKUPS
+            );
+        }
+        let path = backup::backup_path(&self.persist_path(), index);
+        if !path.exists() {
+            bail!("Settings backup {index} does not exist");
+        }
+        let restored = Settings::load(&path)?;
+        self.update_with(|settings| restored.into_patch(settings))
+    }
+
+    /// Get the active profile.
+    pub fn active_profile(&self) -> String {
+        self.profile.read().clone()
+    }
+
+    /// List the available profiles.
+    ///
+    /// The default profile is always listed first.
+    ///
+    /// Tauri command: [`crate::commands::list_profiles`].
+    pub fn list_profiles(&self) -> Result<Vec<String>> {
+        profile::list(&self.dir)
+    }
+
+    /// Create a new profile.
+    ///
+    /// The new profile starts as a copy of the current settings. It does not
+    /// become active until switched to via [`Self::switch_profile`]. An error
+    /// is returned if the profile name is invalid or the profile exists.
+    ///
+    /// Tauri command: [`crate::commands::create_profile`].
+    pub fn create_profile(&self, name: &str) -> Result<()> {
+        profile::validate_name(name)?;
+        let path = profile::settings_path(&self.dir, name);
+        if name == profile::DEFAULT_PROFILE || path.exists() {
+            bail!("Profile {name:?} already exists");
+        }
+        let settings = self.settings.read();
+        self.without_overrides(&settings)
+            .dump(&path, &self.schema_url)
+    }
+
+    /// Switch to another profile.
+    ///
+    /// The current settings are persisted to the active profile first. The
+    /// settings of the new profile are then applied as a patch via
+    /// [`Self::update_with`], so that hooks and events are triggered for
+    /// whatever differs, and hooks registered via [`Self::on_profile_change`]
+    /// are triggered for other data that belongs to profiles. The new profile
+    /// is restored on the next launch. Switching is rejected while the
+    /// settings are locked.
+    ///
+    /// Tauri command: [`crate::commands::switch_profile`].
+    pub fn switch_profile(&self, name: &str) -> Result<()> {
+        if self.is_locked() {
+            bail!("Profiles cannot be switched while settings are locked");
+        }
+        let old = self.active_profile();
+        if old == name {
+            return Ok(());
+        }
+        if name != profile::DEFAULT_PROFILE {
+            profile::validate_name(name)?;
+        }
+        let path = profile::settings_path(&self.dir, name);
+        if name != profile::DEFAULT_PROFILE && !path.exists() {
+            bail!("Profile {name:?} does not exist");
+        }
+
+        self.persist()?;
+        let loaded = Settings::load(&path)?;
+        // The profile is switched while the settings are locked for writing,
+        // so that a concurrent persist never writes the old settings to the
+        // new profile
+        self.update_with(|settings| {
+            *self.profile.write() = name.to_string();
+            // Grants belong to the profile and were approved when granted
+            let widget_grants = loaded.widget_grants_patch(settings);
+            SettingsPatch {
+                widget_grants: Some(widget_grants),
+                ..loaded.into_patch(settings)
+            }
+        })?;
+        *self.persisted_stamp.lock() = file_stamp(&path);
+        profile::write_active(&self.dir, name)?;
+        tracing::info!(%old, new = name, "Switched settings profile");
+
+        UpdateEvent(&self.settings.read()).emit(&self.app_handle)?;
+        self.worker.process(WorkerTask::ProfileChanged {
+            old,
+            new: name.to_string(),
+        })
+    }
+
+    /// Export the current settings to a file.
+    ///
+    /// The exported file can be imported via [`Self::import`], e.g., on another
+    /// machine.
+    ///
+    /// Tauri command: [`crate::commands::export_settings`].
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let settings = self.settings.read();
+        self.without_overrides(&settings)
+            .export(path, &self.schema_url)
+    }
+
+    /// Import settings from a file.
+    ///
+    /// The file is validated against the settings schema and then applied with
+    /// the given merge strategy as a single update, see [`Settings::import`].
+    ///
+    /// Tauri command: [`crate::commands::import_settings`].
+    pub fn import(&self, path: &Path, strategy: MergeStrategy) -> Result<()> {
+        let patch = Settings::import(path, &self.settings.read(), strategy)?;
+        self.update(patch)
+    }
+
+    /// Get the configuration of a widget.
+    ///
+    /// This returns `null` if the widget has no configuration.
+    ///
+    /// Tauri command: [`crate::commands::get_widget_config`].
+    pub fn widget_config(&self, id: &str) -> Value {
+        let settings = self.settings.read();
+        settings.widget_config.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Patch the configuration of a widget.
+    ///
+    /// The patch is a JSON merge patch as per [RFC 7396] applied to the current
+    /// configuration of the widget, via [`Self::update_with`]. Patching the
+    /// configuration to `null` removes it. If the configuration has actually
+    /// changed, a [`WidgetConfigEvent`] will be emitted for the widget.
+    ///
+    /// [RFC 7396]: https://datatracker.ietf.org/doc/html/rfc7396
+    ///
+    /// Tauri command: [`crate::commands::patch_widget_config`].
+    pub fn patch_widget_config(&self, id: &str, patch: Value) -> Result<()> {
+        self.update_with(|settings| {
+            let mut config = settings.widget_config.get(id).cloned().unwrap_or_default();
+            merge_patch(&mut config, patch);
+            let config = (!config.is_null()).then_some(config);
+            SettingsPatch {
+                widget_config: Some([(id.to_string(), config)].into()),
+                ..Default::default()
+            }
+        })
+    }
+
+    /// Run a closure with the cipher for secrets.
+    ///
+    /// The cipher is initialized on first use, see [`Cipher::load_or_create`].
+    fn with_cipher<T>(&self, f: impl FnOnce(&Cipher) -> Result<T>) -> Result<T> {
+        let mut cipher = self.cipher.lock();
+        if cipher.is_none() {
+            *cipher = Some(Cipher::load_or_create()?);
+        }
+        f(cipher.as_ref().unwrap())
+    }
+
+    /// Get a secret by its name.
+    ///
+    /// This returns `None` if the secret does not exist. An error is returned
+    /// if the secret cannot be decrypted, e.g., because the OS keychain is
+    /// inaccessible or the settings file comes from another machine.
+    ///
+    /// Tauri command: [`crate::commands::get_secret`].
+    pub fn secret(&self, name: &str) -> Result<Option<String>> {
+        let encrypted = self.settings.read().secrets.get(name).cloned();
+        let Some(encrypted) = encrypted else {
+            return Ok(None);
+        };
+        self.with_cipher(|cipher| cipher.decrypt(name, &encrypted))
+            .map(Some)
+    }
+
+    /// Set or remove a secret by its name.
+    ///
+    /// The secret is encrypted before it is stored in the settings, see
+    /// [`Settings::secrets`]. `None` means removing the secret.
+    ///
+    /// Tauri command: [`crate::commands::set_secret`].
+    pub fn set_secret(&self, name: &str, secret: Option<&str>) -> Result<()> {
+        if name.is_empty() {
+            bail!("Secret name must not be empty");
+        }
+        let encrypted = secret
+            .map(|secret| self.with_cipher(|cipher| cipher.encrypt(name, secret)))
+            .transpose()?;
+        self.update_with(|_| SettingsPatch {
+            secrets: Some([(name.to_string(), encrypted)].into()),
+            ..Default::default()
+        })
+    }
+
+    /// Get the permissions granted to a widget, if any.
+    ///
+    /// See [`Settings::widget_grants`].
+    pub fn widget_grant(&self, id: &str) -> Option<WidgetPermissions> {
+        self.settings.read().widget_grants.get(id).cloned()
+    }
+
+    /// Set or revoke the permissions granted to a widget.
+    ///
+    /// See [`Settings::widget_grants`]. `None` means revoking all permissions.
+    pub fn set_widget_grant(&self, id: &str, grant: Option<WidgetPermissions>) -> Result<()> {
+        self.update_with(|_| SettingsPatch {
+            widget_grants: Some([(id.to_string(), grant)].into()),
+            ..Default::default()
+        })
+    }
+
     /// Update the settings with a patch.
     ///
     /// This is a wrapper of [`Self::update_with`] that takes a fixed patch.
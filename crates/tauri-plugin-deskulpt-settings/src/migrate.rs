@@ -0,0 +1,74 @@
+//! Versioned migrations of the persisted settings.
+//!
+//! The settings file records the version of its shape. Whenever the shape of
+//! [`Settings`](crate::model::Settings) changes in a way that best-effort
+//! deserialization cannot cope with, e.g., a field is renamed or changes its
+//! type, a migration is appended to [`MIGRATIONS`]. Outdated settings files
+//! are brought up to date on load by applying the pending migrations in order,
+//! so that user data is converted instead of silently reset to defaults.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+/// A migration of raw settings from one version to the next.
+type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// The ordered registry of migrations.
+///
+/// The migration at index `i` brings settings from version `i` to `i + 1`,
+/// where version 0 refers to settings files written before versioning was
+/// introduced. Migrations must only ever be appended.
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+
+/// The current version of the settings file.
+pub const CURRENT_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// The key of the version field in the settings file.
+pub const VERSION_KEY: &str = "version";
+
+/// Introduce the version field.
+///
+/// The shape is otherwise unchanged. The version field itself is written when
+/// the settings are dumped next time.
+fn v0_to_v1(_settings: &mut Map<String, Value>) -> Result<()> {
+    Ok(())
+}
+
+/// Get the version of raw settings.
+///
+/// Settings without a valid version field are considered version 0.
+pub fn version_of(settings: &Map<String, Value>) -> u64 {
+    settings
+        .get(VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// Get the path to back up a settings file of the given version to.
+///
+/// The backup sits next to the settings file, e.g., `settings.json.v0.bak`.
+pub fn backup_path(path: &Path, version: u64) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{version}.bak"));
+    path.with_file_name(name)
+}
+
+/// Migrate raw settings from the given version to [`CURRENT_VERSION`].
+///
+/// The pending migrations are applied in order. Settings from a newer version
+/// are left untouched, since migrations cannot be reverted. An error is
+/// returned if any migration fails, in which case the settings may be partially
+/// migrated.
+pub fn migrate(settings: &mut Map<String, Value>, version: u64) -> Result<()> {
+    if version >= CURRENT_VERSION {
+        return Ok(());
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(settings)
+            .with_context(|| format!("Failed to migrate settings from version {from}"))?;
+    }
+    settings.insert(VERSION_KEY.to_string(), CURRENT_VERSION.into());
+    Ok(())
+}
@@ -2,13 +2,16 @@
 
 use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufReader, BufWriter, Write};
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_with::{DefaultOnError, MapSkipError, serde_as};
+use serde_json::Value;
+use serde_with::{DefaultOnError, MapSkipError, VecSkipError, serde_as};
+
+use crate::{backup, migrate};
 
 /// The light/dark theme of the application interface.
 #[derive(
@@ -36,8 +39,8 @@ pub enum CanvasImode {
     Auto,
     /// Sink mode.
     ///
-    /// The canvas is click-through. Widgets are not interactable. The desktop
-    /// is interactable.
+    /// The canvas is click-through. Widgets are not interactable, except those
+    /// set to be always interactive. The desktop is interactable.
     Sink,
     /// Float mode.
     ///
@@ -56,11 +59,191 @@ pub enum ShortcutAction {
     ToggleCanvasImode,
     /// Open Deskulpt portal.
     OpenPortal,
+    /// Undo the most recent layout change of widgets.
+    UndoLayoutChange,
+    /// Redo the most recently undone layout change of widgets.
+    RedoLayoutChange,
+    /// Refresh all widgets.
+    RefreshAllWidgets,
+    /// Toggle the visibility of all widgets by hiding or showing the canvas.
+    ToggleAllWidgets,
+    /// Switch to the next settings profile, wrapping around.
+    CycleProfile,
+    /// Open the logs viewer in Deskulpt portal.
+    OpenLogsViewer,
+    /// Temporarily switch the canvas to float mode while the shortcut is held.
+    PeekCanvas,
+}
+
+impl ShortcutAction {
+    /// All available actions.
+    ///
+    /// This serves as the registry of actions for discoverability, e.g., by
+    /// external devices that can trigger actions remotely.
+    pub const ALL: &[ShortcutAction] = &[
+        Self::ToggleCanvasImode,
+        Self::OpenPortal,
+        Self::UndoLayoutChange,
+        Self::RedoLayoutChange,
+        Self::RefreshAllWidgets,
+        Self::ToggleAllWidgets,
+        Self::CycleProfile,
+        Self::OpenLogsViewer,
+        Self::PeekCanvas,
+    ];
+}
+
+/// A mirror of a widgets registry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryMirror {
+    /// The URL of the registry index.
+    pub index_url: String,
+    /// The base of the OCI repositories of widget packages.
+    ///
+    /// This is a registry host followed by a namespace, e.g.,
+    /// `ghcr.io/deskulpt-apps/widgets`. Widget packages are expected at
+    /// `{ociBase}/{handle}/{id}`.
+    pub oci_base: String,
+}
+
+/// An additional widgets registry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySource {
+    /// The URL of the registry index.
+    ///
+    /// This also identifies the registry, so it should be unique.
+    pub index_url: String,
+    /// The base of the OCI repositories of widget packages.
+    ///
+    /// See [`RegistryMirror::oci_base`] for the format.
+    pub oci_base: String,
+    /// The name of the secret holding the credentials for the registry.
+    ///
+    /// The secret should be in the format `username:password`, where the
+    /// password may be an access token. It is used for both the index and the
+    /// OCI registry. If not set, the registry is accessed anonymously.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = String)]
+    pub auth_secret: Option<String>,
+    /// The mirrors of the registry, tried in order when it is unreachable.
+    #[serde(default)]
+    pub mirrors: Vec<RegistryMirror>,
+}
+
+/// A trusted publisher of registry widgets.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPublisher {
+    /// The publisher handle.
+    pub handle: String,
+    /// The PEM-encoded cosign public key of the publisher.
+    ///
+    /// Widgets of the publisher are verified against this key on installation,
+    /// and refused if they are not signed by it. Only ECDSA P-256 keys, as
+    /// generated by `cosign generate-key-pair`, are supported.
+    pub public_key: String,
+}
+
+/// How widgets without a trusted signature are treated on installation.
+///
+/// A signature is trusted if it is made by the key of a
+/// [`TrustedPublisher`] with the same handle as the widget.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, specta::Type,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum UnsignedWidgetPolicy {
+    /// Install them silently.
+    Allow,
+    /// Install them, but log a warning.
+    ///
+    /// This is the default, so that publishers without signing set up keep
+    /// working.
+    #[default]
+    Warn,
+    /// Refuse to install them.
+    Block,
+}
+
+/// Permissions of a widget.
+///
+/// Widgets request permissions in their manifests. Registry widgets may only
+/// use the requested permissions that are also granted by the user, see
+/// [`Settings::widget_grants`].
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, specta::Type,
+)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WidgetPermissions {
+    /// The hosts the widget may access over the network.
+    ///
+    /// Each entry is either an exact host name or a wildcard `*.example.com`
+    /// that matches the domain and all its subdomains.
+    ///
+    /// This is advisory until widgets are isolated from each other: the
+    /// network proxy enforces it, and the canvas blocks direct connections
+    /// with its content security policy, but widgets share the canvas and can
+    /// send requests in the name of other widgets.
+    pub network: Vec<String>,
+    /// The paths the widget may access with the `fs` plugin.
+    ///
+    /// Each entry is a path relative to the widget directory, covering the
+    /// path itself and everything under it, e.g., `data`. `.` covers the whole
+    /// widget directory.
+    pub filesystem: Vec<String>,
+    /// The plugin commands the widget may call.
+    ///
+    /// Each entry is either a plugin name covering all its commands, e.g.,
+    /// `sys`, or a plugin name and a command separated by a dot, e.g.,
+    /// `fs.read_file`. Searching files in user directories is covered by
+    /// `search.files`.
+    pub plugins: Vec<String>,
+}
+
+impl WidgetPermissions {
+    /// Whether there are no permissions at all.
+    pub fn is_empty(&self) -> bool {
+        self.network.is_empty() && self.filesystem.is_empty() && self.plugins.is_empty()
+    }
+
+    /// Get the permissions that are in both these and the other permissions.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let keep = |entries: &[String], others: &[String]| {
+            entries
+                .iter()
+                .filter(|entry| others.contains(entry))
+                .cloned()
+                .collect()
+        };
+        Self {
+            network: keep(&self.network, &other.network),
+            filesystem: keep(&self.filesystem, &other.filesystem),
+            plugins: keep(&self.plugins, &other.plugins),
+        }
+    }
+
+    /// Get the permissions that are in these but not the other permissions.
+    pub fn difference(&self, other: &Self) -> Self {
+        let keep = |entries: &[String], others: &[String]| {
+            entries
+                .iter()
+                .filter(|entry| !others.contains(entry))
+                .cloned()
+                .collect()
+        };
+        Self {
+            network: keep(&self.network, &other.network),
+            filesystem: keep(&self.filesystem, &other.filesystem),
+            plugins: keep(&self.plugins, &other.plugins),
+        }
+    }
 }
 
 /// Full settings of the Deskulpt application.
 #[serde_as]
-#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, specta::Type)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, specta::Type)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Settings {
     /// The application theme.
@@ -74,6 +257,201 @@ pub struct Settings {
     /// This maps the actions to the shortcut strings that will trigger them.
     #[serde_as(deserialize_as = "MapSkipError<_, _>")]
     pub shortcuts: BTreeMap<ShortcutAction, String>,
+    /// The path to a user-defined CSS file that overrides the theme.
+    ///
+    /// The stylesheet is injected into Deskulpt windows as a theme layer on
+    /// top of the built-in styles.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub custom_css: Option<String>,
+    /// The locale for displaying widget metadata, as a BCP-47 language tag.
+    ///
+    /// Widget names and descriptions are shown in this locale if the widget
+    /// manifest provides translations, falling back to less specific tags
+    /// (e.g., `zh` for `zh-Hant-TW`) and then to the untranslated values. If
+    /// not set, the untranslated values are always shown.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub locale: Option<String>,
+    /// The local port for remote control by hotkey devices.
+    ///
+    /// If set, a WebSocket server is started on this port of the loopback
+    /// interface, through which devices such as Stream Deck can discover and
+    /// trigger Deskulpt actions. Clients must present the shared token kept
+    /// in the `remote-control-token` secret, and the server is not started
+    /// if the secret is not set. Changes take effect after restart.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub remote_control_port: Option<u16>,
+    /// The timeout in seconds for a widget to finish rendering.
+    ///
+    /// If the canvas does not report completion of evaluating a widget module
+    /// within this window, the widget is reported as timed out. If not set, a
+    /// default timeout of 30 seconds applies. Zero disables the timeout.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub render_timeout_seconds: Option<u64>,
+    /// The maximum number of widgets bundled concurrently.
+    ///
+    /// Renders of the same widget are always processed in order. If not set
+    /// or zero, this defaults to the available parallelism, capped at 4.
+    /// Changes take effect after restart.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub render_concurrency: Option<u32>,
+    /// Whether to type check TypeScript widgets when bundling.
+    ///
+    /// Type errors are reported as warnings alongside the render result and
+    /// do not prevent widgets from rendering. Widgets can override this in
+    /// their manifests. This requires `tsgo` or `tsc` to be installed.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub type_check_widgets: bool,
+    /// The bundle size budget of widgets in KiB.
+    ///
+    /// A warning is emitted whenever the bundle of a widget exceeds this size.
+    /// Widgets can override this in their manifests. If not set, a default
+    /// budget of 1024 KiB applies. Zero disables the budget.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub bundle_size_budget_kib: Option<u64>,
+    /// Whether widgets are in strict mode.
+    ///
+    /// This is meant for widget development. Strict mode performs additional
+    /// checks on widgets, e.g., for usage of deprecated APIs, and reports
+    /// problems as warnings. Widgets can override this in their manifests.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub strict_widgets: bool,
+    /// Whether to automatically update widgets installed from the registry.
+    ///
+    /// If enabled, widgets with available upgrades are updated shortly after
+    /// startup. Each update is validated before it is applied, and widgets
+    /// that fail validation are left unchanged.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub auto_update_widgets: bool,
+    /// The additional widgets registries.
+    ///
+    /// Widgets from these registries are listed alongside those from the
+    /// official registry. If the same widget is in multiple registries, the
+    /// official registry takes precedence, followed by these in order.
+    #[serde_as(deserialize_as = "VecSkipError<_>")]
+    pub registries: Vec<RegistrySource>,
+    /// The mirrors of the official widgets registry.
+    ///
+    /// These are tried in order when the official registry is unreachable,
+    /// e.g., behind a firewall.
+    #[serde_as(deserialize_as = "VecSkipError<_>")]
+    pub registry_mirrors: Vec<RegistryMirror>,
+    /// The proxy for registry traffic.
+    ///
+    /// This is a proxy URL, e.g., `http://proxy.example.com:8080`, for fetching
+    /// registry indices and widget packages. If not set, the system proxy is
+    /// detected and used.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub registry_proxy: Option<String>,
+    /// The path to a PEM file of additional CA certificates.
+    ///
+    /// Certificates in the file are trusted for registry traffic in addition to
+    /// the system roots, e.g., behind proxies that intercept TLS.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub registry_ca_bundle: Option<String>,
+    /// The trusted publishers of registry widgets.
+    ///
+    /// See [`UnsignedWidgetPolicy`] for how their keys are used.
+    #[serde_as(deserialize_as = "VecSkipError<_>")]
+    pub trusted_publishers: Vec<TrustedPublisher>,
+    /// How widgets without a trusted signature are treated on installation.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub unsigned_widgets: UnsignedWidgetPolicy,
+    /// The endpoint for reporting abusive registry widgets.
+    ///
+    /// Reports are posted to this URL as JSON. Reports that cannot be
+    /// submitted, e.g., while offline, are queued and submitted later. If
+    /// not set, widgets cannot be reported.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub report_endpoint: Option<String>,
+    /// The retention window in days for settings of missing widgets.
+    ///
+    /// When a widget goes missing, e.g., because its folder is temporarily
+    /// renamed or still being synced, its settings are kept for this window
+    /// and restored if it reappears. If not set, a default of 7 days applies.
+    /// Zero removes the settings right away.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub missing_widget_retention_days: Option<u64>,
+    /// The filter of recorded logs.
+    ///
+    /// This is a comma-separated list of directives, each being either a
+    /// default level (e.g., `info`) or a level for a target and its children
+    /// (e.g., `deskulpt::widgets=debug`). If not set, all severities are
+    /// recorded. Changes take effect immediately.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub log_filter: Option<String>,
+    /// Whether to disable hardware acceleration of Deskulpt windows.
+    ///
+    /// This may work around rendering artifacts of the transparent canvas on
+    /// some systems, at the cost of performance. It is unsupported on macOS.
+    /// Changes take effect after restart.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub disable_hardware_acceleration: bool,
+    /// Whether to fall back to an opaque canvas.
+    ///
+    /// This may work around rendering artifacts on systems where window
+    /// transparency is broken. The canvas will then cover the desktop with a
+    /// solid background. Changes take effect after restart.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub opaque_canvas: bool,
+    /// Whether to attach the canvas to the desktop wallpaper.
+    ///
+    /// The canvas is then placed behind the desktop icons, so that widgets
+    /// appear as part of the wallpaper and are not hidden by "show desktop".
+    /// If attaching fails, the canvas stays on the bottom of other windows as
+    /// usual. If Explorer restarts, the app is restarted to attach again. It
+    /// is only supported on Windows. Changes take effect after restart.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub attach_to_wallpaper: bool,
+    /// The grid size in pixels to snap widget layouts to.
+    ///
+    /// If set, the edges of widgets are snapped to multiples of this size
+    /// when they are moved or resized. If not set or zero, widgets are not
+    /// snapped to a grid.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub grid_size: Option<u32>,
+    /// Whether to snap widgets to the edges of other widgets.
+    ///
+    /// If enabled, the edges of widgets being moved or resized are snapped to
+    /// nearby edges of other widgets on the canvas, taking precedence over
+    /// the grid, so that widgets can be lined up easily.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub snap_to_edges: bool,
+    /// Whether the settings are locked.
+    ///
+    /// When locked, e.g., on kiosk or demo machines, changes to the layout of
+    /// widgets and to the keyboard shortcuts are rejected, so that the desktop
+    /// setup does not drift. Deskulpt can also be locked regardless of this
+    /// setting by launching it with the `--locked` flag or the
+    /// `DESKULPT_LOCKED` environment variable set to `true`.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub locked: bool,
+    /// The configurations of widgets, keyed by widget ID.
+    ///
+    /// This is where widgets keep their own user configuration. Each widget
+    /// owns the shape of its configuration, which is otherwise opaque to
+    /// Deskulpt.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    #[specta(skip)]
+    pub widget_config: BTreeMap<String, Value>,
+    /// The permissions granted to registry widgets, keyed by widget ID.
+    ///
+    /// Registry widgets may only use the permissions requested in their
+    /// manifests that are also granted here. Local widgets are trusted with
+    /// the permissions they request.
+    #[serde_as(deserialize_as = "MapSkipError<_, _>")]
+    #[specta(skip)]
+    pub widget_grants: BTreeMap<String, WidgetPermissions>,
+    /// The encrypted secrets, keyed by name.
+    ///
+    /// Secrets are encrypted at rest with a key kept in the OS keychain. They
+    /// are only accessible via [`SettingsManager::secret`] and
+    /// [`SettingsManager::set_secret`].
+    ///
+    /// [`SettingsManager::secret`]: crate::SettingsManager::secret
+    /// [`SettingsManager::set_secret`]: crate::SettingsManager::set_secret
+    #[serde_as(deserialize_as = "MapSkipError<_, _>")]
+    #[specta(skip)]
+    pub secrets: BTreeMap<String, String>,
     /// Whether the starter widgets have been added.
     #[serde_as(deserialize_as = "DefaultOnError")]
     #[specta(skip)]
@@ -97,25 +475,215 @@ pub struct SettingsPatch {
     /// or adding that shortcut.
     #[specta(optional, type = BTreeMap<ShortcutAction, Option<String>>)]
     pub shortcuts: Option<BTreeMap<ShortcutAction, Option<String>>>,
+    /// If not `None`, update [`Settings::custom_css`].
+    ///
+    /// `Some(None)` means removing the custom CSS file.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<String>)]
+    pub custom_css: Option<Option<String>>,
+    /// If not `None`, update [`Settings::locale`].
+    ///
+    /// `Some(None)` means showing untranslated widget metadata.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<String>)]
+    pub locale: Option<Option<String>>,
+    /// If not `None`, update [`Settings::remote_control_port`].
+    ///
+    /// `Some(None)` means disabling remote control.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<u16>)]
+    pub remote_control_port: Option<Option<u16>>,
+    /// If not `None`, update [`Settings::render_timeout_seconds`].
+    ///
+    /// `Some(None)` means resetting to the default timeout.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<u64>)]
+    pub render_timeout_seconds: Option<Option<u64>>,
+    /// If not `None`, update [`Settings::render_concurrency`].
+    ///
+    /// `Some(None)` means resetting to the default concurrency.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<u32>)]
+    pub render_concurrency: Option<Option<u32>>,
+    /// If not `None`, update [`Settings::type_check_widgets`].
+    #[specta(optional, type = bool)]
+    pub type_check_widgets: Option<bool>,
+    /// If not `None`, update [`Settings::bundle_size_budget_kib`].
+    ///
+    /// `Some(None)` means resetting to the default budget.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<u64>)]
+    pub bundle_size_budget_kib: Option<Option<u64>>,
+    /// If not `None`, update [`Settings::strict_widgets`].
+    #[specta(optional, type = bool)]
+    pub strict_widgets: Option<bool>,
+    /// If not `None`, update [`Settings::auto_update_widgets`].
+    #[specta(optional, type = bool)]
+    pub auto_update_widgets: Option<bool>,
+    /// If not `None`, update [`Settings::registries`].
+    #[specta(optional, type = Vec<RegistrySource>)]
+    pub registries: Option<Vec<RegistrySource>>,
+    /// If not `None`, update [`Settings::registry_mirrors`].
+    #[specta(optional, type = Vec<RegistryMirror>)]
+    pub registry_mirrors: Option<Vec<RegistryMirror>>,
+    /// If not `None`, update [`Settings::registry_proxy`].
+    ///
+    /// `Some(None)` means using the system proxy.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<String>)]
+    pub registry_proxy: Option<Option<String>>,
+    /// If not `None`, update [`Settings::registry_ca_bundle`].
+    ///
+    /// `Some(None)` means trusting only the system roots.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<String>)]
+    pub registry_ca_bundle: Option<Option<String>>,
+    /// If not `None`, update [`Settings::trusted_publishers`].
+    #[specta(optional, type = Vec<TrustedPublisher>)]
+    pub trusted_publishers: Option<Vec<TrustedPublisher>>,
+    /// If not `None`, update [`Settings::unsigned_widgets`].
+    #[specta(optional, type = UnsignedWidgetPolicy)]
+    pub unsigned_widgets: Option<UnsignedWidgetPolicy>,
+    /// If not `None`, update [`Settings::report_endpoint`].
+    ///
+    /// `Some(None)` means disabling reporting widgets.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<String>)]
+    pub report_endpoint: Option<Option<String>>,
+    /// If not `None`, update [`Settings::missing_widget_retention_days`].
+    ///
+    /// `Some(None)` means resetting to the default retention window.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<u64>)]
+    pub missing_widget_retention_days: Option<Option<u64>>,
+    /// If not `None`, update [`Settings::log_filter`].
+    ///
+    /// `Some(None)` means recording all severities.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<String>)]
+    pub log_filter: Option<Option<String>>,
+    /// If not `None`, update [`Settings::disable_hardware_acceleration`].
+    #[specta(optional, type = bool)]
+    pub disable_hardware_acceleration: Option<bool>,
+    /// If not `None`, update [`Settings::opaque_canvas`].
+    #[specta(optional, type = bool)]
+    pub opaque_canvas: Option<bool>,
+    /// If not `None`, update [`Settings::attach_to_wallpaper`].
+    #[specta(optional, type = bool)]
+    pub attach_to_wallpaper: Option<bool>,
+    /// If not `None`, update [`Settings::grid_size`].
+    ///
+    /// `Some(None)` means disabling grid snapping.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<u32>)]
+    pub grid_size: Option<Option<u32>>,
+    /// If not `None`, update [`Settings::snap_to_edges`].
+    #[specta(optional, type = bool)]
+    pub snap_to_edges: Option<bool>,
+    /// If not `None`, update [`Settings::locked`].
+    #[specta(optional, type = bool)]
+    pub locked: Option<bool>,
+    /// If not `None`, update [`Settings::widget_config`].
+    ///
+    /// Non-specified widgets will remain unchanged. If a configuration value
+    /// is `None`, it means removing that configuration. Otherwise, it means
+    /// replacing or adding that configuration.
+    #[serde(skip)]
+    pub widget_config: Option<BTreeMap<String, Option<Value>>>,
+    /// If not `None`, update [`Settings::widget_grants`].
+    ///
+    /// Non-specified widgets will remain unchanged. If granted permissions are
+    /// `None`, it means revoking all permissions of that widget. Otherwise, it
+    /// means replacing or adding the granted permissions of that widget.
+    #[serde(skip)]
+    pub widget_grants: Option<BTreeMap<String, Option<WidgetPermissions>>>,
+    /// If not `None`, update [`Settings::secrets`].
+    ///
+    /// Non-specified secrets will remain unchanged. If an encrypted secret is
+    /// `None`, it means removing that secret. Otherwise, it means replacing or
+    /// adding that secret.
+    #[serde(skip)]
+    pub secrets: Option<BTreeMap<String, Option<String>>>,
     /// If not `None`, update [`Settings::starter_widgets_added`].
     #[serde(skip)]
     pub starter_widgets_added: Option<bool>,
 }
 
+/// How imported settings are combined with the current settings.
+#[derive(Debug, Clone, Copy, Default, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStrategy {
+    /// Merge the imported settings into the current settings.
+    ///
+    /// Only the settings present in the imported file are applied, and the
+    /// rest are kept as is. Shortcuts are merged per action, and widget
+    /// configurations per widget.
+    #[default]
+    Merge,
+    /// Replace the current settings with the imported settings.
+    ///
+    /// Settings absent from the imported file are reset to their defaults.
+    Replace,
+}
+
+/// The available settings profiles.
+#[derive(Debug, Serialize, specta::Type)]
+pub struct ProfileList {
+    /// The active profile.
+    pub active: String,
+    /// All available profiles, with the default profile first.
+    pub profiles: Vec<String>,
+}
+
+/// The settings with metadata, as written to disk.
+#[derive(Serialize)]
+struct SettingsWithMeta<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'a str,
+    version: u64,
+    #[serde(flatten)]
+    settings: &'a Settings,
+}
+
 impl Settings {
     /// Load the settings from disk.
     ///
     /// Default settings will be returned if the settings file does not exist.
-    /// Corrupted settings file will attempt to recover as much data as
-    /// possible, applying default values for the corrupted parts. However,
-    /// if the file is completely corrupted, an error might still be returned.
+    /// Settings files of older versions are migrated to the current version,
+    /// with the original file backed up beforehand. Corrupted settings file
+    /// will attempt to recover as much data as possible, applying default
+    /// values for the corrupted parts. However, if the file is completely
+    /// corrupted or migration fails, an error might still be returned.
     pub fn load(path: &Path) -> Result<Self> {
         if !path.exists() {
             return Ok(Default::default());
         }
         let file = File::open(path)?;
         let reader = BufReader::new(file);
-        let settings: Settings = serde_json::from_reader(reader)?;
+        let mut value: Value = serde_json::from_reader(reader)?;
+
+        if let Value::Object(raw) = &mut value {
+            let version = migrate::version_of(raw);
+            if version < migrate::CURRENT_VERSION {
+                let backup_path = migrate::backup_path(path, version);
+                std::fs::copy(path, &backup_path)?;
+                migrate::migrate(raw, version)?;
+                tracing::info!(
+                    from = version,
+                    to = migrate::CURRENT_VERSION,
+                    backup = %backup_path.display(),
+                    "Migrated settings",
+                );
+            } else if version > migrate::CURRENT_VERSION {
+                tracing::warn!(
+                    version,
+                    current = migrate::CURRENT_VERSION,
+                    "Settings are from a newer version; loading on a best-effort basis",
+                );
+            }
+        }
+
+        let settings: Settings = serde_json::from_value(value)?;
         Ok(settings)
     }
 
@@ -123,29 +691,239 @@ impl Settings {
     ///
     /// The provided path will be created if it does not exist. The settings
     /// will be serialized in pretty JSON format with `$schema` metadata for
-    /// human readability and editor support.
+    /// human readability and editor support, and the version of the settings
+    /// file for migration.
+    ///
+    /// The settings are first written and synced to a temporary file in the
+    /// same directory, which then atomically replaces the settings file, so a
+    /// crash mid-write never leaves a corrupted settings file behind. The
+    /// replaced settings file is kept as the most recent of the rotated
+    /// backups, see [`SettingsManager::restore_backup`].
+    ///
+    /// [`SettingsManager::restore_backup`]: crate::SettingsManager::restore_backup
     pub fn dump(&self, path: &Path, schema_url: &str) -> Result<()> {
-        #[derive(Serialize)]
-        struct SettingsWithMeta<'a> {
-            #[serde(rename = "$schema")]
-            schema: &'a str,
-            #[serde(flatten)]
-            settings: &'a Settings,
-        }
-
         if let Some(parent) = path.parent()
             && !parent.as_os_str().is_empty()
         {
             std::fs::create_dir_all(parent)?;
         }
 
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let file = File::create(&tmp_path)?;
+        let mut writer = BufWriter::new(file);
+        let settings = SettingsWithMeta {
+            schema: schema_url,
+            version: migrate::CURRENT_VERSION,
+            settings: self,
+        };
+        serde_json::to_writer_pretty(&mut writer, &settings)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+
+        backup::rotate(path)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Export the settings to a file.
+    ///
+    /// The file has the same format as the settings file, so that it can be
+    /// imported on another machine via [`Self::import`]. Unlike [`Self::dump`],
+    /// the file is written in place without backups.
+    pub fn export(&self, path: &Path, schema_url: &str) -> Result<()> {
         let file = File::create(path)?;
-        let writer = BufWriter::new(file);
+        let mut writer = BufWriter::new(file);
         let settings = SettingsWithMeta {
             schema: schema_url,
+            version: migrate::CURRENT_VERSION,
             settings: self,
         };
-        serde_json::to_writer_pretty(writer, &settings)?;
+        serde_json::to_writer_pretty(&mut writer, &settings)?;
+        writer.flush()?;
         Ok(())
     }
+
+    /// Import settings from a file exported via [`Self::export`].
+    ///
+    /// This returns the patch that applies the imported settings to the given
+    /// current settings with the merge strategy. Files of older versions are
+    /// migrated first. Unlike [`Self::load`], the imported settings must be
+    /// valid against the settings schema as a whole, so that a broken file is
+    /// rejected instead of silently resetting parts of the settings.
+    pub fn import(
+        path: &Path,
+        current: &Settings,
+        strategy: MergeStrategy,
+    ) -> Result<SettingsPatch> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let Value::Object(mut raw) = serde_json::from_reader(reader)? else {
+            bail!("Imported settings must be a JSON object");
+        };
+
+        let version = migrate::version_of(&raw);
+        if version > migrate::CURRENT_VERSION {
+            bail!(
+                "Imported settings are from a newer version {version}; expected at most {}",
+                migrate::CURRENT_VERSION
+            );
+        }
+        migrate::migrate(&mut raw, version)?;
+        raw.remove("$schema");
+        raw.remove(migrate::VERSION_KEY);
+
+        let schema = serde_json::to_value(schemars::schema_for!(Settings))?;
+        let validator = jsonschema::validator_for(&schema)?;
+        let instance = Value::Object(raw);
+        let errors = validator
+            .iter_errors(&instance)
+            .map(|e| format!("{}: {e}", e.instance_path))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            bail!("Imported settings are invalid:\n{}", errors.join("\n"));
+        }
+
+        let imported = match strategy {
+            MergeStrategy::Merge => {
+                let mut merged = serde_json::to_value(current)?;
+                if let (Value::Object(base), Value::Object(overlay)) = (&mut merged, instance) {
+                    for (key, value) in overlay {
+                        match (base.get_mut(&key), value) {
+                            (Some(Value::Object(base)), Value::Object(value))
+                                if key == "shortcuts" || key == "widgetConfig" =>
+                            {
+                                base.extend(value);
+                            },
+                            (_, value) => {
+                                base.insert(key, value);
+                            },
+                        }
+                    }
+                }
+                serde_json::from_value::<Settings>(merged)?
+            },
+            MergeStrategy::Replace => serde_json::from_value::<Settings>(instance)?,
+        };
+        Ok(imported.into_patch(current))
+    }
+
+    /// Compute the patch that turns the given settings into these settings.
+    ///
+    /// Internal bookkeeping such as [`Self::starter_widgets_added`] is left
+    /// out of the patch. So are [`Self::secrets`], since they can only be
+    /// decrypted with the key they were encrypted with, and
+    /// [`Self::widget_grants`], so that settings from elsewhere, e.g., an
+    /// imported file, never grant permissions without the user being
+    /// prompted. See [`Self::widget_grants_patch`] for carrying them over.
+    pub fn into_patch(self, current: &Settings) -> SettingsPatch {
+        let mut shortcuts = current
+            .shortcuts
+            .keys()
+            .filter(|action| !self.shortcuts.contains_key(action))
+            .map(|action| (action.clone(), None))
+            .collect::<BTreeMap<_, _>>();
+        shortcuts.extend(
+            self.shortcuts
+                .into_iter()
+                .map(|(action, shortcut)| (action, Some(shortcut))),
+        );
+
+        let mut widget_config = current
+            .widget_config
+            .keys()
+            .filter(|id| !self.widget_config.contains_key(*id))
+            .map(|id| (id.clone(), None))
+            .collect::<BTreeMap<_, _>>();
+        widget_config.extend(
+            self.widget_config
+                .into_iter()
+                .map(|(id, config)| (id, Some(config))),
+        );
+
+        SettingsPatch {
+            theme: Some(self.theme),
+            canvas_imode: Some(self.canvas_imode),
+            shortcuts: Some(shortcuts),
+            custom_css: Some(self.custom_css),
+            locale: Some(self.locale),
+            remote_control_port: Some(self.remote_control_port),
+            render_timeout_seconds: Some(self.render_timeout_seconds),
+            render_concurrency: Some(self.render_concurrency),
+            type_check_widgets: Some(self.type_check_widgets),
+            bundle_size_budget_kib: Some(self.bundle_size_budget_kib),
+            strict_widgets: Some(self.strict_widgets),
+            auto_update_widgets: Some(self.auto_update_widgets),
+            registries: Some(self.registries),
+            registry_mirrors: Some(self.registry_mirrors),
+            registry_proxy: Some(self.registry_proxy),
+            registry_ca_bundle: Some(self.registry_ca_bundle),
+            trusted_publishers: Some(self.trusted_publishers),
+            unsigned_widgets: Some(self.unsigned_widgets),
+            report_endpoint: Some(self.report_endpoint),
+            missing_widget_retention_days: Some(self.missing_widget_retention_days),
+            log_filter: Some(self.log_filter),
+            disable_hardware_acceleration: Some(self.disable_hardware_acceleration),
+            opaque_canvas: Some(self.opaque_canvas),
+            attach_to_wallpaper: Some(self.attach_to_wallpaper),
+            grid_size: Some(self.grid_size),
+            snap_to_edges: Some(self.snap_to_edges),
+            locked: Some(self.locked),
+            widget_config: Some(widget_config),
+            widget_grants: None,
+            secrets: None,
+            starter_widgets_added: None,
+        }
+    }
+
+    /// Compute the patch of [`SettingsPatch::widget_grants`] that turns the
+    /// grants in the given settings into those in these settings.
+    ///
+    /// This is left out of [`Self::into_patch`] and should only be applied
+    /// for settings that the user has already approved, e.g., those of
+    /// another profile.
+    pub fn widget_grants_patch(
+        &self,
+        current: &Settings,
+    ) -> BTreeMap<String, Option<WidgetPermissions>> {
+        let mut widget_grants = current
+            .widget_grants
+            .keys()
+            .filter(|id| !self.widget_grants.contains_key(*id))
+            .map(|id| (id.clone(), None))
+            .collect::<BTreeMap<_, _>>();
+        widget_grants.extend(
+            self.widget_grants
+                .iter()
+                .map(|(id, grant)| (id.clone(), Some(grant.clone()))),
+        );
+        widget_grants
+    }
+}
+
+/// Apply a JSON merge patch to a value.
+///
+/// This follows [RFC 7396](https://datatracker.ietf.org/doc/html/rfc7396):
+/// objects are merged recursively, `null` removes a member, and anything else
+/// replaces the target as a whole.
+pub fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge_patch(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
 }
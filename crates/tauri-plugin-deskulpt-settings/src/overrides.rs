@@ -0,0 +1,136 @@
+//! Launch-time overrides of Deskulpt settings.
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+use tauri::{Manager, Runtime};
+use tracing::Level;
+
+use crate::model::Theme;
+
+/// The prefix of environment variables that override settings.
+const ENV_PREFIX: &str = "DESKULPT_";
+
+/// The names of overridable options.
+///
+/// Each option can be set via an environment variable (e.g., `log-level` via
+/// `DESKULPT_LOG_LEVEL`) or a command line flag (e.g., `--log-level <value>` or
+/// `--log-level=<value>`), with the latter taking precedence.
+const OPTIONS: [&str; 4] = ["theme", "log-level", "log-max-file-size", "widgets-dir"];
+
+/// The names of overridable boolean options.
+///
+/// These are set like [`OPTIONS`] with `true` or `false` as the value, except
+/// that a command line flag without a value (e.g., `--locked`) means `true`.
+const FLAGS: [&str; 1] = ["locked"];
+
+/// Overrides of settings for the current launch.
+///
+/// Overrides are applied by [`crate::SettingsManager`] as an overlay that is
+/// never persisted, which is useful for scripted or CI-like launches. They
+/// should be parsed in the application entry point and managed by the app so
+/// that they are available before any plugin is set up.
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    /// Override of [`crate::model::Settings::theme`].
+    pub theme: Option<Theme>,
+    /// The minimum severity of logs to record.
+    pub log_level: Option<Level>,
+    /// The maximum size of a log file in bytes before it is rotated.
+    pub log_max_file_size: Option<u64>,
+    /// The widgets directory.
+    pub widgets_dir: Option<PathBuf>,
+    /// Whether to lock the settings regardless of
+    /// [`crate::model::Settings::locked`].
+    ///
+    /// Unlike the persisted setting, this cannot be turned off from within
+    /// Deskulpt.
+    pub locked: bool,
+}
+
+impl Overrides {
+    /// Parse overrides from the environment variables and command line flags
+    /// of the current process.
+    ///
+    /// Invalid values are reported to stderr and ignored, since logging is not
+    /// yet available at this point.
+    pub fn from_env() -> Self {
+        Self::parse(std::env::vars(), std::env::args().skip(1))
+    }
+
+    /// Parse overrides from environment variables and command line flags.
+    fn parse(
+        vars: impl IntoIterator<Item = (String, String)>,
+        args: impl IntoIterator<Item = String>,
+    ) -> Self {
+        let mut values = vec![];
+        for (key, value) in vars {
+            if let Some(name) = key.strip_prefix(ENV_PREFIX) {
+                let name = name.to_lowercase().replace('_', "-");
+                if OPTIONS.contains(&name.as_str()) || FLAGS.contains(&name.as_str()) {
+                    values.push((name, value));
+                }
+            }
+        }
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            if OPTIONS.contains(&name) {
+                match value.or_else(|| args.next()) {
+                    Some(value) => values.push((name.to_string(), value)),
+                    None => eprintln!("Missing value for override --{name}"),
+                }
+            } else if FLAGS.contains(&name) {
+                values.push((name.to_string(), value.unwrap_or_else(|| "true".into())));
+            }
+        }
+
+        // Later values take precedence, i.e., flags over environment variables
+        let mut overrides = Self::default();
+        for (name, value) in values {
+            let result = match name.as_str() {
+                "theme" => serde_json::from_value(Value::String(value.clone()))
+                    .map(|theme| overrides.theme = Some(theme))
+                    .map_err(|e| e.to_string()),
+                "log-level" => value
+                    .parse()
+                    .map(|level| overrides.log_level = Some(level))
+                    .map_err(|e| e.to_string()),
+                "log-max-file-size" => value
+                    .parse()
+                    .map(|size| overrides.log_max_file_size = Some(size))
+                    .map_err(|e: std::num::ParseIntError| e.to_string()),
+                "widgets-dir" => {
+                    overrides.widgets_dir = Some(PathBuf::from(&value));
+                    Ok(())
+                },
+                "locked" => value
+                    .parse()
+                    .map(|locked| overrides.locked = locked)
+                    .map_err(|e: std::str::ParseBoolError| e.to_string()),
+                _ => unreachable!(),
+            };
+            if let Err(e) = result {
+                eprintln!("Ignoring invalid override {name}={value:?}: {e}");
+            }
+        }
+        overrides
+    }
+
+    /// Get the overrides managed by the app.
+    ///
+    /// No overrides are returned if they are not managed.
+    pub fn of<R: Runtime, M: Manager<R>>(manager: &M) -> Self {
+        manager
+            .try_state::<Self>()
+            .map(|overrides| overrides.inner().clone())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,107 @@
+//! Named settings profiles.
+//!
+//! Each profile keeps its own settings file next to the others, e.g.,
+//! `settings.office.json` for the profile `office`, while the default profile
+//! keeps the plain `settings.json`. Other data that belongs to a profile, such
+//! as the layout of widgets, follows the same naming scheme via [`file_name`].
+//! The active profile is recorded in a pointer file so that it is restored on
+//! the next launch.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+
+/// The name of the default profile.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The name of the file that records the active profile.
+const ACTIVE_PROFILE_FILE: &str = "active-profile";
+
+/// The maximum length of a profile name.
+const MAX_NAME_LEN: usize = 64;
+
+/// Validate a profile name.
+///
+/// Profile names become part of file names, so they are restricted to ASCII
+/// alphanumerics, `-`, and `_`.
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        bail!("Profile name must be 1 to {MAX_NAME_LEN} characters long");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("Invalid profile name {name:?}; only letters, digits, '-', and '_' are allowed");
+    }
+    Ok(())
+}
+
+/// Get the name of a file that belongs to a profile.
+///
+/// For example, the settings file of the profile `office` is named
+/// `settings.office.json`, while that of the default profile is named
+/// `settings.json`.
+pub fn file_name(stem: &str, extension: &str, profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        format!("{stem}.{extension}")
+    } else {
+        format!("{stem}.{profile}.{extension}")
+    }
+}
+
+/// Get the path of the settings file of a profile in the given directory.
+pub fn settings_path(dir: &Path, profile: &str) -> PathBuf {
+    dir.join(file_name("settings", "json", profile))
+}
+
+/// List the profiles in the given directory.
+///
+/// The default profile is always listed first, followed by other profiles
+/// that have a settings file in alphabetical order.
+pub fn list(dir: &Path) -> Result<Vec<String>> {
+    let mut profiles = vec![];
+    if dir.exists() {
+        for entry in std::fs::read_dir(dir)? {
+            let name = entry?.file_name();
+            let Some(profile) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("settings."))
+                .and_then(|name| name.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            if profile != DEFAULT_PROFILE && validate_name(profile).is_ok() {
+                profiles.push(profile.to_string());
+            }
+        }
+    }
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    Ok(profiles)
+}
+
+/// Read the active profile recorded in the given directory.
+///
+/// The default profile is returned if no valid profile is recorded, or if the
+/// recorded profile no longer exists.
+pub fn read_active(dir: &Path) -> String {
+    let Ok(profile) = std::fs::read_to_string(dir.join(ACTIVE_PROFILE_FILE)) else {
+        return DEFAULT_PROFILE.to_string();
+    };
+    let profile = profile.trim();
+    if profile == DEFAULT_PROFILE
+        || validate_name(profile).is_err()
+        || !settings_path(dir, profile).exists()
+    {
+        return DEFAULT_PROFILE.to_string();
+    }
+    profile.to_string()
+}
+
+/// Record the active profile in the given directory.
+pub fn write_active(dir: &Path, profile: &str) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(ACTIVE_PROFILE_FILE), profile)?;
+    Ok(())
+}
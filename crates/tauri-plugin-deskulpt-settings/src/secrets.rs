@@ -0,0 +1,90 @@
+//! Encrypted secrets in the settings.
+//!
+//! Secrets such as access tokens of widgets and plugins are kept in the
+//! settings file encrypted with ChaCha20-Poly1305, so that they never appear
+//! as plaintext JSON. The key is generated on first use and kept in the OS
+//! keychain, so the settings file alone is not enough to recover the secrets.
+
+use anyhow::{Context, Result, anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+/// The service under which the key is stored in the OS keychain.
+const KEYCHAIN_SERVICE: &str = "io.github.deskulptapps.deskulpt";
+
+/// The account under which the key is stored in the OS keychain.
+const KEYCHAIN_ACCOUNT: &str = "settings-secrets";
+
+/// The length of the nonce prepended to each encrypted secret.
+const NONCE_LEN: usize = 12;
+
+/// The cipher for encrypting and decrypting secrets.
+pub struct Cipher(ChaCha20Poly1305);
+
+impl Cipher {
+    /// Load the key from the OS keychain.
+    ///
+    /// A new key is generated and stored if there is none yet. An error is
+    /// returned if the OS keychain is inaccessible or the stored key is
+    /// malformed.
+    pub fn load_or_create() -> Result<Self> {
+        let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)?;
+        let key = match entry.get_password() {
+            Ok(encoded) => {
+                let bytes = STANDARD.decode(encoded)?;
+                if bytes.len() != size_of::<Key>() {
+                    bail!("Malformed secrets key in the OS keychain");
+                }
+                *Key::from_slice(&bytes)
+            },
+            Err(keyring::Error::NoEntry) => {
+                let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+                entry.set_password(&STANDARD.encode(key))?;
+                tracing::info!("Generated secrets key in the OS keychain");
+                key
+            },
+            Err(e) => return Err(e).context("Failed to access the OS keychain"),
+        };
+        Ok(Self(ChaCha20Poly1305::new(&key)))
+    }
+
+    /// Encrypt a secret.
+    ///
+    /// The name of the secret is authenticated along with it, so that an
+    /// encrypted secret cannot be moved under another name. The result is the
+    /// base64-encoded nonce followed by the ciphertext.
+    pub fn encrypt(&self, name: &str, secret: &str) -> Result<String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: secret.as_bytes(),
+            aad: name.as_bytes(),
+        };
+        let ciphertext = self
+            .0
+            .encrypt(&nonce, payload)
+            .map_err(|_| anyhow!("Failed to encrypt secret {name:?}"))?;
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        Ok(STANDARD.encode(data))
+    }
+
+    /// Decrypt a secret encrypted via [`Self::encrypt`].
+    pub fn decrypt(&self, name: &str, encrypted: &str) -> Result<String> {
+        let data = STANDARD.decode(encrypted)?;
+        if data.len() < NONCE_LEN {
+            bail!("Malformed encrypted secret {name:?}");
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: name.as_bytes(),
+        };
+        let secret = self
+            .0
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| anyhow!("Failed to decrypt secret {name:?}; the key may have changed"))?;
+        Ok(String::from_utf8(secret)?)
+    }
+}
@@ -4,9 +4,11 @@ use std::pin::Pin;
 use std::time::Duration;
 
 use anyhow::Result;
+use parking_lot::Mutex;
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Runtime};
-use tokio::sync::mpsc;
-use tokio::time::{Instant, Sleep};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Instant, Interval, MissedTickBehavior, Sleep};
 
 use crate::SettingsExt;
 use crate::model::{CanvasImode, ShortcutAction, Theme};
@@ -14,16 +16,37 @@ use crate::model::{CanvasImode, ShortcutAction, Theme};
 /// Debounce duration for [`WorkerTask::Persist`].
 const PERSIST_DEBOUNCE: Duration = Duration::from_millis(500);
 
+/// Maximum delay for [`WorkerTask::Persist`].
+///
+/// Continuous persist requests, e.g., while dragging widgets around, keep
+/// resetting the debounce timer. A pending persist is fired no later than this
+/// duration after it was first requested, so that a crash loses at most this
+/// much of changes.
+const PERSIST_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Interval for checking the settings file for external edits.
+///
+/// See [`crate::SettingsManager::reload_if_changed`]. Hooks registered with
+/// [`crate::SettingsManager::on_watch_tick`] are triggered on the same
+/// interval.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
 /// Tasks that the worker can process.
 #[derive(Debug)]
 pub enum WorkerTask {
     /// Persist settings to disk.
     ///
     /// The worker will debounce frequent persist requests within the duration
-    /// [`PERSIST_DEBOUNCE`] into a single persist operation to reduce disk I/O.
-    /// Note that if the channel is closed unexpectedly, pending persists may be
-    /// lost.
+    /// [`PERSIST_DEBOUNCE`] into a single persist operation to reduce disk I/O,
+    /// but no longer than [`PERSIST_MAX_DELAY`] in total. Note that if the
+    /// channel is closed unexpectedly, pending persists may be lost.
     Persist,
+    /// Flush pending persists to disk immediately.
+    ///
+    /// If a [`WorkerTask::Persist`] is pending, the worker will persist the
+    /// settings without waiting for the debounce timer. Otherwise this is a
+    /// no-op.
+    Flush,
     /// Theme has changed.
     ///
     /// The worker will trigger all hooks on theme change.
@@ -40,6 +63,35 @@ pub enum WorkerTask {
         old: Option<String>,
         new: Option<String>,
     },
+    /// Custom CSS has changed.
+    ///
+    /// The worker will trigger all hooks on custom CSS change.
+    CustomCssChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// Locale has changed.
+    ///
+    /// The worker will trigger all hooks on locale change.
+    LocaleChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// Log filter has changed.
+    ///
+    /// The worker will trigger all hooks on log filter change.
+    LogFilterChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// Profile has changed.
+    ///
+    /// The worker will trigger all hooks on profile change.
+    ProfileChanged { old: String, new: String },
+    /// Check whether the worker is responsive.
+    ///
+    /// The worker will reply through the sender once it processes this task.
+    Ping(oneshot::Sender<()>),
 }
 
 /// The worker for processing settings-related tasks.
@@ -48,20 +100,28 @@ struct Worker<R: Runtime> {
     app_handle: AppHandle<R>,
     /// The receiver for incoming tasks.
     rx: mpsc::UnboundedReceiver<WorkerTask>,
-    /// Whether a [`WorkerTask::Persist`] is pending.
-    persist_pending: bool,
+    /// When the pending [`WorkerTask::Persist`] was first requested.
+    ///
+    /// This is `None` if no persist is pending.
+    persist_pending: Option<Instant>,
     /// The debounce timer for [`WorkerTask::Persist`].
     persist_debounce: Pin<Box<Sleep>>,
+    /// The timer for checking the settings file for external edits.
+    watch_interval: Interval,
 }
 
 impl<R: Runtime> Worker<R> {
     /// Create a new [`Worker`] instance.
     fn new(app_handle: AppHandle<R>, rx: mpsc::UnboundedReceiver<WorkerTask>) -> Self {
+        let mut watch_interval =
+            tokio::time::interval_at(Instant::now() + WATCH_INTERVAL, WATCH_INTERVAL);
+        watch_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
         Self {
             app_handle,
             rx,
-            persist_pending: false,
+            persist_pending: None,
             persist_debounce: Box::pin(tokio::time::sleep(PERSIST_DEBOUNCE)),
+            watch_interval,
         }
     }
 
@@ -71,8 +131,15 @@ impl<R: Runtime> Worker<R> {
     async fn run(mut self) {
         loop {
             tokio::select! {
-                _ = &mut self.persist_debounce, if self.persist_pending => {
-                    self.on_persist_deadline();
+                _ = &mut self.persist_debounce, if self.persist_pending.is_some() => {
+                    self.flush();
+                },
+                _ = self.watch_interval.tick() => {
+                    let settings = self.app_handle.settings();
+                    if let Err(e) = settings.reload_if_changed() {
+                        tracing::error!("Failed to reload externally edited settings: {e:?}");
+                    }
+                    settings.trigger_watch_tick_hooks();
                 },
                 task = self.rx.recv() => match task {
                     Some(task) => self.handle_task(task),
@@ -82,9 +149,11 @@ impl<R: Runtime> Worker<R> {
         }
     }
 
-    /// Fire the persist operation when the debounce timer elapses.
-    fn on_persist_deadline(&mut self) {
-        self.persist_pending = false;
+    /// Fire the pending persist operation, if any.
+    fn flush(&mut self) {
+        if self.persist_pending.take().is_none() {
+            return;
+        }
         if let Err(e) = self.app_handle.settings().persist() {
             tracing::error!("Failed to persist settings: {e:?}");
         }
@@ -94,11 +163,12 @@ impl<R: Runtime> Worker<R> {
     fn handle_task(&mut self, task: WorkerTask) {
         match task {
             WorkerTask::Persist => {
-                self.persist_pending = true;
-                self.persist_debounce
-                    .as_mut()
-                    .reset(Instant::now() + PERSIST_DEBOUNCE);
+                let now = Instant::now();
+                let first = *self.persist_pending.get_or_insert(now);
+                let deadline = (now + PERSIST_DEBOUNCE).min(first + PERSIST_MAX_DELAY);
+                self.persist_debounce.as_mut().reset(deadline);
             },
+            WorkerTask::Flush => self.flush(),
             WorkerTask::ThemeChanged { old, new } => {
                 self.app_handle.settings().trigger_theme_hooks(&old, &new);
             },
@@ -114,12 +184,49 @@ impl<R: Runtime> Worker<R> {
                     new.as_ref(),
                 );
             },
+            WorkerTask::CustomCssChanged { old, new } => {
+                self.app_handle
+                    .settings()
+                    .trigger_custom_css_hooks(old.as_ref(), new.as_ref());
+            },
+            WorkerTask::LocaleChanged { old, new } => {
+                self.app_handle
+                    .settings()
+                    .trigger_locale_hooks(old.as_ref(), new.as_ref());
+            },
+            WorkerTask::LogFilterChanged { old, new } => {
+                self.app_handle
+                    .settings()
+                    .trigger_log_filter_hooks(old.as_ref(), new.as_ref());
+            },
+            WorkerTask::ProfileChanged { old, new } => {
+                self.app_handle.settings().trigger_profile_hooks(&old, &new);
+            },
+            WorkerTask::Ping(tx) => {
+                let _ = tx.send(());
+            },
         }
     }
 }
 
+/// Spawn a worker on Tauri's singleton async runtime.
+fn spawn<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> (mpsc::UnboundedSender<WorkerTask>, JoinHandle<()>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let task = tauri::async_runtime::spawn(async move {
+        Worker::new(app_handle, rx).run().await;
+    });
+    (tx, task)
+}
+
 /// Handle for communicating with the worker.
-pub struct WorkerHandle(mpsc::UnboundedSender<WorkerTask>);
+pub struct WorkerHandle {
+    /// The sender for incoming tasks.
+    tx: Mutex<mpsc::UnboundedSender<WorkerTask>>,
+    /// The running worker task.
+    task: Mutex<JoinHandle<()>>,
+}
 
 impl WorkerHandle {
     /// Create a new [`WorkerHandle`] instance.
@@ -128,11 +235,11 @@ impl WorkerHandle {
     /// runtime that listens for incoming [`WorkerTask`]s and processes them
     /// asynchronously in order.
     pub fn new<R: Runtime>(app_handle: AppHandle<R>) -> Self {
-        let (tx, rx) = mpsc::unbounded_channel();
-        tauri::async_runtime::spawn(async move {
-            Worker::new(app_handle, rx).run().await;
-        });
-        Self(tx)
+        let (tx, task) = spawn(app_handle);
+        Self {
+            tx: Mutex::new(tx),
+            task: Mutex::new(task),
+        }
     }
 
     /// Instruct the worker to process a task.
@@ -141,6 +248,29 @@ impl WorkerHandle {
     /// processing and does not wait for completion. An error is returned only
     /// if task submission fails, but not if task processing fails.
     pub fn process(&self, task: WorkerTask) -> Result<()> {
-        Ok(self.0.send(task)?)
+        Ok(self.tx.lock().send(task)?)
+    }
+
+    /// Check whether the worker is responsive.
+    ///
+    /// The returned receiver resolves once the worker processes the ping. It
+    /// never resolves if the worker is stuck, and fails if the worker has
+    /// stopped.
+    pub fn ping(&self) -> Result<oneshot::Receiver<()>> {
+        let (tx, rx) = oneshot::channel();
+        self.process(WorkerTask::Ping(tx))?;
+        Ok(rx)
+    }
+
+    /// Replace the worker with a freshly spawned one.
+    ///
+    /// The old worker is aborted at its next suspension point, and its queued
+    /// tasks are dropped.
+    pub fn restart<R: Runtime>(&self, app_handle: AppHandle<R>) {
+        let (tx, task) = spawn(app_handle);
+        *self.tx.lock() = tx;
+        let mut current = self.task.lock();
+        current.abort();
+        *current = task;
     }
 }
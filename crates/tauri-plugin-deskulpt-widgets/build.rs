@@ -1,15 +1,50 @@
 fn main() {
     tauri_deskulpt_build::Builder::default()
         .commands(&[
+            "align_widgets",
+            "bundle_stats",
+            "cancel_install",
+            "catalog_report",
+            "check_updates",
+            "damage_metrics",
             "fetch_registry_index",
+            "grant_permissions",
+            "import_widget",
             "install",
+            "list_releases",
             "preview",
+            "proxy_fetch",
+            "redo_layout_change",
             "refresh",
             "refresh_all",
+            "registry_categories",
+            "reinstall_from_lockfile",
+            "render_timings",
+            "report_rendered",
+            "report_widget",
+            "rollback_widget",
+            "search_registry",
+            "symbolicate",
+            "take_damage_hints",
+            "undo_layout_change",
             "uninstall",
+            "update_all",
             "update_settings",
+            "update_widget",
             "upgrade",
+            "validate_manifest",
+            "validate_widget",
+            "widget_metrics",
+        ])
+        .events(&[
+            "BundleSizeEvent",
+            "DiffEvent",
+            "InstallProgressEvent",
+            "RenderEvent",
+            "RevokedWidgetsEvent",
+            "TimeoutEvent",
+            "UpdateEvent",
+            "UpgradesAvailableEvent",
         ])
-        .events(&["RenderEvent", "UpdateEvent"])
         .build();
 }
@@ -3,11 +3,31 @@
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use deskulpt_common::outcome::Outcome;
+use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize};
+use tauri_plugin_deskulpt_settings::model::{CanvasImode, WidgetPermissions};
+
+use crate::monitor::MonitorAnchor;
+use crate::plugins::{MissingPlugin, PluginRequirement, compare_versions, parse_version};
+use crate::registry::{RegistryLock, RegistryWidgetReference};
+use crate::strict::StrictWarning;
+use crate::tombstone::Tombstones;
+
+/// The version of Deskulpt.
+///
+/// All crates in the workspace share the version of Deskulpt itself.
+pub(crate) const DESKULPT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Regex for a semantic version.
+static SEMVER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?$").unwrap()
+});
 
 /// An author of a Deskulpt widget.
 #[derive(Debug, Deserialize, Serialize, specta::Type)]
@@ -61,59 +81,547 @@ pub struct WidgetManifest {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[specta(type = String)]
     pub homepage: Option<String>,
+    /// The categories of the widget, e.g., `productivity` or `system`.
+    ///
+    /// These are for browsing widgets by category in the registry.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// The keywords of the widget.
+    ///
+    /// These are matched when searching widgets in the registry, in addition
+    /// to the name, description, and authors.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Translations of [`Self::name`] keyed by BCP-47 language tags.
+    ///
+    /// See [`Self::localize`] for how a translation is picked.
+    #[serde(default, skip_serializing)]
+    pub localized_name: BTreeMap<String, String>,
+    /// Translations of [`Self::description`] keyed by BCP-47 language tags.
+    ///
+    /// See [`Self::localize`] for how a translation is picked.
+    #[serde(default, skip_serializing)]
+    pub localized_description: BTreeMap<String, String>,
     /// The entry module of the widget that exports the widget component.
     ///
     /// This is a path relative to the root of the widget.
     #[serde(skip_serializing)]
     pub entry: String,
+    /// The interval in seconds at which the widget should be periodically
+    /// refreshed.
+    ///
+    /// This is useful for widgets that rely on data baked in at bundle time.
+    /// If not set, the widget is refreshed only on demand.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = u64)]
+    pub refresh_interval_seconds: Option<u64>,
+    /// Compile-time constants to inject into the widget bundle.
+    ///
+    /// Each key is an identifier (possibly dotted, e.g., `config.apiUrl`) that
+    /// is replaced by the JSON value at bundle time. See also the built-in
+    /// constants `__DESKULPT_VERSION__`, `__WIDGET_ID__`, and `__THEME__`,
+    /// which take precedence over the ones defined here.
+    #[serde(default, skip_serializing)]
+    pub define: BTreeMap<String, serde_json::Value>,
+    /// The hosts that the widget is allowed to access over the network.
+    ///
+    /// Each entry is either an exact host name or a wildcard `*.example.com`
+    /// that matches the domain and all its subdomains. Network requests of the
+    /// widget to other hosts are blocked. This is superseded by
+    /// [`Self::permissions`] if set, and is advisory in the same way as
+    /// [`WidgetPermissions::network`].
+    #[serde(default, skip_serializing)]
+    pub network_hosts: Vec<String>,
+    /// The permissions requested by the widget.
+    ///
+    /// Registry widgets may only use the requested permissions granted by the
+    /// user, see [`Widget::permissions`]. If not set, network access is
+    /// restricted to [`Self::network_hosts`], while filesystem and plugin
+    /// access is unrestricted for local widgets and denied for registry
+    /// widgets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = WidgetPermissions)]
+    pub permissions: Option<WidgetPermissions>,
+    /// Whether to allow code splitting of the widget bundle.
+    ///
+    /// If set to true, the widget can use dynamic imports to lazy-load parts
+    /// of itself, which are split into separate chunks. Otherwise, bundling
+    /// fails if the widget bundle would consist of multiple chunks.
+    #[serde(default, skip_serializing)]
+    pub code_splitting: bool,
+    /// Whether to type check the widget when bundling.
+    ///
+    /// Type errors are reported as warnings and do not prevent the widget from
+    /// rendering. If not set, this follows the application settings. Only
+    /// TypeScript widgets are checked, with `tsgo` or `tsc` installed in the
+    /// widget or globally.
+    #[serde(default, skip_serializing)]
+    pub type_check: Option<bool>,
+    /// The bundle size budget of the widget in KiB.
+    ///
+    /// A warning is emitted whenever the bundle of the widget exceeds this
+    /// size. If not set, this follows the application settings.
+    #[serde(default, skip_serializing)]
+    pub bundle_size_budget_kib: Option<u64>,
+    /// Whether the widget is in strict mode.
+    ///
+    /// Strict mode performs additional checks on the widget, e.g., for usage
+    /// of deprecated APIs, and reports problems as warnings. If not set, this
+    /// follows the application settings.
+    #[serde(default, skip_serializing)]
+    pub strict: Option<bool>,
+    /// The plugins required by the widget.
+    ///
+    /// Each entry is a plugin name optionally followed by a version constraint,
+    /// e.g., `fs` or `sys>=0.3`. Unavailable plugins are reported in the
+    /// catalog entry of the widget.
+    #[serde(default, skip_serializing)]
+    pub required_plugins: Vec<PluginRequirement>,
+    /// The minimum version of Deskulpt required by the widget, e.g., `0.4`.
+    ///
+    /// See [`Self::check_compatibility`] for how it is enforced.
+    #[serde(default, skip_serializing)]
+    pub min_deskulpt_version: Option<String>,
+    /// The maximum version of Deskulpt supported by the widget, e.g., `0.4`.
+    ///
+    /// Components not specified are not compared, so `0.4` allows all `0.4.x`
+    /// versions. See [`Self::check_compatibility`] for how it is enforced.
+    #[serde(default, skip_serializing)]
+    pub max_deskulpt_version: Option<String>,
     /// Whether to ignore the widget.
     ///
     /// If set to true, the widget will not be discovered by the application,
     /// despite the presence of the manifest file.
     #[serde(default, skip_serializing)]
     pub ignore: bool,
+    /// The fields in the manifest file that are not recognized.
+    ///
+    /// These are collected when loading the manifest, see [`Self::validate`].
+    #[serde(skip)]
+    pub unknown_fields: Vec<String>,
 }
 
 impl WidgetManifest {
     /// The name of the widget manifest file.
-    const FILE_NAME: &str = "deskulpt.widget.json";
+    pub(crate) const FILE_NAME: &str = "deskulpt.widget.json";
+
+    /// The name of the widget manifest file in TOML format.
+    ///
+    /// [`Self::FILE_NAME`] takes precedence if both exist.
+    pub(crate) const TOML_FILE_NAME: &str = "deskulpt.widget.toml";
+
+    /// Whether a directory contains a widget manifest file in any format.
+    pub(crate) fn exists(dir: &Path) -> bool {
+        dir.join(Self::FILE_NAME).exists() || dir.join(Self::TOML_FILE_NAME).exists()
+    }
+
+    /// Whether a directory contains widget manifest files in both formats.
+    ///
+    /// The TOML one is ignored in that case.
+    pub(crate) fn is_conflicting(dir: &Path) -> bool {
+        dir.join(Self::FILE_NAME).exists() && dir.join(Self::TOML_FILE_NAME).exists()
+    }
 
     /// Load the widget manifest from a directory.
     ///
     /// This method returns `Ok(None)` if the directory is **NOT A WIDGET**,
     /// i.e., either the directory does not contain a widget manifest file, or
-    /// the widget manifest marks itself as ignored (see [`Self::ignore`]). If
-    /// loading or parsing the widget manifest fails, an error is returned.
-    /// Otherwise, the widget manifest is returned wrapped in `Ok(Some(...))`.
+    /// the widget manifest marks itself as ignored (see [`Self::ignore`]). The
+    /// manifest may be written in JSON or TOML, with JSON taking precedence if
+    /// both exist (see [`Self::TOML_FILE_NAME`]). If
+    /// loading or parsing the widget manifest fails, an error is returned,
+    /// where parsing errors carry the path to the offending field. An error is
+    /// also returned if the widget is incompatible with this version of
+    /// Deskulpt, see [`Self::check_compatibility`]. Otherwise, the widget
+    /// manifest is returned wrapped in `Ok(Some(...))`.
     ///
     /// Note that [`Result::transpose`] can bring `Option` out of `Result` for
     /// the result of this method, so that non-widget directories can be
     /// filtered out without nested pattern matching.
-    fn load(dir: &Path) -> Result<Option<Self>> {
+    pub(crate) fn load(dir: &Path) -> Result<Option<Self>> {
+        let manifest = Self::load_unchecked(dir)?;
+        if let Some(manifest) = &manifest {
+            manifest.check_compatibility()?;
+        }
+        Ok(manifest)
+    }
+
+    /// Load the widget manifest from a directory without checking
+    /// compatibility.
+    ///
+    /// See [`Self::load`] for details.
+    pub(crate) fn load_unchecked(dir: &Path) -> Result<Option<Self>> {
         let path = dir.join(Self::FILE_NAME);
-        if !path.exists() {
+        let toml_path = dir.join(Self::TOML_FILE_NAME);
+        let mut unknown_fields = vec![];
+        let mut on_unknown = |field: serde_ignored::Path| unknown_fields.push(field.to_string());
+        let mut config: Self = if path.exists() {
+            if toml_path.exists() {
+                tracing::warn!(
+                    path = %toml_path.display(),
+                    "Ignoring TOML widget manifest in favor of the JSON one",
+                );
+            }
+            let file = File::open(&path)
+                .with_context(|| format!("Failed to open widget manifest: {}", path.display()))?;
+            let reader = BufReader::new(file);
+            let deserializer = serde_ignored::Deserializer::new(
+                &mut serde_json::Deserializer::from_reader(reader),
+                &mut on_unknown,
+            );
+            serde_path_to_error::deserialize(deserializer)
+                .with_context(|| format!("Failed to parse widget manifest: {}", path.display()))?
+        } else if toml_path.exists() {
+            let content = std::fs::read_to_string(&toml_path).with_context(|| {
+                format!("Failed to open widget manifest: {}", toml_path.display())
+            })?;
+            let parse_context =
+                || format!("Failed to parse widget manifest: {}", toml_path.display());
+            let deserializer = serde_ignored::Deserializer::new(
+                toml::Deserializer::parse(&content).with_context(parse_context)?,
+                &mut on_unknown,
+            );
+            serde_path_to_error::deserialize(deserializer).with_context(parse_context)?
+        } else {
             return Ok(None);
-        }
-        let file = File::open(&path)
-            .with_context(|| format!("Failed to open widget manifest: {}", path.display()))?;
-        let reader = BufReader::new(file);
-        let config: Self = serde_json::from_reader(reader)
-            .with_context(|| format!("Failed to parse widget manifest: {}", path.display()))?;
+        };
+        config.unknown_fields = unknown_fields;
         if config.ignore {
             return Ok(None);
         }
         Ok(Some(config))
     }
+
+    /// Check whether the widget is compatible with this version of Deskulpt.
+    ///
+    /// An error is returned if this version of Deskulpt is older than
+    /// [`Self::min_deskulpt_version`] or newer than
+    /// [`Self::max_deskulpt_version`], telling which version is required, or
+    /// if either of them is not a valid version.
+    pub(crate) fn check_compatibility(&self) -> Result<()> {
+        let current = parse_version(DESKULPT_VERSION).unwrap_or_default();
+        if let Some(min) = &self.min_deskulpt_version {
+            let Some(required) = parse_version(min) else {
+                bail!("Invalid minDeskulptVersion: {min:?}");
+            };
+            if compare_versions(&current, &required).is_lt() {
+                bail!("Requires Deskulpt {min} or later; this is Deskulpt {DESKULPT_VERSION}");
+            }
+        }
+        if let Some(max) = &self.max_deskulpt_version {
+            let Some(supported) = parse_version(max) else {
+                bail!("Invalid maxDeskulptVersion: {max:?}");
+            };
+            let current = &current[..current.len().min(supported.len())];
+            if compare_versions(current, &supported).is_gt() {
+                bail!("Requires Deskulpt {max} or earlier; this is Deskulpt {DESKULPT_VERSION}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the widget manifest loaded from a directory.
+    ///
+    /// This reports problems that do not prevent the manifest from being
+    /// parsed, with the paths to the offending fields:
+    ///
+    /// - Unknown fields, which are likely typos, are warnings.
+    /// - A version that is not a semantic version is a warning.
+    /// - A refresh interval of zero, which disables periodic refresh, is a
+    ///   warning.
+    /// - Undeclared permissions, which leave filesystem and plugin access
+    ///   unrestricted, are a warning.
+    /// - A filesystem permission that escapes the widget directory is an error.
+    /// - An entry that escapes the widget directory or does not exist is an
+    ///   error.
+    ///
+    /// Widgets with errors are not loaded into the catalog and are refused on
+    /// installation from the registry.
+    pub fn validate(&self, dir: &Path) -> Vec<ManifestDiagnostic> {
+        let mut diagnostics = self
+            .unknown_fields
+            .iter()
+            .map(|field| ManifestDiagnostic::warning(field, "Unknown field"))
+            .collect::<Vec<_>>();
+        if let Some(version) = &self.version
+            && !SEMVER_RE.is_match(version)
+        {
+            diagnostics.push(ManifestDiagnostic::warning(
+                "version",
+                format!("{version:?} is not a semantic version, e.g., 1.0.0"),
+            ));
+        }
+        if self.refresh_interval_seconds == Some(0) {
+            diagnostics.push(ManifestDiagnostic::warning(
+                "refreshIntervalSeconds",
+                "Refresh interval of zero disables periodic refresh",
+            ));
+        }
+        match &self.permissions {
+            Some(permissions) => {
+                for scope in &permissions.filesystem {
+                    if escapes_dir(scope) {
+                        diagnostics.push(ManifestDiagnostic::error(
+                            "permissions.filesystem",
+                            format!("{scope:?} escapes the widget directory"),
+                        ));
+                    }
+                }
+            },
+            None => diagnostics.push(ManifestDiagnostic::warning(
+                "permissions",
+                "Permissions are not declared; filesystem and plugin access is unrestricted",
+            )),
+        }
+
+        let entry = &self.entry;
+        if escapes_dir(entry) {
+            diagnostics.push(ManifestDiagnostic::error(
+                "entry",
+                format!("{entry:?} escapes the widget directory"),
+            ));
+        } else if !dir.join(entry).is_file() {
+            diagnostics.push(ManifestDiagnostic::error(
+                "entry",
+                format!("File {entry:?} does not exist"),
+            ));
+        }
+        diagnostics
+    }
+
+    /// Resolve the display metadata to a locale.
+    ///
+    /// [`Self::name`] and [`Self::description`] are replaced with their
+    /// translations for the given BCP-47 language tag. If there is no
+    /// translation for the exact tag, less specific tags are tried by dropping
+    /// trailing subtags, e.g., `zh-Hant-TW`, then `zh-Hant`, then `zh`. Tags
+    /// are matched case-insensitively, with `_` treated as `-`. If none of
+    /// them has a translation, the untranslated value is kept.
+    pub fn localize(&mut self, locale: &str) {
+        if let Some(name) = resolve_locale(&self.localized_name, locale) {
+            self.name = name.clone();
+        }
+        if let Some(description) = resolve_locale(&self.localized_description, locale) {
+            self.description = Some(description.clone());
+        }
+    }
+}
+
+/// Whether a filesystem permission covers a path.
+///
+/// Both are relative to the widget directory. The scope covers the path if the
+/// path is the scope itself or under it. Paths escaping the widget directory
+/// are never covered.
+pub(crate) fn covers_path(scope: &str, path: &str) -> bool {
+    if escapes_dir(scope) || escapes_dir(path) {
+        return false;
+    }
+    let normalize = |path: &str| {
+        let mut normalized = PathBuf::new();
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(name) => normalized.push(name),
+                Component::ParentDir => {
+                    normalized.pop();
+                },
+                _ => {},
+            }
+        }
+        normalized
+    };
+    normalize(path).starts_with(normalize(scope))
+}
+
+/// Whether a relative path escapes the directory it is relative to.
+///
+/// Absolute paths are considered escaping as well.
+fn escapes_dir(path: &str) -> bool {
+    let mut depth = 0usize;
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {},
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(parent) => depth = parent,
+                None => return true,
+            },
+            Component::Prefix(_) | Component::RootDir => return true,
+        }
+    }
+    false
+}
+
+/// The severity of a problem in a widget manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ManifestSeverity {
+    /// The widget works but likely not as intended.
+    Warning,
+    /// The widget cannot work.
+    Error,
+}
+
+/// A problem found in a widget manifest.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestDiagnostic {
+    /// The severity of the problem.
+    pub severity: ManifestSeverity,
+    /// The description of the problem.
+    pub message: String,
+    /// The path to the offending field in the manifest, e.g.,
+    /// `requiredPlugins[0]`.
+    ///
+    /// This is `None` if the problem is not specific to a field, e.g., if the
+    /// manifest cannot be read or is not valid JSON or TOML.
+    pub field: Option<String>,
+}
+
+impl ManifestDiagnostic {
+    /// Create a warning about a field.
+    fn warning(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: ManifestSeverity::Warning,
+            message: message.into(),
+            field: Some(field.to_string()),
+        }
+    }
+
+    /// Create an error about a field.
+    fn error(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: ManifestSeverity::Error,
+            message: message.into(),
+            field: Some(field.to_string()),
+        }
+    }
+
+    /// Create an error from a failure to load a widget manifest.
+    ///
+    /// The path to the offending field is extracted from parsing errors.
+    pub fn from_load_error(e: &anyhow::Error) -> Self {
+        let field = e
+            .downcast_ref::<serde_path_to_error::Error<serde_json::Error>>()
+            .filter(|e| e.inner().is_data())
+            .map(|e| e.path().to_string())
+            .or_else(|| {
+                e.downcast_ref::<serde_path_to_error::Error<toml::de::Error>>()
+                    .map(|e| e.path().to_string())
+            });
+        Self {
+            severity: ManifestSeverity::Error,
+            message: format!("{e:?}"),
+            field,
+        }
+    }
+}
+
+impl std::fmt::Display for ManifestDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{field}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Load and validate the widget manifest from a directory.
+///
+/// This returns `None` if the directory is not a widget. If the manifest
+/// cannot be loaded or has errors, the errors are returned in place of the
+/// manifest. Warnings are returned alongside. See [`WidgetManifest::load`] and
+/// [`WidgetManifest::validate`].
+fn load_validated(dir: &Path) -> Option<(Outcome<WidgetManifest>, Vec<ManifestDiagnostic>)> {
+    let manifest = match WidgetManifest::load(dir).transpose()? {
+        Ok(manifest) => manifest,
+        Err(e) => return Some((Outcome::Err(format!("{e:?}")), vec![])),
+    };
+    let (errors, warnings): (Vec<_>, Vec<_>) = manifest
+        .validate(dir)
+        .into_iter()
+        .partition(|diagnostic| diagnostic.severity == ManifestSeverity::Error);
+    if !errors.is_empty() {
+        let message = errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Some((Outcome::Err(message), warnings));
+    }
+    Some((Outcome::Ok(manifest), warnings))
+}
+
+/// Find the translation for a locale with fallback to less specific tags.
+///
+/// See [`WidgetManifest::localize`] for details.
+fn resolve_locale<'a>(
+    translations: &'a BTreeMap<String, String>,
+    locale: &str,
+) -> Option<&'a String> {
+    let normalize = |tag: &str| tag.trim().replace('_', "-").to_ascii_lowercase();
+    let locale = normalize(locale);
+    let mut tag = locale.as_str();
+    loop {
+        let translation = translations
+            .iter()
+            .find(|(key, _)| normalize(key) == tag)
+            .map(|(_, value)| value);
+        if translation.is_some() {
+            return translation;
+        }
+        (tag, _) = tag.rsplit_once('-')?;
+    }
+}
+
+/// How a widget responds to cursor events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum Interactivity {
+    /// Follow the canvas interaction mode.
+    #[default]
+    Inherit,
+    /// Stay interactive even if the canvas is in sink mode.
+    AlwaysInteractive,
+    /// Stay click-through even if the canvas is in float mode.
+    ClickThrough,
+}
+
+impl Interactivity {
+    /// Whether the widget is interactive under a canvas interaction mode.
+    pub fn is_interactive(&self, imode: &CanvasImode) -> bool {
+        match self {
+            Self::Inherit => *imode != CanvasImode::Sink,
+            Self::AlwaysInteractive => true,
+            Self::ClickThrough => false,
+        }
+    }
+}
+
+/// The position of a widget detached into its own floating window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DetachedPosition {
+    /// The leftmost x-coordinate of the window in physical screen pixels.
+    pub x: i32,
+    /// The topmost y-coordinate of the window in physical screen pixels.
+    pub y: i32,
 }
 
 /// Deskulpt widget settings.
-#[derive(Debug, Deserialize, Serialize, specta::Type)]
+#[derive(Debug, Clone, Deserialize, Serialize, specta::Type)]
 #[serde(rename_all = "camelCase", default)]
 pub struct WidgetSettings {
     /// The leftmost x-coordinate in pixels.
     pub x: i32,
     /// The topmost y-coordinate in pixels.
     pub y: i32,
+    /// The anchor of the widget position relative to a monitor.
+    ///
+    /// This is derived from [`WidgetSettings::x`] and [`WidgetSettings::y`]
+    /// whenever they change, and used to re-resolve them when the monitor
+    /// layout changes. It is `None` for settings persisted by older versions,
+    /// in which case it will be derived once monitors are known.
+    pub anchor: Option<MonitorAnchor>,
     /// The width in pixels.
     pub width: u32,
     /// The height in pixels.
@@ -129,6 +637,22 @@ pub struct WidgetSettings {
     pub z_index: i16,
     /// Whether the widget should be loaded on the canvas or not.
     pub is_loaded: bool,
+    /// How the widget responds to cursor events.
+    ///
+    /// This overrides the canvas interaction mode for the widget, e.g., a clock
+    /// can stay click-through even if the canvas is in float mode.
+    pub interactivity: Interactivity,
+    /// Whether the widget is frozen.
+    ///
+    /// A frozen widget keeps its current render, skipping re-renders on
+    /// changes, periodic refreshes, and theme changes, until unfrozen.
+    pub frozen: bool,
+    /// The position of the floating window of the widget, if detached.
+    ///
+    /// A detached widget is rendered in its own always-on-top window instead
+    /// of on the canvas, and [`WidgetSettings::x`] and [`WidgetSettings::y`]
+    /// are kept so that it returns to its place when attached back.
+    pub detached: Option<DetachedPosition>,
 }
 
 impl Default for WidgetSettings {
@@ -136,11 +660,15 @@ impl Default for WidgetSettings {
         Self {
             x: 0,
             y: 0,
+            anchor: None,
             width: 300,
             height: 200,
             opacity: 100,
             z_index: 0,
             is_loaded: true,
+            interactivity: Interactivity::Inherit,
+            frozen: false,
+            detached: None,
         }
     }
 }
@@ -170,6 +698,12 @@ pub struct WidgetSettingsPatch {
     /// If not `None`, update [`WidgetSettings::is_loaded`].
     #[specta(optional, type = bool)]
     pub is_loaded: Option<bool>,
+    /// If not `None`, update [`WidgetSettings::interactivity`].
+    #[specta(optional, type = Interactivity)]
+    pub interactivity: Option<Interactivity>,
+    /// If not `None`, update [`WidgetSettings::frozen`].
+    #[specta(optional, type = bool)]
+    pub frozen: Option<bool>,
 }
 
 impl WidgetSettings {
@@ -220,9 +754,20 @@ impl WidgetSettings {
         dirty |= set_if_changed(&mut self.opacity, patch.opacity);
         dirty |= set_if_changed(&mut self.z_index, patch.z_index);
         dirty |= set_if_changed(&mut self.is_loaded, patch.is_loaded);
+        dirty |= set_if_changed(&mut self.interactivity, patch.interactivity);
+        dirty |= set_if_changed(&mut self.frozen, patch.frozen);
         dirty
     }
 
+    /// Check if the widget can be hit by the cursor on the canvas.
+    ///
+    /// Widgets that are unloaded, fully transparent, or detached into their own
+    /// floating windows are not visible on the canvas, so they should neither
+    /// capture nor block cursor events there.
+    pub fn is_hit_testable(&self) -> bool {
+        self.is_loaded && self.opacity > 0 && self.detached.is_none()
+    }
+
     /// Check if the widget covers the given point geometrically.
     ///
     /// Note that all edges are inclusive.
@@ -236,6 +781,18 @@ impl WidgetSettings {
     }
 }
 
+/// Statistics of the latest successful bundle of a widget.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStats {
+    /// The total size of the bundled code in bytes.
+    ///
+    /// With code splitting, this includes all chunks.
+    pub size: u64,
+    /// The time taken to bundle in milliseconds.
+    pub duration_ms: u64,
+}
+
 /// A Deskulpt widget.
 #[derive(Debug, Serialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
@@ -244,6 +801,41 @@ pub struct Widget {
     pub manifest: Outcome<WidgetManifest>,
     /// The settings of the widget.
     pub settings: WidgetSettings,
+    /// The statistics of the latest successful bundle of the widget.
+    ///
+    /// This is `None` if the widget has not been successfully bundled since
+    /// it was last loaded.
+    pub bundle_stats: Option<BundleStats>,
+    /// The warnings reported in strict mode.
+    ///
+    /// This is empty if the widget is not in strict mode.
+    pub strict_warnings: Vec<StrictWarning>,
+    /// The required plugins of the widget that are unavailable.
+    ///
+    /// The widget may fail at runtime when using these plugins.
+    pub missing_plugins: Vec<MissingPlugin>,
+    /// The warnings found in the widget manifest.
+    ///
+    /// See [`WidgetManifest::validate`]. Errors are reported in place of the
+    /// manifest instead.
+    pub manifest_warnings: Vec<ManifestDiagnostic>,
+    /// The registry package the widget was installed from.
+    ///
+    /// This is `None` for local widgets, including registry widgets installed
+    /// before installs were recorded in the lockfile.
+    pub source: Option<RegistryWidgetReference>,
+    /// The permissions in effect for the widget.
+    ///
+    /// Local widgets are trusted with the permissions they request, while
+    /// registry widgets only get the requested permissions granted by the
+    /// user. This is `None` if a local widget does not declare permissions, see
+    /// [`WidgetManifest::permissions`].
+    pub permissions: Option<WidgetPermissions>,
+    /// The permissions requested by the widget but not granted by the user.
+    ///
+    /// This is always empty for local widgets. The user should be prompted to
+    /// grant these permissions.
+    pub pending_permissions: WidgetPermissions,
 }
 
 impl Widget {
@@ -251,36 +843,258 @@ impl Widget {
     ///
     /// If settings are not provided, they will be derived from the manifest or
     /// set to default.
-    fn new(manifest: Outcome<WidgetManifest>, settings: Option<WidgetSettings>) -> Self {
+    fn new(
+        manifest: Outcome<WidgetManifest>,
+        manifest_warnings: Vec<ManifestDiagnostic>,
+        settings: Option<WidgetSettings>,
+    ) -> Self {
         let settings = settings.unwrap_or_else(|| match &manifest {
             Outcome::Ok(manifest) => WidgetSettings::from_manifest(manifest),
             Outcome::Err(_) => WidgetSettings::default(),
         });
-        Self { manifest, settings }
+        Self {
+            manifest,
+            settings,
+            bundle_stats: None,
+            strict_warnings: vec![],
+            missing_plugins: vec![],
+            manifest_warnings,
+            source: None,
+            permissions: None,
+            pending_permissions: Default::default(),
+        }
     }
+
+    /// Resolve the permissions of the widget given those granted by the user.
+    ///
+    /// This should be called after [`Self::source`] is resolved. See
+    /// [`Self::permissions`] and [`Self::pending_permissions`].
+    pub fn resolve_permissions(&mut self, grant: Option<&WidgetPermissions>) {
+        let requested = match (&self.manifest, &self.source) {
+            (Outcome::Ok(manifest), _) if manifest.permissions.is_some() => {
+                manifest.permissions.clone()
+            },
+            // Registry widgets that do not declare permissions request only
+            // the network hosts, so that they are denied filesystem and plugin
+            // access by default
+            (Outcome::Ok(manifest), Some(_)) => Some(WidgetPermissions {
+                network: manifest.network_hosts.clone(),
+                ..Default::default()
+            }),
+            (Outcome::Err(_), Some(_)) => Some(Default::default()),
+            (_, None) => None,
+        };
+        (self.permissions, self.pending_permissions) = match (requested, &self.source) {
+            (None, _) => (None, Default::default()),
+            (Some(requested), None) => (Some(requested), Default::default()),
+            (Some(requested), Some(_)) => {
+                let granted = grant.cloned().unwrap_or_default();
+                (
+                    Some(requested.intersection(&granted)),
+                    requested.difference(&granted),
+                )
+            },
+        };
+    }
+
+    /// Get the hosts the widget is allowed to access over the network.
+    ///
+    /// These are from [`Self::permissions`], or
+    /// [`WidgetManifest::network_hosts`] if the widget does not declare
+    /// permissions.
+    pub fn allowed_hosts(&self) -> Vec<String> {
+        match (&self.permissions, &self.manifest) {
+            (Some(permissions), _) => permissions.network.clone(),
+            (None, Outcome::Ok(manifest)) => manifest.network_hosts.clone(),
+            (None, Outcome::Err(_)) => vec![],
+        }
+    }
+
+    /// Check whether the widget is allowed to call a plugin command.
+    ///
+    /// The plugin command must be covered by the plugin permissions. For the
+    /// `fs` plugin, the path in the payload, or the widget directory if not
+    /// given, must also be under one of the filesystem permissions. Local
+    /// widgets that do not declare permissions are allowed to call any plugin
+    /// command.
+    pub fn check_plugin_call(
+        &self,
+        plugin: &str,
+        command: &str,
+        payload: Option<&serde_json::Value>,
+    ) -> Result<()> {
+        let Some(permissions) = &self.permissions else {
+            return Ok(());
+        };
+        let qualified = format!("{plugin}.{command}");
+        if !permissions
+            .plugins
+            .iter()
+            .any(|entry| *entry == plugin || *entry == qualified)
+        {
+            bail!("Not permitted to call {qualified}");
+        }
+        if plugin == "fs" {
+            let path = payload
+                .and_then(|payload| payload.get("path"))
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or(".");
+            if !permissions
+                .filesystem
+                .iter()
+                .any(|scope| covers_path(scope, path))
+            {
+                bail!("Not permitted to access {path:?}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the required plugins of the widget against the available ones.
+    ///
+    /// This returns whether the missing plugins of the widget have changed.
+    pub fn check_plugins(&mut self, available: &BTreeMap<String, String>) -> bool {
+        let missing_plugins = match &self.manifest {
+            Outcome::Ok(manifest) => manifest
+                .required_plugins
+                .iter()
+                .filter_map(|requirement| requirement.check(available))
+                .collect(),
+            Outcome::Err(_) => vec![],
+        };
+        if self.missing_plugins == missing_plugins {
+            return false;
+        }
+        self.missing_plugins = missing_plugins;
+        true
+    }
+}
+
+/// Discover the candidate widget directories in the widgets directory.
+///
+/// Top-level directories with a widget manifest file are candidates, with
+/// their directory names as widget IDs. Other top-level directories are
+/// treated as namespaces for organizing widgets, whose subdirectories are
+/// candidates with IDs in the form of `namespace/name`. Only one level of
+/// nesting is supported. Hidden top-level directories are skipped. Since the
+/// paths relative to the widgets directory are unique, they can be used as
+/// widget IDs.
+pub(crate) fn discover_widget_dirs(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut dirs = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_dir() {
+            continue; // Non-directory entries are not widgets, skip
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue; // Hidden directories are reserved, e.g., for staging updates
+        }
+        if WidgetManifest::exists(&path) {
+            dirs.push((name, path));
+            continue;
+        }
+
+        for sub_entry in std::fs::read_dir(&path)? {
+            let sub_entry = sub_entry?;
+            let sub_path = sub_entry.path();
+            if sub_path.is_dir() {
+                let sub_name = sub_entry.file_name().to_string_lossy().to_string();
+                dirs.push((format!("{name}/{sub_name}"), sub_path));
+            }
+        }
+    }
+    Ok(dirs)
 }
 
 /// The catalog of Deskulpt widgets.
+///
+/// Widgets are keyed by their IDs, which are the paths of the widget
+/// directories relative to the widgets directory, separated by `/`.
 #[derive(Debug, Default, Serialize, specta::Type)]
 pub struct WidgetCatalog(pub BTreeMap<String, Widget>);
 
 impl WidgetCatalog {
+    /// Check the required plugins of all widgets against the available ones.
+    ///
+    /// This returns whether the missing plugins of any widget have changed.
+    pub fn check_plugins(&mut self, available: &BTreeMap<String, String>) -> bool {
+        self.0.values_mut().fold(false, |changed, widget| {
+            widget.check_plugins(available) || changed
+        })
+    }
+
+    /// Resolve the registry packages all widgets were installed from.
+    ///
+    /// See [`Widget::source`] for details.
+    pub fn resolve_sources(&mut self, lock: &RegistryLock) {
+        for (id, widget) in self.0.iter_mut() {
+            widget.source = lock.source(id);
+        }
+    }
+
+    /// Resolve the permissions of all widgets given those granted by the user.
+    ///
+    /// This should be called after [`Self::resolve_sources`]. See
+    /// [`Widget::resolve_permissions`] for details.
+    pub fn resolve_permissions(&mut self, grants: &BTreeMap<String, WidgetPermissions>) {
+        for (id, widget) in self.0.iter_mut() {
+            widget.resolve_permissions(grants.get(id));
+        }
+    }
+
+    /// Resolve the display metadata of all widgets to a locale.
+    ///
+    /// This should be applied to freshly reloaded manifests only, since the
+    /// untranslated values are overwritten. See [`WidgetManifest::localize`].
+    pub fn localize(&mut self, locale: &str) {
+        for widget in self.0.values_mut() {
+            if let Outcome::Ok(manifest) = &mut widget.manifest {
+                manifest.localize(locale);
+            }
+        }
+    }
+
+    /// Collect the periodic refresh intervals of all widgets.
+    ///
+    /// Only loaded widgets with a valid manifest that specifies a non-zero
+    /// [`WidgetManifest::refresh_interval_seconds`] are included.
+    pub fn refresh_intervals(&self) -> BTreeMap<String, Duration> {
+        self.0
+            .iter()
+            .filter(|(_, widget)| widget.settings.is_loaded)
+            .filter_map(|(id, widget)| match &widget.manifest {
+                Outcome::Ok(manifest) => manifest
+                    .refresh_interval_seconds
+                    .filter(|secs| *secs > 0)
+                    .map(|secs| (id.clone(), Duration::from_secs(secs))),
+                Outcome::Err(_) => None,
+            })
+            .collect()
+    }
+
     /// Reload a widget in the catalog from its directory.
     ///
-    /// If the widget is gone, it will be removed from the catalog. If the
-    /// widget is new, it will be added to the catalog with default settings. If
-    /// the widget already exists, its manifest will be updated while keeping
-    /// its settings.
-    pub fn reload(&mut self, dir: &Path, id: &str) -> Result<()> {
-        let Some(manifest) = WidgetManifest::load(dir).transpose() else {
-            self.0.remove(id);
+    /// If the widget is gone, it will be removed from the catalog, with its
+    /// settings buried in the tombstones. If the widget is new, it will be
+    /// added to the catalog with the settings revived from the tombstones, or
+    /// default settings if there are none. If the widget already exists, its
+    /// manifest will be updated while keeping its settings.
+    pub fn reload(&mut self, dir: &Path, id: &str, tombstones: &mut Tombstones) -> Result<()> {
+        let Some((manifest, manifest_warnings)) = load_validated(dir) else {
+            if let Some(widget) = self.0.remove(id) {
+                tombstones.bury(id.to_string(), widget.settings);
+            }
             return Ok(());
         };
 
         if let Some(widget) = self.0.get_mut(id) {
-            widget.manifest = manifest.into();
+            widget.manifest = manifest;
+            widget.manifest_warnings = manifest_warnings;
         } else {
-            let widget = Widget::new(manifest.into(), None);
+            let widget = Widget::new(manifest, manifest_warnings, tombstones.revive(id));
             self.0.insert(id.to_string(), widget);
         }
 
@@ -291,34 +1105,29 @@ impl WidgetCatalog {
     ///
     /// This will completely replace the current catalog with the widgets
     /// discovered in the given directory. Existing widgets will keep their
-    /// settings if they are still present.
-    pub fn reload_all(&mut self, dir: &Path) -> Result<()> {
+    /// settings if they are still present, and widgets that are gone will have
+    /// their settings buried in the tombstones. New widgets will have their
+    /// settings revived from the tombstones if any.
+    pub fn reload_all(&mut self, dir: &Path, tombstones: &mut Tombstones) -> Result<()> {
         let mut new_catalog = Self::default();
 
-        let entries = std::fs::read_dir(dir)?;
-        for entry in entries {
-            let entry = entry?;
-
-            let path = entry.path();
-            if !path.is_dir() {
-                continue; // Non-directory entries are not widgets, skip
-            }
-
-            let Some(manifest) = WidgetManifest::load(&path).transpose() else {
+        for (id, path) in discover_widget_dirs(dir)? {
+            let Some((manifest, manifest_warnings)) = load_validated(&path) else {
                 continue; // Not a widget, skip
             };
 
-            // Since each widget must be at the top level of the widgets
-            // directory, the directory names must be unique and we can use them
-            // as widget IDs
-            let id = entry.file_name().to_string_lossy().to_string();
-
-            let settings = self.0.remove(&id).map(|w| w.settings);
-            let widget = Widget::new(manifest.into(), settings);
+            let settings = self
+                .0
+                .remove(&id)
+                .map(|w| w.settings)
+                .or_else(|| tombstones.revive(&id));
+            let widget = Widget::new(manifest, manifest_warnings, settings);
             new_catalog.0.insert(id, widget);
         }
 
-        *self = new_catalog;
+        for (id, widget) in std::mem::replace(self, new_catalog).0 {
+            tombstones.bury(id, widget.settings);
+        }
         Ok(())
     }
 }
@@ -1,16 +1,31 @@
 //! Tauri commands.
 #![doc = include_str!("../permissions/autogenerated/reference.md")]
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 use deskulpt_common::SerResult;
+use deskulpt_common::command::instrument;
+use deskulpt_common::outcome::Outcome;
 use tauri::{AppHandle, Runtime};
+use tauri_plugin_deskulpt_settings::model::WidgetPermissions;
 
 use crate::WidgetsExt;
-use crate::catalog::WidgetSettingsPatch;
-use crate::registry::{RegistryIndex, RegistryWidgetPreview, RegistryWidgetReference};
+use crate::catalog::{BundleStats, ManifestDiagnostic, WidgetSettingsPatch};
+use crate::damage::{DamageHints, DamageMetrics};
+use crate::health::WidgetDirReport;
+use crate::layout::Alignment;
+use crate::network::{NetworkRequest, NetworkResponse, WidgetMetrics};
+use crate::registry::{
+    RegistryEntryRelease, RegistryIndex, RegistrySearchPage, RegistrySort, RegistryWidgetPreview,
+    RegistryWidgetReference, WidgetUpgrade,
+};
+use crate::render::{RenderTimings, ValidationReport};
 
 /// Update the settings of a widget with a patch.
 ///
 /// This command is a wrapper of [`crate::WidgetsManager::update_settings`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn update_settings<R: Runtime>(
@@ -22,9 +37,45 @@ pub async fn update_settings<R: Runtime>(
     Ok(())
 }
 
+/// Align widgets with each other.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::align_widgets`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn align_widgets<R: Runtime>(
+    app_handle: AppHandle<R>,
+    ids: Vec<String>,
+    alignment: Alignment,
+) -> SerResult<()> {
+    app_handle.widgets().align_widgets(&ids, alignment)?;
+    Ok(())
+}
+
+/// Undo the most recent layout change of widgets.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::undo_layout_change`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn undo_layout_change<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<bool> {
+    Ok(app_handle.widgets().undo_layout_change()?)
+}
+
+/// Redo the most recently undone layout change of widgets.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::redo_layout_change`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn redo_layout_change<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<bool> {
+    Ok(app_handle.widgets().redo_layout_change()?)
+}
+
 /// Refresh a specific widget by its ID.
 ///
 /// This command is a wrapper of [`crate::WidgetsManager::refresh`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn refresh<R: Runtime>(app_handle: AppHandle<R>, id: String) -> SerResult<()> {
@@ -32,9 +83,98 @@ pub async fn refresh<R: Runtime>(app_handle: AppHandle<R>, id: String) -> SerRes
     Ok(())
 }
 
+/// Report that the canvas has finished evaluating a widget module.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::report_rendered`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn report_rendered<R: Runtime>(app_handle: AppHandle<R>, id: String) -> SerResult<()> {
+    app_handle.widgets().report_rendered(&id);
+    Ok(())
+}
+
+/// Send a network request on behalf of a widget.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::proxy_fetch`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn proxy_fetch<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    request: NetworkRequest,
+) -> SerResult<NetworkResponse> {
+    let response = app_handle.widgets().proxy_fetch(&id, request).await?;
+    Ok(response)
+}
+
+/// Take the canvas regions damaged since the last frame.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::take_damage_hints`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn take_damage_hints<R: Runtime>(
+    app_handle: AppHandle<R>,
+    viewport_width: u32,
+    viewport_height: u32,
+) -> SerResult<DamageHints> {
+    Ok(app_handle
+        .widgets()
+        .take_damage_hints(viewport_width, viewport_height))
+}
+
+/// Get the metrics of damage tracking.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::damage_metrics`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn damage_metrics<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<DamageMetrics> {
+    Ok(app_handle.widgets().damage_metrics())
+}
+
+/// Get the statistics of the latest successful bundles of widgets.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::bundle_stats`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn bundle_stats<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> SerResult<BTreeMap<String, BundleStats>> {
+    Ok(app_handle.widgets().bundle_stats())
+}
+
+/// Get the timing breakdowns of the latest renders of widgets.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::render_timings`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn render_timings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> SerResult<BTreeMap<String, RenderTimings>> {
+    Ok(app_handle.widgets().render_timings())
+}
+
+/// Get the network usage metrics of widgets.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::widget_metrics`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn widget_metrics<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> SerResult<BTreeMap<String, WidgetMetrics>> {
+    Ok(app_handle.widgets().widget_metrics())
+}
+
 /// Refresh all widgets.
 ///
 /// This command is a wrapper of [`crate::WidgetsManager::refresh_all`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn refresh_all<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<()> {
@@ -46,6 +186,7 @@ pub async fn refresh_all<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<()>
 ///
 /// This command is a wrapper of
 /// [`crate::WidgetsManager::fetch_registry_index`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn fetch_registry_index<R: Runtime>(
@@ -55,35 +196,141 @@ pub async fn fetch_registry_index<R: Runtime>(
     Ok(index)
 }
 
+/// Search the widgets registry.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::search_registry`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn search_registry<R: Runtime>(
+    app_handle: AppHandle<R>,
+    query: String,
+    category: Option<String>,
+    tags: Vec<String>,
+    sort: RegistrySort,
+    page: u32,
+) -> SerResult<RegistrySearchPage> {
+    let page = app_handle
+        .widgets()
+        .search_registry(&query, category.as_deref(), &tags, sort, page)
+        .await?;
+    Ok(page)
+}
+
+/// Count the widgets in each category of the widgets registry.
+///
+/// This command is a wrapper of
+/// [`crate::WidgetsManager::registry_categories`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn registry_categories<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> SerResult<BTreeMap<String, u32>> {
+    let categories = app_handle.widgets().registry_categories().await?;
+    Ok(categories)
+}
+
 /// Preview a widget from the registry.
 ///
 /// This command is a wrapper of [`crate::WidgetsManager::preview`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn preview<R: Runtime>(
     app_handle: AppHandle<R>,
     widget: RegistryWidgetReference,
+    include_assets: bool,
 ) -> SerResult<RegistryWidgetPreview> {
-    let preview = app_handle.widgets().preview(&widget).await?;
+    let preview = app_handle
+        .widgets()
+        .preview(&widget, include_assets)
+        .await?;
     Ok(preview)
 }
 
+/// Import a widget from another desktop widget platform.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::import_widget`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn import_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    src: PathBuf,
+) -> SerResult<String> {
+    let id = app_handle.widgets().import_widget(&src)?;
+    Ok(id)
+}
+
 /// Install a widget from the registry.
 ///
 /// This command is a wrapper of [`crate::WidgetsManager::install`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn install<R: Runtime>(
     app_handle: AppHandle<R>,
     widget: RegistryWidgetReference,
+    version: Option<String>,
+    request_id: Option<String>,
+    grant: Option<WidgetPermissions>,
 ) -> SerResult<()> {
-    app_handle.widgets().install(&widget).await?;
+    app_handle
+        .widgets()
+        .install(&widget, version.as_deref(), request_id.as_deref(), grant)
+        .await?;
     Ok(())
 }
 
+/// Grant permissions to a widget, or revoke them.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::grant_permissions`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn grant_permissions<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    permissions: Option<WidgetPermissions>,
+) -> SerResult<()> {
+    app_handle.widgets().grant_permissions(&id, permissions)?;
+    Ok(())
+}
+
+/// Cancel a pending install or upgrade from the registry.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::cancel_install`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_install<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request_id: String,
+) -> SerResult<bool> {
+    let cancelled = app_handle.widgets().cancel_install(&request_id);
+    Ok(cancelled)
+}
+
+/// List the releases of a widget in the registry.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::list_releases`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn list_releases<R: Runtime>(
+    app_handle: AppHandle<R>,
+    handle: String,
+    id: String,
+) -> SerResult<Vec<RegistryEntryRelease>> {
+    let releases = app_handle.widgets().list_releases(&handle, &id).await?;
+    Ok(releases)
+}
+
 /// Uninstall a widget from the registry.
 ///
 /// This command is a wrapper of [`crate::WidgetsManager::uninstall`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn uninstall<R: Runtime>(
@@ -97,12 +344,152 @@ pub async fn uninstall<R: Runtime>(
 /// Upgrade a widget from the registry.
 ///
 /// This command is a wrapper of [`crate::WidgetsManager::upgrade`].
+#[instrument]
 #[tauri::command]
 #[specta::specta]
 pub async fn upgrade<R: Runtime>(
     app_handle: AppHandle<R>,
     widget: RegistryWidgetReference,
+    request_id: Option<String>,
 ) -> SerResult<()> {
-    app_handle.widgets().upgrade(&widget).await?;
+    app_handle
+        .widgets()
+        .upgrade(&widget, request_id.as_deref())
+        .await?;
+    Ok(())
+}
+
+/// Roll back a widget installed from the registry to its previous release.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::rollback_widget`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn rollback_widget<R: Runtime>(app_handle: AppHandle<R>, id: String) -> SerResult<()> {
+    app_handle.widgets().rollback_widget(&id).await?;
     Ok(())
 }
+
+/// Map a runtime stack trace of a widget back to its original sources.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::symbolicate`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn symbolicate<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    stack: String,
+) -> SerResult<String> {
+    Ok(app_handle.widgets().symbolicate(&id, &stack))
+}
+
+/// Report the health of every candidate widget directory.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::catalog_report`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn catalog_report<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> SerResult<Vec<WidgetDirReport>> {
+    Ok(app_handle.widgets().catalog_report()?)
+}
+
+/// Validate the manifest of a widget.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::validate_manifest`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_manifest<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> SerResult<Vec<ManifestDiagnostic>> {
+    let diagnostics = app_handle.widgets().validate_manifest(&id)?;
+    Ok(diagnostics)
+}
+
+/// Validate a widget without rendering it.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::validate_widget`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn validate_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> SerResult<ValidationReport> {
+    Ok(app_handle.widgets().validate_widget(&id).await)
+}
+
+/// Check for upgrades of widgets installed from the registry.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::check_updates`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn check_updates<R: Runtime>(app_handle: AppHandle<R>) -> SerResult<Vec<WidgetUpgrade>> {
+    let upgrades = app_handle.widgets().check_updates().await?;
+    Ok(upgrades)
+}
+
+/// Update a widget installed from the registry to its latest release.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::update_widget`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn update_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> SerResult<Option<WidgetUpgrade>> {
+    let upgrade = app_handle.widgets().update_widget(&id).await?;
+    Ok(upgrade)
+}
+
+/// Update all widgets installed from the registry to their latest releases.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::update_all`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn update_all<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> SerResult<BTreeMap<String, Outcome<String>>> {
+    let outcomes = app_handle.widgets().update_all().await?;
+    Ok(outcomes)
+}
+
+/// Reinstall the widgets recorded in a lockfile that are missing locally.
+///
+/// This command is a wrapper of
+/// [`crate::WidgetsManager::reinstall_from_lockfile`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn reinstall_from_lockfile<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: Option<PathBuf>,
+) -> SerResult<BTreeMap<String, Outcome<()>>> {
+    let outcomes = app_handle
+        .widgets()
+        .reinstall_from_lockfile(path.as_deref())
+        .await?;
+    Ok(outcomes)
+}
+
+/// Report an abusive widget in the registry.
+///
+/// This command is a wrapper of [`crate::WidgetsManager::report_widget`].
+#[instrument]
+#[tauri::command]
+#[specta::specta]
+pub async fn report_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    widget: RegistryWidgetReference,
+    reason: String,
+) -> SerResult<bool> {
+    let submitted = app_handle.widgets().report_widget(&widget, &reason).await?;
+    Ok(submitted)
+}
@@ -0,0 +1,221 @@
+//! Damage tracking for partial refreshes of the canvas.
+//!
+//! Full-canvas repaints are costly on e-ink-like or low-power setups. Since the
+//! backend knows which widgets are rendered, moved, or resized, it aggregates
+//! the affected regions ("damage") between frames, so that the canvas can
+//! confine repaints to them. Metrics are recorded to validate the savings.
+
+use std::collections::BTreeSet;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::catalog::WidgetSettings;
+
+/// The maximum number of regions in a frame before falling back to a full
+/// refresh.
+///
+/// Confining repaints to many small regions can be more expensive than simply
+/// repainting everything.
+const MAX_REGIONS: usize = 16;
+
+/// The fraction of the viewport that, once damaged, makes a frame fall back to
+/// a full refresh.
+const FULL_REFRESH_THRESHOLD: f64 = 0.5;
+
+/// A rectangular region of the canvas in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+pub struct DamageRect {
+    /// The leftmost x-coordinate.
+    pub x: i32,
+    /// The topmost y-coordinate.
+    pub y: i32,
+    /// The width.
+    pub width: u32,
+    /// The height.
+    pub height: u32,
+}
+
+impl DamageRect {
+    /// The region occupied by a widget.
+    pub fn from_settings(settings: &WidgetSettings) -> Self {
+        Self {
+            x: settings.x,
+            y: settings.y,
+            width: settings.width,
+            height: settings.height,
+        }
+    }
+
+    /// The exclusive right and bottom edges.
+    fn end(&self) -> (i64, i64) {
+        (
+            self.x as i64 + self.width as i64,
+            self.y as i64 + self.height as i64,
+        )
+    }
+
+    /// The area in pixels.
+    fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// Whether the region overlaps or touches another.
+    fn touches(&self, other: &Self) -> bool {
+        let (ex, ey) = self.end();
+        let (oex, oey) = other.end();
+        (self.x as i64) <= oex
+            && (other.x as i64) <= ex
+            && (self.y as i64) <= oey
+            && (other.y as i64) <= ey
+    }
+
+    /// The bounding region of this and another region.
+    fn union(&self, other: &Self) -> Self {
+        let (ex, ey) = self.end();
+        let (oex, oey) = other.end();
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Self {
+            x,
+            y,
+            width: (ex.max(oex) - x as i64) as u32,
+            height: (ey.max(oey) - y as i64) as u32,
+        }
+    }
+
+    /// Clip the region to a viewport at the origin.
+    ///
+    /// This returns `None` if the region lies entirely outside the viewport.
+    fn clip(&self, width: u32, height: u32) -> Option<Self> {
+        let (ex, ey) = self.end();
+        let x = (self.x as i64).max(0);
+        let y = (self.y as i64).max(0);
+        let ex = ex.min(width as i64);
+        let ey = ey.min(height as i64);
+        (x < ex && y < ey).then(|| Self {
+            x: x as i32,
+            y: y as i32,
+            width: (ex - x) as u32,
+            height: (ey - y) as u32,
+        })
+    }
+}
+
+/// Hints of the canvas regions to refresh in the next frame.
+#[derive(Debug, Default, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DamageHints {
+    /// Whether the whole canvas should be refreshed.
+    ///
+    /// If true, [`Self::regions`] should be ignored.
+    pub full: bool,
+    /// The damaged regions, which do not overlap each other.
+    pub regions: Vec<DamageRect>,
+    /// The IDs of the widgets that have changed.
+    pub ids: Vec<String>,
+}
+
+/// Metrics of damage tracking for validating the savings of partial refreshes.
+///
+/// Metrics are accumulated since application startup.
+#[derive(Debug, Clone, Default, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DamageMetrics {
+    /// The number of frames with any damage.
+    pub frames: u64,
+    /// The number of frames that fell back to a full refresh.
+    pub full_frames: u64,
+    /// The total number of pixels refreshed across frames.
+    pub refreshed_pixels: u64,
+    /// The total number of pixels of the viewport across frames.
+    ///
+    /// This is what would have been refreshed without damage tracking.
+    pub viewport_pixels: u64,
+}
+
+/// Mutable part of the [`DamageTracker`].
+#[derive(Default)]
+struct DamageState {
+    /// Whether the whole canvas is damaged.
+    full: bool,
+    /// The damaged regions since the last frame.
+    regions: Vec<DamageRect>,
+    /// The IDs of the changed widgets since the last frame.
+    ids: BTreeSet<String>,
+    /// The accumulated metrics.
+    metrics: DamageMetrics,
+}
+
+/// Tracker of damaged canvas regions between frames.
+#[derive(Default)]
+pub struct DamageTracker(Mutex<DamageState>);
+
+impl DamageTracker {
+    /// Mark the region of a widget as damaged.
+    pub fn damage(&self, id: &str, rect: DamageRect) {
+        let mut state = self.0.lock();
+        state.regions.push(rect);
+        state.ids.insert(id.to_string());
+    }
+
+    /// Mark the whole canvas as damaged.
+    pub fn damage_full(&self) {
+        self.0.lock().full = true;
+    }
+
+    /// Take the damage since the last frame as hints for the next frame.
+    ///
+    /// Overlapping regions are merged and clipped to the viewport. If there
+    /// are too many regions or they cover a large part of the viewport, this
+    /// falls back to a full refresh. The damage is reset afterwards.
+    pub fn take(&self, viewport_width: u32, viewport_height: u32) -> DamageHints {
+        let mut state = self.0.lock();
+        let full = std::mem::take(&mut state.full);
+        let damaged = std::mem::take(&mut state.regions);
+        let ids = std::mem::take(&mut state.ids).into_iter().collect();
+        if !full && damaged.is_empty() {
+            return DamageHints::default();
+        }
+
+        // Merge until no two regions touch, since merging may create new
+        // overlaps with previously merged regions
+        let mut regions: Vec<DamageRect> = vec![];
+        for rect in damaged
+            .iter()
+            .filter_map(|rect| rect.clip(viewport_width, viewport_height))
+        {
+            let mut merged = rect;
+            while let Some(index) = regions.iter().position(|other| other.touches(&merged)) {
+                merged = merged.union(&regions.swap_remove(index));
+            }
+            regions.push(merged);
+        }
+
+        let viewport_pixels = viewport_width as u64 * viewport_height as u64;
+        let damaged_pixels = regions.iter().map(DamageRect::area).sum::<u64>();
+        let full = full
+            || regions.len() > MAX_REGIONS
+            || damaged_pixels as f64 > viewport_pixels as f64 * FULL_REFRESH_THRESHOLD;
+
+        let metrics = &mut state.metrics;
+        metrics.frames += 1;
+        metrics.viewport_pixels += viewport_pixels;
+        if full {
+            metrics.full_frames += 1;
+            metrics.refreshed_pixels += viewport_pixels;
+            return DamageHints {
+                full,
+                regions: vec![],
+                ids,
+            };
+        }
+        metrics.refreshed_pixels += damaged_pixels;
+        DamageHints { full, regions, ids }
+    }
+
+    /// Get the accumulated metrics.
+    pub fn metrics(&self) -> DamageMetrics {
+        self.0.lock().metrics.clone()
+    }
+}
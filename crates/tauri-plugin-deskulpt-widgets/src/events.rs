@@ -4,7 +4,67 @@ use deskulpt_common::event::Event;
 use deskulpt_common::outcome::Outcome;
 use serde::Serialize;
 
-use crate::catalog::WidgetCatalog;
+use crate::catalog::{Widget, WidgetCatalog};
+use crate::registry::{InstallProgress, RevokedWidget, WidgetUpgrade};
+use crate::render::BundleDiagnostic;
+
+/// Event for warning that the bundle of a widget exceeds its size budget.
+///
+/// This is emitted after each successful bundle that is over budget, so that
+/// widget authors notice bloated dependencies early.
+#[derive(Debug, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSizeEvent<'a> {
+    /// The ID of the widget.
+    pub id: &'a str,
+    /// The size of the bundle in bytes.
+    pub size: u64,
+    /// The size budget in bytes that has been exceeded.
+    pub budget: u64,
+}
+
+/// Event for notifying frontend windows of a fine-grained catalog change.
+///
+/// Unlike [`UpdateEvent`] which carries the full catalog, this event only
+/// carries the affected widget entry. The full snapshot is reserved for
+/// (re)synchronizing the whole catalog.
+#[derive(Debug, Serialize, specta::Type, Event)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum DiffEvent<'a> {
+    /// A widget has been added to the catalog.
+    WidgetAdded {
+        /// The ID of the widget.
+        id: &'a str,
+        /// The added widget.
+        widget: &'a Widget,
+    },
+    /// A widget has been removed from the catalog.
+    WidgetRemoved {
+        /// The ID of the widget.
+        id: &'a str,
+    },
+    /// A widget in the catalog has changed.
+    WidgetChanged {
+        /// The ID of the widget.
+        id: &'a str,
+        /// The changed widget.
+        widget: &'a Widget,
+    },
+}
+
+/// Event for reporting the progress of installing a widget from the registry.
+///
+/// This is emitted for installs and upgrades started with a request ID, so
+/// that the frontend can follow the progress of its own request and cancel it
+/// if needed.
+#[derive(Debug, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallProgressEvent<'a> {
+    /// The ID of the install request.
+    pub request_id: &'a str,
+    /// The progress of the installation.
+    pub progress: InstallProgress,
+}
 
 /// Event for reporting the rendering result of a widget to the canvas.
 #[derive(Debug, Serialize, specta::Type, Event)]
@@ -13,8 +73,52 @@ pub struct RenderEvent<'a> {
     pub id: &'a str,
     /// Either the code string to render or a bundling error message.
     pub report: &'a Outcome<String>,
+    /// Structured diagnostics if bundling failed.
+    ///
+    /// Each diagnostic locates the problem in the original widget sources.
+    /// This may be empty even on failure if the error is not attributable to
+    /// specific locations.
+    pub diagnostics: &'a [BundleDiagnostic],
+    /// Structured warnings if bundling succeeded.
+    ///
+    /// These are the type errors found if type checking is enabled for the
+    /// widget. They do not prevent the widget from rendering.
+    pub warnings: &'a [BundleDiagnostic],
+}
+
+/// Event for reporting to the canvas that a widget has timed out rendering.
+///
+/// This is emitted if the canvas does not report completion of evaluating the
+/// widget module within the configured render timeout, e.g., when the module
+/// awaits forever at the top level.
+#[derive(Debug, Serialize, specta::Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeoutEvent<'a> {
+    /// The ID of the widget.
+    pub id: &'a str,
+    /// The render timeout in seconds that has been exceeded.
+    pub timeout_seconds: u64,
 }
 
 /// Event for notifying frontend windows of a widget catalog update.
+///
+/// This carries the full catalog snapshot and is only emitted when the whole
+/// catalog is (re)synchronized. See [`DiffEvent`] for incremental changes.
 #[derive(Debug, Serialize, specta::Type, Event)]
 pub struct UpdateEvent<'a>(pub &'a WidgetCatalog);
+
+/// Event for flagging installed widgets whose packages have been revoked by
+/// the registry.
+///
+/// This is emitted after each check for updates, even if no widgets are
+/// revoked, so that stale flags can be cleared.
+#[derive(Debug, Serialize, specta::Type, Event)]
+pub struct RevokedWidgetsEvent<'a>(pub &'a [RevokedWidget]);
+
+/// Event for notifying frontend windows of available upgrades of widgets
+/// installed from the registry.
+///
+/// This is emitted after each check for updates, even if no upgrades are
+/// available, so that stale badges can be cleared.
+#[derive(Debug, Serialize, specta::Type, Event)]
+pub struct UpgradesAvailableEvent<'a>(pub &'a [WidgetUpgrade]);
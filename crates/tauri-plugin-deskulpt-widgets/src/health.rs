@@ -0,0 +1,135 @@
+//! Health report of the widgets directory.
+//!
+//! The catalog only lists directories that are recognized as widgets, so a
+//! directory skipped for lacking a manifest or marked as ignored leaves no
+//! trace there. The health report covers every candidate directory instead,
+//! telling why it is or is not a widget and what may be wrong with it.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::catalog::{ManifestDiagnostic, WidgetManifest, discover_widget_dirs};
+
+/// The status of a candidate widget directory.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WidgetDirStatus {
+    /// The directory is recognized as a widget.
+    Widget,
+    /// The directory is skipped because it has no widget manifest.
+    NoManifest,
+    /// The directory is skipped because its widget manifest marks itself as
+    /// ignored.
+    Ignored,
+    /// The widget manifest of the directory cannot be loaded.
+    #[serde(rename_all = "camelCase")]
+    InvalidManifest {
+        /// The error message.
+        message: String,
+        /// The path to the offending field in the manifest, e.g.,
+        /// `requiredPlugins[0]`.
+        ///
+        /// This is `None` if the manifest cannot be read or is not valid JSON
+        /// or TOML.
+        field: Option<String>,
+    },
+}
+
+/// The health report of a candidate widget directory.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetDirReport {
+    /// The path of the directory relative to the widgets directory, separated
+    /// by `/`.
+    ///
+    /// This is the widget ID if the directory is recognized as a widget.
+    pub id: String,
+    /// The status of the directory.
+    pub status: WidgetDirStatus,
+    /// The problems found in the widget manifest that do not prevent the
+    /// directory from being recognized as a widget.
+    ///
+    /// This is empty unless the directory is recognized as a widget.
+    pub warnings: Vec<String>,
+}
+
+/// Check a widget manifest for problems that would surface only later.
+///
+/// This includes the diagnostics of [`WidgetManifest::validate`].
+fn manifest_warnings(
+    manifest: &WidgetManifest,
+    dir: &Path,
+    plugins: &BTreeMap<String, String>,
+) -> Vec<String> {
+    let mut warnings = manifest
+        .validate(dir)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    if WidgetManifest::is_conflicting(dir) {
+        warnings.push(format!(
+            "Both {} and {} exist; the latter is ignored",
+            WidgetManifest::FILE_NAME,
+            WidgetManifest::TOML_FILE_NAME
+        ));
+    }
+    for missing in manifest
+        .required_plugins
+        .iter()
+        .filter_map(|requirement| requirement.check(plugins))
+    {
+        let warning = match missing.found {
+            Some(found) => format!(
+                "Required plugin {:?} is unavailable; found version {found}",
+                missing.requirement
+            ),
+            None => format!("Required plugin {:?} is unavailable", missing.requirement),
+        };
+        warnings.push(warning);
+    }
+    warnings
+}
+
+/// Report the health of every candidate widget directory.
+///
+/// Candidates are discovered in the given widgets directory the same way as
+/// when loading the catalog, and reported in the order of their IDs. The given
+/// plugins are used to check the required plugins of widgets.
+pub fn report(dir: &Path, plugins: &BTreeMap<String, String>) -> Result<Vec<WidgetDirReport>> {
+    let mut dirs = discover_widget_dirs(dir)?;
+    dirs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let reports = dirs
+        .into_iter()
+        .map(|(id, path)| {
+            let mut warnings = vec![];
+            let status = if !WidgetManifest::exists(&path) {
+                WidgetDirStatus::NoManifest
+            } else {
+                match WidgetManifest::load(&path) {
+                    Ok(Some(manifest)) => {
+                        warnings = manifest_warnings(&manifest, &path, plugins);
+                        WidgetDirStatus::Widget
+                    },
+                    Ok(None) => WidgetDirStatus::Ignored,
+                    Err(e) => {
+                        let diagnostic = ManifestDiagnostic::from_load_error(&e);
+                        WidgetDirStatus::InvalidManifest {
+                            message: diagnostic.message,
+                            field: diagnostic.field,
+                        }
+                    },
+                }
+            };
+            WidgetDirReport {
+                id,
+                status,
+                warnings,
+            }
+        })
+        .collect();
+    Ok(reports)
+}
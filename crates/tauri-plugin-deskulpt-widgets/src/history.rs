@@ -0,0 +1,156 @@
+//! Undo history of widget layout changes.
+//!
+//! Layout changes, i.e., changes to the position, size, or z-index of widgets,
+//! are recorded in a bounded history so that an accidental drag or resize can
+//! be reverted. Consecutive changes of the same widget in quick succession,
+//! e.g., the intermediate updates of a single drag, are coalesced into one
+//! entry.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::catalog::{WidgetSettings, WidgetSettingsPatch};
+
+/// The maximum number of entries in the undo history.
+const MAX_ENTRIES: usize = 50;
+
+/// The window within which consecutive changes of the same widget are
+/// coalesced into one entry.
+const COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// The layout of a widget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    z_index: i16,
+}
+
+impl Layout {
+    /// The layout of a widget with the given settings.
+    pub fn of(settings: &WidgetSettings) -> Self {
+        Self {
+            x: settings.x,
+            y: settings.y,
+            width: settings.width,
+            height: settings.height,
+            z_index: settings.z_index,
+        }
+    }
+
+    /// This layout with a patch applied.
+    pub fn patched(self, patch: &WidgetSettingsPatch) -> Self {
+        Self {
+            x: patch.x.unwrap_or(self.x),
+            y: patch.y.unwrap_or(self.y),
+            width: patch.width.unwrap_or(self.width),
+            height: patch.height.unwrap_or(self.height),
+            z_index: patch.z_index.unwrap_or(self.z_index),
+        }
+    }
+
+    /// The patch that restores this layout.
+    pub fn to_patch(self) -> WidgetSettingsPatch {
+        WidgetSettingsPatch {
+            x: Some(self.x),
+            y: Some(self.y),
+            width: Some(self.width),
+            height: Some(self.height),
+            z_index: Some(self.z_index),
+            ..Default::default()
+        }
+    }
+}
+
+/// A recorded layout change of a widget.
+#[derive(Debug)]
+pub struct LayoutChange {
+    /// The ID of the widget.
+    pub id: String,
+    /// The layout before the change, restored on undo.
+    pub before: Layout,
+    /// The layout after the change, restored on redo.
+    pub after: Layout,
+    /// When the change was last recorded.
+    ///
+    /// This is `None` if the change must not be coalesced with later ones.
+    at: Option<Instant>,
+}
+
+/// The undo and redo history of widget layout changes.
+#[derive(Debug, Default)]
+pub struct LayoutHistory {
+    /// Changes that can be undone, the most recent at the back.
+    undo: VecDeque<LayoutChange>,
+    /// Changes that can be redone, the most recently undone at the back.
+    redo: Vec<LayoutChange>,
+}
+
+impl LayoutHistory {
+    /// Record a layout change of a widget.
+    ///
+    /// This is a no-op if the layout has not changed. Otherwise the redo
+    /// history is cleared, and the oldest entry is dropped if the history is
+    /// full.
+    pub fn record(&mut self, id: &str, before: Layout, after: Layout) {
+        if before == after {
+            return;
+        }
+        self.redo.clear();
+
+        let now = Instant::now();
+        if let Some(last) = self.undo.back_mut()
+            && last.id == id
+            && last.after == before
+            && last
+                .at
+                .is_some_and(|at| now.duration_since(at) < COALESCE_WINDOW)
+        {
+            last.after = after;
+            last.at = Some(now);
+            if last.before == last.after {
+                self.undo.pop_back();
+            }
+            return;
+        }
+
+        if self.undo.len() == MAX_ENTRIES {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(LayoutChange {
+            id: id.to_string(),
+            before,
+            after,
+            at: Some(now),
+        });
+    }
+
+    /// Take the most recent change to undo, if any.
+    pub fn pop_undo(&mut self) -> Option<LayoutChange> {
+        self.undo.pop_back()
+    }
+
+    /// Take the most recently undone change to redo, if any.
+    pub fn pop_redo(&mut self) -> Option<LayoutChange> {
+        self.redo.pop()
+    }
+
+    /// Keep a change that has just been undone so that it can be redone.
+    pub fn push_redo(&mut self, change: LayoutChange) {
+        self.redo.push(change);
+    }
+
+    /// Keep a change that has just been redone so that it can be undone again.
+    ///
+    /// Unlike [`Self::record`], this leaves the redo history intact and never
+    /// coalesces.
+    pub fn push_undo(&mut self, mut change: LayoutChange) {
+        if self.undo.len() == MAX_ENTRIES {
+            self.undo.pop_front();
+        }
+        change.at = None;
+        self.undo.push_back(change);
+    }
+}
@@ -0,0 +1,169 @@
+//! Hooks on widget changes.
+//!
+//! Other subsystems may register hooks on the [`WidgetsManager`] to react to
+//! changes of individual widgets, instead of polling the whole catalog. Hooks
+//! are triggered by a dedicated worker, in the order that changes are made,
+//! so that they never run while the catalog is locked.
+//!
+//! Bursts of changes to the same widget, e.g., while it is being dragged, are
+//! coalesced into a single trigger with the settings before the first change
+//! and after the last change.
+//!
+//! [`WidgetsManager`]: crate::WidgetsManager
+
+use std::pin::Pin;
+
+use anyhow::Result;
+use tauri::{AppHandle, Runtime};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant, Sleep};
+
+use crate::WidgetsExt;
+use crate::catalog::WidgetSettings;
+
+#[doc(hidden)]
+type OnWidgetSettingsChange = Box<dyn Fn(&str, &WidgetSettings, &WidgetSettings) + Send + Sync>;
+
+/// The collection of hooks on widget changes.
+#[derive(Default)]
+pub struct WidgetsHooks {
+    /// Hooks triggered on widget settings change.
+    ///
+    /// See [`WidgetsManager::on_widget_settings_change`] for registration.
+    ///
+    /// [`WidgetsManager::on_widget_settings_change`]: crate::WidgetsManager::on_widget_settings_change
+    pub on_widget_settings_change: Vec<OnWidgetSettingsChange>,
+}
+
+/// The quiet period after which a burst of changes is considered over.
+const COALESCE_WINDOW: Duration = Duration::from_millis(100);
+
+/// The maximum delay of triggering hooks since the first change of a burst.
+///
+/// This guarantees that hooks are eventually triggered even if changes keep
+/// coming, e.g., during a long drag.
+const COALESCE_MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Tasks that the hook worker can process.
+#[derive(Debug)]
+pub enum HookTask {
+    /// The settings of a widget have changed.
+    ///
+    /// The worker will trigger all hooks on widget settings change.
+    WidgetSettingsChanged {
+        id: String,
+        old: WidgetSettings,
+        new: WidgetSettings,
+    },
+}
+
+/// The worker for triggering hooks on widget changes.
+struct HookWorker<R: Runtime> {
+    /// The Tauri app handle.
+    app_handle: AppHandle<R>,
+    /// The receiver for incoming tasks.
+    rx: mpsc::UnboundedReceiver<HookTask>,
+    /// The coalesced settings changes pending to trigger hooks.
+    ///
+    /// Each entry holds the widget ID, the settings before the first change,
+    /// and the settings after the last change, in the order of first changes.
+    pending: Vec<(String, WidgetSettings, WidgetSettings)>,
+    /// When the first pending change was received.
+    pending_since: Option<Instant>,
+    /// The timer for triggering pending hooks.
+    deadline: Pin<Box<Sleep>>,
+}
+
+impl<R: Runtime> HookWorker<R> {
+    /// Create a new [`HookWorker`] instance.
+    fn new(app_handle: AppHandle<R>, rx: mpsc::UnboundedReceiver<HookTask>) -> Self {
+        Self {
+            app_handle,
+            rx,
+            pending: vec![],
+            pending_since: None,
+            deadline: Box::pin(tokio::time::sleep(COALESCE_WINDOW)),
+        }
+    }
+
+    /// Run the worker event loop.
+    ///
+    /// This function will run indefinitely until the worker channel is closed,
+    /// after which pending hooks are triggered one last time.
+    async fn run(mut self) {
+        loop {
+            tokio::select! {
+                _ = &mut self.deadline, if self.pending_since.is_some() => {
+                    self.flush();
+                },
+                task = self.rx.recv() => match task {
+                    Some(task) => self.handle_task(task),
+                    None => break,
+                },
+            }
+        }
+        self.flush();
+    }
+
+    /// Handle an incoming task.
+    fn handle_task(&mut self, task: HookTask) {
+        match task {
+            HookTask::WidgetSettingsChanged { id, old, new } => {
+                match self
+                    .pending
+                    .iter_mut()
+                    .find(|(pending_id, ..)| *pending_id == id)
+                {
+                    Some((_, _, pending_new)) => *pending_new = new,
+                    None => self.pending.push((id, old, new)),
+                }
+            },
+        }
+
+        let now = Instant::now();
+        let first = *self.pending_since.get_or_insert(now);
+        let deadline = (now + COALESCE_WINDOW).min(first + COALESCE_MAX_DELAY);
+        self.deadline.as_mut().reset(deadline);
+    }
+
+    /// Trigger hooks for all pending changes.
+    fn flush(&mut self) {
+        self.pending_since = None;
+        let widgets = self.app_handle.widgets();
+        for (id, old, new) in self.pending.drain(..) {
+            widgets.trigger_widget_settings_hooks(&id, &old, &new);
+        }
+    }
+}
+
+/// Handle for communicating with the hook worker.
+pub struct HookWorkerHandle(mpsc::UnboundedSender<HookTask>);
+
+impl HookWorkerHandle {
+    /// Create a new [`HookWorkerHandle`] instance.
+    ///
+    /// This immediately spawns a dedicated worker on Tauri's singleton async
+    /// runtime that listens for incoming [`HookTask`]s and processes them
+    /// asynchronously in order, coalescing bursts of changes.
+    pub fn new<R: Runtime>(app_handle: AppHandle<R>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(async move {
+            HookWorker::new(app_handle, rx).run().await;
+        });
+        Self(tx)
+    }
+
+    /// Instruct the worker to process a task.
+    ///
+    /// This does not block. The task is sent to the worker for asynchronous
+    /// processing and does not wait for completion. An error is returned only
+    /// if task submission fails, but not if task processing fails.
+    pub fn process(&self, task: HookTask) -> Result<()> {
+        Ok(self.0.send(task)?)
+    }
+
+    /// Whether the worker is still running.
+    pub fn is_alive(&self) -> bool {
+        !self.0.is_closed()
+    }
+}
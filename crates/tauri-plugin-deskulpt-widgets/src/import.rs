@@ -0,0 +1,123 @@
+//! Import of widgets from other desktop widget platforms.
+//!
+//! Widgets from other platforms are translated into Deskulpt widget scaffolds
+//! by pluggable [`Converter`]s. Scaffolds are best-effort: features that have
+//! no Deskulpt counterpart are annotated with `TODO` comments in the generated
+//! code for the user to follow up on.
+
+mod rainmeter;
+mod ubersicht;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use rainmeter::RainmeterConverter;
+use serde_json::json;
+use ubersicht::UbersichtConverter;
+
+/// A converter from another platform's widget format.
+pub trait Converter: Send + Sync {
+    /// The name of the source platform.
+    fn platform(&self) -> &'static str;
+
+    /// Whether the converter recognizes the source at the given path.
+    fn detect(&self, src: &Path) -> bool;
+
+    /// Convert the source at the given path into a Deskulpt widget scaffold.
+    fn convert(&self, src: &Path) -> Result<WidgetScaffold>;
+}
+
+/// The available converters, tried in order.
+const CONVERTERS: &[&dyn Converter] = &[&RainmeterConverter, &UbersichtConverter];
+
+/// A Deskulpt widget scaffold produced by a [`Converter`].
+#[derive(Debug, Default)]
+pub struct WidgetScaffold {
+    /// The display name of the widget.
+    pub name: String,
+    /// The authors of the widget.
+    pub authors: Vec<String>,
+    /// A short description of the widget.
+    pub description: Option<String>,
+    /// The version of the widget.
+    pub version: Option<String>,
+    /// The refresh interval of the widget in seconds.
+    pub refresh_interval_seconds: Option<u64>,
+    /// The entry file path relative to the root of the widget.
+    pub entry: String,
+    /// The files of the widget keyed by paths relative to the root.
+    pub files: BTreeMap<String, String>,
+}
+
+impl WidgetScaffold {
+    /// Write the scaffold to a widget directory.
+    ///
+    /// This writes the widget manifest along with all files of the scaffold.
+    /// The directory must not exist yet.
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        if dir.exists() {
+            bail!("Directory already exists: {}", dir.display());
+        }
+        std::fs::create_dir_all(dir)?;
+
+        let mut manifest = json!({ "name": self.name, "entry": self.entry });
+        if !self.authors.is_empty() {
+            manifest["authors"] = json!(self.authors);
+        }
+        if let Some(description) = &self.description {
+            manifest["description"] = json!(description);
+        }
+        if let Some(version) = &self.version {
+            manifest["version"] = json!(version);
+        }
+        if let Some(interval) = self.refresh_interval_seconds {
+            manifest["refreshIntervalSeconds"] = json!(interval);
+        }
+        std::fs::write(
+            dir.join("deskulpt.widget.json"),
+            serde_json::to_string_pretty(&manifest)?,
+        )?;
+
+        for (path, content) in &self.files {
+            let path = dir.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Convert a widget from another platform into a Deskulpt widget scaffold.
+///
+/// The first converter that recognizes the source is used. An error is
+/// returned if no converter recognizes the source.
+pub fn convert(src: &Path) -> Result<WidgetScaffold> {
+    let converter = CONVERTERS
+        .iter()
+        .find(|converter| converter.detect(src))
+        .with_context(|| format!("Unrecognized widget format: {}", src.display()))?;
+    tracing::info!(
+        platform = converter.platform(),
+        src = %src.display(),
+        "Converting widget",
+    );
+    converter.convert(src)
+}
+
+/// Render `TODO` annotations as a JavaScript comment block.
+fn todo_comments(platform: &str, name: &str, todos: &[String]) -> String {
+    let mut comments = format!(
+        "// Converted from {platform} widget \"{name}\" by the Deskulpt import assistant.\n"
+    );
+    if !todos.is_empty() {
+        comments.push_str("//\n// Some features could not be converted automatically:\n//\n");
+        for todo in todos {
+            comments.push_str(&format!("// TODO: {todo}\n"));
+        }
+    }
+    comments
+}
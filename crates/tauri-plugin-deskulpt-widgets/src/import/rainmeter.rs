@@ -0,0 +1,271 @@
+//! Converter for Rainmeter skins.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use regex::{Captures, Regex};
+
+use super::{Converter, WidgetScaffold, todo_comments};
+
+/// Regex for variable references in option values.
+static VARIABLE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"#([^#\s]+)#").unwrap());
+
+/// A section of a Rainmeter skin INI file.
+struct Section {
+    /// The name of the section.
+    name: String,
+    /// The options of the section, with keys in lowercase.
+    options: HashMap<String, String>,
+}
+
+impl Section {
+    /// Get an option by its lowercase key.
+    fn get(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(|s| s.as_str())
+    }
+}
+
+/// Parse a Rainmeter skin INI file into sections in order.
+///
+/// Variables defined in the `[Variables]` section are substituted in option
+/// values where referenced as `#Name#`.
+fn parse_ini(content: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = vec![];
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            sections.push(Section {
+                name: name.trim().to_string(),
+                options: HashMap::new(),
+            });
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some(section) = sections.last_mut()
+        {
+            let value = value.trim().trim_matches('"').to_string();
+            section.options.insert(key.trim().to_lowercase(), value);
+        }
+    }
+
+    let variables = sections
+        .iter()
+        .find(|s| s.name.eq_ignore_ascii_case("Variables"))
+        .map(|s| s.options.clone())
+        .unwrap_or_default();
+    for section in &mut sections {
+        for value in section.options.values_mut() {
+            *value = VARIABLE_RE
+                .replace_all(value, |caps: &Captures| {
+                    variables
+                        .get(&caps[1].to_lowercase())
+                        .cloned()
+                        .unwrap_or_else(|| caps[0].to_string())
+                })
+                .into_owned();
+        }
+    }
+    sections
+}
+
+/// Convert a Rainmeter `R,G,B[,A]` color into a CSS color.
+fn css_color(color: &str) -> Option<String> {
+    let parts = color
+        .split(',')
+        .map(|p| p.trim().parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    match parts[..] {
+        [r, g, b] => Some(format!("rgb({r}, {g}, {b})")),
+        [r, g, b, a] => Some(format!("rgba({r}, {g}, {b}, {:.2})", a as f32 / 255.0)),
+        _ => None,
+    }
+}
+
+/// Parse a Rainmeter position, which is absolute only if it is a plain number.
+fn position(value: Option<&str>) -> Option<i32> {
+    value.unwrap_or("0").trim().parse().ok()
+}
+
+/// Converter for Rainmeter skins.
+///
+/// The source can be either a skin INI file or a skin folder containing one.
+/// String meters are converted with their positions and fonts, and time
+/// measures are converted into a live clock. Other meters and measures are
+/// annotated as `TODO`.
+pub struct RainmeterConverter;
+
+impl RainmeterConverter {
+    /// Locate the skin INI file of the source.
+    fn skin_file(src: &Path) -> Option<PathBuf> {
+        let is_ini = |p: &Path| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ini"))
+        };
+        if src.is_file() {
+            return is_ini(src).then(|| src.to_path_buf());
+        }
+        let mut files = std::fs::read_dir(src)
+            .ok()?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|p| p.is_file() && is_ini(p))
+            .collect::<Vec<_>>();
+        files.sort();
+        files.into_iter().next()
+    }
+}
+
+impl Converter for RainmeterConverter {
+    fn platform(&self) -> &'static str {
+        "Rainmeter"
+    }
+
+    fn detect(&self, src: &Path) -> bool {
+        Self::skin_file(src).is_some()
+    }
+
+    fn convert(&self, src: &Path) -> Result<WidgetScaffold> {
+        let skin_file = Self::skin_file(src).context("No Rainmeter skin file found")?;
+        let content = std::fs::read_to_string(&skin_file)
+            .with_context(|| format!("Failed to read {}", skin_file.display()))?;
+        let sections = parse_ini(&content);
+
+        let metadata = sections
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case("Metadata"));
+        let name = metadata
+            .and_then(|m| m.get("name"))
+            .map(str::to_string)
+            .or_else(|| Some(skin_file.file_stem()?.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "Rainmeter Skin".to_string());
+
+        let mut todos = vec![];
+        let mut time_measures = HashSet::new();
+        for section in &sections {
+            match section.get("measure").map(str::to_lowercase).as_deref() {
+                Some("time") => {
+                    if section.get("format").is_some() {
+                        todos.push(format!(
+                            "Measure [{}] uses a custom time format, which is approximated with \
+                             the locale format",
+                            section.name
+                        ));
+                    }
+                    time_measures.insert(section.name.to_lowercase());
+                },
+                Some(kind) => todos.push(format!(
+                    "Measure [{}] of type {kind} is unsupported",
+                    section.name
+                )),
+                None => {},
+            }
+        }
+
+        let mut elements = vec![];
+        for section in &sections {
+            let Some(kind) = section.get("meter").map(str::to_lowercase) else {
+                continue;
+            };
+            if kind != "string" {
+                todos.push(format!(
+                    "Meter [{}] of type {kind} is unsupported",
+                    section.name
+                ));
+                continue;
+            }
+
+            let (x, y) = match (position(section.get("x")), position(section.get("y"))) {
+                (Some(x), Some(y)) => (x, y),
+                (x, y) => {
+                    todos.push(format!(
+                        "Meter [{}] uses relative positioning, which is approximated with 0",
+                        section.name
+                    ));
+                    (x.unwrap_or_default(), y.unwrap_or_default())
+                },
+            };
+
+            let mut style = vec![
+                "position: \"absolute\"".to_string(),
+                format!("left: {x}"),
+                format!("top: {y}"),
+            ];
+            if let Some(size) = section.get("fontsize").and_then(|s| s.parse::<f32>().ok()) {
+                style.push(format!("fontSize: {size}"));
+            }
+            if let Some(color) = section.get("fontcolor").and_then(css_color) {
+                style.push(format!("color: {}", serde_json::to_string(&color)?));
+            }
+            if let Some(face) = section.get("fontface") {
+                style.push(format!("fontFamily: {}", serde_json::to_string(face)?));
+            }
+
+            let measure = section.get("measurename").map(str::to_lowercase);
+            let value = match &measure {
+                Some(m) if time_measures.contains(m) => "{now.toLocaleTimeString()}",
+                Some(_) => {
+                    todos.push(format!(
+                        "Meter [{}] displays an unsupported measure",
+                        section.name
+                    ));
+                    "{\"?\"}"
+                },
+                None => "",
+            };
+            let text = match section.get("text") {
+                Some(text) => text
+                    .split("%1")
+                    .map(|part| Ok(format!("{{{}}}", serde_json::to_string(part)?)))
+                    .collect::<Result<Vec<_>>>()?
+                    .join(value),
+                None => value.to_string(),
+            };
+
+            elements.push(format!(
+                "      <div style={{{{ {} }}}}>{text}</div>",
+                style.join(", ")
+            ));
+        }
+
+        let uses_time = !time_measures.is_empty();
+        let mut code = todo_comments(self.platform(), &name, &todos);
+        if uses_time {
+            code.push_str("\nimport { useEffect, useState } from \"@deskulpt-test/react\";\n");
+        }
+        code.push_str("\nexport default function Widget() {\n");
+        if uses_time {
+            code.push_str(
+                "  const [now, setNow] = useState(new Date());\n\n  useEffect(() => {\n    const \
+                 timer = setInterval(() => setNow(new Date()), 1000);\n    return () => \
+                 clearInterval(timer);\n  }, []);\n\n",
+            );
+        }
+        code.push_str(
+            "  return (\n    <div style={{ position: \"relative\", width: \"100%\", height: \
+             \"100%\" }}>\n",
+        );
+        for element in elements {
+            code.push_str(&element);
+            code.push('\n');
+        }
+        code.push_str("    </div>\n  );\n}\n");
+
+        Ok(WidgetScaffold {
+            name,
+            authors: metadata
+                .and_then(|m| m.get("author"))
+                .map(|author| vec![author.to_string()])
+                .unwrap_or_default(),
+            description: metadata
+                .and_then(|m| m.get("information"))
+                .map(str::to_string),
+            version: metadata.and_then(|m| m.get("version")).map(str::to_string),
+            refresh_interval_seconds: None,
+            entry: "index.jsx".to_string(),
+            files: [("index.jsx".to_string(), code)].into(),
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! Converter for Übersicht widgets.
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+
+use super::{Converter, WidgetScaffold, todo_comments};
+
+/// Regex for the refresh frequency export of an Übersicht widget.
+static REFRESH_FREQUENCY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"export\s+const\s+refreshFrequency\s*=\s*(\d+|false)").unwrap());
+
+/// Regex for imports from the `uebersicht` module.
+static UEBERSICHT_IMPORT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"from\s+["']uebersicht["']"#).unwrap());
+
+/// The shim replacing the `uebersicht` module.
+const UEBERSICHT_SHIM: &str = r#"// Shim for the Übersicht APIs used by the converted widget.
+
+export const css = (strings, ...values) => String.raw(strings, ...values);
+
+// TODO: Running shell commands is unsupported in Deskulpt. Consider using the
+// Deskulpt APIs or plugins instead.
+export const run = () =>
+  Promise.reject(new Error("Running shell commands is unsupported"));
+"#;
+
+/// Converter for Übersicht widgets.
+///
+/// The source can be either a `.widget` folder containing an `index.jsx` or
+/// `index.js` entry, or a single JSX widget file. The widget source is kept as
+/// is and wrapped by a generated entry that calls its `render` function. Shell
+/// commands have no Deskulpt counterpart and are annotated as `TODO`.
+pub struct UbersichtConverter;
+
+impl UbersichtConverter {
+    /// Locate the widget source file of the source.
+    fn source_file(src: &Path) -> Option<PathBuf> {
+        if src.is_file() {
+            let is_js = src
+                .extension()
+                .is_some_and(|ext| ext == "jsx" || ext == "js" || ext == "coffee");
+            return is_js.then(|| src.to_path_buf());
+        }
+        ["index.jsx", "index.js", "index.coffee"]
+            .iter()
+            .map(|name| src.join(name))
+            .find(|path| path.is_file())
+    }
+}
+
+impl Converter for UbersichtConverter {
+    fn platform(&self) -> &'static str {
+        "Übersicht"
+    }
+
+    fn detect(&self, src: &Path) -> bool {
+        Self::source_file(src).is_some()
+    }
+
+    fn convert(&self, src: &Path) -> Result<WidgetScaffold> {
+        let source_file = Self::source_file(src).context("No Übersicht widget source found")?;
+        if source_file.extension().is_some_and(|ext| ext == "coffee") {
+            bail!("CoffeeScript Übersicht widgets are unsupported; please convert to JSX first");
+        }
+        let source = std::fs::read_to_string(&source_file)
+            .with_context(|| format!("Failed to read {}", source_file.display()))?;
+        if !source.contains("render") {
+            bail!("Not an Übersicht widget: {}", source_file.display());
+        }
+
+        let name = src
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Übersicht Widget".to_string());
+
+        let mut todos = vec![];
+        if source.contains("command") {
+            todos.push(
+                "The widget runs a shell command, which is unsupported; `output` is always empty"
+                    .to_string(),
+            );
+        }
+
+        let refresh_interval_seconds = REFRESH_FREQUENCY_RE
+            .captures(&source)
+            .and_then(|caps| caps[1].parse::<u64>().ok())
+            .map(|ms| (ms / 1000).max(1));
+
+        let mut files = vec![];
+        let source = if UEBERSICHT_IMPORT_RE.is_match(&source) {
+            todos
+                .push("Übersicht APIs are replaced by a partial shim in uebersicht.js".to_string());
+            files.push(("uebersicht.js".to_string(), UEBERSICHT_SHIM.to_string()));
+            UEBERSICHT_IMPORT_RE
+                .replace_all(&source, r#"from "./uebersicht.js""#)
+                .into_owned()
+        } else {
+            source
+        };
+        files.push(("ubersicht.jsx".to_string(), source));
+
+        let mut entry = todo_comments(self.platform(), &name, &todos);
+        entry.push_str(
+            r#"
+import * as source from "./ubersicht.jsx";
+
+export default function Widget() {
+  const props = { output: "", error: null };
+  return (
+    <div css={source.className} style={{ width: "100%", height: "100%" }}>
+      {source.render(props)}
+    </div>
+  );
+}
+"#,
+        );
+        files.push(("index.jsx".to_string(), entry));
+
+        Ok(WidgetScaffold {
+            name,
+            refresh_interval_seconds,
+            entry: "index.jsx".to_string(),
+            files: files.into_iter().collect(),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,291 @@
+//! Snapping and alignment of widget layouts.
+//!
+//! Layout changes made by users, e.g., by dragging widgets on the canvas, are
+//! snapped to the grid and to the edges of other widgets as configured in the
+//! settings before they are applied. Widgets can also be aligned with each
+//! other explicitly.
+
+use serde::Deserialize;
+use tauri_plugin_deskulpt_settings::model::Settings;
+
+use crate::catalog::{WidgetSettings, WidgetSettingsPatch};
+
+/// The distance in pixels within which edges snap to those of other widgets.
+const EDGE_SNAP_DISTANCE: i64 = 8;
+
+/// How to align widgets with each other.
+#[derive(Debug, Clone, Copy, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum Alignment {
+    /// Align the left edges with the leftmost one.
+    Left,
+    /// Align the top edges with the topmost one.
+    Top,
+    /// Align the right edges with the rightmost one.
+    Right,
+    /// Align the bottom edges with the bottommost one.
+    Bottom,
+    /// Distribute widgets horizontally with equal gaps between them.
+    ///
+    /// The leftmost and rightmost widgets stay in place.
+    DistributeHorizontally,
+    /// Distribute widgets vertically with equal gaps between them.
+    ///
+    /// The topmost and bottommost widgets stay in place.
+    DistributeVertically,
+}
+
+/// The snapping configuration of widget layouts.
+#[derive(Debug, Clone, Copy)]
+pub struct SnapConfig {
+    /// The grid size in pixels, or zero for no grid.
+    grid: i64,
+    /// Whether to snap to the edges of other widgets.
+    edges: bool,
+}
+
+impl SnapConfig {
+    /// The snapping configuration in the settings.
+    ///
+    /// See [`Settings::grid_size`] and [`Settings::snap_to_edges`].
+    pub fn of(settings: &Settings) -> Self {
+        Self {
+            grid: settings.grid_size.unwrap_or(0) as i64,
+            edges: settings.snap_to_edges,
+        }
+    }
+
+    /// Whether any snapping is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.grid > 0 || self.edges
+    }
+
+    /// Snap a coordinate to the nearest of the given edges, if any is close
+    /// enough.
+    fn snap_to_edges(&self, value: i64, edges: &[i64]) -> Option<i64> {
+        if !self.edges {
+            return None;
+        }
+        edges
+            .iter()
+            .copied()
+            .filter(|edge| (edge - value).abs() <= EDGE_SNAP_DISTANCE)
+            .min_by_key(|edge| (edge - value).abs())
+    }
+
+    /// Snap a coordinate to the nearest grid line.
+    fn snap_to_grid(&self, value: i64) -> i64 {
+        if self.grid <= 0 {
+            return value;
+        }
+        (value as f64 / self.grid as f64).round() as i64 * self.grid
+    }
+
+    /// Snap a coordinate to the given edges, or to the grid otherwise.
+    fn snap(&self, value: i64, edges: &[i64]) -> i64 {
+        self.snap_to_edges(value, edges)
+            .unwrap_or_else(|| self.snap_to_grid(value))
+    }
+
+    /// Snap the start and size along an axis in a patch.
+    ///
+    /// Only changed edges are snapped. If the start changes alone, the widget
+    /// is moved, and either its leading or trailing edge is snapped while its
+    /// size is kept. Otherwise, the leading and trailing edges are snapped
+    /// independently, so that the opposite edge stays in place on resizing.
+    fn snap_axis(
+        &self,
+        start: &mut Option<i32>,
+        size: &mut Option<u32>,
+        current: (i64, i64),
+        edges: &[i64],
+    ) {
+        let (current_start, current_size) = current;
+        let new_start = start.map_or(current_start, |start| start as i64);
+        let new_size = size.map_or(current_size, |size| size as i64);
+
+        if size.is_none_or(|size| size as i64 == current_size) {
+            if new_start != current_start {
+                let snapped = self
+                    .snap_to_edges(new_start, edges)
+                    .or_else(|| {
+                        self.snap_to_edges(new_start + new_size, edges)
+                            .map(|end| end - new_size)
+                    })
+                    .unwrap_or_else(|| self.snap_to_grid(new_start));
+                *start = Some(snapped as i32);
+            }
+            return;
+        }
+
+        let end = new_start + new_size;
+        let snapped_start = if new_start != current_start {
+            self.snap(new_start, edges)
+        } else {
+            new_start
+        };
+        let snapped_end = if end != current_start + current_size {
+            self.snap(end, edges)
+        } else {
+            end
+        };
+        // Snapping must never collapse the widget
+        if snapped_end > snapped_start {
+            if start.is_some() {
+                *start = Some(snapped_start as i32);
+            }
+            *size = Some((snapped_end - snapped_start) as u32);
+        }
+    }
+
+    /// Snap the position and size in a patch of a widget.
+    ///
+    /// The other widgets are those whose edges the widget may snap to.
+    pub fn snap_patch(
+        &self,
+        patch: &mut WidgetSettingsPatch,
+        current: &WidgetSettings,
+        others: &[&WidgetSettings],
+    ) {
+        let horizontal = others
+            .iter()
+            .flat_map(|other| [other.x as i64, other.x as i64 + other.width as i64])
+            .collect::<Vec<_>>();
+        self.snap_axis(
+            &mut patch.x,
+            &mut patch.width,
+            (current.x as i64, current.width as i64),
+            &horizontal,
+        );
+
+        let vertical = others
+            .iter()
+            .flat_map(|other| [other.y as i64, other.y as i64 + other.height as i64])
+            .collect::<Vec<_>>();
+        self.snap_axis(
+            &mut patch.y,
+            &mut patch.height,
+            (current.y as i64, current.height as i64),
+            &vertical,
+        );
+    }
+}
+
+/// Compute the patches aligning widgets with each other.
+///
+/// Only widgets that actually move are patched. Distributing needs at least
+/// three widgets, and is a no-op otherwise.
+pub fn align(
+    widgets: &[(&str, &WidgetSettings)],
+    alignment: Alignment,
+) -> Vec<(String, WidgetSettingsPatch)> {
+    let right = |settings: &WidgetSettings| settings.x + settings.width as i32;
+    let bottom = |settings: &WidgetSettings| settings.y + settings.height as i32;
+    let patch_x = |id: &str, x: i32| {
+        (
+            id.to_string(),
+            WidgetSettingsPatch {
+                x: Some(x),
+                ..Default::default()
+            },
+        )
+    };
+    let patch_y = |id: &str, y: i32| {
+        (
+            id.to_string(),
+            WidgetSettingsPatch {
+                y: Some(y),
+                ..Default::default()
+            },
+        )
+    };
+
+    match alignment {
+        Alignment::Left => {
+            let Some(left) = widgets.iter().map(|(_, settings)| settings.x).min() else {
+                return vec![];
+            };
+            widgets
+                .iter()
+                .filter(|(_, settings)| settings.x != left)
+                .map(|(id, _)| patch_x(id, left))
+                .collect()
+        },
+        Alignment::Top => {
+            let Some(top) = widgets.iter().map(|(_, settings)| settings.y).min() else {
+                return vec![];
+            };
+            widgets
+                .iter()
+                .filter(|(_, settings)| settings.y != top)
+                .map(|(id, _)| patch_y(id, top))
+                .collect()
+        },
+        Alignment::Right => {
+            let Some(edge) = widgets.iter().map(|(_, settings)| right(settings)).max() else {
+                return vec![];
+            };
+            widgets
+                .iter()
+                .filter(|(_, settings)| right(settings) != edge)
+                .map(|(id, settings)| patch_x(id, edge - settings.width as i32))
+                .collect()
+        },
+        Alignment::Bottom => {
+            let Some(edge) = widgets.iter().map(|(_, settings)| bottom(settings)).max() else {
+                return vec![];
+            };
+            widgets
+                .iter()
+                .filter(|(_, settings)| bottom(settings) != edge)
+                .map(|(id, settings)| patch_y(id, edge - settings.height as i32))
+                .collect()
+        },
+        Alignment::DistributeHorizontally => distribute(
+            widgets,
+            |settings| (settings.x, settings.width as i32),
+            patch_x,
+        ),
+        Alignment::DistributeVertically => distribute(
+            widgets,
+            |settings| (settings.y, settings.height as i32),
+            patch_y,
+        ),
+    }
+}
+
+/// Distribute widgets along an axis with equal gaps between them.
+///
+/// The axis is given by a function returning the start and size of a widget
+/// along it, and a function making the patch that moves a widget to a start.
+fn distribute(
+    widgets: &[(&str, &WidgetSettings)],
+    axis: impl Fn(&WidgetSettings) -> (i32, i32),
+    patch: impl Fn(&str, i32) -> (String, WidgetSettingsPatch),
+) -> Vec<(String, WidgetSettingsPatch)> {
+    if widgets.len() < 3 {
+        return vec![];
+    }
+    let mut sorted = widgets
+        .iter()
+        .map(|(id, settings)| (*id, axis(settings)))
+        .collect::<Vec<_>>();
+    sorted.sort_by_key(|(_, (start, _))| *start);
+
+    let (_, (first_start, _)) = sorted[0];
+    let (_, (last_start, last_size)) = sorted[sorted.len() - 1];
+    let total_size = sorted.iter().map(|(_, (_, size))| *size).sum::<i32>();
+    let free = (last_start + last_size - first_start - total_size) as f64;
+    let gap = free / (sorted.len() - 1) as f64;
+
+    let mut patches = vec![];
+    let mut cursor = first_start as f64;
+    for (id, (start, size)) in sorted {
+        let target = cursor.round() as i32;
+        if target != start {
+            patches.push(patch(id, target));
+        }
+        cursor += size as f64 + gap;
+    }
+    patches
+}
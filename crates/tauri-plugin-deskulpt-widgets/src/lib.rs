@@ -6,23 +6,87 @@
 
 mod catalog;
 mod commands;
-mod events;
+mod damage;
+pub mod events;
+mod health;
+mod history;
+mod hooks;
+mod import;
+mod layout;
 mod manager;
+mod monitor;
+mod network;
 pub mod persist;
+mod plugins;
+mod refresh;
 mod registry;
 mod render;
+mod strict;
+mod tombstone;
 
-pub use manager::WidgetsManager;
+use std::time::Duration;
+
+pub use catalog::{DetachedPosition, Interactivity, WidgetSettings, WidgetSettingsPatch};
+pub use manager::{HitTest, WidgetsManager};
+pub use registry::WidgetPackage;
 use tauri::plugin::TauriPlugin;
 use tauri::{Manager, Runtime};
+use tauri_plugin_deskulpt_settings::SettingsExt;
 
 deskulpt_common::bindings::build_bindings!();
 
+/// The delay after startup before widgets are automatically updated.
+const AUTO_UPDATE_DELAY: Duration = Duration::from_secs(10);
+
 /// Initialize the internal Deskulpt widgets plugin.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     deskulpt_common::init::init_builder!()
+        .register_uri_scheme_protocol(render::ASSET_PROTOCOL, render::handle_asset_request)
         .setup(|app_handle, _| {
             app_handle.manage(WidgetsManager::new(app_handle.clone())?);
+
+            let app_handle_cloned = app_handle.clone();
+            app_handle.settings().on_theme_change(move |_, _| {
+                if let Err(e) = app_handle_cloned.widgets().render_themed() {
+                    tracing::error!("Failed to re-render themed widgets: {e:?}");
+                }
+            });
+
+            // Display metadata is resolved on reload, so the catalog has to
+            // be reloaded to pick up the new locale
+            let app_handle_cloned = app_handle.clone();
+            app_handle.settings().on_locale_change(move |_, _| {
+                if let Err(e) = app_handle_cloned.widgets().reload_all() {
+                    tracing::error!("Failed to reload widgets for new locale: {e:?}");
+                }
+            });
+
+            let app_handle_cloned = app_handle.clone();
+            app_handle.settings().on_profile_change(move |_, new| {
+                if let Err(e) = app_handle_cloned.widgets().switch_profile(new) {
+                    tracing::error!("Failed to switch widgets to profile {new}: {e:?}");
+                }
+            });
+
+            // Retry widget reports queued while offline in previous sessions
+            let app_handle_cloned = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(AUTO_UPDATE_DELAY).await;
+                if let Err(e) = app_handle_cloned.widgets().submit_reports().await {
+                    tracing::error!("Failed to submit queued widget reports: {e:?}");
+                }
+            });
+
+            if app_handle.settings().read().auto_update_widgets {
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    // Let startup rendering settle before competing with it
+                    tokio::time::sleep(AUTO_UPDATE_DELAY).await;
+                    if let Err(e) = app_handle.widgets().update_all().await {
+                        tracing::error!("Failed to automatically update widgets: {e:?}");
+                    }
+                });
+            }
             Ok(())
         })
         .build()
@@ -1,23 +1,91 @@
 //! Deskulpt widgets manager and its APIs.
 
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 use anyhow::{Context, Result, anyhow, bail};
 use deskulpt_common::event::Event;
 use deskulpt_common::outcome::Outcome;
-use parking_lot::RwLock;
+use deskulpt_common::window::DeskulptWindow;
+use futures_util::future::join_all;
+use parking_lot::{Mutex, RwLock};
+use rolldown_sourcemap::SourceMap;
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Manager, Runtime};
-use tauri_plugin_deskulpt_settings::SettingsExt;
-use tauri_plugin_deskulpt_settings::model::SettingsPatch;
+use tauri_plugin_deskulpt_settings::model::{
+    CanvasImode, SettingsPatch, UnsignedWidgetPolicy, WidgetPermissions,
+};
+use tauri_plugin_deskulpt_settings::overrides::Overrides;
+use tauri_plugin_deskulpt_settings::{SettingsExt, profile};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
 
-use crate::catalog::{WidgetCatalog, WidgetSettingsPatch};
-use crate::events::UpdateEvent;
+use crate::WidgetsExt;
+use crate::catalog::{
+    BundleStats, DetachedPosition, ManifestDiagnostic, ManifestSeverity, Widget, WidgetCatalog,
+    WidgetManifest, WidgetSettings, WidgetSettingsPatch,
+};
+use crate::damage::{DamageHints, DamageMetrics, DamageRect, DamageTracker};
+use crate::events::{
+    BundleSizeEvent, DiffEvent, InstallProgressEvent, RevokedWidgetsEvent, TimeoutEvent,
+    UpdateEvent, UpgradesAvailableEvent,
+};
+use crate::health::WidgetDirReport;
+use crate::history::{Layout, LayoutChange, LayoutHistory};
+use crate::hooks::{HookTask, HookWorkerHandle, WidgetsHooks};
+use crate::layout::{self, Alignment, SnapConfig};
+use crate::monitor::{MonitorAnchor, MonitorBounds};
+use crate::network::{NetworkProxy, NetworkRequest, NetworkResponse, WidgetMetrics};
 use crate::persist::{PersistWorkerHandle, PersistedWidgetCatalog, PersistedWidgetCatalogView};
+use crate::refresh::{RefreshSchedulerHandle, RefreshSchedulerTask};
 use crate::registry::{
-    RegistryIndex, RegistryIndexFetcher, RegistryWidgetFetcher, RegistryWidgetPreview,
-    RegistryWidgetReference,
+    InstallProgress, Registry, RegistryCredentials, RegistryEntryRelease, RegistryIndex,
+    RegistryIndexFetcher, RegistryLock, RegistryNetwork, RegistrySearchPage, RegistrySort,
+    RegistryWidgetFetcher, RegistryWidgetPreview, RegistryWidgetReference, ReportQueue,
+    SignatureStatus, WidgetReport, WidgetUpgrade,
+};
+use crate::render::{
+    RenderTimings, RenderWorkerHandle, RenderWorkerTask, ValidationReport, symbolicate, validate,
 };
-use crate::render::{RenderWorkerHandle, RenderWorkerTask};
+use crate::strict::StrictWarning;
+use crate::tombstone::{DEFAULT_RETENTION_DAYS, Tombstone, Tombstones, now_secs};
+
+/// The default timeout for a widget to finish rendering.
+///
+/// This applies if not configured in the settings.
+const DEFAULT_RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The default bundle size budget of a widget in KiB.
+///
+/// This applies if configured in neither the widget manifest nor the settings.
+const DEFAULT_BUNDLE_SIZE_BUDGET_KIB: u64 = 1024;
+
+/// The subdirectory of a widget that is preserved across updates.
+///
+/// Widgets should keep their own data, e.g., written via the file system
+/// plugin, in this subdirectory so that it survives updates.
+const WIDGET_DATA_DIR: &str = "data";
+
+/// The hidden directory in the widgets directory where updates are staged.
+///
+/// It is on the same file system as the widgets, so that staged updates can be
+/// moved into place by renaming.
+const STAGING_DIR: &str = ".staging";
+
+/// The hidden directory in the widgets directory where the previously
+/// installed packages of widgets are kept for rolling back.
+const ROLLBACK_DIR: &str = ".rollback";
+
+/// The result of hit testing the canvas at a point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HitTest {
+    /// The ID of the topmost widget at the point, if any.
+    pub widget: Option<String>,
+    /// Whether the canvas should be interactive at the point.
+    pub is_interactive: bool,
+}
 
 /// Manager for Deskulpt widgets.
 pub struct WidgetsManager<R: Runtime> {
@@ -25,35 +93,79 @@ pub struct WidgetsManager<R: Runtime> {
     app_handle: AppHandle<R>,
     /// The widgets directory.
     dir: PathBuf,
+    /// The directory where static assets of widgets are copied to.
+    assets_dir: PathBuf,
     /// The widget catalog.
     catalog: RwLock<WidgetCatalog>,
-    /// The path where widgets are persisted.
-    persist_path: PathBuf,
+    /// The path where widgets are persisted for the active settings profile.
+    persist_path: RwLock<PathBuf>,
     /// The handle for the render worker.
     render_worker: RenderWorkerHandle,
     /// The handle for the persist worker.
     persist_worker: PersistWorkerHandle,
+    /// The handle for the periodic refresh scheduler.
+    refresh_scheduler: RefreshSchedulerHandle,
+    /// The source maps of the latest successful bundles of widgets.
+    source_maps: RwLock<HashMap<String, SourceMap>>,
+    /// The pending render timeouts of widgets.
+    render_timeouts: Mutex<HashMap<String, JoinHandle<()>>>,
+    /// The network proxy for widgets.
+    network: NetworkProxy,
+    /// The tracker of damaged canvas regions.
+    damage: DamageTracker,
+    /// The timing breakdowns of the latest renders of widgets.
+    render_timings: RwLock<HashMap<String, RenderTimings>>,
+    /// The available plugins, mapping plugin names to versions.
+    plugins: RwLock<BTreeMap<String, String>>,
+    /// The tombstones of widgets that have gone missing.
+    tombstones: Mutex<Tombstones>,
+    /// The handle for the hook worker.
+    hook_worker: HookWorkerHandle,
+    /// The collection of hooks on widget changes.
+    hooks: RwLock<WidgetsHooks>,
+    /// The undo history of widget layout changes.
+    layout_history: Mutex<LayoutHistory>,
+    /// Whether rendering is suspended, see [`Self::suspend_rendering`].
+    rendering_suspended: AtomicBool,
+    /// The path to the lockfile of widgets installed from the registry.
+    registry_lock_path: PathBuf,
+    /// The lockfile of widgets installed from the registry.
+    registry_lock: Mutex<RegistryLock>,
+    /// The cancellation tokens of pending installs, keyed by request ID.
+    install_requests: Mutex<HashMap<String, CancellationToken>>,
+    /// The queue of widget reports yet to be submitted.
+    report_queue: Mutex<ReportQueue>,
+    /// The bounds of monitors as of the last display change.
+    ///
+    /// This is refreshed by [`Self::resolve_monitor_positions`], so that
+    /// widgets can be anchored without querying monitors on every move.
+    monitors: RwLock<Vec<MonitorBounds>>,
 }
 
 impl<R: Runtime> WidgetsManager<R> {
     /// Initialize the [`WidgetsManager`].
     ///
     /// The catalog will be populated with widgets in the widgets directory and
-    /// the persisted settings file. A render worker and a persist worker will
-    /// be started immediately.
+    /// the persisted settings file. Persisted settings of widgets that are not
+    /// found are kept as tombstones until the retention window passes. A
+    /// render worker, a persist worker, a hook worker, and a periodic refresh
+    /// scheduler will be started immediately.
     pub fn new(app_handle: AppHandle<R>) -> Result<Self> {
-        let dir = if cfg!(debug_assertions) {
-            app_handle.path().resource_dir()?
-        } else {
-            app_handle.path().document_dir()?.join("Deskulpt")
-        };
-        let dir = dunce::simplified(&dir).join("widgets");
+        let dir = Self::resolve_dir(&app_handle)?;
         std::fs::create_dir_all(&dir)?;
 
         let mut catalog = WidgetCatalog::default();
-        catalog.reload_all(&dir)?;
+        let mut tombstones = Tombstones::default();
+        tracing::info_span!("load_catalog")
+            .in_scope(|| catalog.reload_all(&dir, &mut tombstones))?;
+        if let Some(locale) = Self::locale(&app_handle) {
+            catalog.localize(&locale);
+        }
 
-        let persist_path = app_handle.path().app_local_data_dir()?.join("widgets.json");
+        let assets_dir = app_handle.path().app_cache_dir()?.join("widget-assets");
+
+        let persist_path =
+            Self::persist_path_for(&app_handle, &app_handle.settings().active_profile())?;
         let mut persisted_catalog =
             PersistedWidgetCatalog::load(&persist_path).unwrap_or_else(|e| {
                 tracing::error!("Failed to load persisted widgets: {e:?}");
@@ -64,91 +176,774 @@ impl<R: Runtime> WidgetsManager<R> {
                 v.settings = persisted.settings;
             }
         });
+        for (id, persisted) in persisted_catalog.0 {
+            let tombstone = Tombstone {
+                settings: persisted.settings,
+                removed_at: persisted.removed_at.unwrap_or_else(now_secs),
+            };
+            tombstones.0.insert(id, tombstone);
+        }
+        tombstones.prune(Self::retention(&app_handle));
 
         let render_worker = RenderWorkerHandle::new(app_handle.clone());
         let persist_worker = PersistWorkerHandle::new(app_handle.clone())?;
+        let refresh_scheduler = RefreshSchedulerHandle::new(app_handle.clone());
+        refresh_scheduler.process(RefreshSchedulerTask::Sync(catalog.refresh_intervals()))?;
+        let hook_worker = HookWorkerHandle::new(app_handle.clone());
+
+        let registry_lock_path = app_handle
+            .path()
+            .app_local_data_dir()?
+            .join(RegistryLock::FILE_NAME);
+        if let Err(e) = RegistryLock::migrate(
+            &dir.join(RegistryLock::LEGACY_FILE_NAME),
+            &registry_lock_path,
+        ) {
+            tracing::error!("Failed to migrate registry lockfile: {e:?}");
+        }
+        let registry_lock = RegistryLock::load(&registry_lock_path).unwrap_or_else(|e| {
+            tracing::error!("Failed to load registry lockfile: {e:?}");
+            Default::default()
+        });
+        catalog.resolve_sources(&registry_lock);
+        catalog.resolve_permissions(&app_handle.settings().read().widget_grants);
+        let report_queue =
+            ReportQueue::load(&dir.join(ReportQueue::FILE_NAME)).unwrap_or_else(|e| {
+                tracing::error!("Failed to load widget report queue: {e:?}");
+                Default::default()
+            });
 
         Ok(Self {
             app_handle,
             dir,
+            assets_dir,
             catalog: RwLock::new(catalog),
-            persist_path,
+            persist_path: RwLock::new(persist_path),
             render_worker,
             persist_worker,
+            refresh_scheduler,
+            source_maps: Default::default(),
+            render_timeouts: Default::default(),
+            network: Default::default(),
+            damage: Default::default(),
+            render_timings: Default::default(),
+            plugins: Default::default(),
+            tombstones: Mutex::new(tombstones),
+            hook_worker,
+            hooks: Default::default(),
+            layout_history: Default::default(),
+            rendering_suspended: Default::default(),
+            registry_lock_path,
+            registry_lock: Mutex::new(registry_lock),
+            install_requests: Default::default(),
+            report_queue: Mutex::new(report_queue),
+            monitors: Default::default(),
         })
     }
 
+    /// Resolve the widgets directory.
+    ///
+    /// This is available before the [`WidgetsManager`] is initialized, e.g.,
+    /// for migrating legacy data. The directory may not exist yet. It can be
+    /// overridden for the launch via [`Overrides::widgets_dir`].
+    pub fn resolve_dir(app_handle: &AppHandle<R>) -> Result<PathBuf> {
+        if let Some(dir) = Overrides::of(app_handle).widgets_dir {
+            return Ok(dunce::simplified(&dir).to_path_buf());
+        }
+
+        let dir = if cfg!(debug_assertions) {
+            app_handle.path().resource_dir()?
+        } else {
+            app_handle.path().document_dir()?.join("Deskulpt")
+        };
+        Ok(dunce::simplified(&dir).join("widgets"))
+    }
+
     /// Get the widgets directory.
     pub fn dir(&self) -> &Path {
         &self.dir
     }
 
+    /// Get the plugins available to widgets, mapping plugin names to versions.
+    pub fn plugins(&self) -> BTreeMap<String, String> {
+        self.plugins.read().clone()
+    }
+
+    /// Get the names of plugins required by widgets that are unavailable or
+    /// whose available versions do not satisfy the requirements.
+    pub fn missing_plugins(&self) -> BTreeSet<String> {
+        self.catalog
+            .read()
+            .0
+            .values()
+            .flat_map(|widget| &widget.missing_plugins)
+            .map(|plugin| plugin.name.clone())
+            .collect()
+    }
+
+    /// Get whether each background worker is still running.
+    ///
+    /// The workers are keyed by `render`, `persist`, `hooks`, and `refresh`.
+    pub fn workers_alive(&self) -> BTreeMap<String, bool> {
+        BTreeMap::from([
+            ("render".to_string(), self.render_worker.is_alive()),
+            ("persist".to_string(), self.persist_worker.is_alive()),
+            ("hooks".to_string(), self.hook_worker.is_alive()),
+            ("refresh".to_string(), self.refresh_scheduler.is_alive()),
+        ])
+    }
+
+    /// Check whether the render worker is responsive.
+    ///
+    /// The returned receiver resolves once the render worker processes the
+    /// ping, so a caller should wait on it with a timeout.
+    pub fn ping_render_worker(&self) -> Result<oneshot::Receiver<()>> {
+        self.render_worker.ping()
+    }
+
+    /// Restart the render worker, e.g., if it stops responding.
+    ///
+    /// All widgets are rendered again since the state of the old render worker
+    /// is lost.
+    pub fn restart_render_worker(&self) -> Result<()> {
+        self.render_worker.restart(self.app_handle.clone());
+        self.render_all()
+    }
+
+    /// Register plugins available to widgets.
+    ///
+    /// The required plugins of all widgets are checked again, and the catalog
+    /// is notified to the frontend if the missing plugins of any widget have
+    /// changed. Registering a plugin that is already registered replaces its
+    /// version.
+    pub fn register_plugins(
+        &self,
+        plugins: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<()> {
+        let available = {
+            let mut available = self.plugins.write();
+            available.extend(plugins);
+            available.clone()
+        };
+
+        let mut catalog = self.catalog.write();
+        if catalog.check_plugins(&available) {
+            UpdateEvent(&catalog).emit(&self.app_handle)?;
+        }
+        Ok(())
+    }
+
+    /// Get the directory of a widget by its ID.
+    ///
+    /// Widgets may be nested one level deep in namespace directories, in which
+    /// case their IDs are in the form of `namespace/name`.
+    pub fn widget_dir(&self, id: &str) -> PathBuf {
+        id.split('/')
+            .fold(self.dir.clone(), |dir, name| dir.join(name))
+    }
+
+    /// Get the directory where static assets of widgets are copied to.
+    ///
+    /// Each widget has its own subdirectory named by its ID.
+    pub fn assets_dir(&self) -> &Path {
+        &self.assets_dir
+    }
+
     /// Update the settings of a widget with a patch.
     ///
-    /// An error is returned if the widget does not exist.
-    pub fn update_settings(&self, id: &str, patch: WidgetSettingsPatch) -> Result<()> {
+    /// If a widget in strict mode is unloaded with network requests still in
+    /// flight, a strict mode warning is reported. If a widget is unfrozen, it
+    /// is re-rendered to catch up with changes skipped while frozen. Hooks
+    /// registered via [`Self::on_widget_settings_change`] are triggered if the
+    /// settings have actually changed. Layout changes are recorded so that they
+    /// can be reverted via [`Self::undo_layout_change`]. Position and size
+    /// changes are snapped to the grid and to the edges of other widgets on
+    /// the canvas as configured in the settings, see [`SnapConfig`]. An error
+    /// is returned if the widget does not exist.
+    ///
+    /// Tauri command: [`crate::commands::update_settings`].
+    pub fn update_settings(&self, id: &str, mut patch: WidgetSettingsPatch) -> Result<()> {
+        let snap_config = SnapConfig::of(&self.app_handle.settings().read());
+        if snap_config.is_enabled() {
+            let catalog = self.catalog.read();
+            if let Some(widget) = catalog.0.get(id) {
+                let others = catalog
+                    .0
+                    .iter()
+                    .filter(|(other_id, other)| *other_id != id && other.settings.is_hit_testable())
+                    .map(|(_, other)| &other.settings)
+                    .collect::<Vec<_>>();
+                snap_config.snap_patch(&mut patch, &widget.settings, &others);
+            }
+        }
+        self.apply_settings_patch(id, patch, true)
+    }
+
+    /// Align widgets with each other.
+    ///
+    /// See [`Alignment`] for the available alignments. The change of each
+    /// moved widget is recorded in the undo history separately. An error is
+    /// returned if any widget does not exist.
+    ///
+    /// Tauri command: [`crate::commands::align_widgets`].
+    pub fn align_widgets(&self, ids: &[String], alignment: Alignment) -> Result<()> {
+        let patches = {
+            let catalog = self.catalog.read();
+            let widgets = ids
+                .iter()
+                .map(|id| {
+                    let widget = catalog
+                        .0
+                        .get(id)
+                        .ok_or_else(|| anyhow!("Widget not found: {id}"))?;
+                    Ok((id.as_str(), &widget.settings))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            layout::align(&widgets, alignment)
+        };
+        for (id, patch) in patches {
+            self.apply_settings_patch(&id, patch, true)?;
+        }
+        Ok(())
+    }
+
+    /// Undo the most recent layout change of widgets.
+    ///
+    /// The position, size, and z-index of the affected widget are restored to
+    /// what they were before the change. Changes of widgets that no longer
+    /// exist are skipped. This returns whether any change has been undone.
+    ///
+    /// Tauri command: [`crate::commands::undo_layout_change`].
+    pub fn undo_layout_change(&self) -> Result<bool> {
+        loop {
+            let Some(change) = self.layout_history.lock().pop_undo() else {
+                return Ok(false);
+            };
+            if self.restore_layout(&change, change.before)? {
+                self.layout_history.lock().push_redo(change);
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Redo the most recently undone layout change of widgets.
+    ///
+    /// This reverts [`Self::undo_layout_change`]. The redo history is cleared
+    /// once a new layout change is made. This returns whether any change has
+    /// been redone.
+    ///
+    /// Tauri command: [`crate::commands::redo_layout_change`].
+    pub fn redo_layout_change(&self) -> Result<bool> {
+        loop {
+            let Some(change) = self.layout_history.lock().pop_redo() else {
+                return Ok(false);
+            };
+            if self.restore_layout(&change, change.after)? {
+                self.layout_history.lock().push_undo(change);
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Restore a widget to a layout recorded in the undo history.
+    ///
+    /// This returns `false` if the widget no longer exists.
+    fn restore_layout(&self, change: &LayoutChange, layout: Layout) -> Result<bool> {
+        if !self.catalog.read().0.contains_key(&change.id) {
+            return Ok(false);
+        }
+        self.apply_settings_patch(&change.id, layout.to_patch(), false)?;
+        Ok(true)
+    }
+
+    /// Apply a patch to the settings of a widget.
+    ///
+    /// See [`Self::update_settings`] for details. Layout changes are recorded
+    /// in the undo history only if `record` is set.
+    fn apply_settings_patch(
+        &self,
+        id: &str,
+        patch: WidgetSettingsPatch,
+        record: bool,
+    ) -> Result<()> {
         let mut catalog = self.catalog.write();
         let widget = catalog
             .0
             .get_mut(id)
             .ok_or_else(|| anyhow!("Widget not found: {id}"))?;
 
+        let layout = Layout::of(&widget.settings);
+        if layout.patched(&patch) != layout && self.app_handle.settings().is_locked() {
+            bail!("Layout of widget {id} cannot be changed while settings are locked");
+        }
+
+        let old_settings = widget.settings.clone();
+        let old_rect = DamageRect::from_settings(&widget.settings);
+        let was_loaded = widget.settings.is_loaded;
+        let was_frozen = widget.settings.frozen;
         let changed = widget.settings.apply_patch(patch);
+        if widget.settings.x != old_settings.x || widget.settings.y != old_settings.y {
+            widget.settings.anchor = self.locate_anchor(widget.settings.x, widget.settings.y);
+        }
+        let unfrozen = was_frozen && !widget.settings.frozen && widget.settings.is_loaded;
         if changed {
-            UpdateEvent(&catalog).emit(&self.app_handle)?;
+            if was_loaded && !widget.settings.is_loaded {
+                self.check_unloaded(id, widget);
+            }
+            self.damage.damage(id, old_rect);
+            self.damage
+                .damage(id, DamageRect::from_settings(&widget.settings));
+            if record {
+                self.layout_history.lock().record(
+                    id,
+                    Layout::of(&old_settings),
+                    Layout::of(&widget.settings),
+                );
+            }
+            DiffEvent::WidgetChanged { id, widget }.emit(&self.app_handle)?;
+            self.hook_worker.process(HookTask::WidgetSettingsChanged {
+                id: id.to_string(),
+                old: old_settings,
+                new: widget.settings.clone(),
+            })?;
+            self.persist_worker.notify()?;
+            self.sync_refresh_schedule(&catalog)?;
+            self.sync_render_cache(&catalog)?;
+        }
+        drop(catalog);
+
+        if unfrozen {
+            self.render(id)?;
+        }
+        Ok(())
+    }
+
+    /// Anchor a position in canvas coordinates to the nearest monitor.
+    ///
+    /// This uses the monitors cached by [`Self::resolve_monitor_positions`],
+    /// since it is called with the catalog locked on every move, e.g., at
+    /// every step of dragging a widget. There is no anchor until monitors are
+    /// first resolved, when positions without an anchor are anchored anyway.
+    fn locate_anchor(&self, x: i32, y: i32) -> Option<MonitorAnchor> {
+        MonitorAnchor::locate(&self.monitors.read(), x, y)
+    }
+
+    /// Re-resolve widget positions against the current monitor layout.
+    ///
+    /// This should be called when the display configuration changes. Widgets
+    /// anchored to a monitor are moved to their position relative to that
+    /// monitor, or to the first monitor if it is no longer available. Widgets
+    /// without an anchor, e.g., those persisted by older versions with only
+    /// absolute coordinates, are anchored based on their current position.
+    /// The monitors are cached for anchoring widgets when they are moved.
+    pub fn resolve_monitor_positions(&self) -> Result<()> {
+        let monitors = MonitorBounds::all(&self.app_handle)?;
+        *self.monitors.write() = monitors.clone();
+        if monitors.is_empty() {
+            return Ok(());
+        }
+
+        let mut catalog = self.catalog.write();
+        let mut dirty = false;
+        for (id, widget) in catalog.0.iter_mut() {
+            let settings = &mut widget.settings;
+            let Some(anchor) = &settings.anchor else {
+                settings.anchor = MonitorAnchor::locate(&monitors, settings.x, settings.y);
+                dirty = true;
+                continue;
+            };
+            let Some((x, y)) = anchor.resolve(&monitors, settings.width, settings.height) else {
+                continue;
+            };
+            if (x, y) != (settings.x, settings.y) {
+                let old_rect = DamageRect::from_settings(settings);
+                settings.x = x;
+                settings.y = y;
+                self.damage.damage(id, old_rect);
+                self.damage.damage(id, DamageRect::from_settings(settings));
+                DiffEvent::WidgetChanged { id, widget }.emit(&self.app_handle)?;
+                dirty = true;
+            }
+        }
+        drop(catalog);
+
+        if dirty {
             self.persist_worker.notify()?;
         }
         Ok(())
     }
 
-    /// Try to check if a point is covered by any widget geometrically.
+    /// Get the settings of a widget.
+    ///
+    /// An error is returned if the widget does not exist.
+    pub fn widget_settings(&self, id: &str) -> Result<WidgetSettings> {
+        self.catalog
+            .read()
+            .0
+            .get(id)
+            .map(|widget| widget.settings.clone())
+            .ok_or_else(|| anyhow!("Widget not found: {id}"))
+    }
+
+    /// Get the widgets detached into floating windows.
+    ///
+    /// Each widget is returned with the position of its floating window, see
+    /// [`WidgetSettings::detached`].
+    pub fn detached_widgets(&self) -> Vec<(String, DetachedPosition)> {
+        self.catalog
+            .read()
+            .0
+            .iter()
+            .filter_map(|(id, widget)| Some((id.clone(), widget.settings.detached?)))
+            .collect()
+    }
+
+    /// Set the floating window position of a widget.
+    ///
+    /// Setting it to `None` attaches the widget back to the canvas. This only
+    /// records the state in the widget settings; the floating window itself is
+    /// managed by the caller. An error is returned if the widget does not
+    /// exist.
+    pub fn set_detached(&self, id: &str, detached: Option<DetachedPosition>) -> Result<()> {
+        let mut catalog = self.catalog.write();
+        let widget = catalog
+            .0
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("Widget not found: {id}"))?;
+        if widget.settings.detached == detached {
+            return Ok(());
+        }
+
+        // Detaching or attaching moves the widget off or onto the canvas
+        if widget.settings.detached.is_none() != detached.is_none() {
+            self.damage
+                .damage(id, DamageRect::from_settings(&widget.settings));
+        }
+        widget.settings.detached = detached;
+        DiffEvent::WidgetChanged { id, widget }.emit(&self.app_handle)?;
+        drop(catalog);
+
+        self.persist_worker.notify()?;
+        Ok(())
+    }
+
+    /// Register a hook that will be triggered on widget settings change.
+    ///
+    /// The three arguments are respectively the widget ID, and the old and new
+    /// settings of the widget. Hooks are triggered asynchronously in the order
+    /// of changes, and only if the settings have actually changed. Bursts of
+    /// changes to the same widget within a short window are coalesced into a
+    /// single trigger, so the old settings may differ from the new settings by
+    /// more than one patch.
+    pub fn on_widget_settings_change<F>(&self, hook: F)
+    where
+        F: Fn(&str, &WidgetSettings, &WidgetSettings) + Send + Sync + 'static,
+    {
+        let mut hooks = self.hooks.write();
+        hooks.on_widget_settings_change.push(Box::new(hook));
+    }
+
+    /// Trigger all registered widget settings change hooks.
+    pub(crate) fn trigger_widget_settings_hooks(
+        &self,
+        id: &str,
+        old: &WidgetSettings,
+        new: &WidgetSettings,
+    ) {
+        let hooks = self.hooks.read();
+        for hook in &hooks.on_widget_settings_change {
+            hook(id, old, new);
+        }
+    }
+
+    /// Check a widget that has just been unloaded in strict mode.
+    ///
+    /// Network requests still in flight at this point would never be consumed
+    /// by the widget, which usually indicates missing cleanup, e.g., requests
+    /// not aborted in effect cleanups. This is a no-op if the widget is not in
+    /// strict mode.
+    fn check_unloaded(&self, id: &str, widget: &mut Widget) {
+        let strict = match &widget.manifest {
+            Outcome::Ok(manifest) => manifest.strict,
+            Outcome::Err(_) => None,
+        }
+        .unwrap_or_else(|| self.app_handle.settings().read().strict_widgets);
+        if !strict {
+            return;
+        }
+
+        let count = self.network.in_flight(id);
+        if count > 0 {
+            let warning = StrictWarning::UnclosedRequests { count };
+            warning.log(id);
+            widget.strict_warnings.push(warning);
+        }
+    }
+
+    /// Synchronize the periodic refresh schedule with the catalog.
+    ///
+    /// This should be called whenever the catalog changes in a way that might
+    /// affect which widgets are periodically refreshed and at what intervals.
+    fn sync_refresh_schedule(&self, catalog: &WidgetCatalog) -> Result<()> {
+        self.refresh_scheduler
+            .process(RefreshSchedulerTask::Sync(catalog.refresh_intervals()))
+    }
+
+    /// Re-render the widgets that depend on the current theme.
+    ///
+    /// These are widgets whose bundles use the `__THEME__` compile-time
+    /// constant. This method is non-blocking and does not wait for the tasks
+    /// to complete.
+    pub fn render_themed(&self) -> Result<()> {
+        if self.is_rendering_suspended() {
+            return Ok(());
+        }
+        self.render_worker.process(RenderWorkerTask::RenderThemed)
+    }
+
+    /// Suspend rendering of widgets for the rest of the session.
+    ///
+    /// Subsequent render requests are silently dropped, while the catalog is
+    /// still maintained as usual. This is meant for safe mode, so that a
+    /// broken widget cannot crash the app on startup.
+    pub fn suspend_rendering(&self) {
+        self.rendering_suspended.store(true, Ordering::Release);
+    }
+
+    /// Whether rendering is suspended, see [`Self::suspend_rendering`].
+    fn is_rendering_suspended(&self) -> bool {
+        let suspended = self.rendering_suspended.load(Ordering::Acquire);
+        if suspended {
+            tracing::debug!("Skipped render since rendering is suspended");
+        }
+        suspended
+    }
+
+    /// Synchronize the bundlers kept by the render worker with the catalog.
     ///
-    /// This method is non-blocking and might return `None` if the widget
-    /// catalog is currently locked for writing.
-    pub fn try_covers_point(&self, x: f64, y: f64) -> Option<bool> {
+    /// This should be called whenever the catalog changes in a way that might
+    /// remove or unload widgets, so that their bundlers are dropped.
+    fn sync_render_cache(&self, catalog: &WidgetCatalog) -> Result<()> {
+        let ids = catalog
+            .0
+            .iter()
+            .filter(|(_, widget)| widget.settings.is_loaded)
+            .map(|(id, _)| id.clone())
+            .collect();
+        self.render_worker.process(RenderWorkerTask::Retain { ids })
+    }
+
+    /// Try to hit test the canvas at a point.
+    ///
+    /// Among widgets that can be hit at the point, see
+    /// [`WidgetSettings::is_hit_testable`], the topmost one by z-index is the
+    /// one hit, and it decides whether the canvas is interactive under the
+    /// given canvas interaction mode, see [`WidgetSettings::interactivity`].
+    /// Ties in z-index are broken by widget ID, matching the order in which the
+    /// canvas stacks widgets, so that the widget hit is the one rendered on
+    /// top regardless of its interactivity. In float mode, the canvas is also
+    /// interactive where no widget is, so that only click-through widgets let
+    /// cursor events pass. This method is non-blocking and might return `None`
+    /// if the widget catalog is currently locked for writing.
+    ///
+    /// [`WidgetSettings::is_hit_testable`]: crate::catalog::WidgetSettings::is_hit_testable
+    /// [`WidgetSettings::interactivity`]: crate::catalog::WidgetSettings::interactivity
+    pub fn try_hit_test(&self, x: f64, y: f64, imode: &CanvasImode) -> Option<HitTest> {
         let catalog = self.catalog.try_read()?;
-        let covers = catalog
+        let topmost = catalog
             .0
-            .values()
-            .any(|widget| widget.settings.covers_point(x, y));
-        Some(covers)
+            .iter()
+            .filter(|(_, widget)| {
+                widget.settings.is_hit_testable() && widget.settings.covers_point(x, y)
+            })
+            .max_by_key(|(id, widget)| (widget.settings.z_index, *id));
+        Some(match topmost {
+            Some((id, widget)) => HitTest {
+                widget: Some(id.clone()),
+                is_interactive: widget.settings.interactivity.is_interactive(imode),
+            },
+            None => HitTest {
+                widget: None,
+                is_interactive: *imode == CanvasImode::Float,
+            },
+        })
+    }
+
+    /// Try to check if any widget can be hit by the cursor on the canvas.
+    ///
+    /// See [`WidgetSettings::is_hit_testable`] for details. This method is
+    /// non-blocking and might return `None` if the widget catalog is currently
+    /// locked for writing.
+    ///
+    /// [`WidgetSettings::is_hit_testable`]: crate::catalog::WidgetSettings::is_hit_testable
+    pub fn try_has_hit_testable(&self) -> Option<bool> {
+        let catalog = self.catalog.try_read()?;
+        Some(
+            catalog
+                .0
+                .values()
+                .any(|widget| widget.settings.is_hit_testable()),
+        )
     }
 
     /// Persist the current widgets to disk.
+    ///
+    /// Tombstones older than the retention window are pruned beforehand, so
+    /// that the settings of widgets missing for too long are removed for good.
     pub fn persist(&self) -> Result<()> {
         let catalog = self.catalog.read();
-        PersistedWidgetCatalogView(&catalog).persist(&self.persist_path)?;
+        let mut tombstones = self.tombstones.lock();
+        tombstones.prune(Self::retention(&self.app_handle));
+        PersistedWidgetCatalogView(&catalog, &tombstones).persist(&self.persist_path.read())?;
         Ok(())
     }
 
+    /// Get the path where widgets are persisted for a settings profile.
+    fn persist_path_for(app_handle: &AppHandle<R>, profile: &str) -> Result<PathBuf> {
+        let name = profile::file_name("widgets", "json", profile);
+        Ok(app_handle.path().app_local_data_dir()?.join(name))
+    }
+
+    /// Switch the persisted widget settings to another settings profile.
+    ///
+    /// The current widget settings are persisted to the active profile first.
+    /// The widget settings of the new profile are then loaded into the catalog,
+    /// which is notified to the frontend via a full snapshot [`UpdateEvent`],
+    /// and all widgets are re-rendered. A profile without persisted widgets,
+    /// e.g., one that has just been created, keeps the current widget settings.
+    /// The layout history is cleared, since changes made under one profile
+    /// make no sense to undo under another.
+    pub fn switch_profile(&self, profile: &str) -> Result<()> {
+        self.persist()?;
+        let path = Self::persist_path_for(&self.app_handle, profile)?;
+        let persisted = if path.exists() {
+            Some(PersistedWidgetCatalog::load(&path)?)
+        } else {
+            None
+        };
+
+        let mut catalog = self.catalog.write();
+        if let Some(mut persisted) = persisted {
+            for (id, widget) in catalog.0.iter_mut() {
+                widget.settings = persisted
+                    .0
+                    .remove(id)
+                    .map(|persisted| persisted.settings)
+                    .unwrap_or_default();
+            }
+            self.tombstones.lock().0 = persisted
+                .0
+                .into_iter()
+                .map(|(id, persisted)| {
+                    let tombstone = Tombstone {
+                        settings: persisted.settings,
+                        removed_at: persisted.removed_at.unwrap_or_else(now_secs),
+                    };
+                    (id, tombstone)
+                })
+                .collect();
+        }
+        *self.persist_path.write() = path;
+        *self.layout_history.lock() = Default::default();
+        self.damage.damage_full();
+
+        UpdateEvent(&catalog).emit(&self.app_handle)?;
+        self.persist_worker.notify()?;
+        self.sync_refresh_schedule(&catalog)?;
+        self.sync_render_cache(&catalog)?;
+        drop(catalog);
+
+        self.render_all()
+    }
+
+    /// Get the locale for displaying widget metadata from the settings.
+    fn locale(app_handle: &AppHandle<R>) -> Option<String> {
+        app_handle.settings().read().locale.clone()
+    }
+
+    /// Get the retention window of tombstones from the settings.
+    fn retention(app_handle: &AppHandle<R>) -> Duration {
+        let days = app_handle
+            .settings()
+            .read()
+            .missing_widget_retention_days
+            .unwrap_or(DEFAULT_RETENTION_DAYS);
+        Duration::from_secs(days.saturating_mul(24 * 60 * 60))
+    }
+
     /// Reload a specific widget by its ID.
     ///
     /// This method loads the widget manifest from the corresponding widget
     /// directory and updates the catalog entry for that widget. This could be
-    /// an addition, removal, or modification. It then syncs the settings with
-    /// the updated catalog. If any step fails, an error is returned.
+    /// an addition, removal, or modification, which is notified to the
+    /// frontend via a [`DiffEvent`]. It then syncs the settings with the
+    /// updated catalog. If any step fails, an error is returned.
     pub fn reload(&self, id: &str) -> Result<()> {
-        let widget_dir = self.dir.join(id);
+        let _span = tracing::info_span!("reload", widget = id).entered();
+        let widget_dir = self.widget_dir(id);
+        let locale = Self::locale(&self.app_handle);
 
         let mut catalog = self.catalog.write();
-        catalog.reload(&widget_dir, id)?;
+        let old_rect = catalog
+            .0
+            .get(id)
+            .map(|widget| DamageRect::from_settings(&widget.settings));
+        catalog.reload(&widget_dir, id, &mut self.tombstones.lock())?;
+        if let Some(widget) = catalog.0.get_mut(id) {
+            widget.check_plugins(&self.plugins.read());
+            widget.source = self.registry_lock.lock().source(id);
+            widget.resolve_permissions(self.app_handle.settings().widget_grant(id).as_ref());
+            if let (Some(locale), Outcome::Ok(manifest)) = (&locale, &mut widget.manifest) {
+                manifest.localize(locale);
+            }
+        }
+        let existed = old_rect.is_some();
+        if let Some(old_rect) = old_rect {
+            self.damage.damage(id, old_rect);
+        }
+        if let Some(widget) = catalog.0.get(id) {
+            self.damage
+                .damage(id, DamageRect::from_settings(&widget.settings));
+        }
 
-        UpdateEvent(&catalog).emit(&self.app_handle)?;
+        let event = match (existed, catalog.0.get(id)) {
+            (false, Some(widget)) => Some(DiffEvent::WidgetAdded { id, widget }),
+            (true, Some(widget)) => Some(DiffEvent::WidgetChanged { id, widget }),
+            (true, None) => Some(DiffEvent::WidgetRemoved { id }),
+            (false, None) => None,
+        };
+        if let Some(event) = event {
+            event.emit(&self.app_handle)?;
+        }
         self.persist_worker.notify()?;
+        self.sync_refresh_schedule(&catalog)?;
+        self.sync_render_cache(&catalog)?;
         Ok(())
     }
 
     /// Reload all widgets.
     ///
     /// This method loads a new widget catalog from the widgets directory and
-    /// replaces the existing catalog. It then syncs the settings with the
+    /// replaces the existing catalog, which is notified to the frontend via a
+    /// full snapshot [`UpdateEvent`]. It then syncs the settings with the
     /// updated catalog. If any step fails, an error is returned.
     pub fn reload_all(&self) -> Result<()> {
+        let _span = tracing::info_span!("reload_all").entered();
+        let locale = Self::locale(&self.app_handle);
         let mut catalog = self.catalog.write();
-        catalog.reload_all(&self.dir)?;
+        catalog.reload_all(&self.dir, &mut self.tombstones.lock())?;
+        catalog.check_plugins(&self.plugins.read());
+        catalog.resolve_sources(&self.registry_lock.lock());
+        catalog.resolve_permissions(&self.app_handle.settings().read().widget_grants);
+        if let Some(locale) = &locale {
+            catalog.localize(locale);
+        }
+        self.damage.damage_full();
 
         UpdateEvent(&catalog).emit(&self.app_handle)?;
         self.persist_worker.notify()?;
+        self.sync_refresh_schedule(&catalog)?;
+        self.sync_render_cache(&catalog)?;
         Ok(())
     }
 
@@ -157,8 +952,12 @@ impl<R: Runtime> WidgetsManager<R> {
     /// This method submits a render task for the specified widget to the render
     /// worker. If the widget does not exist in the catalog or if task
     /// submission fails, an error is returned. This method is non-blocking and
-    /// does not wait for the task to complete.
+    /// does not wait for the task to complete. It is a no-op if rendering is
+    /// suspended.
     pub fn render(&self, id: &str) -> Result<()> {
+        if self.is_rendering_suspended() {
+            return Ok(());
+        }
         let catalog = self.catalog.read();
         let widget = catalog
             .0
@@ -169,6 +968,11 @@ impl<R: Runtime> WidgetsManager<R> {
             self.render_worker.process(RenderWorkerTask::Render {
                 id: id.to_string(),
                 entry: manifest.entry.clone(),
+                define: manifest.define.clone(),
+                code_splitting: manifest.code_splitting,
+                type_check: manifest.type_check,
+                strict: manifest.strict,
+                frozen: widget.settings.frozen,
             })?;
         }
         Ok(())
@@ -179,8 +983,11 @@ impl<R: Runtime> WidgetsManager<R> {
     /// This method submits render tasks for all widgets in the catalog to the
     /// render worker. If any task submission fails, an error containing all
     /// accumulated errors is returned. This method is non-blocking and does not
-    /// wait for the tasks to complete.
+    /// wait for the tasks to complete. It is a no-op if rendering is suspended.
     pub fn render_all(&self) -> Result<()> {
+        if self.is_rendering_suspended() {
+            return Ok(());
+        }
         let catalog = self.catalog.read();
 
         let mut errors = vec![];
@@ -189,6 +996,11 @@ impl<R: Runtime> WidgetsManager<R> {
                 && let Err(e) = self.render_worker.process(RenderWorkerTask::Render {
                     id: id.clone(),
                     entry: manifest.entry.clone(),
+                    define: manifest.define.clone(),
+                    code_splitting: manifest.code_splitting,
+                    type_check: manifest.type_check,
+                    strict: manifest.strict,
+                    frozen: widget.settings.frozen,
                 })
             {
                 errors.push(e.context(format!("Failed to send render task for widget {id}")));
@@ -207,44 +1019,368 @@ impl<R: Runtime> WidgetsManager<R> {
         Ok(())
     }
 
-    /// Refresh a specific widget by its ID.
+    /// Report the health of every candidate widget directory.
     ///
-    /// This is equivalent to reloading that widget with [`Self::reload`] then
-    /// rendering it with [`Self::render`].
+    /// Unlike the catalog, this also covers directories that are not
+    /// recognized as widgets, telling why they are skipped, and checks the
+    /// manifests afresh from disk. See [`WidgetDirReport`] for details.
     ///
-    /// Tauri command: [`crate::commands::refresh`].
-    pub fn refresh(&self, id: &str) -> Result<()> {
-        self.reload(id)?;
-        self.render(id)?;
-        Ok(())
+    /// Tauri command: [`crate::commands::catalog_report`].
+    pub fn catalog_report(&self) -> Result<Vec<WidgetDirReport>> {
+        crate::health::report(&self.dir, &self.plugins.read())
     }
 
-    /// Refresh all widgets.
+    /// Validate the manifest of a widget.
     ///
-    /// This is equivalent to reloading all widgets with [`Self::reload_all`]
-    /// then rendering all widgets with [`Self::render_all`].
+    /// This returns all problems found in the widget manifest, see
+    /// [`WidgetManifest::validate`]. A manifest that cannot be loaded or is
+    /// incompatible with this version of Deskulpt is reported as a single
+    /// error. Unlike [`Self::validate_widget`], the widget is not bundled, so
+    /// this is cheap enough to run as the manifest is being edited. An error
+    /// is returned if the widget directory has no manifest.
     ///
-    /// Tauri command: [`crate::commands::refresh_all`].
-    pub fn refresh_all(&self) -> Result<()> {
-        self.reload_all()?;
-        self.render_all()?;
-        Ok(())
+    /// Tauri command: [`crate::commands::validate_manifest`].
+    pub fn validate_manifest(&self, id: &str) -> Result<Vec<ManifestDiagnostic>> {
+        let widget_dir = self.widget_dir(id);
+        if !WidgetManifest::exists(&widget_dir) {
+            bail!("Widget {id} has no manifest");
+        }
+        let manifest = match WidgetManifest::load_unchecked(&widget_dir) {
+            Ok(Some(manifest)) => manifest,
+            Ok(None) => return Ok(vec![]),
+            Err(e) => return Ok(vec![ManifestDiagnostic::from_load_error(&e)]),
+        };
+        if let Err(e) = manifest.check_compatibility() {
+            return Ok(vec![ManifestDiagnostic::from_load_error(&e)]);
+        }
+        Ok(manifest.validate(&widget_dir))
     }
 
-    /// Add starter widgets if not already added.
+    /// Validate a widget without rendering it.
     ///
-    /// If the starter widgets have not been marked as added, this method will
-    /// copy the starter widgets from the bundled resources to the widgets base
-    /// directory. Failure to add individual starter widgets will be logged as
-    /// errors, but will not prevent others from being added, and will not cause
-    /// this method to return an error. However, only if all starter widgets are
-    /// added successfully will the settings be updated to mark them as added.
+    /// This loads the widget manifest from the widget directory and bundles
+    /// the widget, returning structured diagnostics. Unlike [`Self::render`],
+    /// no [`RenderEvent`](crate::events::RenderEvent) is emitted and neither
+    /// the catalog nor the render worker is touched, so this can verify a
+    /// widget before it ever hits the canvas, e.g., when installing it.
     ///
-    /// This method is idempotent. If all starter widgets have been successfully
-    /// added once, subsequent calls are no-ops. If some starter widgets have
-    /// been added but not all, subsequent calls will silently skip already
-    /// existing starter widgets and attempt to add the remaining ones.
-    pub fn maybe_add_starter(&self) -> Result<()> {
+    /// Tauri command: [`crate::commands::validate_widget`].
+    pub async fn validate_widget(&self, id: &str) -> ValidationReport {
+        validate(&self.app_handle, id, &self.widget_dir(id)).await
+    }
+
+    /// Set the source map of the latest successful bundle of a widget.
+    ///
+    /// `None` removes the source map of the widget.
+    pub(crate) fn set_source_map(&self, id: &str, source_map: Option<SourceMap>) {
+        let mut source_maps = self.source_maps.write();
+        match source_map {
+            Some(source_map) => source_maps.insert(id.to_string(), source_map),
+            None => source_maps.remove(id),
+        };
+    }
+
+    /// Record the statistics of the latest successful bundle of a widget.
+    ///
+    /// The statistics are stored in the catalog entry of the widget, which is
+    /// notified to the frontend. If the bundle exceeds the size budget of the
+    /// widget, a [`BundleSizeEvent`] is emitted as well. An error is returned
+    /// if the widget does not exist or if emitting events fails.
+    pub(crate) fn record_bundle_stats(&self, id: &str, stats: BundleStats) -> Result<()> {
+        let mut catalog = self.catalog.write();
+        let widget = catalog
+            .0
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("Widget not found: {id}"))?;
+
+        let budget_kib = match &widget.manifest {
+            Outcome::Ok(manifest) => manifest.bundle_size_budget_kib,
+            Outcome::Err(_) => None,
+        }
+        .or(self.app_handle.settings().read().bundle_size_budget_kib)
+        .unwrap_or(DEFAULT_BUNDLE_SIZE_BUDGET_KIB);
+        let budget = budget_kib * 1024;
+        let size = stats.size;
+
+        widget.bundle_stats = Some(stats);
+        DiffEvent::WidgetChanged { id, widget }.emit(&self.app_handle)?;
+
+        // Zero disables the budget
+        if budget > 0 && size > budget {
+            tracing::warn!(id, size, budget, "Widget bundle exceeds its size budget");
+            BundleSizeEvent { id, size, budget }.emit(&self.app_handle)?;
+        }
+        Ok(())
+    }
+
+    /// Set the strict mode warnings of a widget.
+    ///
+    /// Each warning is logged, and the warnings are stored in the catalog entry
+    /// of the widget, which is notified to the frontend if changed. An error is
+    /// returned if the widget does not exist or if emitting events fails.
+    pub(crate) fn set_strict_warnings(&self, id: &str, warnings: Vec<StrictWarning>) -> Result<()> {
+        let mut catalog = self.catalog.write();
+        let widget = catalog
+            .0
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("Widget not found: {id}"))?;
+
+        for warning in &warnings {
+            warning.log(id);
+        }
+        if widget.strict_warnings != warnings {
+            widget.strict_warnings = warnings;
+            DiffEvent::WidgetChanged { id, widget }.emit(&self.app_handle)?;
+        }
+        Ok(())
+    }
+
+    /// Mark the region of a widget as damaged on the canvas.
+    ///
+    /// This should be called whenever the content of a widget changes, e.g.,
+    /// when it is rendered. Widgets not in the catalog are ignored.
+    pub(crate) fn damage_widget(&self, id: &str) {
+        if let Some(widget) = self.catalog.read().0.get(id) {
+            self.damage
+                .damage(id, DamageRect::from_settings(&widget.settings));
+        }
+    }
+
+    /// Take the canvas regions damaged since the last frame.
+    ///
+    /// The canvas should call this once per frame with any pending changes,
+    /// and confine its refresh to the returned regions. See
+    /// [`DamageTracker::take`] for details.
+    ///
+    /// Tauri command: [`crate::commands::take_damage_hints`].
+    pub fn take_damage_hints(&self, viewport_width: u32, viewport_height: u32) -> DamageHints {
+        self.damage.take(viewport_width, viewport_height)
+    }
+
+    /// Get the metrics of damage tracking.
+    ///
+    /// Tauri command: [`crate::commands::damage_metrics`].
+    pub fn damage_metrics(&self) -> DamageMetrics {
+        self.damage.metrics()
+    }
+
+    /// Get the statistics of the latest successful bundles of widgets.
+    ///
+    /// Widgets that have not been successfully bundled since they were last
+    /// loaded are omitted.
+    ///
+    /// Tauri command: [`crate::commands::bundle_stats`].
+    pub fn bundle_stats(&self) -> BTreeMap<String, BundleStats> {
+        self.catalog
+            .read()
+            .0
+            .iter()
+            .filter_map(|(id, widget)| Some((id.clone(), widget.bundle_stats.clone()?)))
+            .collect()
+    }
+
+    /// Record the timing breakdown of the latest render of a widget.
+    pub(crate) fn record_render_timings(&self, id: &str, timings: RenderTimings) {
+        self.render_timings.write().insert(id.to_string(), timings);
+    }
+
+    /// Get the timing breakdowns of the latest renders of widgets.
+    ///
+    /// This helps to find out which widgets slow down startup. Widgets that
+    /// are not in the catalog are omitted.
+    ///
+    /// Tauri command: [`crate::commands::render_timings`].
+    pub fn render_timings(&self) -> BTreeMap<String, RenderTimings> {
+        let catalog = self.catalog.read();
+        self.render_timings
+            .read()
+            .iter()
+            .filter(|(id, _)| catalog.0.contains_key(*id))
+            .map(|(id, timings)| (id.clone(), timings.clone()))
+            .collect()
+    }
+
+    /// Map a runtime stack trace of a widget back to its original sources.
+    ///
+    /// Frames are mapped using the source map of the latest successful bundle
+    /// of the widget. If no source map is available, the stack trace is
+    /// returned unchanged. Note that this is best-effort, and frames may be
+    /// slightly off if the code is modified by the canvas before evaluation.
+    ///
+    /// Tauri command: [`crate::commands::symbolicate`].
+    pub fn symbolicate(&self, id: &str, stack: &str) -> String {
+        match self.source_maps.read().get(id) {
+            Some(source_map) => symbolicate(stack, source_map),
+            None => stack.to_string(),
+        }
+    }
+
+    /// Start watching a widget for render timeout.
+    ///
+    /// This should be called right before the rendered code of a widget is
+    /// sent to the canvas. If the canvas does not report completion via
+    /// [`Self::report_rendered`] within the configured render timeout, a
+    /// [`TimeoutEvent`] is emitted to the canvas. Any previous watch of the
+    /// widget is replaced.
+    pub(crate) fn watch_render_timeout(&self, id: &str) {
+        let timeout = match self.app_handle.settings().read().render_timeout_seconds {
+            Some(0) => None,
+            Some(seconds) => Some(Duration::from_secs(seconds)),
+            None => Some(DEFAULT_RENDER_TIMEOUT),
+        };
+
+        let mut render_timeouts = self.render_timeouts.lock();
+        let handle = timeout.map(|timeout| {
+            let app_handle = self.app_handle.clone();
+            let id = id.to_string();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(timeout).await;
+                app_handle.widgets().handle_render_timeout(&id, timeout);
+            })
+        });
+        let old_handle = match handle {
+            Some(handle) => render_timeouts.insert(id.to_string(), handle),
+            None => render_timeouts.remove(id),
+        };
+        if let Some(old_handle) = old_handle {
+            old_handle.abort();
+        }
+    }
+
+    /// Handle a widget that has timed out rendering.
+    fn handle_render_timeout(&self, id: &str, timeout: Duration) {
+        self.render_timeouts.lock().remove(id);
+        tracing::warn!(%id, ?timeout, "Widget render timed out");
+
+        let event = TimeoutEvent {
+            id,
+            timeout_seconds: timeout.as_secs(),
+        };
+        if let Err(e) = event.emit_to(&self.app_handle, DeskulptWindow::Canvas) {
+            tracing::error!(error = ?e, %id, "Failed to emit TimeoutEvent");
+        }
+    }
+
+    /// Report that the canvas has finished evaluating a widget module.
+    ///
+    /// This cancels the pending render timeout of the widget, if any. The
+    /// evaluation may have either succeeded or failed.
+    ///
+    /// Tauri command: [`crate::commands::report_rendered`].
+    pub fn report_rendered(&self, id: &str) {
+        if let Some(handle) = self.render_timeouts.lock().remove(id) {
+            handle.abort();
+        }
+    }
+
+    /// Send a network request on behalf of a widget.
+    ///
+    /// The request is only sent if the widget is allowed to access its host,
+    /// see [`Widget::allowed_hosts`], and its bandwidth usage is recorded in
+    /// the metrics of the widget. An error is returned if the widget does
+    /// not exist, if the host is not allowed, or if the request fails.
+    ///
+    /// Tauri command: [`crate::commands::proxy_fetch`].
+    pub async fn proxy_fetch(&self, id: &str, request: NetworkRequest) -> Result<NetworkResponse> {
+        let allowed_hosts = {
+            let catalog = self.catalog.read();
+            let widget = catalog
+                .0
+                .get(id)
+                .ok_or_else(|| anyhow!("Widget {id} does not exist in the catalog"))?;
+            widget.allowed_hosts()
+        };
+        self.network.fetch(id, &allowed_hosts, request).await
+    }
+
+    /// Check whether a widget is allowed to call a plugin command.
+    ///
+    /// This should be checked before calling any plugin command on behalf of
+    /// a widget. An error is returned if the widget does not exist or is not
+    /// allowed to make the call, see [`Widget::check_plugin_call`].
+    pub fn authorize_plugin_call(
+        &self,
+        id: &str,
+        plugin: &str,
+        command: &str,
+        payload: Option<&serde_json::Value>,
+    ) -> Result<()> {
+        let catalog = self.catalog.read();
+        let widget = catalog
+            .0
+            .get(id)
+            .ok_or_else(|| anyhow!("Widget {id} does not exist in the catalog"))?;
+        widget
+            .check_plugin_call(plugin, command, payload)
+            .with_context(|| format!("Widget {id} is denied a plugin call"))
+    }
+
+    /// Grant permissions to a widget, or revoke them.
+    ///
+    /// The granted permissions replace any previously granted ones, and `None`
+    /// revokes all of them. Only the permissions requested by the widget take
+    /// effect, see [`Widget::permissions`]. The widget is refreshed afterwards
+    /// to apply the change.
+    ///
+    /// Tauri command: [`crate::commands::grant_permissions`].
+    pub fn grant_permissions(
+        &self,
+        id: &str,
+        permissions: Option<WidgetPermissions>,
+    ) -> Result<()> {
+        self.app_handle
+            .settings()
+            .set_widget_grant(id, permissions)?;
+        self.refresh(id)
+    }
+
+    /// Get the network usage metrics of widgets.
+    ///
+    /// Widgets that have not used the network are omitted.
+    ///
+    /// Tauri command: [`crate::commands::widget_metrics`].
+    pub fn widget_metrics(&self) -> BTreeMap<String, WidgetMetrics> {
+        self.network.metrics()
+    }
+
+    /// Refresh a specific widget by its ID.
+    ///
+    /// This is equivalent to reloading that widget with [`Self::reload`] then
+    /// rendering it with [`Self::render`].
+    ///
+    /// Tauri command: [`crate::commands::refresh`].
+    pub fn refresh(&self, id: &str) -> Result<()> {
+        self.reload(id)?;
+        self.render(id)?;
+        Ok(())
+    }
+
+    /// Refresh all widgets.
+    ///
+    /// This is equivalent to reloading all widgets with [`Self::reload_all`]
+    /// then rendering all widgets with [`Self::render_all`].
+    ///
+    /// Tauri command: [`crate::commands::refresh_all`].
+    pub fn refresh_all(&self) -> Result<()> {
+        self.reload_all()?;
+        self.render_all()?;
+        Ok(())
+    }
+
+    /// Add starter widgets if not already added.
+    ///
+    /// If the starter widgets have not been marked as added, this method will
+    /// copy the starter widgets from the bundled resources to the widgets base
+    /// directory. Failure to add individual starter widgets will be logged as
+    /// errors, but will not prevent others from being added, and will not cause
+    /// this method to return an error. However, only if all starter widgets are
+    /// added successfully will the settings be updated to mark them as added.
+    ///
+    /// This method is idempotent. If all starter widgets have been successfully
+    /// added once, subsequent calls are no-ops. If some starter widgets have
+    /// been added but not all, subsequent calls will silently skip already
+    /// existing starter widgets and attempt to add the remaining ones.
+    pub fn maybe_add_starter(&self) -> Result<()> {
         if self.app_handle.settings().read().starter_widgets_added {
             return Ok(());
         }
@@ -293,39 +1429,343 @@ impl<R: Runtime> WidgetsManager<R> {
         Ok(())
     }
 
+    /// Import a widget from another desktop widget platform.
+    ///
+    /// The source is converted into a Deskulpt widget scaffold and written to
+    /// a new widget directory named after the widget, with a numeric suffix if
+    /// necessary to avoid collisions. After importing, the widget is
+    /// automatically refreshed to update the catalog and render it. The ID of
+    /// the imported widget is returned.
+    ///
+    /// Tauri command: [`crate::commands::import_widget`].
+    pub fn import_widget(&self, src: &Path) -> Result<String> {
+        let scaffold = crate::import::convert(src)?;
+
+        let slug = scaffold
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect::<String>()
+            .split('-')
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let slug = if slug.is_empty() {
+            "imported".to_string()
+        } else {
+            slug
+        };
+
+        let id = (1..)
+            .map(|i| {
+                if i == 1 {
+                    slug.clone()
+                } else {
+                    format!("{slug}-{i}")
+                }
+            })
+            .find(|id| !self.dir.join(id).exists())
+            .expect("Infinite iterator should always find an ID");
+
+        scaffold.write(&self.dir.join(&id))?;
+        tracing::info!(%id, src = %src.display(), "Imported widget");
+
+        self.refresh(&id)?;
+        Ok(id)
+    }
+
+    /// Get the network configuration for registry traffic.
+    ///
+    /// An error is returned if the configured CA bundle cannot be read.
+    fn registry_network(&self) -> Result<RegistryNetwork> {
+        let (proxy, ca_bundle) = {
+            let settings = self.app_handle.settings().read();
+            (
+                settings.registry_proxy.clone(),
+                settings.registry_ca_bundle.clone(),
+            )
+        };
+        let ca_bundle = ca_bundle
+            .map(|path| {
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read CA bundle: {path}"))
+            })
+            .transpose()?;
+        RegistryNetwork::new(proxy, ca_bundle.as_deref())
+    }
+
+    /// Get the configured widgets registries, with the official one first.
+    ///
+    /// Credentials of additional registries are resolved from the secrets in
+    /// the settings. If that fails, it is logged and the registry is accessed
+    /// anonymously. All registries share the network configuration from
+    /// [`Self::registry_network`].
+    fn registries(&self) -> Result<Vec<Registry>> {
+        let network = self.registry_network()?;
+        let (mirrors, sources) = {
+            let settings = self.app_handle.settings().read();
+            (
+                settings.registry_mirrors.clone(),
+                settings.registries.clone(),
+            )
+        };
+
+        let mut registries = vec![Registry::official(&mirrors, network.clone())];
+        for source in &sources {
+            let credentials = source.auth_secret.as_deref().and_then(|name| {
+                match self.app_handle.settings().secret(name) {
+                    Ok(Some(secret)) => {
+                        let credentials = RegistryCredentials::parse(&secret);
+                        if credentials.is_none() {
+                            tracing::warn!(name, "Malformed registry credentials in secret");
+                        }
+                        credentials
+                    },
+                    Ok(None) => {
+                        tracing::warn!(name, "Secret for registry credentials not found");
+                        None
+                    },
+                    Err(e) => {
+                        tracing::warn!(name, error = ?e, "Failed to read registry credentials");
+                        None
+                    },
+                }
+            });
+            registries.push(Registry::additional(source, credentials, network.clone()));
+        }
+        Ok(registries)
+    }
+
+    /// Get a configured widgets registry by its ID.
+    ///
+    /// See [`Registry::id`] for details. An error is returned if the registry
+    /// is no longer configured.
+    fn registry(&self, id: Option<&str>) -> Result<Registry> {
+        self.registries()?
+            .into_iter()
+            .find(|registry| registry.id.as_deref() == id)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Widgets registry {} is not configured",
+                    id.unwrap_or("official")
+                )
+            })
+    }
+
+    /// Get the merged index of all configured widgets registries.
+    ///
+    /// The index of each registry is obtained concurrently with the given
+    /// closure, and merged as in [`RegistryIndex::merge`]. Registries whose
+    /// index cannot be obtained are skipped; an error is returned only if no
+    /// index can be obtained at all.
+    async fn merged_registry_index<F, Fut>(&self, f: F) -> Result<RegistryIndex>
+    where
+        F: Fn(RegistryIndexFetcher) -> Fut,
+        Fut: Future<Output = Result<RegistryIndex>>,
+    {
+        let cache_dir = self.app_handle.path().app_cache_dir()?;
+        let registries = self.registries()?;
+        let names = registries
+            .iter()
+            .map(|registry| registry.name().to_string())
+            .collect::<Vec<_>>();
+        let results = join_all(registries.into_iter().map(|registry| {
+            let fetcher = RegistryIndexFetcher::new(&cache_dir, registry);
+            let f = &f;
+            async move { f(fetcher?).await }
+        }))
+        .await;
+
+        let mut indices = Vec::new();
+        let mut first_error = None;
+        for (name, result) in names.iter().zip(results) {
+            match result {
+                Ok(index) => indices.push(index),
+                Err(e) => {
+                    tracing::debug!(registry = %name, error = ?e, "Skipping registry index");
+                    first_error.get_or_insert(e);
+                },
+            }
+        }
+        RegistryIndex::merge(indices)
+            .ok_or_else(|| first_error.unwrap_or_else(|| anyhow!("No widgets registries")))
+    }
+
     /// Fetch the widgets registry index.
     ///
-    /// Before fetching, this method ensures that the catalog is up-to-date by
-    /// reloading all widgets. This is necessary for the frontend to know which
-    /// widgets are already installed.
+    /// This fetches and merges the indices of all configured registries, see
+    /// [`Self::merged_registry_index`]. Before fetching, this method ensures
+    /// that the catalog is up-to-date by reloading all widgets. This is
+    /// necessary for the frontend to know which widgets are already installed.
     pub async fn fetch_registry_index(&self) -> Result<RegistryIndex> {
         self.reload_all()?;
+        self.merged_registry_index(|fetcher| async move { fetcher.fetch().await })
+            .await
+    }
 
+    /// Check whether all configured widgets registries are reachable.
+    ///
+    /// See [`RegistryIndexFetcher::ping`] for details. An error is returned
+    /// for the first unreachable registry.
+    pub async fn ping_registry(&self) -> Result<()> {
         let cache_dir = self.app_handle.path().app_cache_dir()?;
-        let fetcher = RegistryIndexFetcher::new(&cache_dir);
-        fetcher.fetch().await
+        let registries = self.registries()?;
+        let results = join_all(registries.iter().map(|registry| {
+            let fetcher = RegistryIndexFetcher::new(&cache_dir, registry.clone());
+            async move { fetcher?.ping().await }
+        }))
+        .await;
+        for (registry, result) in registries.iter().zip(results) {
+            result.with_context(|| format!("Registry {} is unreachable", registry.name()))?;
+        }
+        Ok(())
+    }
+
+    /// Read the widgets registry index cached by the last fetch.
+    ///
+    /// Unlike [`Self::fetch_registry_index`], this does not touch the network
+    /// or the catalog. Registries whose index has never been fetched are
+    /// skipped; an error is returned if none has been fetched.
+    pub async fn cached_registry_index(&self) -> Result<RegistryIndex> {
+        self.merged_registry_index(|fetcher| async move { fetcher.read_cache().await })
+            .await
+    }
+
+    /// Search the widgets registry.
+    ///
+    /// This searches the index cached by the last fetch, and fetches the index
+    /// only if it has never been fetched. See [`RegistryIndex::search`] for
+    /// how widgets are matched, sorted, and paged.
+    pub async fn search_registry(
+        &self,
+        query: &str,
+        category: Option<&str>,
+        tags: &[String],
+        sort: RegistrySort,
+        page: u32,
+    ) -> Result<RegistrySearchPage> {
+        let index = self.cached_or_fetch_registry_index().await?;
+        Ok(index.search(query, category, tags, sort, page))
+    }
+
+    /// Count the widgets in each category of the widgets registry.
+    ///
+    /// Like [`Self::search_registry`], this uses the cached index if any. See
+    /// [`RegistryIndex::categories`] for how categories are grouped.
+    ///
+    /// Tauri command: [`crate::commands::registry_categories`].
+    pub async fn registry_categories(&self) -> Result<BTreeMap<String, u32>> {
+        let index = self.cached_or_fetch_registry_index().await?;
+        Ok(index.categories())
+    }
+
+    /// List the releases of a widget in the registry.
+    ///
+    /// The releases are ordered from newest to oldest. This uses the index
+    /// cached by the last fetch, and fetches the index only if it has never
+    /// been fetched. An error is returned if the widget is not in the index.
+    pub async fn list_releases(&self, handle: &str, id: &str) -> Result<Vec<RegistryEntryRelease>> {
+        let index = self.cached_or_fetch_registry_index().await?;
+        let entry = index
+            .find(handle, id)
+            .ok_or_else(|| anyhow!("Widget @{handle}.{id} not found in the registry"))?;
+        Ok(entry.releases().to_vec())
+    }
+
+    /// Read the cached registry index, or fetch it if it is not cached.
+    ///
+    /// This applies per registry. Unlike [`Self::fetch_registry_index`], this
+    /// does not touch the catalog.
+    async fn cached_or_fetch_registry_index(&self) -> Result<RegistryIndex> {
+        self.merged_registry_index(|fetcher| async move {
+            match fetcher.read_cache().await {
+                Ok(index) => Ok(index),
+                Err(e) => {
+                    tracing::debug!(error = ?e, "No usable cached registry index; fetching");
+                    fetcher.fetch().await
+                },
+            }
+        })
+        .await
     }
 
     /// Preview a widget from the registry.
-    pub async fn preview(&self, widget: &RegistryWidgetReference) -> Result<RegistryWidgetPreview> {
-        RegistryWidgetFetcher::default().preview(widget).await
+    ///
+    /// The display metadata is resolved to the locale in the settings. If
+    /// assets are included, the README and screenshots of the widget are
+    /// fetched as well, and cached on disk for subsequent previews.
+    pub async fn preview(
+        &self,
+        widget: &RegistryWidgetReference,
+        include_assets: bool,
+    ) -> Result<RegistryWidgetPreview> {
+        let locale = Self::locale(&self.app_handle);
+        let assets_cache_dir = if include_assets {
+            Some(
+                self.app_handle
+                    .path()
+                    .app_cache_dir()?
+                    .join("registry-assets"),
+            )
+        } else {
+            None
+        };
+        RegistryWidgetFetcher::new(self.registry(widget.registry.as_deref())?)
+            .preview(widget, locale.as_deref(), assets_cache_dir.as_deref())
+            .await
     }
 
     /// Install a widget from the registry.
     ///
-    /// If the widget already exists locally, an error is returned. After
-    /// installation, the widget is automatically refreshed to update the
-    /// catalog and render it.
-    pub async fn install(&self, widget: &RegistryWidgetReference) -> Result<()> {
+    /// If a version is given, the release with that version is installed and
+    /// the digest of the given reference is ignored; an error is returned if
+    /// there is no such release in the registry index. If the widget already
+    /// exists locally, an error is returned. If a request ID is given, the
+    /// progress is reported and the install can be cancelled, see
+    /// [`Self::fetch_package`]. The widget is granted the given permissions,
+    /// as approved by the user when prompted with those requested in the
+    /// preview, see [`Self::grant_permissions`]. After installation, the
+    /// widget is automatically refreshed to update the catalog and render it.
+    pub async fn install(
+        &self,
+        widget: &RegistryWidgetReference,
+        version: Option<&str>,
+        request_id: Option<&str>,
+        grant: Option<WidgetPermissions>,
+    ) -> Result<()> {
+        let resolved;
+        let widget = match version {
+            Some(version) => {
+                let index = self.cached_or_fetch_registry_index().await?;
+                resolved = index
+                    .find(&widget.handle, &widget.id)
+                    .and_then(|entry| entry.release(version))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Release {version} of widget {} not found",
+                            widget.local_id()
+                        )
+                    })?;
+                &resolved
+            },
+            None => widget,
+        };
+
         let id = widget.local_id();
-        let widget_dir = self.dir.join(&id);
+        let widget_dir = self.widget_dir(&id);
         if widget_dir.exists() {
             bail!("Widget {id} already installed");
         }
 
-        RegistryWidgetFetcher::default()
-            .install(&widget_dir, widget)
-            .await?;
+        self.fetch_package(&widget_dir, widget, request_id).await?;
+        self.lock_installed(widget).await;
+        self.app_handle.settings().set_widget_grant(&id, grant)?;
 
         self.refresh(&id)?;
         Ok(())
@@ -338,13 +1778,25 @@ impl<R: Runtime> WidgetsManager<R> {
     /// the catalog.
     pub async fn uninstall(&self, widget: &RegistryWidgetReference) -> Result<()> {
         let id = widget.local_id();
-        let widget_dir = self.dir.join(&id);
+        let widget_dir = self.widget_dir(&id);
         if !widget_dir.exists() {
             bail!("Widget {id} is not installed");
         }
         tokio::fs::remove_dir_all(&widget_dir)
             .await
             .with_context(|| format!("Failed to remove directory {}", widget_dir.display()))?;
+        let rollback_dir = self.dir.join(ROLLBACK_DIR).join(&id);
+        if rollback_dir.exists()
+            && let Err(e) = tokio::fs::remove_dir_all(&rollback_dir).await
+        {
+            tracing::warn!(id, error = ?e, "Failed to remove previous widget package");
+        }
+        self.update_registry_lock(|lock| {
+            lock.0.remove(&id);
+        });
+        if let Err(e) = self.app_handle.settings().set_widget_grant(&id, None) {
+            tracing::warn!(%id, error = ?e, "Failed to revoke widget permissions");
+        }
 
         self.reload(&id)?;
         Ok(())
@@ -352,27 +1804,634 @@ impl<R: Runtime> WidgetsManager<R> {
 
     /// Upgrade a widget from the registry.
     ///
-    /// If the widget does not exist locally, an error is returned. After
-    /// upgrading, the widget is automatically refreshed to update the catalog
-    /// and render it.
-    pub async fn upgrade(&self, widget: &RegistryWidgetReference) -> Result<()> {
+    /// If the widget does not exist locally, an error is returned. The new
+    /// package is staged and validated before it replaces the installed one,
+    /// see [`Self::stage_and_swap`]. If a request ID is given, the progress is
+    /// reported and the upgrade can be cancelled, see [`Self::fetch_package`].
+    /// After upgrading, the widget is automatically refreshed to update the
+    /// catalog and render it.
+    pub async fn upgrade(
+        &self,
+        widget: &RegistryWidgetReference,
+        request_id: Option<&str>,
+    ) -> Result<()> {
+        let id = widget.local_id();
+        self.stage_and_swap(widget, request_id).await?;
+        self.lock_installed(widget).await;
+        self.refresh(&id)?;
+        Ok(())
+    }
+
+    /// Update a widget installed from the registry to its latest release.
+    ///
+    /// This returns the applied upgrade, or `None` if the widget is already
+    /// up-to-date. The update is staged and validated before it replaces the
+    /// widget, see [`Self::stage_and_swap`]. An [`UpgradesAvailableEvent`] is
+    /// emitted with the remaining upgrades afterwards.
+    pub async fn update_widget(&self, id: &str) -> Result<Option<WidgetUpgrade>> {
+        let index = self.fetch_index_for_updates().await?;
+        let Some(upgrade) = self
+            .pending_upgrades(&index)
+            .into_iter()
+            .find(|upgrade| upgrade.id == id)
+        else {
+            return Ok(None);
+        };
+
+        self.stage_and_swap(&upgrade.latest, None).await?;
+        self.update_registry_lock(|lock| lock.record(&upgrade.latest, Some(&index)));
+        self.refresh(id)?;
+
+        UpgradesAvailableEvent(&self.pending_upgrades(&index)).emit(&self.app_handle)?;
+        Ok(Some(upgrade))
+    }
+
+    /// Update all widgets installed from the registry to their latest
+    /// releases.
+    ///
+    /// Widgets are updated one by one as in [`Self::update_widget`], and
+    /// failure to update one widget does not stop the others. This returns the
+    /// outcome of each attempted update keyed by widget ID, with the new
+    /// version on success. An error is returned only if the registry index
+    /// cannot be fetched.
+    pub async fn update_all(&self) -> Result<BTreeMap<String, Outcome<String>>> {
+        let index = self.fetch_index_for_updates().await?;
+
+        let mut outcomes = BTreeMap::new();
+        for upgrade in self.pending_upgrades(&index) {
+            let result = async {
+                self.stage_and_swap(&upgrade.latest, None).await?;
+                self.update_registry_lock(|lock| lock.record(&upgrade.latest, Some(&index)));
+                self.refresh(&upgrade.id)
+            }
+            .await;
+            match &result {
+                Ok(()) => tracing::info!(
+                    id = %upgrade.id,
+                    version = %upgrade.latest_version,
+                    "Updated widget",
+                ),
+                Err(e) => tracing::error!(id = %upgrade.id, error = ?e, "Failed to update widget"),
+            }
+            let outcome = result.map(|_| upgrade.latest_version.clone()).into();
+            outcomes.insert(upgrade.id, outcome);
+        }
+
+        UpgradesAvailableEvent(&self.pending_upgrades(&index)).emit(&self.app_handle)?;
+        Ok(outcomes)
+    }
+
+    /// Reinstall the widgets recorded in a lockfile that are missing locally.
+    ///
+    /// If no lockfile is given, the lockfile of this machine is used, which
+    /// restores widgets whose directories have been removed. Given a lockfile
+    /// copied from another machine, this restores the widget set of that
+    /// machine. Each widget is reinstalled from the exact package recorded, and
+    /// failure to reinstall one widget does not stop the others. Widgets that
+    /// are already installed are skipped, and reinstalled widgets are recorded
+    /// in the lockfile of this machine. This returns the outcome of each
+    /// attempted reinstall keyed by widget ID. An error is returned only if the
+    /// lockfile cannot be loaded.
+    pub async fn reinstall_from_lockfile(
+        &self,
+        path: Option<&Path>,
+    ) -> Result<BTreeMap<String, Outcome<()>>> {
+        let lock = match path {
+            Some(path) => {
+                if !path.exists() {
+                    bail!("Lockfile {} does not exist", path.display());
+                }
+                RegistryLock::load(path)?
+            },
+            None => RegistryLock(self.registry_lock.lock().0.clone()),
+        };
+
+        let mut outcomes = BTreeMap::new();
+        for locked in lock.0.into_values() {
+            // The ID is derived from the reference rather than taken from the
+            // lockfile, which may come from elsewhere
+            let id = locked.reference.local_id();
+            let widget_dir = self.widget_dir(&id);
+            if widget_dir.exists() {
+                continue;
+            }
+
+            let result = async {
+                self.fetch_package(&widget_dir, &locked.reference, None)
+                    .await?;
+                self.update_registry_lock(|lock| {
+                    lock.record(&locked.reference, None);
+                    if let Some(recorded) = lock.0.get_mut(&id) {
+                        recorded.version = locked.version.clone();
+                    }
+                });
+                self.refresh(&id)
+            }
+            .await;
+            match &result {
+                Ok(()) => tracing::info!(%id, "Reinstalled widget"),
+                Err(e) => tracing::error!(%id, error = ?e, "Failed to reinstall widget"),
+            }
+            outcomes.insert(id, result.into());
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Download a widget from the registry in place of an installed one.
+    ///
+    /// The widget package is downloaded into a staging directory and validated
+    /// by loading its manifest and bundling it once. Only if that succeeds is
+    /// the staged package moved into place, see [`Self::swap_into_place`]. If
+    /// anything fails, the installed widget is left unchanged. Settings of the
+    /// widget are keyed by its ID and are therefore kept as well.
+    ///
+    /// If a request ID is given, the progress is reported with it, see
+    /// [`Self::fetch_package`].
+    async fn stage_and_swap(
+        &self,
+        widget: &RegistryWidgetReference,
+        request_id: Option<&str>,
+    ) -> Result<()> {
         let id = widget.local_id();
-        let widget_dir = self.dir.join(&id);
+        let widget_dir = self.widget_dir(&id);
         if !widget_dir.exists() {
             bail!("Widget {id} is not installed");
         }
 
-        // TODO: We should ideally perform some form of backup to allow rollback
-        // on failure, to avoid leaving the widget in a broken state
-        tokio::fs::remove_dir_all(&widget_dir)
-            .await
-            .with_context(|| format!("Failed to remove directory {}", widget_dir.display()))?;
+        let staged_dir = self.dir.join(STAGING_DIR).join(&id);
+        if staged_dir.exists() {
+            tokio::fs::remove_dir_all(&staged_dir)
+                .await
+                .with_context(|| {
+                    format!("Failed to clean up directory {}", staged_dir.display())
+                })?;
+        }
+        tokio::fs::create_dir_all(self.dir.join(STAGING_DIR)).await?;
+
+        let staged = async {
+            self.fetch_package(&staged_dir, widget, request_id).await?;
+            self.report_install_progress(request_id, InstallProgress::Validating);
+            if let Outcome::Err(e) = validate(&self.app_handle, &id, &staged_dir).await.report {
+                bail!("Validation of the new release failed: {e}");
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(e) = staged {
+            if staged_dir.exists()
+                && let Err(cleanup_error) = tokio::fs::remove_dir_all(&staged_dir).await
+            {
+                tracing::warn!(id, error = ?cleanup_error, "Failed to clean up staged update");
+            }
+            return Err(e);
+        }
+
+        self.swap_into_place(&id, &staged_dir)
+    }
+
+    /// Download and unpack a widget package from the registry.
+    ///
+    /// If a request ID is given, an [`InstallProgressEvent`] is emitted with it
+    /// on each progress, and the request can be cancelled via
+    /// [`Self::cancel_install`] until unpacking starts. Once unpacked, the
+    /// package is removed again if its requirements are not met, see
+    /// [`Self::check_requirements`].
+    async fn fetch_package(
+        &self,
+        dir: &Path,
+        widget: &RegistryWidgetReference,
+        request_id: Option<&str>,
+    ) -> Result<()> {
+        let cancel = CancellationToken::new();
+        if let Some(request_id) = request_id {
+            self.install_requests
+                .lock()
+                .insert(request_id.to_string(), cancel.clone());
+        }
+
+        let result = async {
+            self.check_revocation(widget).await?;
+            let fetcher = RegistryWidgetFetcher::new(self.registry(widget.registry.as_deref())?);
+            self.check_signature(&fetcher, widget, &cancel, request_id)
+                .await?;
+            fetcher
+                .install(dir, widget, &cancel, |progress| {
+                    self.report_install_progress(request_id, progress)
+                })
+                .await?;
+            if let Err(e) = self.check_requirements(dir, widget) {
+                if let Err(cleanup_error) = tokio::fs::remove_dir_all(dir).await {
+                    tracing::warn!(
+                        widget = %widget.local_id(),
+                        error = ?cleanup_error,
+                        "Failed to remove widget package",
+                    );
+                }
+                return Err(e);
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Some(request_id) = request_id {
+            self.install_requests.lock().remove(request_id);
+        }
+        result
+    }
+
+    /// Refuse a widget package whose requirements are not met.
+    ///
+    /// The widget must be compatible with this version of Deskulpt, see
+    /// [`WidgetManifest::check_compatibility`], and its manifest must have no
+    /// errors, see [`WidgetManifest::validate`]. Plugins are built into
+    /// Deskulpt and cannot be installed along with the widget, so a widget
+    /// requiring a plugin that is unavailable or too old would arrive broken
+    /// as well. Packages without a valid manifest are left to the usual
+    /// validation.
+    fn check_requirements(&self, dir: &Path, widget: &RegistryWidgetReference) -> Result<()> {
+        let Ok(Some(manifest)) = WidgetManifest::load_unchecked(dir) else {
+            return Ok(());
+        };
+        manifest
+            .check_compatibility()
+            .with_context(|| format!("Widget {} is incompatible", widget.local_id()))?;
+        let errors = manifest
+            .validate(dir)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == ManifestSeverity::Error)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            bail!(
+                "Widget {} has an invalid manifest: {}",
+                widget.local_id(),
+                errors.join("; ")
+            );
+        }
+        let available = self.plugins.read();
+        let missing = manifest
+            .required_plugins
+            .iter()
+            .filter_map(|requirement| requirement.check(&available))
+            .map(|missing| match missing.found {
+                Some(found) => format!("{} (found {found})", missing.requirement),
+                None => missing.requirement,
+            })
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            bail!(
+                "Widget {} requires unavailable plugins: {}",
+                widget.local_id(),
+                missing.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Refuse to install a widget package revoked by the registry.
+    ///
+    /// The revocations are taken from the cached registry index, or the
+    /// fetched one if not cached. If the index is unavailable, the check is
+    /// skipped with a warning.
+    async fn check_revocation(&self, widget: &RegistryWidgetReference) -> Result<()> {
+        match self.cached_or_fetch_registry_index().await {
+            Ok(index) => {
+                if let Some(revoked) = index.revocation(&widget.digest) {
+                    bail!(
+                        "Widget package {} of {} has been revoked: {}",
+                        widget.digest,
+                        widget.local_id(),
+                        revoked.reason
+                    );
+                }
+            },
+            Err(e) => tracing::warn!(
+                widget = %widget.local_id(),
+                error = ?e,
+                "Skipping revocation check; registry index unavailable",
+            ),
+        }
+        Ok(())
+    }
+
+    /// Check the signature of a widget package before installing it.
+    ///
+    /// If the publisher of the widget is trusted, the widget package must be
+    /// signed by one of its keys, otherwise an error is returned. Otherwise,
+    /// the widget is treated as configured by [`UnsignedWidgetPolicy`]. The
+    /// check can be cancelled via the given token.
+    async fn check_signature(
+        &self,
+        fetcher: &RegistryWidgetFetcher,
+        widget: &RegistryWidgetReference,
+        cancel: &CancellationToken,
+        request_id: Option<&str>,
+    ) -> Result<()> {
+        let (public_keys, policy) = {
+            let settings = self.app_handle.settings().read();
+            let public_keys = settings
+                .trusted_publishers
+                .iter()
+                .filter(|publisher| publisher.handle == widget.handle)
+                .map(|publisher| publisher.public_key.clone())
+                .collect::<Vec<_>>();
+            (public_keys, settings.unsigned_widgets)
+        };
+        if public_keys.is_empty() && policy == UnsignedWidgetPolicy::Allow {
+            return Ok(());
+        }
+
+        self.report_install_progress(request_id, InstallProgress::Verifying);
+        let status = cancel
+            .run_until_cancelled(fetcher.verify_signature(widget, &public_keys))
+            .await;
+        let Some(status) = status else {
+            bail!("Installation of widget {} cancelled", widget.local_id());
+        };
+        let status = status?;
+
+        if status == SignatureStatus::Verified {
+            return Ok(());
+        }
+        if !public_keys.is_empty() {
+            bail!(
+                "Widget {} is not signed by trusted publisher {} ({status:?})",
+                widget.local_id(),
+                widget.handle
+            );
+        }
+        match policy {
+            UnsignedWidgetPolicy::Allow => {},
+            UnsignedWidgetPolicy::Warn => tracing::warn!(
+                widget = %widget.local_id(),
+                ?status,
+                "Installing widget without a trusted signature",
+            ),
+            UnsignedWidgetPolicy::Block => bail!(
+                "Widget {} has no trusted signature ({status:?})",
+                widget.local_id()
+            ),
+        }
+        Ok(())
+    }
+
+    /// Report the progress of an install request, if any.
+    fn report_install_progress(&self, request_id: Option<&str>, progress: InstallProgress) {
+        let Some(request_id) = request_id else {
+            return;
+        };
+        let event = InstallProgressEvent {
+            request_id,
+            progress,
+        };
+        if let Err(e) = event.emit(&self.app_handle) {
+            tracing::warn!(request_id, error = ?e, "Failed to emit InstallProgressEvent");
+        }
+    }
+
+    /// Cancel a pending install or upgrade from the registry.
+    ///
+    /// This returns whether there is such a pending request. A request can
+    /// only be cancelled while the widget package is being resolved or
+    /// downloaded; the cancelled install or upgrade then fails without
+    /// changing the installed widgets.
+    pub fn cancel_install(&self, request_id: &str) -> bool {
+        match self.install_requests.lock().get(request_id) {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Roll back a widget installed from the registry to its previous release.
+    ///
+    /// The package of the previously installed release is kept when a widget
+    /// is upgraded or updated, see [`Self::swap_into_place`]. Rolling back
+    /// swaps it with the installed package, so that rolling back again
+    /// restores the release rolled back from. This is meant for recovering
+    /// from an update that breaks rendering. If no previous package is kept,
+    /// an error is returned. After rolling back, the widget is automatically
+    /// refreshed to update the catalog and render it.
+    pub async fn rollback_widget(&self, id: &str) -> Result<()> {
+        if !self.widget_dir(id).exists() {
+            bail!("Widget {id} is not installed");
+        }
+        let rollback_dir = self.dir.join(ROLLBACK_DIR).join(id);
+        if !rollback_dir.exists() {
+            bail!("No previous release of widget {id} to roll back to");
+        }
+
+        self.swap_into_place(id, &rollback_dir)?;
+        self.update_registry_lock(|lock| lock.rollback(id));
+        self.refresh(id)?;
+
+        // The rolled back widget may have an upgrade again; the cached index
+        // suffices since it is refreshed whenever updates are checked
+        if let Ok(index) = self.cached_registry_index().await {
+            UpgradesAvailableEvent(&self.pending_upgrades(&index)).emit(&self.app_handle)?;
+        }
+        Ok(())
+    }
+
+    /// Move a widget package into place of an installed widget.
+    ///
+    /// The installed widget directory is swapped with the given one, carrying
+    /// over the [`WIDGET_DATA_DIR`] subdirectory. If the given package cannot
+    /// be moved into place, the installed widget is restored. The replaced
+    /// package is kept in the [`ROLLBACK_DIR`] afterwards, replacing any
+    /// package kept before.
+    fn swap_into_place(&self, id: &str, package_dir: &Path) -> Result<()> {
+        let widget_dir = self.widget_dir(id);
+        let staging_dir = self.dir.join(STAGING_DIR);
+        let backup_dir = staging_dir.join(format!("{id}.old"));
+        if backup_dir.exists() {
+            std::fs::remove_dir_all(&backup_dir).with_context(|| {
+                format!("Failed to clean up directory {}", backup_dir.display())
+            })?;
+        }
+        std::fs::create_dir_all(&staging_dir)?;
+
+        // Swap the directories, restoring the installed widget if the new
+        // package cannot be moved into place
+        std::fs::rename(&widget_dir, &backup_dir)
+            .with_context(|| format!("Failed to move directory {}", widget_dir.display()))?;
+        if let Err(e) = std::fs::rename(package_dir, &widget_dir) {
+            std::fs::rename(&backup_dir, &widget_dir)
+                .with_context(|| format!("Failed to restore directory {}", widget_dir.display()))?;
+            return Err(e).context("Failed to move widget package into place");
+        }
+
+        let old_data_dir = backup_dir.join(WIDGET_DATA_DIR);
+        if old_data_dir.exists() {
+            let new_data_dir = widget_dir.join(WIDGET_DATA_DIR);
+            if new_data_dir.exists() {
+                std::fs::remove_dir_all(&new_data_dir)?;
+            }
+            std::fs::rename(&old_data_dir, &new_data_dir)
+                .context("Failed to carry over widget data")?;
+        }
+
+        // Keep the replaced package for rolling back; failure to do so only
+        // affects rolling back and is thus not fatal
+        if let Err(e) = move_dir_replacing(&backup_dir, &self.dir.join(ROLLBACK_DIR).join(id)) {
+            tracing::warn!(id, error = ?e, "Failed to keep previous widget package");
+            if let Err(e) = std::fs::remove_dir_all(&backup_dir) {
+                tracing::warn!(id, error = ?e, "Failed to remove previous widget package");
+            }
+        }
+        Ok(())
+    }
 
-        RegistryWidgetFetcher::default()
-            .install(&widget_dir, widget)
+    /// Check for upgrades of widgets installed from the registry.
+    ///
+    /// This fetches the registry index and compares it against the lockfile of
+    /// installed widgets, see [`RegistryLock::upgrades`]. Widgets whose
+    /// directories have been removed are skipped. An
+    /// [`UpgradesAvailableEvent`] is emitted with the result.
+    pub async fn check_updates(&self) -> Result<Vec<WidgetUpgrade>> {
+        let index = self.fetch_index_for_updates().await?;
+        let upgrades = self.pending_upgrades(&index);
+        UpgradesAvailableEvent(&upgrades).emit(&self.app_handle)?;
+        Ok(upgrades)
+    }
+
+    /// Fetch the registry index to check for updates against.
+    ///
+    /// Installed widgets revoked in the index are flagged along the way, see
+    /// [`Self::flag_revoked`].
+    async fn fetch_index_for_updates(&self) -> Result<RegistryIndex> {
+        let index = self
+            .merged_registry_index(|fetcher| async move { fetcher.fetch().await })
             .await?;
+        self.flag_revoked(&index)?;
+        Ok(index)
+    }
 
-        self.refresh(&id)?;
+    /// Flag installed widgets whose packages are revoked in an index.
+    ///
+    /// Widgets whose directories have been removed are skipped. A
+    /// [`RevokedWidgetsEvent`] is emitted with the result.
+    fn flag_revoked(&self, index: &RegistryIndex) -> Result<()> {
+        let mut revoked = self.registry_lock.lock().revoked(index);
+        revoked.retain(|widget| self.widget_dir(&widget.id).exists());
+        for widget in &revoked {
+            tracing::warn!(id = %widget.id, reason = %widget.reason, "Installed widget revoked");
+        }
+        RevokedWidgetsEvent(&revoked).emit(&self.app_handle)?;
         Ok(())
     }
+
+    /// Report an abusive widget in the registry.
+    ///
+    /// The report is queued, and then all queued reports are submitted, see
+    /// [`Self::submit_reports`]. This returns whether the report has been
+    /// submitted; if not, e.g., while offline, it stays queued to be submitted
+    /// later. An error is returned if no endpoint for reporting widgets is
+    /// configured in the settings.
+    ///
+    /// Tauri command: [`crate::commands::report_widget`].
+    pub async fn report_widget(
+        &self,
+        widget: &RegistryWidgetReference,
+        reason: &str,
+    ) -> Result<bool> {
+        if self.app_handle.settings().read().report_endpoint.is_none() {
+            bail!("No endpoint configured for reporting widgets");
+        }
+        let report = WidgetReport::new(widget, reason)?;
+        self.update_report_queue(|queue| queue.0.push(report));
+        self.submit_reports().await
+    }
+
+    /// Submit the queued widget reports.
+    ///
+    /// Reports are submitted in order to the endpoint in the settings, see
+    /// [`WidgetReport::submit`]. Submission stops at the first failure, so
+    /// that the remaining reports stay queued. This returns whether the queue
+    /// has been drained. If no endpoint is configured, nothing is submitted.
+    pub async fn submit_reports(&self) -> Result<bool> {
+        let endpoint = self.app_handle.settings().read().report_endpoint.clone();
+        let Some(endpoint) = endpoint else {
+            return Ok(self.report_queue.lock().0.is_empty());
+        };
+        let client = self.registry_network()?.http_client()?;
+
+        let reports = std::mem::take(&mut self.report_queue.lock().0);
+        let mut remaining = Vec::new();
+        for report in reports {
+            if remaining.is_empty() {
+                match report.submit(&client, &endpoint).await {
+                    Ok(()) => continue,
+                    Err(e) => tracing::warn!(error = ?e, "Failed to submit widget report"),
+                }
+            }
+            remaining.push(report);
+        }
+
+        let drained = remaining.is_empty();
+        self.update_report_queue(|queue| {
+            // Reports queued in the meantime go after the remaining ones
+            remaining.append(&mut queue.0);
+            queue.0 = remaining;
+        });
+        Ok(drained)
+    }
+
+    /// Update the queue of widget reports.
+    ///
+    /// Failure to write the queue file is logged but not fatal, since it only
+    /// affects retrying reports after restart.
+    fn update_report_queue(&self, f: impl FnOnce(&mut ReportQueue)) {
+        let mut queue = self.report_queue.lock();
+        f(&mut queue);
+        if let Err(e) = queue.dump(&self.dir.join(ReportQueue::FILE_NAME)) {
+            tracing::error!("Failed to write widget report queue: {e:?}");
+        }
+    }
+
+    /// Get the available upgrades of installed widgets against an index.
+    ///
+    /// Widgets whose directories have been removed are skipped.
+    fn pending_upgrades(&self, index: &RegistryIndex) -> Vec<WidgetUpgrade> {
+        let mut upgrades = self.registry_lock.lock().upgrades(index);
+        upgrades.retain(|upgrade| self.widget_dir(&upgrade.id).exists());
+        upgrades
+    }
+
+    /// Record a widget just installed from the registry in the lockfile.
+    ///
+    /// The version of the widget is resolved from the cached registry index
+    /// if available.
+    async fn lock_installed(&self, widget: &RegistryWidgetReference) {
+        let index = self.cached_registry_index().await.ok();
+        self.update_registry_lock(|lock| lock.record(widget, index.as_ref()));
+    }
+
+    /// Update the lockfile of widgets installed from the registry.
+    ///
+    /// Failure to write the lockfile is logged but not fatal, since it only
+    /// affects update checking.
+    fn update_registry_lock(&self, f: impl FnOnce(&mut RegistryLock)) {
+        let mut lock = self.registry_lock.lock();
+        f(&mut lock);
+        if let Err(e) = lock.dump(&self.registry_lock_path) {
+            tracing::error!("Failed to write registry lockfile: {e:?}");
+        }
+    }
+}
+
+/// Move a directory to a destination, replacing any existing directory there.
+///
+/// Parent directories of the destination are created if needed.
+fn move_dir_replacing(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.exists() {
+        std::fs::remove_dir_all(to)?;
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(from, to)
 }
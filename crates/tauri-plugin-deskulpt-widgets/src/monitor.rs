@@ -0,0 +1,125 @@
+//! Monitor-aware positioning of widgets.
+
+use anyhow::Result;
+use deskulpt_common::window::DeskulptWindow;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+/// The position of a widget relative to the monitor it is on.
+///
+/// Absolute coordinates break when the monitor layout changes, e.g., when a
+/// monitor is unplugged or its DPI changes. Anchoring widgets to monitors
+/// allows re-resolving their absolute coordinates in such cases.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorAnchor {
+    /// The identifier of the monitor.
+    pub monitor: String,
+    /// The x-coordinate relative to the left edge of the monitor in pixels.
+    pub x: i32,
+    /// The y-coordinate relative to the top edge of the monitor in pixels.
+    pub y: i32,
+}
+
+/// The bounds of a monitor in canvas coordinates.
+#[derive(Debug, Clone)]
+pub struct MonitorBounds {
+    /// The identifier of the monitor.
+    id: String,
+    /// The leftmost x-coordinate in pixels.
+    x: i32,
+    /// The topmost y-coordinate in pixels.
+    y: i32,
+    /// The width in pixels.
+    width: i32,
+    /// The height in pixels.
+    height: i32,
+}
+
+impl MonitorBounds {
+    /// Get the bounds of all available monitors in canvas coordinates.
+    ///
+    /// Canvas coordinates are logical pixels relative to the top-left corner
+    /// of the canvas, in which widget coordinates are expressed.
+    pub fn all<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Vec<Self>> {
+        let canvas = DeskulptWindow::Canvas.webview_window(app_handle)?;
+        let origin = canvas.outer_position()?;
+        let scale_factor = canvas.scale_factor()?;
+        let to_logical = |v: i32| (v as f64 / scale_factor).round() as i32;
+
+        let monitors = canvas
+            .available_monitors()?
+            .into_iter()
+            .map(|monitor| {
+                let position = monitor.position();
+                let size = monitor.size();
+                // Monitor names are not guaranteed, in which case fall back to
+                // the physical layout as a best-effort identifier
+                let id = monitor.name().cloned().unwrap_or_else(|| {
+                    format!(
+                        "{}x{}@{},{}",
+                        size.width, size.height, position.x, position.y
+                    )
+                });
+                Self {
+                    id,
+                    x: to_logical(position.x - origin.x),
+                    y: to_logical(position.y - origin.y),
+                    width: to_logical(size.width as i32),
+                    height: to_logical(size.height as i32),
+                }
+            })
+            .collect();
+        Ok(monitors)
+    }
+
+    /// The squared distance from a point to the monitor.
+    ///
+    /// This is zero if the point is within the monitor.
+    fn distance_squared(&self, x: i32, y: i32) -> i64 {
+        let dx = (self.x - x).max(x - (self.x + self.width - 1)).max(0) as i64;
+        let dy = (self.y - y).max(y - (self.y + self.height - 1)).max(0) as i64;
+        dx * dx + dy * dy
+    }
+}
+
+impl MonitorAnchor {
+    /// Anchor a position in canvas coordinates to the nearest monitor.
+    ///
+    /// This returns `None` if there are no monitors.
+    pub fn locate(monitors: &[MonitorBounds], x: i32, y: i32) -> Option<Self> {
+        let monitor = monitors
+            .iter()
+            .min_by_key(|monitor| monitor.distance_squared(x, y))?;
+        Some(Self {
+            monitor: monitor.id.clone(),
+            x: x - monitor.x,
+            y: y - monitor.y,
+        })
+    }
+
+    /// Resolve the anchor to a position in canvas coordinates.
+    ///
+    /// If the anchored monitor is no longer available, the first monitor is
+    /// used instead, keeping the anchor intact so that the widget returns once
+    /// the monitor is back. The position is clamped so that a widget of the
+    /// given size stays within the monitor as much as possible. This returns
+    /// `None` if there are no monitors.
+    pub fn resolve(
+        &self,
+        monitors: &[MonitorBounds],
+        width: u32,
+        height: u32,
+    ) -> Option<(i32, i32)> {
+        let monitor = monitors
+            .iter()
+            .find(|monitor| monitor.id == self.monitor)
+            .or_else(|| monitors.first())?;
+        let max_x = monitor.x + (monitor.width - width as i32).max(0);
+        let max_y = monitor.y + (monitor.height - height as i32).max(0);
+        Some((
+            (monitor.x + self.x).clamp(monitor.x, max_x),
+            (monitor.y + self.y).clamp(monitor.y, max_y),
+        ))
+    }
+}
@@ -0,0 +1,294 @@
+//! Network proxy and metering for Deskulpt widgets.
+//!
+//! Network requests of widgets are intercepted in the canvas by a shim of
+//! `fetch` and `XMLHttpRequest`, and routed through [`NetworkProxy`]. This
+//! enforces the hosts declared in the widget manifest on every redirect hop and
+//! records per-widget bandwidth usage in [`WidgetMetrics`].
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use parking_lot::Mutex;
+use reqwest::header::{AUTHORIZATION, COOKIE, HeaderMap, HeaderName, HeaderValue, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of redirects followed for a request.
+const MAX_REDIRECTS: usize = 10;
+
+/// The maximum size of a response body in bytes.
+const MAX_RESPONSE_SIZE: u64 = 32 * 1024 * 1024;
+
+/// A network request issued by a widget.
+#[derive(Debug, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkRequest {
+    /// The absolute URL of the request.
+    pub url: String,
+    /// The HTTP method of the request.
+    pub method: String,
+    /// The headers of the request.
+    pub headers: Vec<(String, String)>,
+    /// The base64-encoded body of the request, if any.
+    #[specta(optional, type = String)]
+    pub body: Option<String>,
+}
+
+/// The response to a [`NetworkRequest`].
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkResponse {
+    /// The final URL of the response after redirects.
+    pub url: String,
+    /// The HTTP status code.
+    pub status: u16,
+    /// The HTTP status text.
+    pub status_text: String,
+    /// The headers of the response.
+    pub headers: Vec<(String, String)>,
+    /// The base64-encoded body of the response.
+    pub body: String,
+}
+
+/// Network usage metrics of a widget.
+///
+/// Metrics are accumulated since application startup.
+#[derive(Debug, Clone, Default, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetMetrics {
+    /// The number of requests completed.
+    pub requests: u64,
+    /// The number of requests blocked by the host allowlist.
+    pub blocked_requests: u64,
+    /// The number of request body bytes sent.
+    pub bytes_sent: u64,
+    /// The number of response body bytes received.
+    pub bytes_received: u64,
+}
+
+/// Whether a host is allowed by a list of host patterns.
+///
+/// A pattern is either an exact host name, or a wildcard `*.example.com` that
+/// matches `example.com` and all its subdomains.
+fn is_host_allowed(host: &str, patterns: &[String]) -> bool {
+    let host = host.to_ascii_lowercase();
+    patterns.iter().any(|pattern| {
+        let pattern = pattern.to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => {
+                host == domain
+                    || host
+                        .strip_suffix(domain)
+                        .is_some_and(|sub| sub.ends_with('.'))
+            },
+            None => host == pattern,
+        }
+    })
+}
+
+/// The network proxy for widgets.
+pub struct NetworkProxy {
+    /// The HTTP client shared by all widgets.
+    ///
+    /// It does not follow redirects by itself, since the allowed hosts differ
+    /// per widget; see [`NetworkProxy::fetch`].
+    client: Client,
+    /// The network usage metrics keyed by widget ID.
+    metrics: Mutex<BTreeMap<String, WidgetMetrics>>,
+    /// The number of requests in flight keyed by widget ID.
+    in_flight: Mutex<BTreeMap<String, u64>>,
+}
+
+impl Default for NetworkProxy {
+    fn default() -> Self {
+        Self {
+            client: Client::builder()
+                .redirect(Policy::none())
+                .build()
+                .expect("Failed to build the HTTP client for widgets"),
+            metrics: Default::default(),
+            in_flight: Default::default(),
+        }
+    }
+}
+
+/// Guard counting a request as in flight until dropped.
+struct InFlightGuard<'a> {
+    /// The in-flight counts of the proxy.
+    in_flight: &'a Mutex<BTreeMap<String, u64>>,
+    /// The widget ID.
+    id: &'a str,
+}
+
+impl<'a> InFlightGuard<'a> {
+    /// Count a request of a widget as in flight.
+    fn new(in_flight: &'a Mutex<BTreeMap<String, u64>>, id: &'a str) -> Self {
+        *in_flight.lock().entry(id.to_string()).or_default() += 1;
+        Self { in_flight, id }
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock();
+        if let Some(count) = in_flight.get_mut(self.id) {
+            *count -= 1;
+            if *count == 0 {
+                in_flight.remove(self.id);
+            }
+        }
+    }
+}
+
+impl NetworkProxy {
+    /// Check that a widget may send a request to a URL.
+    ///
+    /// Only HTTP(S) URLs with hosts matching the allowed host patterns pass;
+    /// otherwise an error is returned and the request is counted as blocked.
+    fn check_url(&self, id: &str, allowed_hosts: &[String], url: &Url) -> Result<()> {
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("Unsupported URL scheme: {}", url.scheme());
+        }
+        let host = url.host_str().unwrap_or_default();
+        if !is_host_allowed(host, allowed_hosts) {
+            self.metrics
+                .lock()
+                .entry(id.to_string())
+                .or_default()
+                .blocked_requests += 1;
+            tracing::warn!(id, host, "Blocked network request to undeclared host");
+            bail!("Host {host} is not declared in the networkHosts of the widget manifest of {id}");
+        }
+        Ok(())
+    }
+
+    /// Send a network request on behalf of a widget.
+    ///
+    /// The request and every redirect it is sent to must pass the allowed host
+    /// patterns, see [`NetworkProxy::check_url`]; at most [`MAX_REDIRECTS`]
+    /// redirects are followed. Response bodies larger than
+    /// [`MAX_RESPONSE_SIZE`] are rejected. Bandwidth is counted by request and
+    /// response body sizes.
+    pub async fn fetch(
+        &self,
+        id: &str,
+        allowed_hosts: &[String],
+        request: NetworkRequest,
+    ) -> Result<NetworkResponse> {
+        let mut url = Url::parse(&request.url)?;
+        self.check_url(id, allowed_hosts, &url)?;
+
+        let mut method = Method::from_bytes(request.method.as_bytes())?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &request.headers {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(value)?,
+            );
+        }
+        let mut body = match &request.body {
+            Some(body) => BASE64.decode(body)?,
+            None => vec![],
+        };
+        let mut bytes_sent = 0;
+
+        // Counted until the response body is fully read or the request fails
+        let guard = InFlightGuard::new(&self.in_flight, id);
+        let mut redirects = 0;
+        let mut response = loop {
+            bytes_sent += body.len() as u64;
+            let response = self
+                .client
+                .request(method.clone(), url.clone())
+                .headers(headers.clone())
+                .body(body.clone())
+                .send()
+                .await?;
+
+            let status = response.status();
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .map(str::to_string);
+            let Some(location) = location.filter(|_| status.is_redirection()) else {
+                break response;
+            };
+            if redirects == MAX_REDIRECTS {
+                bail!("Too many redirects for network request to {}", request.url);
+            }
+            redirects += 1;
+
+            let next = url.join(&location)?;
+            self.check_url(id, allowed_hosts, &next)?;
+            // Browsers switch to GET without a body for these redirects
+            if status == StatusCode::SEE_OTHER
+                || (matches!(status, StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND)
+                    && method == Method::POST)
+            {
+                method = Method::GET;
+                body.clear();
+            }
+            // Credentials must not leak to other hosts
+            if next.host_str() != url.host_str() {
+                headers.remove(AUTHORIZATION);
+                headers.remove(COOKIE);
+            }
+            url = next;
+        };
+
+        let url = response.url().to_string();
+        let status = response.status();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                )
+            })
+            .collect();
+        if response
+            .content_length()
+            .is_some_and(|len| len > MAX_RESPONSE_SIZE)
+        {
+            bail!("Response body exceeds the limit of {MAX_RESPONSE_SIZE} bytes");
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if body.len() as u64 > MAX_RESPONSE_SIZE {
+                bail!("Response body exceeds the limit of {MAX_RESPONSE_SIZE} bytes");
+            }
+        }
+        drop(guard);
+
+        let mut metrics = self.metrics.lock();
+        let metrics = metrics.entry(id.to_string()).or_default();
+        metrics.requests += 1;
+        metrics.bytes_sent += bytes_sent;
+        metrics.bytes_received += body.len() as u64;
+
+        Ok(NetworkResponse {
+            url,
+            status: status.as_u16(),
+            status_text: status.canonical_reason().unwrap_or_default().to_string(),
+            headers,
+            body: BASE64.encode(&body),
+        })
+    }
+
+    /// Get the network usage metrics of all widgets that have used the network.
+    pub fn metrics(&self) -> BTreeMap<String, WidgetMetrics> {
+        self.metrics.lock().clone()
+    }
+
+    /// Get the number of requests of a widget currently in flight.
+    pub fn in_flight(&self, id: &str) -> u64 {
+        self.in_flight.lock().get(id).copied().unwrap_or_default()
+    }
+}
@@ -13,12 +13,18 @@ use tokio::time::{Duration, Instant, Sleep};
 
 use crate::WidgetsExt;
 use crate::catalog::{WidgetCatalog, WidgetSettings};
+use crate::tombstone::Tombstones;
 
 /// Persisted representation of a widget.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PersistedWidget {
     pub settings: WidgetSettings,
+    /// When the widget went missing, in seconds since the Unix epoch.
+    ///
+    /// This is `None` if the widget was present when persisted.
+    #[serde(default)]
+    pub removed_at: Option<u64>,
 }
 
 /// Persisted representation of the widget catalog.
@@ -44,12 +50,13 @@ impl PersistedWidgetCatalog {
     }
 }
 
-/// A view of the widget catalog for persistence.
+/// A view of the widget catalog and the tombstones for persistence.
 ///
 /// The serialization format will follow the representation of
-/// [`PersistedWidgetCatalog`].
+/// [`PersistedWidgetCatalog`], where tombstones are distinguished by having
+/// [`PersistedWidget::removed_at`] set.
 #[derive(Debug)]
-pub struct PersistedWidgetCatalogView<'a>(pub &'a WidgetCatalog);
+pub struct PersistedWidgetCatalogView<'a>(pub &'a WidgetCatalog, pub &'a Tombstones);
 
 impl<'a> PersistedWidgetCatalogView<'a> {
     /// Persist the widget catalog to disk.
@@ -72,14 +79,32 @@ impl<'a> Serialize for PersistedWidgetCatalogView<'a> {
         #[serde(rename_all = "camelCase")]
         struct PersistedWidgetView<'a> {
             settings: &'a WidgetSettings,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            removed_at: Option<u64>,
         }
 
-        let mut map = serializer.serialize_map(Some(self.0.0.len()))?;
+        let tombstones = self
+            .1
+            .0
+            .iter()
+            .filter(|(k, _)| !self.0.0.contains_key(*k))
+            .collect::<Vec<_>>();
+        let mut map = serializer.serialize_map(Some(self.0.0.len() + tombstones.len()))?;
         for (k, v) in self.0.0.iter() {
             map.serialize_entry(
                 k,
                 &PersistedWidgetView {
                     settings: &v.settings,
+                    removed_at: None,
+                },
+            )?;
+        }
+        for (k, v) in tombstones {
+            map.serialize_entry(
+                k,
+                &PersistedWidgetView {
+                    settings: &v.settings,
+                    removed_at: Some(v.removed_at),
                 },
             )?;
         }
@@ -173,4 +198,9 @@ impl PersistWorkerHandle {
     pub fn notify(&self) -> Result<()> {
         Ok(self.0.send(())?)
     }
+
+    /// Whether the worker is still running.
+    pub fn is_alive(&self) -> bool {
+        !self.0.is_closed()
+    }
 }
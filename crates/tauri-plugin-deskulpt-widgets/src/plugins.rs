@@ -0,0 +1,129 @@
+//! Plugin requirements of Deskulpt widgets.
+//!
+//! Widgets may declare the plugins they require in their manifests, e.g.,
+//! `"requiredPlugins": ["fs", "sys>=0.3"]`. Requirements are checked against
+//! the plugins registered via [`WidgetsManager::register_plugins`], so that
+//! missing plugins show up in the catalog entries of the widgets instead of
+//! failing opaquely at runtime.
+//!
+//! [`WidgetsManager::register_plugins`]: crate::WidgetsManager::register_plugins
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use anyhow::{Result, bail};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Regex for a plugin requirement, i.e., a plugin name optionally followed by
+/// a version constraint.
+static REQUIREMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*([A-Za-z0-9_-]+)\s*(?:(>=|<=|>|<|=)\s*(\d+(?:\.\d+)*)\s*)?$").unwrap()
+});
+
+/// Parse a dotted version into its numeric components.
+///
+/// Pre-release and build metadata suffixes are ignored. This returns `None` if
+/// the version does not start with a numeric component.
+pub(crate) fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let core = version.split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Compare two versions component-wise, padding missing components with zero.
+pub(crate) fn compare_versions(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    let pad = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| pad(a, i).cmp(&pad(b, i)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// A plugin required by a widget.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PluginRequirement {
+    /// The requirement as declared in the widget manifest.
+    raw: String,
+    /// The name of the plugin.
+    name: String,
+    /// The version constraint as a comparison operator and a version.
+    constraint: Option<(String, Vec<u64>)>,
+}
+
+impl TryFrom<String> for PluginRequirement {
+    type Error = anyhow::Error;
+
+    fn try_from(raw: String) -> Result<Self> {
+        let Some(captures) = REQUIREMENT_RE.captures(&raw) else {
+            bail!("Invalid plugin requirement: {raw:?}; expected e.g. \"fs\" or \"sys>=0.3\"");
+        };
+        let name = captures[1].to_string();
+        let constraint = match (captures.get(2), captures.get(3)) {
+            (Some(op), Some(version)) => Some((
+                op.as_str().to_string(),
+                parse_version(version.as_str()).unwrap_or_default(),
+            )),
+            _ => None,
+        };
+        Ok(Self {
+            raw,
+            name,
+            constraint,
+        })
+    }
+}
+
+impl PluginRequirement {
+    /// Whether a version of the plugin satisfies the requirement.
+    ///
+    /// Unparsable versions only satisfy requirements without constraints.
+    fn is_satisfied_by(&self, version: &str) -> bool {
+        let Some((op, required)) = &self.constraint else {
+            return true;
+        };
+        let Some(version) = parse_version(version) else {
+            return false;
+        };
+        let ordering = compare_versions(&version, required);
+        match op.as_str() {
+            ">=" => ordering.is_ge(),
+            "<=" => ordering.is_le(),
+            ">" => ordering.is_gt(),
+            "<" => ordering.is_lt(),
+            _ => ordering.is_eq(),
+        }
+    }
+
+    /// Check the requirement against the available plugins.
+    ///
+    /// This returns `None` if the requirement is satisfied, or the structured
+    /// reason otherwise.
+    pub fn check(&self, available: &BTreeMap<String, String>) -> Option<MissingPlugin> {
+        match available.get(&self.name) {
+            Some(version) if self.is_satisfied_by(version) => None,
+            found => Some(MissingPlugin {
+                name: self.name.clone(),
+                requirement: self.raw.trim().to_string(),
+                found: found.cloned(),
+            }),
+        }
+    }
+}
+
+/// A plugin required by a widget that is unavailable.
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingPlugin {
+    /// The name of the plugin.
+    pub name: String,
+    /// The requirement as declared in the widget manifest, e.g., `sys>=0.3`.
+    pub requirement: String,
+    /// The version of the plugin that is available but does not satisfy the
+    /// requirement.
+    ///
+    /// This is `None` if the plugin is not available at all.
+    pub found: Option<String>,
+}
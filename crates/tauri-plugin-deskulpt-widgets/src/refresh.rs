@@ -0,0 +1,120 @@
+//! Periodic refresh scheduling of Deskulpt widgets.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, RandomState};
+use std::time::Duration;
+
+use anyhow::Result;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Runtime};
+use tokio::sync::mpsc;
+
+use crate::WidgetsExt;
+
+/// Tasks that the refresh scheduler can process.
+#[derive(Debug)]
+pub enum RefreshSchedulerTask {
+    /// Synchronize the refresh schedule.
+    ///
+    /// This maps widget IDs to their refresh intervals. Widgets no longer in
+    /// the map will stop being refreshed, new widgets will start being
+    /// refreshed, and widgets whose intervals changed will be rescheduled.
+    Sync(BTreeMap<String, Duration>),
+}
+
+/// Compute a random jitter within 10% of the given interval.
+///
+/// This is used to spread out refreshes of widgets with the same interval so
+/// that they do not all re-bundle at the same moment.
+fn jitter(interval: Duration) -> Duration {
+    let max_millis = (interval.as_millis() / 10) as u64;
+    if max_millis == 0 {
+        return Duration::ZERO;
+    }
+    let random = RandomState::new().hash_one(interval);
+    Duration::from_millis(random % max_millis)
+}
+
+/// Spawn a timer that periodically renders a widget.
+fn spawn_timer<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(interval + jitter(interval)).await;
+            tracing::debug!(%id, "Periodically refreshing widget");
+            if let Err(e) = app_handle.widgets().render(&id) {
+                tracing::error!(error = ?e, %id, "Failed to periodically refresh widget");
+            }
+        }
+    })
+}
+
+/// The main refresh scheduler loop.
+async fn refresh_scheduler<R: Runtime>(
+    app_handle: AppHandle<R>,
+    mut rx: mpsc::UnboundedReceiver<RefreshSchedulerTask>,
+) {
+    let mut timers: HashMap<String, (Duration, JoinHandle<()>)> = HashMap::new();
+
+    while let Some(task) = rx.recv().await {
+        match task {
+            RefreshSchedulerTask::Sync(intervals) => {
+                timers.retain(|id, (interval, handle)| {
+                    let keep = intervals.get(id) == Some(interval);
+                    if !keep {
+                        handle.abort();
+                    }
+                    keep
+                });
+
+                for (id, interval) in intervals {
+                    if timers.contains_key(&id) {
+                        continue;
+                    }
+                    let handle = spawn_timer(app_handle.clone(), id.clone(), interval);
+                    timers.insert(id, (interval, handle));
+                }
+            },
+        }
+    }
+
+    for (_, (_, handle)) in timers {
+        handle.abort();
+    }
+}
+
+/// Handle for communicating with the refresh scheduler.
+pub struct RefreshSchedulerHandle(mpsc::UnboundedSender<RefreshSchedulerTask>);
+
+impl RefreshSchedulerHandle {
+    /// Create a new [`RefreshSchedulerHandle`] instance.
+    ///
+    /// This immediately spawns a dedicated refresh scheduler on Tauri's
+    /// singleton async runtime that listens for incoming
+    /// [`RefreshSchedulerTask`]s and maintains one timer per periodically
+    /// refreshed widget.
+    pub fn new<R: Runtime>(app_handle: AppHandle<R>) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tauri::async_runtime::spawn(async move {
+            refresh_scheduler(app_handle, rx).await;
+        });
+        Self(tx)
+    }
+
+    /// Instruct the refresh scheduler to process a task.
+    ///
+    /// This does not block. The task is sent to the refresh scheduler for
+    /// asynchronous processing and does not wait for completion. An error is
+    /// returned if task submission fails, but not if task processing fails.
+    pub fn process(&self, task: RefreshSchedulerTask) -> Result<()> {
+        Ok(self.0.send(task)?)
+    }
+
+    /// Whether the refresh scheduler is still running.
+    pub fn is_alive(&self) -> bool {
+        !self.0.is_closed()
+    }
+}
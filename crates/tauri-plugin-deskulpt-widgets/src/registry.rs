@@ -1,7 +1,24 @@
 //! Deskulpt widgets registry.
 
 mod index;
+mod lock;
+mod network;
+mod publish;
+mod report;
+mod signature;
+mod source;
 mod widget;
 
-pub use index::{RegistryIndex, RegistryIndexFetcher};
-pub use widget::{RegistryWidgetFetcher, RegistryWidgetPreview, RegistryWidgetReference};
+pub use index::{
+    RegistryEntryRelease, RegistryIndex, RegistryIndexFetcher, RegistrySearchPage, RegistrySort,
+    RevokedPackage,
+};
+pub use lock::{RegistryLock, RevokedWidget, WidgetUpgrade};
+pub use network::RegistryNetwork;
+pub use publish::WidgetPackage;
+pub use report::{ReportQueue, WidgetReport};
+pub use signature::SignatureStatus;
+pub use source::{Registry, RegistryCredentials};
+pub use widget::{
+    InstallProgress, RegistryWidgetFetcher, RegistryWidgetPreview, RegistryWidgetReference,
+};
@@ -1,18 +1,28 @@
 //! Utilities for interacting with the widgets registry index.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
+use deskulpt_common::fuzzy;
 use reqwest::header::{ETAG, IF_NONE_MATCH};
-use reqwest::{Client, Response, StatusCode};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, Url};
 use serde::{Deserialize, Serialize};
 
 use crate::catalog::WidgetManifestAuthor;
+use crate::registry::{Registry, RegistryWidgetReference};
+
+/// The timeout for checking whether the registry index is reachable.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The number of widgets per page of search results.
+const SEARCH_PAGE_SIZE: usize = 20;
 
 /// An entry for a specific release of a widget in the registry.
-#[derive(Debug, Serialize, Deserialize, specta::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
-struct RegistryEntryRelease {
+pub struct RegistryEntryRelease {
     /// The version string of the release.
     version: String,
     /// The publication datetime of the release, in ISO 8601 format.
@@ -25,23 +35,192 @@ struct RegistryEntryRelease {
 }
 
 /// An entry for a widget in the registry.
-#[derive(Debug, Serialize, Deserialize, specta::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
-struct RegistryEntry {
+pub struct RegistryEntry {
     /// The publisher handle.
-    handle: String,
+    pub handle: String,
     /// The widget ID.
     ///
     /// Note that this ID is unique only within the publisher's namespace.
-    id: String,
+    pub id: String,
     /// The name of the widget.
-    name: String,
+    pub name: String,
     /// The authors of the widget.
     authors: Vec<WidgetManifestAuthor>,
     /// A short description of the widget.
-    description: String,
+    pub description: String,
     /// The releases of the widget, ordered from newest to oldest.
     releases: Vec<RegistryEntryRelease>,
+    /// The tags of the widget.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The categories of the widget.
+    ///
+    /// See [`crate::catalog::WidgetManifest::categories`].
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// The keywords of the widget.
+    ///
+    /// See [`crate::catalog::WidgetManifest::keywords`].
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// The number of downloads of the widget across all releases.
+    #[serde(default)]
+    pub downloads: u64,
+    /// The ID of the registry the widget comes from.
+    ///
+    /// See [`Registry::id`] for details. This is not part of the registry
+    /// index but filled in when the index is fetched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = String)]
+    pub registry: Option<String>,
+}
+
+impl RegistryEntry {
+    /// Score how well the widget matches the query text.
+    ///
+    /// Matches in the name weigh the most, followed by the description, the
+    /// keywords, and the authors. This returns `None` if nothing matches.
+    fn score(&self, query: &str) -> Option<f64> {
+        let name = fuzzy::score(query, &self.name);
+        let description = fuzzy::score(query, &self.description).map(|score| score * 0.8);
+        let keywords = self
+            .keywords
+            .iter()
+            .filter_map(|keyword| fuzzy::score(query, keyword).map(|score| score * 0.7));
+        let authors = self.authors.iter().filter_map(|author| {
+            let name = match author {
+                WidgetManifestAuthor::Extended { name, .. } => name,
+                WidgetManifestAuthor::Name(name) => name,
+            };
+            fuzzy::score(query, name).map(|score| score * 0.6)
+        });
+        name.into_iter()
+            .chain(description)
+            .chain(keywords)
+            .chain(authors)
+            .max_by(f64::total_cmp)
+    }
+
+    /// Whether the widget has all the given tags, case-insensitively.
+    fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter()
+            .all(|tag| self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Whether the widget is in the given category, case-insensitively.
+    ///
+    /// All widgets are in the category `None`.
+    fn in_category(&self, category: Option<&str>) -> bool {
+        category.is_none_or(|category| {
+            self.categories
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(category))
+        })
+    }
+
+    /// The publication datetime of the latest release, if any.
+    fn published_at(&self) -> Option<&str> {
+        self.releases
+            .first()
+            .map(|release| release.published_at.as_str())
+    }
+
+    /// Get the releases of the widget, ordered from newest to oldest.
+    pub fn releases(&self) -> &[RegistryEntryRelease] {
+        &self.releases
+    }
+
+    /// Get the reference to the release with the given version, if any.
+    pub fn release(&self, version: &str) -> Option<RegistryWidgetReference> {
+        self.releases
+            .iter()
+            .find(|release| release.version == version)
+            .map(|release| RegistryWidgetReference {
+                handle: self.handle.clone(),
+                id: self.id.clone(),
+                digest: release.digest.clone(),
+                registry: self.registry.clone(),
+            })
+    }
+
+    /// Get the version of the release with the given digest, if any.
+    pub fn version_of(&self, digest: &str) -> Option<&str> {
+        self.releases
+            .iter()
+            .find(|release| release.digest == digest)
+            .map(|release| release.version.as_str())
+    }
+
+    /// Get the reference to and the version of the latest release, if any.
+    pub fn latest(&self) -> Option<(RegistryWidgetReference, &str)> {
+        self.releases.first().map(|release| {
+            let reference = RegistryWidgetReference {
+                handle: self.handle.clone(),
+                id: self.id.clone(),
+                digest: release.digest.clone(),
+                registry: self.registry.clone(),
+            };
+            (reference, release.version.as_str())
+        })
+    }
+}
+
+/// The order of registry search results.
+#[derive(Debug, Default, Clone, Copy, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RegistrySort {
+    /// Best matches first; widgets keep their order in the index if there is
+    /// no query text.
+    #[default]
+    Relevance,
+    /// Most recently released first.
+    Recent,
+    /// Most downloaded first.
+    Popular,
+}
+
+/// A page of registry search results.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySearchPage {
+    /// The widgets on this page.
+    pub widgets: Vec<RegistryEntry>,
+    /// The zero-based index of this page.
+    pub page: u32,
+    /// The total number of pages.
+    pub page_count: u32,
+    /// The total number of matching widgets across all pages.
+    pub total: u32,
+}
+
+/// A widget package revoked by the registry, e.g., for being malicious.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokedPackage {
+    /// The SHA-256 digest of the revoked widget package.
+    pub digest: String,
+    /// The reason for the revocation.
+    pub reason: String,
+}
+
+/// A delta between two generations of a registry index.
+///
+/// The delta endpoint advertised by a registry index is requested with the
+/// generation of the cached index as the `since` query parameter, and responds
+/// with a chain of deltas leading to the latest generation. A registry that
+/// can no longer serve a chain from that generation, e.g., because old deltas
+/// have been pruned, responds with an error instead, in which case the full
+/// index is fetched.
+#[derive(Debug, Deserialize)]
+struct RegistryIndexDelta {
+    /// The generation the delta applies to.
+    from: u64,
+    /// The generation the delta leads to.
+    to: u64,
+    /// The JSON patch to apply to the index.
+    patch: json_patch::Patch,
 }
 
 /// The widgets registry index.
@@ -52,14 +231,164 @@ pub struct RegistryIndex {
     api: i32,
     /// The datetime when the index was generated, in ISO 8601 format.
     generated_at: String,
+    /// The generation of the index, if the registry supports delta updates.
+    ///
+    /// This increases every time the index changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = u64)]
+    generation: Option<u64>,
+    /// The URL of the delta endpoint, if the registry supports delta updates.
+    ///
+    /// See [`RegistryIndexDelta`] for details.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = String)]
+    delta_url: Option<String>,
     /// The list of widgets in the registry.
     widgets: Vec<RegistryEntry>,
+    /// The list of revoked widget packages.
+    ///
+    /// Revoked packages are refused on installation, and flagged if already
+    /// installed.
+    #[serde(default)]
+    revoked: Vec<RevokedPackage>,
+}
+
+impl RegistryIndex {
+    /// Merge the indices of multiple registries into one.
+    ///
+    /// The metadata of the first index is kept. If the same widget, identified
+    /// by its publisher handle and ID, is in multiple indices, the entry from
+    /// the earliest index is kept. Packages revoked by any of the indices are
+    /// revoked in the merged index. This returns `None` if there are no
+    /// indices.
+    pub fn merge(indices: impl IntoIterator<Item = RegistryIndex>) -> Option<Self> {
+        let mut indices = indices.into_iter();
+        let mut merged = indices.next()?;
+        for index in indices {
+            for entry in index.widgets {
+                if merged.find(&entry.handle, &entry.id).is_none() {
+                    merged.widgets.push(entry);
+                }
+            }
+            for revoked in index.revoked {
+                if merged.revocation(&revoked.digest).is_none() {
+                    merged.revoked.push(revoked);
+                }
+            }
+        }
+        Some(merged)
+    }
+
+    /// Find the revocation of a widget package by its digest.
+    pub fn revocation(&self, digest: &str) -> Option<&RevokedPackage> {
+        self.revoked
+            .iter()
+            .find(|revoked| revoked.digest.eq_ignore_ascii_case(digest))
+    }
+
+    /// Mark all widgets in the index as coming from the given registry.
+    fn tag_registry(mut self, registry: &Registry) -> Self {
+        for entry in &mut self.widgets {
+            entry.registry = registry.id.clone();
+        }
+        self
+    }
+
+    /// Get the list of widgets in the registry.
+    pub fn widgets(&self) -> &[RegistryEntry] {
+        &self.widgets
+    }
+
+    /// Find the entry of a widget by its publisher handle and ID.
+    pub fn find(&self, handle: &str, id: &str) -> Option<&RegistryEntry> {
+        self.widgets
+            .iter()
+            .find(|entry| entry.handle == handle && entry.id == id)
+    }
+
+    /// Count the widgets in each category of the registry.
+    ///
+    /// Categories are grouped case-insensitively and keyed in lowercase.
+    pub fn categories(&self) -> BTreeMap<String, u32> {
+        let mut categories = BTreeMap::new();
+        for entry in &self.widgets {
+            let mut seen = BTreeSet::new();
+            for category in &entry.categories {
+                let category = category.to_lowercase();
+                if seen.insert(category.clone()) {
+                    *categories.entry(category).or_default() += 1;
+                }
+            }
+        }
+        categories
+    }
+
+    /// Search the widgets in the registry.
+    ///
+    /// Widgets are fuzzy-matched against the query text by their names,
+    /// descriptions, keywords, and authors, and only those in the given
+    /// category, if any, and with all the given tags are kept. An empty query
+    /// text matches all widgets. The matches are sorted
+    /// as specified and split into pages of 20 widgets, of which the page at
+    /// the given zero-based index is returned. A page out of range is empty.
+    pub fn search(
+        self,
+        query: &str,
+        category: Option<&str>,
+        tags: &[String],
+        sort: RegistrySort,
+        page: u32,
+    ) -> RegistrySearchPage {
+        let query = query.trim();
+        let mut matches = self
+            .widgets
+            .into_iter()
+            .filter(|entry| entry.in_category(category) && entry.has_tags(tags))
+            .filter_map(|entry| {
+                if query.is_empty() {
+                    return Some((0.0, entry));
+                }
+                entry.score(query).map(|score| (score, entry))
+            })
+            .collect::<Vec<_>>();
+
+        // Sorting is stable, so ties keep their order in the index
+        match sort {
+            RegistrySort::Relevance => matches.sort_by(|a, b| b.0.total_cmp(&a.0)),
+            RegistrySort::Recent => {
+                matches.sort_by(|a, b| b.1.published_at().cmp(&a.1.published_at()))
+            },
+            RegistrySort::Popular => {
+                matches.sort_by(|a, b| b.1.downloads.cmp(&a.1.downloads).then(b.0.total_cmp(&a.0)))
+            },
+        }
+
+        let total = matches.len();
+        let widgets = matches
+            .into_iter()
+            .skip(page as usize * SEARCH_PAGE_SIZE)
+            .take(SEARCH_PAGE_SIZE)
+            .map(|(_, entry)| entry)
+            .collect();
+
+        RegistrySearchPage {
+            widgets,
+            page,
+            page_count: total.div_ceil(SEARCH_PAGE_SIZE) as u32,
+            total: total as u32,
+        }
+    }
 }
 
-/// A fetcher for the widgets registry index.
+/// A fetcher for the index of a widgets registry.
+///
+/// Requests go to the primary endpoint of the registry first, falling back to
+/// its mirrors in order if it is unreachable or fails with a server error.
 pub struct RegistryIndexFetcher {
     /// The HTTP client.
     client: Client,
+    /// The registry to fetch the index of.
+    registry: Registry,
     /// The cache directory.
     cache_dir: PathBuf,
     /// The path to the cached index file.
@@ -69,29 +398,49 @@ pub struct RegistryIndexFetcher {
 }
 
 impl RegistryIndexFetcher {
-    /// The static URL of the widgets registry index.
-    const URL: &str = "https://cdn.jsdelivr.net/gh/deskulpt-apps/widgets@registry/index.json";
-
     /// Create a new [`RegistryIndexFetcher`] instance.
     ///
-    /// This will automatically assign cache paths within the given cache
-    /// directory. A new HTTP client will be created to perform requests.
-    pub fn new(cache_dir: &Path) -> Self {
-        Self {
-            client: Client::new(),
+    /// This will automatically assign cache paths for the registry within the
+    /// given cache directory. A new HTTP client will be created to perform
+    /// requests, as configured by the network configuration of the registry.
+    pub fn new(cache_dir: &Path, registry: Registry) -> Result<Self> {
+        let stem = match registry.cache_key() {
+            Some(key) => format!("widgets-registry-index-{key}"),
+            None => "widgets-registry-index".to_string(),
+        };
+        Ok(Self {
+            client: registry.network.http_client()?,
+            registry,
             cache_dir: cache_dir.to_path_buf(),
-            cache_path: cache_dir.join("widgets-registry-index.json"),
-            etag_path: cache_dir.join("widgets-registry-index.etag"),
+            cache_path: cache_dir.join(format!("{stem}.json")),
+            etag_path: cache_dir.join(format!("{stem}.etag")),
+        })
+    }
+
+    /// Build a request to an index URL, with credentials if any.
+    fn request(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.registry.credentials {
+            Some(credentials) => {
+                request.basic_auth(&credentials.username, Some(&credentials.password))
+            },
+            None => request,
         }
     }
 
-    /// Fetch the widgets registry index.
+    /// Fetch the registry index.
     ///
-    /// This will use a cached etag to perform a conditional GET request. If the
-    /// registry index has not changed since the last fetch, the cached version
-    /// will be used if available and valid. Otherwise, a fresh copy will be
-    /// fetched and cached.
-    #[tracing::instrument(skip_all, level = "debug")]
+    /// If the cached index advertises a delta endpoint, it is updated with the
+    /// deltas since its generation, see [`RegistryIndexDelta`]. If that is
+    /// not possible, e.g., because the delta chain is broken, the full index
+    /// is fetched as follows.
+    ///
+    /// This will use a cached etag to perform a conditional GET request to the
+    /// primary endpoint. If the registry index has not changed since the last
+    /// fetch, the cached version will be used if available and valid.
+    /// Otherwise, a fresh copy will be fetched and cached. If the primary
+    /// endpoint is unreachable or fails with a server error, the mirrors are
+    /// tried in order without the etag.
+    #[tracing::instrument(skip_all, level = "debug", fields(registry = self.registry.name()))]
     pub async fn fetch(&self) -> Result<RegistryIndex> {
         tokio::fs::create_dir_all(&self.cache_dir)
             .await
@@ -106,33 +455,177 @@ impl RegistryIndexFetcher {
             None
         });
 
-        let mut request = self.client.get(Self::URL);
-        if let Some(etag) = cached_etag {
-            tracing::debug!(%etag, "Using cached etag");
-            request = request.header(IF_NONE_MATCH, etag);
+        match self.fetch_delta().await {
+            Ok(Some(index)) => return Ok(index.tag_registry(&self.registry)),
+            Ok(None) => {},
+            Err(e) => tracing::warn!(
+                error = ?e,
+                "Failed to update registry index with deltas; falling back to full fetch",
+            ),
+        }
+
+        let mut last_error = None;
+        for (i, endpoint) in self.registry.endpoints.iter().enumerate() {
+            let is_primary = i == 0;
+            let mut request = self.request(self.client.get(&endpoint.index_url));
+            if is_primary && let Some(etag) = &cached_etag {
+                tracing::debug!(%etag, "Using cached etag");
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+
+            let response = match request.send().await {
+                Ok(response) if !response.status().is_server_error() => response,
+                Ok(response) => {
+                    let status = response.status();
+                    tracing::warn!(url = endpoint.index_url, %status, "Registry index endpoint failed");
+                    last_error = Some(anyhow!("HTTP request failed with status code {status}"));
+                    continue;
+                },
+                Err(e) => {
+                    tracing::warn!(url = endpoint.index_url, error = ?e, "Registry index endpoint unreachable");
+                    last_error = Some(anyhow::Error::new(e).context("Failed to send HTTP request"));
+                    continue;
+                },
+            };
+
+            let index = match response.status() {
+                StatusCode::OK => self.handle_ok(response, is_primary).await?,
+                StatusCode::NOT_MODIFIED if is_primary => {
+                    self.handle_not_modified(&endpoint.index_url).await?
+                },
+                status => bail!("HTTP request failed with status code {status}"),
+            };
+            return Ok(index.tag_registry(&self.registry));
         }
 
-        let response = request
-            .send()
-            .await
-            .context("Failed to send HTTP request")?;
+        Err(last_error.unwrap_or_else(|| anyhow!("No endpoints for registry")))
+    }
 
-        match response.status() {
-            StatusCode::OK => self.handle_ok(response).await,
-            StatusCode::NOT_MODIFIED => self.handle_not_modified().await,
-            status => {
-                bail!("HTTP request failed with status code {status}");
-            },
+    /// Check whether the registry index is reachable.
+    ///
+    /// This sends a `HEAD` request to each endpoint in order so that the index
+    /// is not downloaded, succeeding as soon as one request succeeds. An error
+    /// is returned if none of the endpoints is reachable.
+    pub async fn ping(&self) -> Result<()> {
+        let mut last_error = None;
+        for endpoint in &self.registry.endpoints {
+            let result = self
+                .request(self.client.head(&endpoint.index_url))
+                .timeout(PING_TIMEOUT)
+                .send()
+                .await;
+            match result {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    last_error = Some(anyhow!(
+                        "HTTP request failed with status code {}",
+                        response.status()
+                    ));
+                },
+                Err(e) => {
+                    last_error = Some(anyhow::Error::new(e).context("Failed to send HTTP request"));
+                },
+            }
         }
+        Err(last_error.unwrap_or_else(|| anyhow!("No endpoints for registry")))
     }
 
     /// Read the cached registry index from disk.
-    async fn read_cache(&self) -> Result<RegistryIndex> {
+    pub async fn read_cache(&self) -> Result<RegistryIndex> {
         let cache = tokio::fs::read(&self.cache_path)
             .await
             .context("Failed to read cache")?;
-        let index = serde_json::from_slice(&cache).context("Failed to deserialize cache")?;
-        Ok(index)
+        let index: RegistryIndex =
+            serde_json::from_slice(&cache).context("Failed to deserialize cache")?;
+        Ok(index.tag_registry(&self.registry))
+    }
+
+    /// Update the cached registry index with deltas.
+    ///
+    /// This returns `Ok(None)` if there is no cached index or it does not
+    /// support delta updates, so that the full index should be fetched. An
+    /// error is returned if the deltas cannot be fetched or do not form a
+    /// chain from the cached generation to a valid index. On success, the
+    /// updated index is cached, and the cached etag is removed since it no
+    /// longer matches the cache.
+    async fn fetch_delta(&self) -> Result<Option<RegistryIndex>> {
+        let Ok(cache) = tokio::fs::read(&self.cache_path).await else {
+            return Ok(None);
+        };
+        let mut value: serde_json::Value =
+            serde_json::from_slice(&cache).context("Failed to deserialize cache")?;
+        let cached: RegistryIndex =
+            serde_json::from_value(value.clone()).context("Failed to deserialize cache")?;
+        let (Some(generation), Some(delta_url)) = (cached.generation, cached.delta_url) else {
+            return Ok(None);
+        };
+
+        let mut url = Url::parse(&delta_url)
+            .with_context(|| format!("Invalid delta endpoint URL: {delta_url}"))?;
+        url.query_pairs_mut()
+            .append_pair("since", &generation.to_string());
+        let response = self
+            .request(self.client.get(url))
+            .send()
+            .await
+            .context("Failed to send HTTP request")?;
+        let status = response.status();
+        if status != StatusCode::OK {
+            bail!("HTTP request failed with status code {status}");
+        }
+        let deltas: Vec<RegistryIndexDelta> = response
+            .json()
+            .await
+            .context("Failed to deserialize response body")?;
+        if deltas.is_empty() {
+            tracing::debug!(generation, "Widgets registry index up to date; using cache");
+            return Ok(Some(cached));
+        }
+
+        let mut current = generation;
+        for delta in &deltas {
+            if delta.from != current {
+                bail!(
+                    "Delta chain broken: expected delta from generation {current}, got {}",
+                    delta.from
+                );
+            }
+            json_patch::patch(&mut value, &delta.patch)
+                .with_context(|| format!("Failed to apply delta to generation {current}"))?;
+            current = delta.to;
+        }
+
+        let body = serde_json::to_vec(&value)?;
+        let index: RegistryIndex =
+            serde_json::from_value(value).context("Failed to deserialize patched index")?;
+        if index.generation != Some(current) {
+            bail!("Patched index does not match generation {current}");
+        }
+        tracing::debug!(
+            from = generation,
+            to = current,
+            "Applied registry index deltas"
+        );
+
+        match tokio::fs::write(&self.cache_path, &body).await {
+            Ok(_) => tracing::debug!(path = %self.cache_path.display(), "Cached registry index"),
+            Err(e) => tracing::warn!(
+                error = ?e,
+                path = %self.cache_path.display(),
+                "Failed to cache registry index",
+            ),
+        }
+        if let Err(e) = tokio::fs::remove_file(&self.etag_path).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!(
+                error = ?e,
+                path = %self.etag_path.display(),
+                "Failed to remove stale etag",
+            );
+        }
+
+        Ok(Some(index))
     }
 
     /// Read the cached etag from disk.
@@ -149,15 +642,18 @@ impl RegistryIndexFetcher {
 
     /// Handle a 200 OK response.
     ///
-    /// This will read the response body, deserialize it, and cache both the
-    /// body and the etag (if present) to disk. Failure to cache will not be
-    /// treated as an error.
-    async fn handle_ok(&self, response: Response) -> Result<RegistryIndex> {
+    /// This will read the response body, deserialize it, and cache it to disk.
+    /// The etag (if present) is cached only for responses from the primary
+    /// endpoint, since mirrors may tag the same index differently; otherwise
+    /// the cached etag is removed. Failure to cache will not be treated as an
+    /// error.
+    async fn handle_ok(&self, response: Response, is_primary: bool) -> Result<RegistryIndex> {
         let etag = response
             .headers()
             .get(ETAG)
             .and_then(|v| v.to_str().ok())
-            .map(|v| v.to_string());
+            .map(|v| v.to_string())
+            .filter(|_| is_primary);
 
         let body = response
             .bytes()
@@ -174,25 +670,36 @@ impl RegistryIndexFetcher {
             ),
         }
 
-        if let Some(etag) = etag {
-            match tokio::fs::write(&self.etag_path, &etag).await {
+        match etag {
+            Some(etag) => match tokio::fs::write(&self.etag_path, &etag).await {
                 Ok(_) => tracing::debug!(path = %self.etag_path.display(), "Cached etag"),
                 Err(e) => tracing::warn!(
                     error = ?e,
                     path = %self.etag_path.display(),
                     "Failed to cache etag",
                 ),
-            }
+            },
+            None => {
+                if let Err(e) = tokio::fs::remove_file(&self.etag_path).await
+                    && e.kind() != std::io::ErrorKind::NotFound
+                {
+                    tracing::warn!(
+                        error = ?e,
+                        path = %self.etag_path.display(),
+                        "Failed to remove stale etag",
+                    );
+                }
+            },
         }
 
         Ok(index)
     }
 
-    /// Handle a 304 Not Modified response.
+    /// Handle a 304 Not Modified response from the given URL.
     ///
     /// This will attempt to read the cached index from disk. If that fails, it
-    /// will fall back to performing a fresh fetch.
-    async fn handle_not_modified(&self) -> Result<RegistryIndex> {
+    /// will fall back to performing a fresh fetch from the same URL.
+    async fn handle_not_modified(&self, url: &str) -> Result<RegistryIndex> {
         match self.read_cache().await {
             Ok(index) => {
                 tracing::debug!("Widgets registry index not modified; using cache");
@@ -206,14 +713,13 @@ impl RegistryIndexFetcher {
         }
 
         let response = self
-            .client
-            .get(Self::URL)
+            .request(self.client.get(url))
             .send()
             .await
             .context("Failed to send HTTP request")?;
 
         match response.status() {
-            StatusCode::OK => self.handle_ok(response).await,
+            StatusCode::OK => self.handle_ok(response, true).await,
             status => bail!("Fetching failed with status code {status}"),
         }
     }
@@ -0,0 +1,209 @@
+//! Lockfile of widgets installed from the registry.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::registry::{RegistryIndex, RegistryWidgetReference};
+
+/// A widget installed from the registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedWidget {
+    /// The reference to the installed widget package.
+    pub reference: RegistryWidgetReference,
+    /// The version of the installed release, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The datetime when the release was installed, in RFC 3339 format.
+    ///
+    /// This is unknown for widgets recorded by older versions of Deskulpt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed_at: Option<String>,
+    /// The previously installed release, if its package is kept for rolling
+    /// back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<Box<LockedWidget>>,
+}
+
+/// An available upgrade of an installed widget.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetUpgrade {
+    /// The local ID of the widget.
+    pub id: String,
+    /// The version of the installed release, if known.
+    pub current_version: Option<String>,
+    /// The version of the latest release.
+    pub latest_version: String,
+    /// The reference to the latest release, to be passed to the upgrade.
+    pub latest: RegistryWidgetReference,
+}
+
+/// An installed widget whose package has been revoked by the registry.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokedWidget {
+    /// The local ID of the widget.
+    pub id: String,
+    /// The digest of the installed widget package.
+    pub digest: String,
+    /// The reason for the revocation.
+    pub reason: String,
+}
+
+/// The lockfile of widgets installed from the registry.
+///
+/// It records which package each installed widget comes from, keyed by the
+/// local widget ID, so that registry widgets can be told apart from local
+/// ones, updates can be checked against the registry index, and the set of
+/// installed widgets can be restored on another machine. Widgets installed
+/// before the lockfile existed are not tracked.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RegistryLock(pub BTreeMap<String, LockedWidget>);
+
+impl RegistryLock {
+    /// The name of the lockfile in the app local data directory.
+    pub const FILE_NAME: &str = "widgets.lock.json";
+
+    /// The name of the lockfile in the widgets directory used by older
+    /// versions of Deskulpt.
+    pub const LEGACY_FILE_NAME: &str = "registry-lock.json";
+
+    /// Load the lockfile.
+    ///
+    /// If the lockfile does not exist, an empty lockfile is returned.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let lock = serde_json::from_reader(reader)?;
+        Ok(lock)
+    }
+
+    /// Move a legacy lockfile into place.
+    ///
+    /// Nothing is done if the legacy lockfile does not exist, or if the
+    /// lockfile already exists. The legacy lockfile is copied rather than
+    /// renamed since the two may be on different file systems.
+    pub fn migrate(legacy_path: &Path, path: &Path) -> Result<()> {
+        if !legacy_path.exists() || path.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(legacy_path, path)?;
+        std::fs::remove_file(legacy_path)?;
+        Ok(())
+    }
+
+    /// Dump the lockfile.
+    pub fn dump(&self, path: &Path) -> Result<()> {
+        let file = File::create(path)?;
+        let writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Record a widget installed from the registry.
+    ///
+    /// The version is resolved from the index if given, and the install time is
+    /// set to now. If a different release of the widget was recorded, it is
+    /// kept as the previous release.
+    pub fn record(&mut self, reference: &RegistryWidgetReference, index: Option<&RegistryIndex>) {
+        let id = reference.local_id();
+        let version = index
+            .and_then(|index| index.find(&reference.handle, &reference.id))
+            .and_then(|entry| entry.version_of(&reference.digest))
+            .map(|version| version.to_string());
+        let previous = self
+            .0
+            .remove(&id)
+            .filter(|locked| locked.reference.digest != reference.digest)
+            .map(|mut locked| {
+                locked.previous = None;
+                Box::new(locked)
+            });
+        let locked = LockedWidget {
+            reference: reference.clone(),
+            version,
+            installed_at: OffsetDateTime::now_utc().format(&Rfc3339).ok(),
+            previous,
+        };
+        self.0.insert(id, locked);
+    }
+
+    /// Record a widget rolled back to its previous release.
+    ///
+    /// The current and previous releases are swapped, so that rolling back
+    /// again restores the release rolled back from. If the previous release is
+    /// unknown, the widget is no longer tracked.
+    pub fn rollback(&mut self, id: &str) {
+        let Some(mut current) = self.0.remove(id) else {
+            return;
+        };
+        if let Some(mut previous) = current.previous.take() {
+            previous.previous = Some(Box::new(current));
+            self.0.insert(id.to_string(), *previous);
+        }
+    }
+
+    /// Get the package a widget was installed from, if it is tracked.
+    pub fn source(&self, id: &str) -> Option<RegistryWidgetReference> {
+        self.0.get(id).map(|locked| locked.reference.clone())
+    }
+
+    /// Compare the installed widgets against the registry index.
+    ///
+    /// An upgrade is available if the latest release of a widget in the index
+    /// has a different digest than the installed one and is not revoked.
+    /// Widgets that are no longer in the index are skipped.
+    pub fn upgrades(&self, index: &RegistryIndex) -> Vec<WidgetUpgrade> {
+        self.0
+            .iter()
+            .filter_map(|(id, locked)| {
+                let entry = index.find(&locked.reference.handle, &locked.reference.id)?;
+                let (latest, latest_version) = entry.latest()?;
+                if latest.digest == locked.reference.digest
+                    || index.revocation(&latest.digest).is_some()
+                {
+                    return None;
+                }
+                Some(WidgetUpgrade {
+                    id: id.clone(),
+                    current_version: locked.version.clone().or_else(|| {
+                        entry
+                            .version_of(&locked.reference.digest)
+                            .map(|version| version.to_string())
+                    }),
+                    latest_version: latest_version.to_string(),
+                    latest,
+                })
+            })
+            .collect()
+    }
+
+    /// Find the installed widgets whose packages are revoked in the index.
+    pub fn revoked(&self, index: &RegistryIndex) -> Vec<RevokedWidget> {
+        self.0
+            .iter()
+            .filter_map(|(id, locked)| {
+                let revoked = index.revocation(&locked.reference.digest)?;
+                Some(RevokedWidget {
+                    id: id.clone(),
+                    digest: locked.reference.digest.clone(),
+                    reason: revoked.reason.clone(),
+                })
+            })
+            .collect()
+    }
+}
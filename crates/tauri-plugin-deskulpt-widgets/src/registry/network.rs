@@ -0,0 +1,99 @@
+//! Network configuration for registry traffic.
+
+use anyhow::{Context, Result, bail};
+use oci_client::client::{Certificate as OciCertificate, CertificateEncoding, ClientConfig};
+use reqwest::{Certificate, Client, Proxy};
+
+/// The header of a PEM-encoded certificate.
+const PEM_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+
+/// The footer of a PEM-encoded certificate.
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+/// Network configuration for registry traffic.
+///
+/// This applies to both the requests for registry indices and the pulls of
+/// widget packages.
+#[derive(Debug, Clone, Default)]
+pub struct RegistryNetwork {
+    /// The proxy URL to use instead of the system proxy, if any.
+    pub proxy: Option<String>,
+    /// Additional PEM-encoded CA certificates to trust.
+    pub ca_certificates: Vec<String>,
+}
+
+impl RegistryNetwork {
+    /// Create a new [`RegistryNetwork`] instance.
+    ///
+    /// The CA bundle, if given, is the content of a PEM file that is split
+    /// into individual certificates. An error is returned if it contains no
+    /// certificate at all.
+    pub fn new(proxy: Option<String>, ca_bundle: Option<&str>) -> Result<Self> {
+        let ca_certificates = match ca_bundle {
+            Some(bundle) => {
+                let certificates = split_pem_bundle(bundle);
+                if certificates.is_empty() {
+                    bail!("No PEM-encoded certificates in CA bundle");
+                }
+                certificates
+            },
+            None => vec![],
+        };
+        Ok(Self {
+            proxy,
+            ca_certificates,
+        })
+    }
+
+    /// Build an HTTP client with this configuration.
+    ///
+    /// If no proxy is configured, the client uses the system proxy.
+    pub fn http_client(&self) -> Result<Client> {
+        let mut builder = Client::builder();
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy).with_context(|| format!("Invalid proxy URL: {proxy}"))?;
+            builder = builder.proxy(proxy);
+        }
+        for pem in &self.ca_certificates {
+            let certificate =
+                Certificate::from_pem(pem.as_bytes()).context("Invalid CA certificate")?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Build an OCI client with this configuration.
+    ///
+    /// If no proxy is configured, the client uses the system proxy.
+    pub fn oci_client(&self) -> oci_client::Client {
+        let config = ClientConfig {
+            http_proxy: self.proxy.clone(),
+            https_proxy: self.proxy.clone(),
+            extra_root_certificates: self
+                .ca_certificates
+                .iter()
+                .map(|pem| OciCertificate {
+                    encoding: CertificateEncoding::Pem,
+                    data: pem.clone().into_bytes(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        oci_client::Client::new(config)
+    }
+}
+
+/// Split a PEM bundle into individual certificates.
+///
+/// Anything outside the certificate blocks, e.g., comments, is ignored.
+fn split_pem_bundle(bundle: &str) -> Vec<String> {
+    bundle
+        .split_inclusive(PEM_END)
+        .filter(|chunk| chunk.ends_with(PEM_END))
+        .filter_map(|chunk| {
+            chunk
+                .find(PEM_BEGIN)
+                .map(|start| chunk[start..].to_string())
+        })
+        .collect()
+}
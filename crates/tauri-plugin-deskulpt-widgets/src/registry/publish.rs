@@ -0,0 +1,327 @@
+//! Utilities for publishing widgets to an OCI widgets registry.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_compression::tokio::write::GzipEncoder;
+use oci_client::client::{Config, ImageLayer};
+use oci_client::manifest::OciImageManifest;
+use oci_client::secrets::RegistryAuth;
+use oci_client::{Client, Reference};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tokio::io::AsyncWriteExt;
+use tokio_tar::Builder;
+
+use super::RegistryWidgetFetcher;
+use super::widget::{
+    MAX_PACKAGE_SIZE, MAX_README_SIZE, MAX_SCREENSHOT_SIZE, MAX_SCREENSHOTS, MAX_UNPACKED_SIZE,
+    README_ANNOTATION, SCREENSHOTS_ANNOTATION,
+};
+use crate::catalog::WidgetManifest;
+
+/// The media type of the widget package layer.
+const LAYER_MEDIA_TYPE: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
+
+/// The media type of the empty config of widget packages.
+const EMPTY_CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.empty.v1+json";
+
+/// Entries that are never included in widget packages.
+const EXCLUDED_ENTRIES: &[&str] = &[".git", "node_modules"];
+
+/// The README file attached to widget packages for previews.
+const README_FILE: &str = "README.md";
+
+/// The directory of screenshots attached to widget packages for previews.
+const SCREENSHOTS_DIR: &str = "screenshots";
+
+/// A widget packed for publishing.
+pub struct WidgetPackage {
+    /// The gzipped tarball of the widget files.
+    data: Vec<u8>,
+    /// The preview assets of the widget package, e.g., README and screenshots.
+    assets: Vec<ImageLayer>,
+    /// The annotations of the widget package generated from the manifest.
+    annotations: BTreeMap<String, String>,
+}
+
+impl WidgetPackage {
+    /// Pack a local widget directory.
+    ///
+    /// All files in the directory are included except for
+    /// [`EXCLUDED_ENTRIES`] and symbolic links, the latter being rejected on
+    /// installation anyway. Annotations are generated from the widget manifest
+    /// in the format read by [`RegistryWidgetFetcher::preview`], with the
+    /// creation time set to now and the source set to the given git
+    /// repository URL if any. An error is returned if the directory is not a
+    /// widget, or if the package would exceed the limits enforced on
+    /// installation. Compatibility with Deskulpt versions is not checked, since
+    /// the widget may target a version other than the one publishing it.
+    ///
+    /// [`README_FILE`] and images in [`SCREENSHOTS_DIR`], if any, are attached
+    /// as preview assets, see [`pack_assets`].
+    pub async fn pack(dir: &Path, git: Option<&str>) -> Result<Self> {
+        let manifest = WidgetManifest::load_unchecked(dir)?
+            .ok_or_else(|| anyhow!("Not a widget directory: {}", dir.display()))?;
+
+        let mut files = vec![];
+        collect_entries(dir, Path::new(""), &mut files)?;
+
+        let mut unpacked_size = 0u64;
+        let mut builder = Builder::new(GzipEncoder::new(vec![]));
+        for (path, name) in &files {
+            let metadata = tokio::fs::metadata(path).await?;
+            if metadata.is_dir() {
+                builder.append_dir(name, path).await?;
+                continue;
+            }
+            unpacked_size += metadata.len();
+            if unpacked_size > MAX_UNPACKED_SIZE {
+                bail!("Widget files exceed the limit of {MAX_UNPACKED_SIZE} bytes");
+            }
+            builder.append_path_with_name(path, name).await?;
+        }
+        let mut encoder = builder.into_inner().await?;
+        encoder.shutdown().await?;
+        let data = encoder.into_inner();
+        if data.len() as u64 > MAX_PACKAGE_SIZE {
+            bail!(
+                "Widget package of {} bytes exceeds the limit of {MAX_PACKAGE_SIZE} bytes",
+                data.len()
+            );
+        }
+
+        let mut annotations = annotations(&manifest, git)?;
+        let assets = pack_assets(dir, &mut annotations).await?;
+        Ok(Self {
+            data,
+            assets,
+            annotations,
+        })
+    }
+
+    /// The size of the widget package in bytes.
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The version of the widget from the manifest, if any.
+    pub fn version(&self) -> Option<&str> {
+        self.annotations
+            .get("org.opencontainers.image.version")
+            .map(String::as_str)
+    }
+
+    /// Push the widget package to an OCI repository.
+    ///
+    /// The reference is the full OCI reference, e.g.,
+    /// `ghcr.io/deskulpt-apps/widgets/<handle>/<id>:<tag>`. The package is
+    /// pushed as an artifact with an empty config, a single layer of widget
+    /// files, and a layer per preview asset, as expected by
+    /// [`RegistryWidgetFetcher`]. The digest of the pushed manifest is
+    /// returned, which is what the registry index refers to.
+    pub async fn push(self, reference: &str, username: &str, password: &str) -> Result<String> {
+        let reference: Reference = reference
+            .parse()
+            .with_context(|| format!("Invalid OCI reference: {reference}"))?;
+        let auth = RegistryAuth::Basic(username.to_string(), password.to_string());
+
+        let mut layers = vec![ImageLayer::new(
+            self.data,
+            LAYER_MEDIA_TYPE.to_string(),
+            None,
+        )];
+        layers.extend(self.assets);
+        let config = Config::new(b"{}".to_vec(), EMPTY_CONFIG_MEDIA_TYPE.to_string(), None);
+        let mut manifest = OciImageManifest::build(&layers, &config, Some(self.annotations));
+        manifest.artifact_type = Some(RegistryWidgetFetcher::EXPECTED_ARTIFACT_TYPE.to_string());
+
+        let client = Client::default();
+        client
+            .push(&reference, &layers, config, &auth, Some(manifest))
+            .await
+            .with_context(|| format!("Failed to push widget package to {reference}"))?;
+        let digest = client
+            .fetch_manifest_digest(&reference, &auth)
+            .await
+            .context("Failed to resolve digest of pushed widget package")?;
+        Ok(digest)
+    }
+}
+
+/// Recursively collect the entries of a widget directory to pack.
+///
+/// Each entry is recorded as its path on disk and its name in the package,
+/// in a deterministic order with directories preceding their contents.
+fn collect_entries(dir: &Path, prefix: &Path, entries: &mut Vec<(PathBuf, PathBuf)>) -> Result<()> {
+    let mut children = std::fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    children.sort_by_key(|child| child.file_name());
+    for child in children {
+        let file_name = child.file_name();
+        if EXCLUDED_ENTRIES
+            .iter()
+            .any(|excluded| file_name == *excluded)
+        {
+            continue;
+        }
+        let file_type = child.file_type()?;
+        let name = prefix.join(&file_name);
+        if file_type.is_dir() {
+            entries.push((child.path(), name.clone()));
+            collect_entries(&child.path(), &name, entries)?;
+        } else if file_type.is_file() {
+            entries.push((child.path(), name));
+        } else {
+            tracing::warn!(path = %child.path().display(), "Skipping non-regular file");
+        }
+    }
+    Ok(())
+}
+
+/// Pack the preview assets of a widget directory.
+///
+/// The assets are returned as layers, and referenced by digests in the
+/// annotations as read by [`RegistryWidgetFetcher::preview`]. An error is
+/// returned if an asset exceeds the limits enforced on previewing.
+async fn pack_assets(
+    dir: &Path,
+    annotations: &mut BTreeMap<String, String>,
+) -> Result<Vec<ImageLayer>> {
+    let mut assets = vec![];
+
+    let readme_path = dir.join(README_FILE);
+    if readme_path.is_file() {
+        let data = tokio::fs::read(&readme_path).await?;
+        if data.len() as u64 > MAX_README_SIZE {
+            bail!("{README_FILE} exceeds the limit of {MAX_README_SIZE} bytes");
+        }
+        annotations.insert(README_ANNOTATION.to_string(), sha256_digest(&data));
+        assets.push(ImageLayer::new(data, "text/markdown".to_string(), None));
+    }
+
+    let screenshots_dir = dir.join(SCREENSHOTS_DIR);
+    if screenshots_dir.is_dir() {
+        let mut paths = std::fs::read_dir(&screenshots_dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.sort();
+
+        let mut digests = vec![];
+        for path in paths {
+            let Some(media_type) = image_media_type(&path) else {
+                continue;
+            };
+            if digests.len() == MAX_SCREENSHOTS {
+                tracing::warn!(path = %path.display(), "Skipping screenshot over the limit");
+                continue;
+            }
+            let data = tokio::fs::read(&path).await?;
+            if data.len() as u64 > MAX_SCREENSHOT_SIZE {
+                bail!(
+                    "Screenshot {} exceeds the limit of {MAX_SCREENSHOT_SIZE} bytes",
+                    path.display()
+                );
+            }
+            digests.push(sha256_digest(&data));
+            assets.push(ImageLayer::new(data, media_type.to_string(), None));
+        }
+        if !digests.is_empty() {
+            annotations.insert(
+                SCREENSHOTS_ANNOTATION.to_string(),
+                serde_json::to_string(&digests)?,
+            );
+        }
+    }
+
+    Ok(assets)
+}
+
+/// Get the media type of an image file by its extension.
+fn image_media_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Compute the SHA-256 digest of data in the OCI format.
+fn sha256_digest(data: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(data))
+}
+
+/// Generate the annotations of a widget package from its manifest.
+fn annotations(manifest: &WidgetManifest, git: Option<&str>) -> Result<BTreeMap<String, String>> {
+    let mut annotations = BTreeMap::new();
+    annotations.insert(
+        "org.opencontainers.image.created".to_string(),
+        OffsetDateTime::now_utc().format(&Rfc3339)?,
+    );
+    annotations.insert(
+        "org.opencontainers.image.title".to_string(),
+        manifest.name.clone(),
+    );
+
+    let optional = [
+        ("org.opencontainers.image.source", git.map(str::to_string)),
+        ("org.opencontainers.image.version", manifest.version.clone()),
+        (
+            "org.opencontainers.image.licenses",
+            manifest.license.clone(),
+        ),
+        (
+            "org.opencontainers.image.description",
+            manifest.description.clone(),
+        ),
+        ("org.opencontainers.image.url", manifest.homepage.clone()),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            annotations.insert(key.to_string(), value);
+        }
+    }
+
+    if let Some(authors) = &manifest.authors {
+        annotations.insert(
+            "org.opencontainers.image.authors".to_string(),
+            serde_json::to_string(authors)?,
+        );
+    }
+    if !manifest.localized_name.is_empty() {
+        annotations.insert(
+            "app.deskulpt.widget.localized-name".to_string(),
+            serde_json::to_string(&manifest.localized_name)?,
+        );
+    }
+    if !manifest.localized_description.is_empty() {
+        annotations.insert(
+            "app.deskulpt.widget.localized-description".to_string(),
+            serde_json::to_string(&manifest.localized_description)?,
+        );
+    }
+    if !manifest.categories.is_empty() {
+        annotations.insert(
+            "app.deskulpt.widget.categories".to_string(),
+            serde_json::to_string(&manifest.categories)?,
+        );
+    }
+    if !manifest.keywords.is_empty() {
+        annotations.insert(
+            "app.deskulpt.widget.keywords".to_string(),
+            serde_json::to_string(&manifest.keywords)?,
+        );
+    }
+    if let Some(permissions) = &manifest.permissions {
+        annotations.insert(
+            "app.deskulpt.widget.permissions".to_string(),
+            serde_json::to_string(permissions)?,
+        );
+    }
+
+    Ok(annotations)
+}
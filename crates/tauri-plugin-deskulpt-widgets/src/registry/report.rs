@@ -0,0 +1,98 @@
+//! Reports of abusive widgets in the registry.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::registry::RegistryWidgetReference;
+
+/// A report of an abusive widget in the registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetReport {
+    /// The reported widget package.
+    pub reference: RegistryWidgetReference,
+    /// The reason for the report, as given by the user.
+    pub reason: String,
+    /// The datetime when the report was made, in RFC 3339 format.
+    pub created: String,
+}
+
+impl WidgetReport {
+    /// Create a new [`WidgetReport`] made now.
+    pub fn new(reference: &RegistryWidgetReference, reason: &str) -> Result<Self> {
+        Ok(Self {
+            reference: reference.clone(),
+            reason: reason.to_string(),
+            created: OffsetDateTime::now_utc().format(&Rfc3339)?,
+        })
+    }
+
+    /// Submit the report to an endpoint.
+    ///
+    /// The report is posted as JSON. A report rejected by the endpoint with a
+    /// client error is logged and considered done, since submitting it again
+    /// would not help. Otherwise, an error is returned if the submission fails,
+    /// e.g., while offline, so that the report can be retried later.
+    pub async fn submit(&self, client: &Client, endpoint: &str) -> Result<()> {
+        let response = client.post(endpoint).json(self).send().await?;
+        let status = response.status();
+        if status.is_client_error() {
+            tracing::warn!(
+                id = %self.reference.local_id(),
+                %status,
+                "Widget report rejected; dropping it",
+            );
+            return Ok(());
+        }
+        response.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// The queue of widget reports yet to be submitted.
+///
+/// Reports that cannot be submitted right away, e.g., while offline, are kept
+/// in this queue and persisted, so that they can be submitted later.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ReportQueue(pub Vec<WidgetReport>);
+
+impl ReportQueue {
+    /// The name of the queue file in the widgets directory.
+    pub const FILE_NAME: &str = "registry-reports.json";
+
+    /// Load the queue.
+    ///
+    /// If the queue file does not exist, an empty queue is returned.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        let queue = serde_json::from_reader(reader)?;
+        Ok(queue)
+    }
+
+    /// Dump the queue.
+    ///
+    /// The queue file is removed if the queue is empty.
+    pub fn dump(&self, path: &Path) -> Result<()> {
+        if self.0.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        let file = File::create(path)?;
+        let writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
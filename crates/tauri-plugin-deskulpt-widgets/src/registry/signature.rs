@@ -0,0 +1,160 @@
+//! Verification of cosign signatures of widget packages.
+//!
+//! Cosign stores the signatures of an artifact as an OCI image tagged
+//! `sha256-<hex>.sig` in the same repository. Each layer of the image is a
+//! "simple signing" payload that names the digest of the signed artifact, and
+//! carries the base64-encoded signature of the payload in an annotation.
+
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use oci_client::errors::{OciDistributionError, OciErrorCode};
+use oci_client::secrets::RegistryAuth;
+use oci_client::{Client, Reference};
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::pkcs8::DecodePublicKey;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// The media type of cosign simple signing payloads.
+const PAYLOAD_MEDIA_TYPE: &str = "application/vnd.dev.cosign.simplesigning.v1+json";
+
+/// The annotation carrying the signature of a payload.
+const SIGNATURE_ANNOTATION: &str = "dev.cosignproject.cosign/signature";
+
+/// The maximum size of a simple signing payload in bytes.
+const MAX_PAYLOAD_SIZE: i64 = 64 * 1024;
+
+/// The result of verifying the signatures of a widget package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Signed by one of the trusted keys.
+    Verified,
+    /// Signed, but not by any of the trusted keys.
+    Untrusted,
+    /// Not signed at all.
+    Unsigned,
+}
+
+/// A cosign simple signing payload.
+#[derive(Deserialize)]
+struct SimpleSigning {
+    critical: SimpleSigningCritical,
+}
+
+/// The critical section of a cosign simple signing payload.
+#[derive(Deserialize)]
+struct SimpleSigningCritical {
+    image: SimpleSigningImage,
+}
+
+/// The signed image in a cosign simple signing payload.
+#[derive(Deserialize)]
+struct SimpleSigningImage {
+    #[serde(rename = "docker-manifest-digest")]
+    docker_manifest_digest: String,
+}
+
+/// Verify the cosign signatures of a widget package.
+///
+/// The reference is the widget package pinned by digest. Payloads that do
+/// not name this digest are ignored, as are payloads whose signatures fail to
+/// decode. Keys that fail to parse are logged and skipped.
+pub async fn verify(
+    client: &Client,
+    reference: &Reference,
+    auth: &RegistryAuth,
+    public_keys: &[String],
+) -> Result<SignatureStatus> {
+    let digest = reference
+        .digest()
+        .context("Widget package reference is not pinned by digest")?;
+    let Some(hex) = digest.strip_prefix("sha256:") else {
+        bail!("Unsupported digest algorithm of widget package: {digest}");
+    };
+    let signature_reference = Reference::with_tag(
+        reference.registry().to_string(),
+        reference.repository().to_string(),
+        format!("sha256-{hex}.sig"),
+    );
+
+    let manifest = match client.pull_image_manifest(&signature_reference, auth).await {
+        Ok((manifest, _)) => manifest,
+        Err(OciDistributionError::ImageManifestNotFoundError(_)) => {
+            return Ok(SignatureStatus::Unsigned);
+        },
+        Err(OciDistributionError::RegistryError { envelope, .. })
+            if envelope
+                .errors
+                .iter()
+                .any(|e| matches!(e.code, OciErrorCode::ManifestUnknown)) =>
+        {
+            return Ok(SignatureStatus::Unsigned);
+        },
+        Err(e) => return Err(e).context("Failed to pull widget package signatures"),
+    };
+
+    let keys = public_keys
+        .iter()
+        .filter_map(|pem| match VerifyingKey::from_public_key_pem(pem) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                tracing::warn!(error = ?e, "Skipping malformed publisher public key");
+                None
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let mut signed = false;
+    for layer in &manifest.layers {
+        if layer.media_type != PAYLOAD_MEDIA_TYPE || layer.size > MAX_PAYLOAD_SIZE {
+            continue;
+        }
+        let Some(signature) = layer
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.get(SIGNATURE_ANNOTATION))
+        else {
+            continue;
+        };
+
+        let mut payload = Vec::new();
+        client
+            .pull_blob(&signature_reference, layer, &mut payload)
+            .await?;
+        if format!("sha256:{:x}", Sha256::digest(&payload)) != layer.digest {
+            bail!(
+                "Signature payload digest mismatch: expected {}",
+                layer.digest
+            );
+        }
+        let Ok(payload_json) = serde_json::from_slice::<SimpleSigning>(&payload) else {
+            continue;
+        };
+        if payload_json.critical.image.docker_manifest_digest != digest {
+            continue;
+        }
+        signed = true;
+
+        let Some(signature) = BASE64
+            .decode(signature)
+            .ok()
+            .and_then(|bytes| Signature::from_der(&bytes).ok())
+        else {
+            continue;
+        };
+        if keys
+            .iter()
+            .any(|key| key.verify(&payload, &signature).is_ok())
+        {
+            return Ok(SignatureStatus::Verified);
+        }
+    }
+
+    Ok(if signed {
+        SignatureStatus::Untrusted
+    } else {
+        SignatureStatus::Unsigned
+    })
+}
@@ -0,0 +1,110 @@
+//! Widgets registries to fetch from.
+
+use sha2::{Digest, Sha256};
+use tauri_plugin_deskulpt_settings::model::{RegistryMirror, RegistrySource};
+
+use super::RegistryNetwork;
+
+/// The credentials for a widgets registry.
+#[derive(Debug, Clone)]
+pub struct RegistryCredentials {
+    /// The username.
+    pub username: String,
+    /// The password, which may be an access token.
+    pub password: String,
+}
+
+impl RegistryCredentials {
+    /// Parse credentials in the format `username:password`.
+    ///
+    /// This returns `None` if there is no colon separating the two parts.
+    pub fn parse(credentials: &str) -> Option<Self> {
+        let (username, password) = credentials.split_once(':')?;
+        Some(Self {
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+}
+
+/// A widgets registry with its mirrors.
+#[derive(Debug, Clone)]
+pub struct Registry {
+    /// The ID of the registry.
+    ///
+    /// This is the index URL of an additional registry as configured in the
+    /// settings, or `None` for the official registry. It is recorded in
+    /// [`RegistryWidgetReference::registry`] to locate widget packages.
+    ///
+    /// [`RegistryWidgetReference::registry`]: crate::registry::RegistryWidgetReference::registry
+    pub id: Option<String>,
+    /// The endpoints of the registry, followed by those of its mirrors.
+    ///
+    /// Endpoints are tried in order until one is reachable.
+    pub endpoints: Vec<RegistryMirror>,
+    /// The credentials for the registry, if any.
+    pub credentials: Option<RegistryCredentials>,
+    /// The network configuration for accessing the registry.
+    pub network: RegistryNetwork,
+}
+
+impl Registry {
+    /// The URL of the official widgets registry index.
+    const OFFICIAL_INDEX_URL: &str =
+        "https://cdn.jsdelivr.net/gh/deskulpt-apps/widgets@registry/index.json";
+
+    /// The base of the OCI repositories of the official widgets registry.
+    const OFFICIAL_OCI_BASE: &str = "ghcr.io/deskulpt-apps/widgets";
+
+    /// The official widgets registry with the given mirrors.
+    pub fn official(mirrors: &[RegistryMirror], network: RegistryNetwork) -> Self {
+        let official = RegistryMirror {
+            index_url: Self::OFFICIAL_INDEX_URL.to_string(),
+            oci_base: Self::OFFICIAL_OCI_BASE.to_string(),
+        };
+        Self {
+            id: None,
+            endpoints: std::iter::once(official)
+                .chain(mirrors.iter().cloned())
+                .collect(),
+            credentials: None,
+            network,
+        }
+    }
+
+    /// An additional widgets registry configured in the settings.
+    pub fn additional(
+        source: &RegistrySource,
+        credentials: Option<RegistryCredentials>,
+        network: RegistryNetwork,
+    ) -> Self {
+        let primary = RegistryMirror {
+            index_url: source.index_url.clone(),
+            oci_base: source.oci_base.clone(),
+        };
+        Self {
+            id: Some(source.index_url.clone()),
+            endpoints: std::iter::once(primary)
+                .chain(source.mirrors.iter().cloned())
+                .collect(),
+            credentials,
+            network,
+        }
+    }
+
+    /// A human-readable name of the registry for diagnostics.
+    pub fn name(&self) -> &str {
+        self.id.as_deref().unwrap_or("official")
+    }
+
+    /// A key of the registry that is valid as part of a file name.
+    ///
+    /// This is `None` for the official registry, and otherwise derived from
+    /// the hash of the registry ID.
+    pub fn cache_key(&self) -> Option<String> {
+        self.id.as_ref().map(|id| {
+            let digest = format!("{:x}", Sha256::digest(id.as_bytes()));
+            digest[..16].to_string()
+        })
+    }
+}
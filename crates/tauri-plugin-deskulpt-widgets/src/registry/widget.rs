@@ -1,35 +1,98 @@
 //! Utilities for fetching widgets from the GHCR wigdets registry.
 
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Component, Path};
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 use async_compression::tokio::bufread::GzipDecoder;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use futures_util::StreamExt;
 use oci_client::manifest::OciDescriptor;
 use oci_client::secrets::RegistryAuth;
 use oci_client::{Client, Reference};
 use serde::{Deserialize, Serialize};
-use tokio::io::BufReader;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
 use tokio_tar::Archive;
 use tokio_util::io::StreamReader;
+use tokio_util::sync::CancellationToken;
 
+use super::signature::{self, SignatureStatus};
 use crate::catalog::WidgetManifest;
+use crate::registry::Registry;
+
+/// The minimum number of downloaded bytes between two download progress
+/// reports.
+const PROGRESS_INTERVAL_BYTES: u64 = 64 * 1024;
+
+/// The maximum size of a widget package in bytes, before decompression.
+pub(super) const MAX_PACKAGE_SIZE: u64 = 32 * 1024 * 1024;
+
+/// The maximum total size of the files in a widget package in bytes, after
+/// decompression.
+pub(super) const MAX_UNPACKED_SIZE: u64 = 128 * 1024 * 1024;
+
+/// The maximum size of the README attached to a widget package in bytes.
+pub(super) const MAX_README_SIZE: u64 = 1024 * 1024;
+
+/// The maximum size of a screenshot attached to a widget package in bytes.
+pub(super) const MAX_SCREENSHOT_SIZE: u64 = 4 * 1024 * 1024;
+
+/// The maximum number of screenshots attached to a widget package.
+pub(super) const MAX_SCREENSHOTS: usize = 8;
+
+/// The annotation referencing the README layer by digest.
+pub(super) const README_ANNOTATION: &str = "app.deskulpt.widget.readme";
+
+/// The annotation referencing the screenshot layers by digests.
+///
+/// The value is a JSON array of digests, in display order.
+pub(super) const SCREENSHOTS_ANNOTATION: &str = "app.deskulpt.widget.screenshots";
+
+/// The progress of installing a widget from the registry.
+#[derive(Debug, Clone, Copy, Serialize, specta::Type)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum InstallProgress {
+    /// Verifying the signature of the widget package.
+    Verifying,
+    /// Resolving the widget package in the registry.
+    Resolving,
+    /// Downloading the widget package.
+    Downloading {
+        /// The number of bytes downloaded so far.
+        received: u64,
+        /// The total size of the widget package in bytes.
+        total: u64,
+    },
+    /// Unpacking the downloaded widget package.
+    Unpacking,
+    /// Validating the unpacked widget.
+    Validating,
+}
 
 /// A reference to a widget in the registry.
 ///
 /// These information uniquely and immutably identify a widget package in the
 /// widgets registry.
-#[derive(Debug, Deserialize, specta::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct RegistryWidgetReference {
     /// The publisher handle.
-    handle: String,
+    pub handle: String,
     /// The widget ID.
     ///
     /// Note that this ID is unique only within the publisher's namespace.
-    id: String,
+    pub id: String,
     /// The SHA-256 digest of the widget package.
-    digest: String,
+    pub digest: String,
+    /// The ID of the registry the widget comes from.
+    ///
+    /// See [`Registry::id`] for details. If not set, the widget comes from the
+    /// official registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = String)]
+    pub registry: Option<String>,
 }
 
 impl RegistryWidgetReference {
@@ -50,9 +113,12 @@ struct RegistryWidgetDescriptor {
     reference: Reference,
     /// The layer descriptor of the widget package.
     ///
-    /// There should be only one layer in the package. This layer contains the
-    /// actual widget files, compressed as a gzipped tarball.
+    /// There should be only one such layer in the package. This layer contains
+    /// the actual widget files, compressed as a gzipped tarball.
     layer: OciDescriptor,
+    /// The layer descriptors of the preview assets, e.g., README and
+    /// screenshots, referenced by the annotations.
+    assets: Vec<OciDescriptor>,
     /// The annotations of the widget package containing widget metadata.
     annotations: Option<BTreeMap<String, String>>,
 }
@@ -77,44 +143,74 @@ pub struct RegistryWidgetPreview {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[specta(type = String)]
     git: Option<String>,
+    /// The README of the widget in Markdown, if fetched and available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(type = String)]
+    readme: Option<String>,
+    /// The screenshots of the widget as data URLs, if fetched and available.
+    screenshots: Vec<String>,
     /// More information as in the widget manifest.
     #[serde(flatten)]
     manifest: WidgetManifest,
 }
 
-/// A fetcher for widgets from the registry.
-///
-/// Use [`RegistryWidgetFetcher::default`] to create a new instance, which will
-/// create a new OCI client internally.
-#[derive(Default)]
-pub struct RegistryWidgetFetcher(Client);
+/// A fetcher for widgets from a registry.
+pub struct RegistryWidgetFetcher {
+    /// The OCI client.
+    client: Client,
+    /// The registry to fetch widgets from.
+    registry: Registry,
+}
 
 impl RegistryWidgetFetcher {
-    /// The base URL of the widgets registry in GHCR.
-    const REGISTRY_BASE: &str = "ghcr.io/deskulpt-apps/widgets";
-
     /// The expected artifact type of the widget packages.
-    const EXPECTED_ARTIFACT_TYPE: &str = "application/vnd.deskulpt.widget.v1";
+    pub(super) const EXPECTED_ARTIFACT_TYPE: &str = "application/vnd.deskulpt.widget.v1";
+
+    /// Create a new [`RegistryWidgetFetcher`] instance.
+    ///
+    /// A new OCI client will be created internally, as configured by the
+    /// network configuration of the registry.
+    pub fn new(registry: Registry) -> Self {
+        Self {
+            client: registry.network.oci_client(),
+            registry,
+        }
+    }
 
     /// Fetch the descriptor of a widget from the registry.
     ///
-    /// This does not download the actual widget files, only the metadata. It
-    /// verifies that the artifact type, number of layers, and media type of the
-    /// layer are as expected.
+    /// The endpoints of the registry are tried in order until the manifest of
+    /// the widget package can be pulled. This does not download the actual
+    /// widget files, only the metadata. It verifies that the artifact type,
+    /// number of layers, and media type of the layer are as expected.
     async fn fetch(&self, widget: &RegistryWidgetReference) -> Result<RegistryWidgetDescriptor> {
-        let reference: Reference = format!(
-            "{}/{}/{}@{}",
-            Self::REGISTRY_BASE,
-            widget.handle,
-            widget.id,
-            widget.digest
-        )
-        .parse()?;
-
-        let (manifest, _) = self
-            .0
-            .pull_image_manifest(&reference, &RegistryAuth::Anonymous)
-            .await?;
+        let auth = self.auth();
+
+        let mut pulled = None;
+        let mut last_error = None;
+        for endpoint in &self.registry.endpoints {
+            let reference: Reference = format!(
+                "{}/{}/{}@{}",
+                endpoint.oci_base, widget.handle, widget.id, widget.digest
+            )
+            .parse()?;
+            match self.client.pull_image_manifest(&reference, &auth).await {
+                Ok((manifest, _)) => {
+                    pulled = Some((reference, manifest));
+                    break;
+                },
+                Err(e) => {
+                    tracing::warn!(%reference, error = ?e, "Failed to pull widget manifest");
+                    last_error = Some(e);
+                },
+            }
+        }
+        let Some((reference, manifest)) = pulled else {
+            return Err(match last_error {
+                Some(e) => anyhow::Error::new(e).context("Failed to pull widget manifest"),
+                None => anyhow!("No endpoints for registry {}", self.registry.name()),
+            });
+        };
 
         if manifest.artifact_type.as_deref() != Some(Self::EXPECTED_ARTIFACT_TYPE) {
             bail!(
@@ -124,49 +220,195 @@ impl RegistryWidgetFetcher {
             );
         }
 
-        let num_layers = manifest.layers.len();
-        if num_layers != 1 {
-            bail!("Expected only one layer; got {num_layers}",);
+        // The widget files are in the only gzip-compressed tar layer, and any
+        // other layers are preview assets
+        let (mut packages, assets): (Vec<_>, Vec<_>) = manifest
+            .layers
+            .into_iter()
+            .partition(|layer| layer.media_type.ends_with("tar+gzip"));
+        let num_packages = packages.len();
+        if num_packages != 1 {
+            bail!("Expected only one gzip-compressed tar layer; got {num_packages}");
         }
 
         // Safe to unwrap because we have checked that there is one element
-        let layer = manifest.layers.into_iter().next().unwrap();
-        if !layer.media_type.ends_with("tar+gzip") {
-            bail!("Expected gzip-compressed tar; got {}", layer.media_type);
-        }
+        let layer = packages.pop().unwrap();
 
         Ok(RegistryWidgetDescriptor {
             reference,
             layer,
+            assets,
             annotations: manifest.annotations,
         })
     }
 
+    /// Get the authentication for the registry.
+    fn auth(&self) -> RegistryAuth {
+        match &self.registry.credentials {
+            Some(credentials) => {
+                RegistryAuth::Basic(credentials.username.clone(), credentials.password.clone())
+            },
+            None => RegistryAuth::Anonymous,
+        }
+    }
+
+    /// Verify the cosign signatures of a widget package.
+    ///
+    /// The signatures are looked up at the endpoint the widget package is
+    /// resolved from, and verified against the given PEM-encoded public keys.
+    /// See [`signature::verify`] for details.
+    pub async fn verify_signature(
+        &self,
+        widget: &RegistryWidgetReference,
+        public_keys: &[String],
+    ) -> Result<SignatureStatus> {
+        let RegistryWidgetDescriptor { reference, .. } = self.fetch(widget).await?;
+        signature::verify(&self.client, &reference, &self.auth(), public_keys).await
+    }
+
     /// Install a widget from the registry into the given directory.
-    pub async fn install(&self, dir: &Path, widget: &RegistryWidgetReference) -> Result<()> {
+    ///
+    /// The directory must not exist yet. The widget package is verified
+    /// against its digest before unpacking, see [`Self::download`], and is
+    /// unpacked into a temporary directory next to the given one first, see
+    /// [`unpack`]. Only if everything succeeds is it moved into place.
+    ///
+    /// The progress is reported to the given callback as the installation
+    /// goes. Resolving and downloading the widget package can be cancelled via
+    /// the given token, in which case an error is returned and nothing is
+    /// unpacked into the directory. Once unpacking starts, the installation
+    /// can no longer be cancelled.
+    pub async fn install(
+        &self,
+        dir: &Path,
+        widget: &RegistryWidgetReference,
+        cancel: &CancellationToken,
+        on_progress: impl Fn(InstallProgress),
+    ) -> Result<()> {
+        let package = cancel
+            .run_until_cancelled(self.download(widget, &on_progress))
+            .await;
+        let Some(package) = package else {
+            bail!("Installation of widget {} cancelled", widget.local_id());
+        };
+        let package = package?;
+
+        on_progress(InstallProgress::Unpacking);
+        if tokio::fs::try_exists(dir).await? {
+            bail!("Directory {} already exists", dir.display());
+        }
+        let file_name = dir
+            .file_name()
+            .with_context(|| format!("Invalid directory {}", dir.display()))?;
+        let temp_dir = dir.with_file_name(format!(".{}.partial", file_name.to_string_lossy()));
+        if tokio::fs::try_exists(&temp_dir).await? {
+            tokio::fs::remove_dir_all(&temp_dir).await?;
+        }
+        tokio::fs::create_dir_all(&temp_dir).await?;
+
+        if let Err(e) = unpack(&package, &temp_dir).await {
+            if let Err(cleanup_error) = tokio::fs::remove_dir_all(&temp_dir).await {
+                tracing::warn!(
+                    error = ?cleanup_error,
+                    path = %temp_dir.display(),
+                    "Failed to clean up partially unpacked widget package",
+                );
+            }
+            return Err(e.context(format!("Failed to unpack widget {}", widget.local_id())));
+        }
+        tokio::fs::rename(&temp_dir, dir)
+            .await
+            .with_context(|| format!("Failed to move widget into {}", dir.display()))?;
+
+        Ok(())
+    }
+
+    /// Download the package of a widget from the registry into memory.
+    ///
+    /// The download progress is reported at most once per
+    /// [`PROGRESS_INTERVAL_BYTES`], and always once the download completes.
+    /// The package is hashed as it is downloaded, and an error is returned if
+    /// its size or SHA-256 digest does not match the layer descriptor, or if it
+    /// is larger than [`MAX_PACKAGE_SIZE`].
+    async fn download(
+        &self,
+        widget: &RegistryWidgetReference,
+        on_progress: &impl Fn(InstallProgress),
+    ) -> Result<Vec<u8>> {
+        on_progress(InstallProgress::Resolving);
         let RegistryWidgetDescriptor {
             reference, layer, ..
         } = self.fetch(widget).await?;
 
-        let sized_stream = self.0.pull_blob_stream(&reference, &layer).await?;
-        let reader = StreamReader::new(sized_stream.stream);
+        let total = layer.size.max(0) as u64;
+        if total > MAX_PACKAGE_SIZE {
+            bail!("Widget package of {total} bytes exceeds the limit of {MAX_PACKAGE_SIZE} bytes");
+        }
+        let Some(expected_digest) = layer.digest.strip_prefix("sha256:") else {
+            bail!("Unsupported digest algorithm of layer: {}", layer.digest);
+        };
+        on_progress(InstallProgress::Downloading { received: 0, total });
 
-        let buf = BufReader::new(reader);
-        let gz = GzipDecoder::new(buf);
-        let mut ar = Archive::new(gz);
-        ar.unpack(dir).await?;
+        let sized_stream = self.client.pull_blob_stream(&reference, &layer).await?;
+        let mut reader = StreamReader::new(sized_stream.stream);
+        let mut hasher = Sha256::new();
+        let mut package = Vec::new();
+        let mut reported = 0;
+        loop {
+            let hashed = package.len();
+            if reader.read_buf(&mut package).await? == 0 {
+                break;
+            }
+            hasher.update(&package[hashed..]);
 
-        Ok(())
+            let received = package.len() as u64;
+            if received > total {
+                bail!("Widget package is larger than the declared {total} bytes");
+            }
+            if received - reported >= PROGRESS_INTERVAL_BYTES {
+                on_progress(InstallProgress::Downloading { received, total });
+                reported = received;
+            }
+        }
+
+        let received = package.len() as u64;
+        if received != reported {
+            on_progress(InstallProgress::Downloading { received, total });
+        }
+        if received != total {
+            bail!("Widget package is truncated: expected {total} bytes, got {received}");
+        }
+        let digest = format!("{:x}", hasher.finalize());
+        if !digest.eq_ignore_ascii_case(expected_digest) {
+            bail!(
+                "Widget package digest mismatch: expected sha256:{expected_digest}, got \
+                 sha256:{digest}"
+            );
+        }
+        Ok(package)
     }
 
     /// Preview metadata about a widget in the registry.
     ///
     /// This does not download the actual widget files, but only fetches the
-    /// widget package metadata.
-    pub async fn preview(&self, widget: &RegistryWidgetReference) -> Result<RegistryWidgetPreview> {
+    /// widget package metadata. If a locale is given, the display metadata is
+    /// resolved to it as in [`WidgetManifest::localize`]. The permissions
+    /// requested by the widget are included, so that the user can be prompted
+    /// to grant them before installation.
+    ///
+    /// If an assets cache directory is given, the README and screenshots of
+    /// the widget are fetched as well, see [`Self::fetch_asset`]. Failure to
+    /// fetch them is logged and does not fail the preview.
+    pub async fn preview(
+        &self,
+        widget: &RegistryWidgetReference,
+        locale: Option<&str>,
+        assets_cache_dir: Option<&Path>,
+    ) -> Result<RegistryWidgetPreview> {
         let RegistryWidgetDescriptor {
             reference,
             layer,
+            assets,
             annotations,
         } = self.fetch(widget).await?;
 
@@ -177,7 +419,15 @@ impl RegistryWidgetFetcher {
             ..Default::default()
         };
 
+        let mut readme = None;
+        let mut screenshots = Vec::<String>::new();
         if let Some(mut annotations) = annotations {
+            readme = annotations.remove(README_ANNOTATION);
+            screenshots = annotations
+                .remove(SCREENSHOTS_ANNOTATION)
+                .and_then(|digests| serde_json::from_str(&digests).ok())
+                .unwrap_or_default();
+
             preview.created = annotations.remove("org.opencontainers.image.created");
             preview.git = annotations
                 .remove("org.opencontainers.image.source")
@@ -195,8 +445,160 @@ impl RegistryWidgetFetcher {
             preview.manifest.description =
                 annotations.remove("org.opencontainers.image.description");
             preview.manifest.homepage = annotations.remove("org.opencontainers.image.url");
+            preview.manifest.localized_name = annotations
+                .remove("app.deskulpt.widget.localized-name")
+                .and_then(|names| serde_json::from_str(&names).ok())
+                .unwrap_or_default();
+            preview.manifest.localized_description = annotations
+                .remove("app.deskulpt.widget.localized-description")
+                .and_then(|descriptions| serde_json::from_str(&descriptions).ok())
+                .unwrap_or_default();
+            preview.manifest.categories = annotations
+                .remove("app.deskulpt.widget.categories")
+                .and_then(|categories| serde_json::from_str(&categories).ok())
+                .unwrap_or_default();
+            preview.manifest.keywords = annotations
+                .remove("app.deskulpt.widget.keywords")
+                .and_then(|keywords| serde_json::from_str(&keywords).ok())
+                .unwrap_or_default();
+            preview.manifest.permissions = annotations
+                .remove("app.deskulpt.widget.permissions")
+                .and_then(|permissions| serde_json::from_str(&permissions).ok());
+        }
+
+        if let Some(locale) = locale {
+            preview.manifest.localize(locale);
+        }
+
+        let Some(cache_dir) = assets_cache_dir else {
+            return Ok(preview);
+        };
+        if let Some(digest) = readme {
+            let readme = self
+                .fetch_asset(&reference, &assets, &digest, MAX_README_SIZE, cache_dir)
+                .await
+                .and_then(|(_, data)| Ok(String::from_utf8(data)?));
+            match readme {
+                Ok(readme) => preview.readme = Some(readme),
+                Err(e) => tracing::warn!(%reference, error = ?e, "Failed to fetch widget README"),
+            }
+        }
+        for digest in screenshots.iter().take(MAX_SCREENSHOTS) {
+            let screenshot = self
+                .fetch_asset(&reference, &assets, digest, MAX_SCREENSHOT_SIZE, cache_dir)
+                .await
+                .and_then(|(media_type, data)| {
+                    if !media_type.starts_with("image/") {
+                        bail!("Expected an image; got {media_type}");
+                    }
+                    Ok(format!("data:{media_type};base64,{}", BASE64.encode(data)))
+                });
+            match screenshot {
+                Ok(screenshot) => preview.screenshots.push(screenshot),
+                Err(e) => {
+                    tracing::warn!(%reference, error = ?e, "Failed to fetch widget screenshot")
+                },
+            }
         }
 
         Ok(preview)
     }
+
+    /// Fetch a preview asset of a widget package by its digest.
+    ///
+    /// The asset must be one of the given layers of the package, so that it
+    /// is covered by the package digest, and must not be larger than the given
+    /// size. Assets are cached in the given directory by digest, and verified
+    /// against the digest whether they are read from the cache or downloaded.
+    /// The media type and content of the asset are returned.
+    async fn fetch_asset(
+        &self,
+        reference: &Reference,
+        assets: &[OciDescriptor],
+        digest: &str,
+        max_size: u64,
+        cache_dir: &Path,
+    ) -> Result<(String, Vec<u8>)> {
+        let asset = assets
+            .iter()
+            .find(|asset| asset.digest == digest)
+            .with_context(|| format!("Asset {digest} is not in the widget package"))?;
+        if asset.size.max(0) as u64 > max_size {
+            bail!(
+                "Asset of {} bytes exceeds the limit of {max_size} bytes",
+                asset.size
+            );
+        }
+        let Some(expected_digest) = digest.strip_prefix("sha256:") else {
+            bail!("Unsupported digest algorithm of asset: {digest}");
+        };
+
+        let cache_path = cache_dir.join(expected_digest);
+        if let Ok(data) = tokio::fs::read(&cache_path).await {
+            if format!("{:x}", Sha256::digest(&data)).eq_ignore_ascii_case(expected_digest) {
+                return Ok((asset.media_type.clone(), data));
+            }
+            tracing::debug!(path = %cache_path.display(), "Discarding corrupted cached asset");
+        }
+
+        let mut data = Vec::new();
+        self.client.pull_blob(reference, asset, &mut data).await?;
+        if data.len() as u64 > max_size {
+            bail!("Asset exceeds the limit of {max_size} bytes");
+        }
+        let actual_digest = format!("{:x}", Sha256::digest(&data));
+        if !actual_digest.eq_ignore_ascii_case(expected_digest) {
+            bail!("Asset digest mismatch: expected {digest}, got sha256:{actual_digest}");
+        }
+
+        tokio::fs::create_dir_all(cache_dir).await?;
+        if let Err(e) = tokio::fs::write(&cache_path, &data).await {
+            tracing::warn!(path = %cache_path.display(), error = ?e, "Failed to cache asset");
+        }
+        Ok((asset.media_type.clone(), data))
+    }
+}
+
+/// Unpack a gzipped tarball of a widget package into a directory.
+///
+/// Only regular files and directories are allowed, at relative paths that stay
+/// within the directory; links and other special entries are rejected. An
+/// error is returned as soon as an offending entry is found or the total size
+/// of the files exceeds [`MAX_UNPACKED_SIZE`], in which case the directory may
+/// be left partially unpacked.
+async fn unpack(package: &[u8], dir: &Path) -> Result<()> {
+    let gz = GzipDecoder::new(package);
+    let mut ar = Archive::new(gz);
+    let mut entries = ar.entries()?;
+
+    let mut unpacked_size = 0u64;
+    while let Some(entry) = entries.next().await {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if !path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            bail!("Unsafe path in widget package: {}", path.display());
+        }
+
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            bail!(
+                "Unsupported entry of type {entry_type:?} in widget package: {}",
+                path.display()
+            );
+        }
+
+        unpacked_size += entry.header().size()?;
+        if unpacked_size > MAX_UNPACKED_SIZE {
+            bail!("Unpacked widget package exceeds the limit of {MAX_UNPACKED_SIZE} bytes");
+        }
+
+        if !entry.unpack_in(dir).await? {
+            bail!("Unsafe path in widget package: {}", path.display());
+        }
+    }
+
+    Ok(())
 }
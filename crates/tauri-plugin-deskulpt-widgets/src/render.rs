@@ -1,7 +1,19 @@
 //! Bundling and rendering of Deskulpt widgets.
 
 mod alias_plugin;
+mod asset_plugin;
+mod asset_protocol;
 mod bundler;
+mod css_plugin;
+mod define_plugin;
+mod diagnostics;
+mod oneshot;
+mod strict_plugin;
+mod type_check;
+mod validate;
 mod worker;
 
-pub use worker::{RenderWorkerHandle, RenderWorkerTask};
+pub use asset_protocol::{ASSET_PROTOCOL, handle_asset_request};
+pub use diagnostics::{BundleDiagnostic, symbolicate};
+pub use validate::{ValidationReport, validate};
+pub use worker::{RenderTimings, RenderWorkerHandle, RenderWorkerTask};
@@ -0,0 +1,103 @@
+//! Deskulpt static asset plugin for rolldown.
+
+use std::borrow::Cow;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use rolldown::plugin::{
+    HookLoadArgs, HookLoadOutput, HookLoadReturn, HookUsage, Plugin, PluginContext,
+};
+use rolldown_common::ModuleType;
+
+/// Assets no larger than this size in bytes are inlined as data URLs.
+const INLINE_LIMIT: usize = 4096;
+
+/// Get the MIME type of a static asset by its extension.
+///
+/// This returns `None` for files that are not considered static assets, which
+/// are left to be handled by the bundler as usual.
+pub fn mime_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    let mime = match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "bmp" => "image/bmp",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "txt" => "text/plain",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Deskulpt static asset plugin.
+///
+/// This allows widget code to import static assets, e.g., `import icon from
+/// "./icon.png"`, where the default export is a URL to the asset. Small assets
+/// are inlined as data URLs. Larger ones are copied to the asset directory of
+/// the widget, named by their content hashes, and referenced by URLs served
+/// through the `deskulpt-asset://` protocol.
+#[derive(Debug)]
+pub struct AssetPlugin {
+    /// The asset directory of the widget.
+    pub dir: PathBuf,
+    /// The base URL under which the asset directory is served.
+    pub url: String,
+}
+
+impl Plugin for AssetPlugin {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("deskulpt:asset")
+    }
+
+    async fn load(&self, _ctx: &PluginContext, args: &HookLoadArgs<'_>) -> HookLoadReturn {
+        let path = Path::new(args.id);
+        let Some(mime) = mime_type(path) else {
+            return Ok(None);
+        };
+
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read asset: {}", path.display()))?;
+
+        let url = if bytes.len() <= INLINE_LIMIT {
+            format!("data:{mime};base64,{}", STANDARD.encode(&bytes))
+        } else {
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            let ext = path.extension().unwrap_or_default().to_string_lossy();
+            let name = format!("{:016x}.{ext}", hasher.finish());
+
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(self.dir.join(&name), &bytes)
+                .await
+                .with_context(|| format!("Failed to copy asset: {}", path.display()))?;
+            format!("{}/{name}", self.url)
+        };
+
+        Ok(Some(HookLoadOutput {
+            code: format!("export default {};", serde_json::to_string(&url)?).into(),
+            module_type: Some(ModuleType::Js),
+            ..Default::default()
+        }))
+    }
+
+    fn register_hook_usage(&self) -> HookUsage {
+        HookUsage::Load
+    }
+}
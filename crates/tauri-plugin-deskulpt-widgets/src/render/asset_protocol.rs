@@ -0,0 +1,130 @@
+//! The `deskulpt-asset://` protocol for serving widget static assets.
+
+use std::borrow::Cow;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use deskulpt_common::window::{DeskulptWindow, is_detached_widget_label};
+use percent_encoding::percent_decode_str;
+use tauri::http::{Request, Response, StatusCode, header};
+use tauri::{Manager, Runtime, UriSchemeContext};
+
+use crate::WidgetsExt;
+use crate::render::asset_plugin::mime_type;
+
+/// The name of the asset protocol.
+pub const ASSET_PROTOCOL: &str = "deskulpt-asset";
+
+/// Resolve the file path of an asset from the request URI path.
+///
+/// The URI path is expected to be `/{widget_id}/{asset_name}`, both percent
+/// encoded. Paths that would escape the asset directory are rejected.
+fn resolve_asset_path(assets_dir: &Path, uri_path: &str) -> Result<PathBuf> {
+    let mut path = assets_dir.to_path_buf();
+    for segment in uri_path.split('/').filter(|s| !s.is_empty()) {
+        let segment = percent_decode_str(segment).decode_utf8()?;
+        let mut components = Path::new(segment.as_ref()).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(component)), None) => path.push(component),
+            _ => bail!("Invalid asset path: {uri_path}"),
+        }
+    }
+    Ok(path)
+}
+
+/// Resolve the placeholders in a chunk emitted by code splitting.
+///
+/// The canvas resolves the placeholders in the code it evaluates directly, but
+/// chunks are loaded by the webview from this protocol. `__DESKULPT_BASE_URL__`
+/// is resolved to the origin of the requesting webview, and
+/// `__DESKULPT_ASSETS_URL__` to the origin of the request.
+fn resolve_chunk_placeholders<R: Runtime>(
+    ctx: &UriSchemeContext<'_, R>,
+    request: &Request<Vec<u8>>,
+    code: &str,
+) -> Result<String> {
+    let base_url = ctx
+        .app_handle()
+        .get_webview(ctx.webview_label())
+        .context("Webview not found")?
+        .url()?
+        .origin()
+        .ascii_serialization();
+    let uri = request.uri();
+    let assets_url = format!(
+        "{}://{}",
+        uri.scheme_str().unwrap_or(ASSET_PROTOCOL),
+        uri.authority().map(|a| a.as_str()).unwrap_or("localhost"),
+    );
+    Ok(code
+        .replace("__DESKULPT_BASE_URL__", &base_url)
+        .replace("__DESKULPT_ASSETS_URL__", &assets_url))
+}
+
+/// Build a response with the given status and no body.
+fn empty_response(status: StatusCode) -> Response<Cow<'static, [u8]>> {
+    Response::builder()
+        .status(status)
+        .body(Cow::Borrowed(&[][..]))
+        .unwrap()
+}
+
+/// Handle a request to the [`ASSET_PROTOCOL`].
+///
+/// Only the canvas and floating windows of detached widgets, where widgets are
+/// rendered, are allowed to load widget assets. JavaScript chunks emitted by
+/// code splitting have their placeholders resolved before being served. Assets
+/// are served with caching disabled because they are named by content hashes
+/// and stale ones are replaced on every re-bundle anyway.
+pub fn handle_asset_request<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: Request<Vec<u8>>,
+) -> Response<Cow<'static, [u8]>> {
+    let widgets = ctx.app_handle().widgets();
+    let label = ctx.webview_label();
+    let is_allowed = match DeskulptWindow::try_from(label) {
+        Ok(window) => matches!(window, DeskulptWindow::Canvas),
+        Err(_) => is_detached_widget_label(label),
+    };
+    if !is_allowed {
+        return empty_response(StatusCode::FORBIDDEN);
+    }
+
+    let assets_dir = widgets.assets_dir();
+    let path = match resolve_asset_path(assets_dir, request.uri().path()) {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::warn!(error = ?e, uri = %request.uri(), "Rejected asset request");
+            return empty_response(StatusCode::BAD_REQUEST);
+        },
+    };
+
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = ?e, path = %path.display(), "Failed to read asset");
+            return empty_response(StatusCode::NOT_FOUND);
+        },
+    };
+
+    let (bytes, content_type) = if path.extension().is_some_and(|ext| ext == "js") {
+        let code = String::from_utf8_lossy(&bytes);
+        match resolve_chunk_placeholders(&ctx, &request, &code) {
+            Ok(code) => (code.into_bytes(), "text/javascript"),
+            Err(e) => {
+                tracing::warn!(error = ?e, path = %path.display(), "Failed to resolve chunk");
+                return empty_response(StatusCode::INTERNAL_SERVER_ERROR);
+            },
+        }
+    } else {
+        let content_type = mime_type(&path).unwrap_or("application/octet-stream");
+        (bytes, content_type)
+    };
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CACHE_CONTROL, "no-cache")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(Cow::Owned(bytes))
+        .unwrap()
+}
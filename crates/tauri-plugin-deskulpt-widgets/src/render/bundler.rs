@@ -1,16 +1,27 @@
 //! Rolldown-based bundler for Deskulpt widgets.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use anyhow::{Result, anyhow, bail};
+use anyhow::{Context, Result, bail};
 use either::Either;
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use rolldown::plugin::SharedPluginable;
 use rolldown::{
     BundlerOptions, BundlerTransformOptions, JsxOptions, OutputFormat, Platform, RawMinifyOptions,
+    SourceMapType,
 };
-use rolldown_common::Output;
+use rolldown_common::{ExperimentalOptions, Output};
+use rolldown_sourcemap::SourceMap;
 
 use crate::render::alias_plugin::AliasPlugin;
+use crate::render::asset_plugin::AssetPlugin;
+use crate::render::css_plugin::CssPlugin;
+use crate::render::define_plugin::DefinePlugin;
+use crate::render::diagnostics::BundleError;
+use crate::render::strict_plugin::StrictPlugin;
+use crate::strict::StrictWarning;
 
 /// A default Deskulpt dependency provided by the Deskulpt runtime.
 struct DefaultDependency {
@@ -20,11 +31,47 @@ struct DefaultDependency {
     url: &'static str,
 }
 
+/// The name of the module through which chunks import the widget APIs.
+const CHUNK_APIS_MODULE: &str = "deskulpt-apis.js";
+
+/// The output of bundling a widget.
+pub struct BundleOutput {
+    /// The bundled code.
+    pub code: String,
+    /// The source map of the bundled code, if available.
+    ///
+    /// With code splitting, this is the source map of the entry chunk.
+    pub source_map: Option<SourceMap>,
+    /// The total size of the bundled chunks in bytes.
+    pub size: u64,
+}
+
 /// The Deskulpt widget bundler.
 ///
 /// Under the hood it wraps a [`rolldown::Bundler`] but is pre-configured to
-/// suit Deskulpt widgets' needs.
-pub struct Bundler(rolldown::Bundler);
+/// suit Deskulpt widgets' needs. A bundler is meant to be long-lived for a
+/// widget: repeated calls to [`Bundler::bundle`] rebuild incrementally, reusing
+/// the work done for modules that have not changed since the last bundle.
+pub struct Bundler {
+    /// The underlying rolldown bundler.
+    inner: rolldown::Bundler,
+    /// The define plugin, kept for querying the used constants.
+    define_plugin: Arc<DefinePlugin>,
+    /// The code splitting configuration, if the widget opts in.
+    code_splitting: Option<CodeSplitting>,
+    /// The strict plugin, kept for querying the warnings, if in strict mode.
+    strict_plugin: Option<Arc<StrictPlugin>>,
+}
+
+/// The code splitting configuration of a [`Bundler`].
+struct CodeSplitting {
+    /// The ID of the widget.
+    id: String,
+    /// The directory where chunks are emitted.
+    dir: PathBuf,
+    /// The base URL under which the chunk directory is served.
+    url: String,
+}
 
 impl Bundler {
     /// The default dependencies provided by the Deskulpt runtime.
@@ -37,6 +84,9 @@ impl Bundler {
     ///   served from.
     /// - `__DESKULPT_APIS_BLOB_URL__`: The URL of the blob containing the
     ///   generated Deskulpt APIs.
+    ///
+    /// Similarly, bundled code may reference `__DESKULPT_ASSETS_URL__`, the
+    /// base URL of the `deskulpt-asset://` protocol, for static assets.
     const DEFAULT_DEPENDENCIES: &[DefaultDependency] = &[
         DefaultDependency {
             name: "@deskulpt-test/emotion/jsx-runtime",
@@ -62,11 +112,16 @@ impl Bundler {
 
     /// Create a new [`Bundler`] instance.
     ///
-    /// This takes the root directory of the widget and the entry file path
-    /// relative to the root directory. The returned bundler is configured with
+    /// This takes the ID of the widget, its root directory, the entry file
+    /// path relative to the root directory, the asset directory of the widget,
+    /// the compile-time constants mapping identifiers to JavaScript
+    /// expressions, whether the widget opts in to code splitting, and whether
+    /// the widget is in strict mode. The returned bundler is configured with
     /// the following features:
     ///
-    /// - Minified ESM output for browser target.
+    /// - ESM output for browser target, minified in release builds.
+    /// - Source maps, inlined into the output in debug builds so that devtools
+    ///   can show original sources, and hidden otherwise.
     /// - TypeScript supported by rolldown out of the box.
     /// - JSX transform with `@deskulpt-test/emotion` automatic runtime. This
     ///   resolves to `@deskulpt-test/emotion/jsx-runtime` which is listed in
@@ -75,13 +130,49 @@ impl Bundler {
     ///   [`AliasPlugin`], so widget code can import them by module names.
     /// - Externalize the aliased URLs of [`Self::DEFAULT_DEPENDENCIES`], so the
     ///   bundler will not try to resolve them at bundle time (which will fail).
-    pub fn new(root: PathBuf, entry: String) -> Result<Self> {
+    /// - Static asset imports with [`AssetPlugin`], where small assets are
+    ///   inlined and larger ones are copied to the asset directory.
+    /// - CSS and CSS module imports with [`CssPlugin`], where styles are
+    ///   injected into the canvas tagged with the widget ID.
+    /// - Compile-time constants replaced with [`DefinePlugin`].
+    /// - If opted in, code splitting for dynamic imports, where chunks are
+    ///   emitted to the asset directory and loaded via the asset protocol.
+    /// - Incremental rebuilds, so that subsequent bundles of the same widget
+    ///   only redo the work for changed modules.
+    /// - In strict mode, detection of deprecated API usage with
+    ///   [`StrictPlugin`].
+    ///
+    /// Assets copied by previously existing bundlers of the widget are cleared.
+    pub fn new(
+        id: String,
+        root: PathBuf,
+        entry: String,
+        assets_dir: PathBuf,
+        constants: BTreeMap<String, String>,
+        code_splitting: bool,
+        strict: bool,
+    ) -> Result<Self> {
+        // IDs of nested widgets contain slashes, which are kept as path
+        // separators so that the URL mirrors the assets directory
+        let assets_url = format!(
+            "__DESKULPT_ASSETS_URL__/{}",
+            id.split('/')
+                .map(|name| utf8_percent_encode(name, NON_ALPHANUMERIC).to_string())
+                .collect::<Vec<_>>()
+                .join("/")
+        );
+
         let bundler_options = BundlerOptions {
             input: Some(vec![entry.into()]),
-            cwd: Some(root),
+            cwd: Some(root.clone()),
             format: Some(OutputFormat::Esm),
             platform: Some(Platform::Browser),
-            minify: Some(RawMinifyOptions::Bool(true)),
+            minify: Some(RawMinifyOptions::Bool(!cfg!(debug_assertions))),
+            sourcemap: Some(if cfg!(debug_assertions) {
+                SourceMapType::Inline
+            } else {
+                SourceMapType::Hidden
+            }),
             transform: Some(BundlerTransformOptions {
                 jsx: Some(Either::Right(JsxOptions {
                     runtime: Some("automatic".to_string()),
@@ -97,9 +188,22 @@ impl Bundler {
                     .collect::<Vec<_>>()
                     .into(),
             ),
+            // Entry chunks are named by content hashes as well for code splitting,
+            // so that re-bundles are not shadowed by the module cache
+            entry_filenames: code_splitting.then(|| "[name]-[hash].js".to_string().into()),
+            experimental: Some(ExperimentalOptions {
+                incremental_build: Some(true),
+                ..Default::default()
+            }),
             ..Default::default()
         };
 
+        // Assets are only copied when their modules are loaded, which may be
+        // skipped by incremental rebuilds, so we can only start afresh here
+        if assets_dir.exists() {
+            std::fs::remove_dir_all(&assets_dir)?;
+        }
+
         let alias_plugin = AliasPlugin(
             Self::DEFAULT_DEPENDENCIES
                 .iter()
@@ -107,38 +211,147 @@ impl Bundler {
                 .collect(),
         );
 
-        let inner = rolldown::Bundler::with_plugins(bundler_options, vec![Arc::new(alias_plugin)])?;
-        Ok(Self(inner))
+        let code_splitting = code_splitting.then(|| CodeSplitting {
+            id: id.clone(),
+            dir: assets_dir.join("chunks"),
+            url: format!("{assets_url}/chunks"),
+        });
+
+        let asset_plugin = AssetPlugin {
+            dir: assets_dir,
+            url: assets_url,
+        };
+
+        let css_plugin = CssPlugin { id };
+
+        let define_plugin = Arc::new(DefinePlugin::new(constants));
+
+        // The strict plugin must see the original sources, so it goes before
+        // plugins that transform the code
+        let strict_plugin = strict.then(|| Arc::new(StrictPlugin::new(root)));
+
+        let mut plugins: Vec<SharedPluginable> = vec![];
+        if let Some(strict_plugin) = &strict_plugin {
+            plugins.push(strict_plugin.clone());
+        }
+        plugins.extend([
+            Arc::new(alias_plugin) as SharedPluginable,
+            Arc::new(asset_plugin),
+            Arc::new(css_plugin),
+            define_plugin.clone(),
+        ]);
+
+        let inner = rolldown::Bundler::with_plugins(bundler_options, plugins)?;
+        Ok(Self {
+            inner,
+            define_plugin,
+            code_splitting,
+            strict_plugin,
+        })
+    }
+
+    /// Get the strict mode warnings of the latest bundle.
+    ///
+    /// This is empty if the widget is not in strict mode.
+    pub fn strict_warnings(&self) -> Vec<StrictWarning> {
+        self.strict_plugin
+            .as_ref()
+            .map(|plugin| plugin.warnings())
+            .unwrap_or_default()
+    }
+
+    /// Whether a compile-time constant has been used by any bundle so far.
+    pub fn uses_constant(&self, name: &str) -> bool {
+        self.define_plugin.used.lock().contains(name)
     }
 
     /// Bundle the widget into a single output code string.
-    pub async fn bundle(&mut self) -> Result<String> {
-        let result = self.0.generate().await.map_err(|e| {
-            anyhow!(
+    ///
+    /// If the widget opts in to code splitting, all chunks are emitted to the
+    /// chunk directory, and the output code is a stub that loads the entry
+    /// chunk from there. Otherwise, the bundle must consist of a single chunk.
+    /// If bundling fails with diagnostics, the returned error can be downcast
+    /// to [`BundleError`] for structured diagnostics.
+    pub async fn bundle(&mut self) -> Result<BundleOutput> {
+        let result = self.inner.generate().await.map_err(|e| {
+            BundleError::new(
                 e.into_vec()
                     .iter()
                     .map(|diagnostic| diagnostic.to_diagnostic().to_string())
-                    .collect::<Vec<String>>()
-                    .join("\n")
+                    .collect(),
             )
         })?;
 
+        // Source maps may be emitted as separate assets, which we do not need
+        // because the source map is also attached to the chunk itself
+        let chunks = result
+            .assets
+            .iter()
+            .filter_map(|output| match output {
+                Output::Chunk(chunk) => Some(chunk),
+                Output::Asset(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let size = chunks.iter().map(|chunk| chunk.code.len() as u64).sum();
+
+        if let Some(code_splitting) = &self.code_splitting {
+            // Chunks are served from the asset protocol and cannot import the
+            // per-widget APIs blob, so they import a module that reads the
+            // APIs from a global set by the stub instead
+            tokio::fs::create_dir_all(&code_splitting.dir).await?;
+            let id = serde_json::to_string(&code_splitting.id)?;
+            let mut entry = None;
+            for chunk in &chunks {
+                let code = chunk.code.replace(
+                    "\"__DESKULPT_APIS_BLOB_URL__\"",
+                    &format!("\"./{CHUNK_APIS_MODULE}\""),
+                );
+                tokio::fs::write(code_splitting.dir.join(chunk.filename.as_str()), code).await?;
+                if chunk.is_entry {
+                    entry = Some(chunk);
+                }
+            }
+            tokio::fs::write(
+                code_splitting.dir.join(CHUNK_APIS_MODULE),
+                format!("export default window.__DESKULPT_WIDGET_APIS__[{id}];"),
+            )
+            .await?;
+
+            let entry = entry.context("No entry chunk in the bundled output")?;
+            let entry_url = format!("{}/{}", code_splitting.url, entry.filename);
+            let code = format!(
+                r#"import apis from "__DESKULPT_APIS_BLOB_URL__";
+(window.__DESKULPT_WIDGET_APIS__ ??= {{}})[{id}] = apis;
+const {{ default: Widget }} = await import({entry_url});
+export default Widget;"#,
+                entry_url = serde_json::to_string(&entry_url)?,
+            );
+            let output = BundleOutput {
+                code,
+                source_map: entry.map.clone(),
+                size,
+            };
+            return Ok(output);
+        }
+
         // We have supplied a single entry file, so we expect a single output
-        // bundle; this can be broken if widget code contains e.g. dynamic
-        // imports, which we do not allow
-        if result.assets.len() != 1 {
+        // chunk; this can be broken if widget code contains e.g. dynamic
+        // imports, which are only allowed if the widget opts in to code
+        // splitting
+        if chunks.len() != 1 {
             bail!(
                 "Expected 1 bundled output, found {}; ensure that widget code does not contain \
-                 e.g. dynamic imports that may result in extra chunks",
-                result.assets.len()
+                 e.g. dynamic imports that may result in extra chunks, or set `codeSplitting` in \
+                 the widget manifest to allow them",
+                chunks.len()
             );
         }
 
-        let output = &result.assets[0];
-        let code = match output {
-            Output::Asset(asset) => asset.source.clone().try_into_string()?,
-            Output::Chunk(chunk) => chunk.code.clone(),
+        let output = BundleOutput {
+            code: chunks[0].code.clone(),
+            source_map: chunks[0].map.clone(),
+            size,
         };
-        Ok(code)
+        Ok(output)
     }
 }
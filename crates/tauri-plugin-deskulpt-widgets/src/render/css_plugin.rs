@@ -0,0 +1,132 @@
+//! Deskulpt CSS plugin for rolldown.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use anyhow::Context;
+use regex::{Captures, Regex};
+use rolldown::plugin::{
+    HookLoadArgs, HookLoadOutput, HookLoadReturn, HookUsage, Plugin, PluginContext,
+};
+use rolldown_common::ModuleType;
+
+/// Regex for class selectors in a CSS selector list.
+static CLASS_SELECTOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.(-?[_a-zA-Z][_a-zA-Z0-9-]*)").unwrap());
+
+/// Scope the class selectors in a CSS module.
+///
+/// Each class name in selectors is suffixed with `suffix`. Declarations and
+/// at-rule preludes (e.g., `@media` queries) are left untouched, and comments
+/// are stripped. This returns the scoped CSS and the mapping from original to
+/// scoped class names.
+fn scope_css_module(css: &str, suffix: &str) -> (String, BTreeMap<String, String>) {
+    let mut scoped = String::with_capacity(css.len());
+    let mut classes = BTreeMap::new();
+    let mut segment = String::new();
+
+    let mut chars = css.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            },
+            '{' => {
+                // The segment before an opening brace is either an at-rule
+                // prelude or a selector list
+                if segment.trim_start().starts_with('@') {
+                    scoped.push_str(&segment);
+                } else {
+                    let selectors = CLASS_SELECTOR_RE.replace_all(&segment, |caps: &Captures| {
+                        let scoped_name = format!("{}_{suffix}", &caps[1]);
+                        classes.insert(caps[1].to_string(), scoped_name.clone());
+                        format!(".{scoped_name}")
+                    });
+                    scoped.push_str(&selectors);
+                }
+                scoped.push('{');
+                segment.clear();
+            },
+            '}' | ';' => {
+                scoped.push_str(&segment);
+                scoped.push(c);
+                segment.clear();
+            },
+            _ => segment.push(c),
+        }
+    }
+    scoped.push_str(&segment);
+
+    (scoped, classes)
+}
+
+/// Deskulpt CSS plugin.
+///
+/// This allows widget code to import stylesheets, e.g., `import "./style.css"`
+/// or `import styles from "./style.module.css"`. Imported styles are injected
+/// into the canvas as `<style>` elements tagged with the widget ID, so that the
+/// canvas can remove them when the widget unloads or re-renders. For CSS
+/// modules, class names are scoped per widget and file, and the default export
+/// maps the original class names to the scoped ones.
+#[derive(Debug)]
+pub struct CssPlugin {
+    /// The ID of the widget.
+    pub id: String,
+}
+
+impl Plugin for CssPlugin {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("deskulpt:css")
+    }
+
+    async fn load(&self, _ctx: &PluginContext, args: &HookLoadArgs<'_>) -> HookLoadReturn {
+        if !args.id.ends_with(".css") {
+            return Ok(None);
+        }
+
+        let path = Path::new(args.id);
+        let css = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read stylesheet: {}", path.display()))?;
+
+        let (css, classes) = if args.id.ends_with(".module.css") {
+            let mut hasher = DefaultHasher::new();
+            (&self.id, args.id).hash(&mut hasher);
+            let suffix = format!("{:08x}", hasher.finish() as u32);
+            scope_css_module(&css, &suffix)
+        } else {
+            (css, BTreeMap::new())
+        };
+
+        let code = format!(
+            r#"const style = document.createElement("style");
+style.dataset.deskulptWidget = {id};
+style.textContent = {css};
+document.head.appendChild(style);
+export default {classes};"#,
+            id = serde_json::to_string(&self.id)?,
+            css = serde_json::to_string(&css)?,
+            classes = serde_json::to_string(&classes)?,
+        );
+
+        Ok(Some(HookLoadOutput {
+            code: code.into(),
+            module_type: Some(ModuleType::Js),
+            ..Default::default()
+        }))
+    }
+
+    fn register_hook_usage(&self) -> HookUsage {
+        HookUsage::Load
+    }
+}
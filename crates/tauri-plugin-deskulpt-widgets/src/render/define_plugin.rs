@@ -0,0 +1,107 @@
+//! Deskulpt define plugin for rolldown.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::LazyLock;
+
+use parking_lot::Mutex;
+use regex::Regex;
+use rolldown::plugin::{
+    HookTransformArgs, HookTransformOutput, HookTransformReturn, HookUsage, Plugin,
+    SharedTransformPluginContext,
+};
+use rolldown_common::ModuleType;
+
+/// Regex for possibly dotted identifiers, e.g., `foo` or `foo.bar.baz`.
+static IDENTIFIER_PATH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z_$][A-Za-z0-9_$]*(?:\.[A-Za-z_$][A-Za-z0-9_$]*)*").unwrap()
+});
+
+/// Deskulpt define plugin.
+///
+/// This replaces compile-time constants in widget code with their values, in
+/// the same spirit as the `define` option of other bundlers. Replacement is
+/// textual on identifier boundaries: an identifier path is replaced if it
+/// equals a constant or starts with a constant followed by a property access,
+/// unless it is itself a property access (e.g., `obj.__WIDGET_ID__`).
+#[derive(Debug)]
+pub struct DefinePlugin {
+    /// The constants mapping from identifiers to JavaScript expressions.
+    pub constants: BTreeMap<String, String>,
+    /// The constants that have been replaced in any module so far.
+    pub used: Mutex<BTreeSet<String>>,
+}
+
+impl DefinePlugin {
+    /// Create a new [`DefinePlugin`] instance with the given constants.
+    pub fn new(constants: BTreeMap<String, String>) -> Self {
+        Self {
+            constants,
+            used: Default::default(),
+        }
+    }
+
+    /// Find the longest constant that the identifier path refers to.
+    fn lookup(&self, path: &str) -> Option<(&str, &str)> {
+        self.constants
+            .iter()
+            .filter(|(name, _)| {
+                path.strip_prefix(name.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            })
+            .max_by_key(|(name, _)| name.len())
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+impl Plugin for DefinePlugin {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("deskulpt:define")
+    }
+
+    async fn transform(
+        &self,
+        _ctx: SharedTransformPluginContext,
+        args: &HookTransformArgs<'_>,
+    ) -> HookTransformReturn {
+        if self.constants.is_empty()
+            || !matches!(
+                args.module_type,
+                ModuleType::Js | ModuleType::Jsx | ModuleType::Ts | ModuleType::Tsx
+            )
+        {
+            return Ok(None);
+        }
+
+        let code = args.code.as_str();
+        let mut replaced = String::with_capacity(code.len());
+        let mut last = 0;
+        let mut used = BTreeSet::new();
+        for m in IDENTIFIER_PATH_RE.find_iter(code) {
+            let before = &code[..m.start()];
+            if before.ends_with('.') && !before.ends_with("...") {
+                continue;
+            }
+            if let Some((name, value)) = self.lookup(m.as_str()) {
+                replaced.push_str(&code[last..m.start()]);
+                replaced.push_str(&format!("({value})"));
+                last = m.start() + name.len();
+                used.insert(name.to_string());
+            }
+        }
+        if used.is_empty() {
+            return Ok(None);
+        }
+        replaced.push_str(&code[last..]);
+        self.used.lock().extend(used);
+
+        Ok(Some(HookTransformOutput {
+            code: Some(replaced),
+            ..Default::default()
+        }))
+    }
+
+    fn register_hook_usage(&self) -> HookUsage {
+        HookUsage::Transform
+    }
+}
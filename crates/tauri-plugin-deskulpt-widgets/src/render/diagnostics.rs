@@ -0,0 +1,131 @@
+//! Structured diagnostics and source mapping for widget bundling.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use rolldown_sourcemap::SourceMap;
+use serde::Serialize;
+
+/// Regex for locating `file:line:column` in a rendered rolldown diagnostic.
+///
+/// Rolldown renders the location of a diagnostic in the form of
+/// `╭─[ file:line:column ]`.
+static LOCATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"─\[\s*(.+?):(\d+):(\d+)\s*\]").unwrap());
+
+/// Regex for locating `blob:...:line:column` frames in a runtime stack trace.
+static STACK_FRAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"blob:[^\s()]+?:(\d+):(\d+)").unwrap());
+
+/// A structured diagnostic produced when bundling a widget.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleDiagnostic {
+    /// The diagnostic message.
+    pub message: String,
+    /// The file that the diagnostic refers to, relative to the widget root.
+    pub file: Option<String>,
+    /// The 1-based line number in the file.
+    pub line: Option<u32>,
+    /// The 1-based column number in the file.
+    pub column: Option<u32>,
+    /// The code snippet around the location, as rendered by the bundler.
+    pub snippet: Option<String>,
+}
+
+impl BundleDiagnostic {
+    /// Parse a diagnostic rendered by rolldown.
+    ///
+    /// The first line is taken as the message. If a location can be found,
+    /// the file, line, column, and the rest of the rendered text as snippet
+    /// are extracted as well. Unrecognized formats fall back to the whole
+    /// rendered text as the message.
+    pub fn parse(rendered: &str) -> Self {
+        let (message, rest) = rendered.split_once('\n').unwrap_or((rendered, ""));
+        let mut diagnostic = Self {
+            message: message.trim().to_string(),
+            file: None,
+            line: None,
+            column: None,
+            snippet: None,
+        };
+
+        if let Some(captures) = LOCATION_RE.captures(rest) {
+            diagnostic.file = Some(captures[1].to_string());
+            diagnostic.line = captures[2].parse().ok();
+            diagnostic.column = captures[3].parse().ok();
+            diagnostic.snippet = Some(rest.to_string());
+        }
+        diagnostic
+    }
+}
+
+/// Error returned when bundling a widget fails.
+///
+/// This carries the structured diagnostics from the bundler. Its display
+/// representation is the concatenation of the diagnostic messages.
+#[derive(Debug)]
+pub struct BundleError {
+    /// The rendered diagnostics.
+    pub rendered: Vec<String>,
+    /// The structured diagnostics.
+    pub diagnostics: Vec<BundleDiagnostic>,
+}
+
+impl BundleError {
+    /// Create a new [`BundleError`] from rendered diagnostics.
+    pub fn new(rendered: Vec<String>) -> Self {
+        let diagnostics = rendered
+            .iter()
+            .map(|r| BundleDiagnostic::parse(r))
+            .collect();
+        Self {
+            rendered,
+            diagnostics,
+        }
+    }
+}
+
+impl std::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rendered.join("\n"))
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// Map a runtime stack trace of a widget back to its original sources.
+///
+/// Frames pointing into the evaluated widget module (loaded from a blob URL)
+/// are rewritten to `source:line:column` of the original widget sources using
+/// the given source map. Frames that cannot be mapped are kept as is.
+pub fn symbolicate(stack: &str, source_map: &SourceMap) -> String {
+    let lookup_table = source_map.generate_lookup_table();
+    STACK_FRAME_RE
+        .replace_all(stack, |captures: &regex::Captures| {
+            let original = captures[0].to_string();
+            let (Ok(line), Ok(column)) = (captures[1].parse::<u32>(), captures[2].parse::<u32>())
+            else {
+                return original;
+            };
+            let Some(token) = source_map.lookup_token(
+                &lookup_table,
+                line.saturating_sub(1),
+                column.saturating_sub(1),
+            ) else {
+                return original;
+            };
+            let Some(source) = token
+                .get_source_id()
+                .and_then(|id| source_map.get_source(id))
+            else {
+                return original;
+            };
+            format!(
+                "{source}:{}:{}",
+                token.get_src_line() + 1,
+                token.get_src_col() + 1
+            )
+        })
+        .into_owned()
+}
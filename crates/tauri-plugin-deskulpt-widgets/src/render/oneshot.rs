@@ -0,0 +1,76 @@
+//! One-off bundling of Deskulpt widgets outside of the render worker.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_deskulpt_settings::SettingsExt;
+
+use crate::catalog::WidgetManifest;
+use crate::render::bundler::{BundleOutput, Bundler};
+use crate::render::diagnostics::BundleDiagnostic;
+use crate::render::type_check::{is_type_checkable, type_check_or_report};
+use crate::render::worker::constants;
+use crate::strict::StrictWarning;
+
+/// The result of a one-off bundle of a widget.
+pub struct OneshotBundle {
+    /// The bundle output and the strict mode warnings, or the bundling error.
+    ///
+    /// The strict mode warnings are empty if the widget is not in strict mode.
+    pub result: Result<(BundleOutput, Vec<StrictWarning>)>,
+    /// The type errors found if type checking is enabled for the widget.
+    pub warnings: Vec<BundleDiagnostic>,
+}
+
+/// Bundle a widget once with a fresh bundler.
+///
+/// The widget manifest is loaded from the given widget directory, regardless
+/// of whether the widget is in the catalog, and static assets are written to
+/// the given assets directory. Type checking and strict mode follow the
+/// manifest and the application settings as rendering does. Neither the catalog
+/// nor the render worker is touched. An error is returned if the manifest
+/// cannot be loaded.
+pub async fn bundle_oneshot<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+    widget_dir: &Path,
+    assets_dir: PathBuf,
+) -> Result<OneshotBundle> {
+    let Some(manifest) = WidgetManifest::load(widget_dir)? else {
+        bail!("{id} is not a widget");
+    };
+
+    let (type_check_enabled, strict) = {
+        let settings = app_handle.settings().read();
+        (
+            manifest.type_check.unwrap_or(settings.type_check_widgets),
+            manifest.strict.unwrap_or(settings.strict_widgets),
+        )
+    };
+    let type_check_enabled = type_check_enabled && is_type_checkable(widget_dir, &manifest.entry);
+    let type_check_task = async {
+        if !type_check_enabled {
+            return vec![];
+        }
+        type_check_or_report(widget_dir, id, &manifest.entry).await
+    };
+
+    let bundle_task = async {
+        let constants = constants(app_handle, id, &manifest.define)?;
+        let mut bundler = Bundler::new(
+            id.to_string(),
+            widget_dir.to_path_buf(),
+            manifest.entry.clone(),
+            assets_dir,
+            constants,
+            manifest.code_splitting,
+            strict,
+        )?;
+        let output = bundler.bundle().await?;
+        anyhow::Ok((output, bundler.strict_warnings()))
+    };
+    let (result, warnings) = tokio::join!(bundle_task, type_check_task);
+
+    Ok(OneshotBundle { result, warnings })
+}
@@ -0,0 +1,110 @@
+//! Deskulpt strict plugin for rolldown.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use parking_lot::Mutex;
+use regex::Regex;
+use rolldown::plugin::{
+    HookTransformArgs, HookTransformReturn, HookUsage, Plugin, SharedTransformPluginContext,
+};
+
+use crate::strict::{DEPRECATED_GLOBALS, DEPRECATED_IMPORTS, StrictWarning};
+
+/// Regex for module specifiers in static imports, re-exports, and dynamic
+/// imports.
+static SPECIFIER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:\bfrom\s*|\bimport\s*\(?\s*)["']([^"']+)["']"#).unwrap());
+
+/// Deskulpt strict plugin.
+///
+/// This detects usage of deprecated APIs in the widget sources, which is
+/// enabled in strict mode only. Detection is textual and may thus have false
+/// positives, e.g., in comments. Since bundles are rebuilt incrementally and
+/// only changed modules are transformed, findings are kept per module.
+#[derive(Debug)]
+pub struct StrictPlugin {
+    /// The root directory of the widget.
+    pub root: PathBuf,
+    /// The warnings found so far, keyed by module ID.
+    pub found: Mutex<BTreeMap<String, Vec<StrictWarning>>>,
+}
+
+impl StrictPlugin {
+    /// Create a new [`StrictPlugin`] instance for the widget root directory.
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            found: Default::default(),
+        }
+    }
+
+    /// Get all warnings found so far.
+    pub fn warnings(&self) -> Vec<StrictWarning> {
+        self.found.lock().values().flatten().cloned().collect()
+    }
+
+    /// Get the path of a module relative to the widget root, if possible.
+    fn relative_path(&self, id: &str) -> String {
+        Path::new(id)
+            .strip_prefix(&self.root)
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| id.to_string())
+    }
+}
+
+impl Plugin for StrictPlugin {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("deskulpt:strict")
+    }
+
+    async fn transform(
+        &self,
+        _ctx: SharedTransformPluginContext,
+        args: &HookTransformArgs<'_>,
+    ) -> HookTransformReturn {
+        // Dependencies are not under the control of widget authors
+        if args.id.contains("node_modules") {
+            return Ok(None);
+        }
+
+        let code = args.code.as_str();
+        let file = Some(self.relative_path(args.id));
+        let mut warnings = vec![];
+        for captures in SPECIFIER_RE.captures_iter(code) {
+            if let Some((api, advice)) = DEPRECATED_IMPORTS
+                .iter()
+                .find(|(api, _)| *api == &captures[1])
+            {
+                warnings.push(StrictWarning::DeprecatedApi {
+                    api: api.to_string(),
+                    advice: advice.to_string(),
+                    file: file.clone(),
+                });
+            }
+        }
+        for (api, advice) in DEPRECATED_GLOBALS {
+            if code.contains(api) {
+                warnings.push(StrictWarning::DeprecatedApi {
+                    api: api.to_string(),
+                    advice: advice.to_string(),
+                    file: file.clone(),
+                });
+            }
+        }
+
+        let mut found = self.found.lock();
+        if warnings.is_empty() {
+            found.remove(args.id);
+        } else {
+            found.insert(args.id.to_string(), warnings);
+        }
+        Ok(None)
+    }
+
+    fn register_hook_usage(&self) -> HookUsage {
+        HookUsage::Transform
+    }
+}
@@ -0,0 +1,185 @@
+//! Optional type checking of TypeScript widgets.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use tokio::process::Command;
+
+use crate::render::BundleDiagnostic;
+
+/// The type checkers to look for, in order of preference.
+///
+/// `tsgo` is the native port of the TypeScript compiler and is much faster,
+/// so it is preferred if available.
+const CHECKERS: &[&str] = &["tsgo", "tsc"];
+
+/// Compiler options used when the widget does not have a `tsconfig.json`.
+///
+/// These mirror how widgets are bundled: ES modules resolved in bundler style
+/// with the automatic JSX runtime.
+const DEFAULT_OPTIONS: &[&str] = &[
+    "--strict",
+    "--skipLibCheck",
+    "--jsx",
+    "react-jsx",
+    "--module",
+    "esnext",
+    "--moduleResolution",
+    "bundler",
+    "--target",
+    "esnext",
+];
+
+/// Regex for a diagnostic line in the non-pretty output of the type checker.
+///
+/// The type checker reports a diagnostic in the form of
+/// `file(line,column): error TS1234: message`, with the message possibly
+/// continued on subsequent indented lines.
+static DIAGNOSTIC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(.+?)\((\d+),(\d+)\): (?:error|warning) (TS\d+): (.*)$").unwrap()
+});
+
+/// Find the type checker executable for a widget.
+///
+/// Type checkers installed locally in the widget take precedence over those
+/// on `PATH`.
+fn find_checker(widget_dir: &Path) -> Option<PathBuf> {
+    let local_bin = widget_dir.join("node_modules").join(".bin");
+    let dirs = std::iter::once(local_bin).chain(
+        std::env::var_os("PATH")
+            .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+            .unwrap_or_default(),
+    );
+    let dirs = dirs.collect::<Vec<_>>();
+
+    CHECKERS.iter().find_map(|name| {
+        dirs.iter().find_map(|dir| {
+            let candidates = if cfg!(windows) {
+                vec![
+                    dir.join(format!("{name}.cmd")),
+                    dir.join(format!("{name}.exe")),
+                ]
+            } else {
+                vec![dir.join(name)]
+            };
+            candidates.into_iter().find(|path| path.is_file())
+        })
+    })
+}
+
+/// Parse the non-pretty output of the type checker into diagnostics.
+fn parse_output(output: &str) -> Vec<BundleDiagnostic> {
+    let mut diagnostics: Vec<BundleDiagnostic> = vec![];
+    for line in output.lines() {
+        if let Some(captures) = DIAGNOSTIC_RE.captures(line) {
+            diagnostics.push(BundleDiagnostic {
+                message: format!("{}: {}", &captures[4], &captures[5]),
+                file: Some(captures[1].replace('\\', "/")),
+                line: captures[2].parse().ok(),
+                column: captures[3].parse().ok(),
+                snippet: None,
+            });
+        } else if line.starts_with(' ')
+            && let Some(last) = diagnostics.last_mut()
+        {
+            last.message.push('\n');
+            last.message.push_str(line.trim());
+        } else if !line.trim().is_empty() {
+            diagnostics.push(BundleDiagnostic {
+                message: line.trim().to_string(),
+                file: None,
+                line: None,
+                column: None,
+                snippet: None,
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Whether a widget should be type checked.
+///
+/// This is the case if the widget has a `tsconfig.json` or its entry file is a
+/// TypeScript file. Plain JavaScript widgets are not checked.
+pub fn is_type_checkable(widget_dir: &Path, entry: &str) -> bool {
+    widget_dir.join("tsconfig.json").is_file()
+        || Path::new(entry)
+            .extension()
+            .is_some_and(|ext| ext == "ts" || ext == "tsx")
+}
+
+/// Type check a widget.
+///
+/// This invokes `tsgo` or `tsc` without emitting output. If the widget has a
+/// `tsconfig.json`, the project is checked as configured; otherwise, the entry
+/// file is checked with default options. The type errors are returned as
+/// diagnostics with files relative to the widget root. If no type checker is
+/// found, a single diagnostic saying so is returned instead. An error is
+/// returned only if the type checker cannot be run.
+pub async fn type_check(widget_dir: &Path, entry: &str) -> Result<Vec<BundleDiagnostic>> {
+    let Some(checker) = find_checker(widget_dir) else {
+        return Ok(vec![BundleDiagnostic {
+            message: "Type checking is enabled but neither tsgo nor tsc is found; install either \
+                      in the widget or globally"
+                .to_string(),
+            file: None,
+            line: None,
+            column: None,
+            snippet: None,
+        }]);
+    };
+
+    let mut command = Command::new(&checker);
+    command
+        .current_dir(widget_dir)
+        .args(["--noEmit", "--pretty", "false"])
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    if widget_dir.join("tsconfig.json").is_file() {
+        command.args(["--project", "."]);
+    } else {
+        command.args(DEFAULT_OPTIONS).arg(entry);
+    }
+
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {}", checker.display()))?;
+
+    // The type checker exits with a non-zero code when there are type errors,
+    // which are reported on stdout, so the exit code is not an error here
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut diagnostics = parse_output(&stdout);
+    if diagnostics.is_empty() && !output.status.success() {
+        diagnostics = parse_output(&String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(diagnostics)
+}
+
+/// Type check a widget, reporting failures to run the type checker as
+/// diagnostics.
+///
+/// This is [`type_check`] except that an error is logged and turned into a
+/// single diagnostic saying so, which suits reporting alongside bundling.
+pub async fn type_check_or_report(
+    widget_dir: &Path,
+    id: &str,
+    entry: &str,
+) -> Vec<BundleDiagnostic> {
+    match type_check(widget_dir, entry).await {
+        Ok(diagnostics) => diagnostics,
+        Err(e) => {
+            tracing::warn!("Failed to type check widget {id}: {e:?}");
+            vec![BundleDiagnostic {
+                message: format!("Failed to type check the widget: {e}"),
+                file: None,
+                line: None,
+                column: None,
+                snippet: None,
+            }]
+        },
+    }
+}
@@ -0,0 +1,94 @@
+//! Validation of Deskulpt widgets without rendering.
+
+use std::path::Path;
+
+use deskulpt_common::outcome::Outcome;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::render::diagnostics::{BundleDiagnostic, BundleError};
+use crate::render::oneshot::bundle_oneshot;
+use crate::strict::StrictWarning;
+
+/// The report of validating a widget.
+///
+/// This mirrors the [`RenderEvent`](crate::events::RenderEvent) that rendering
+/// the widget would emit, with the bundled code replaced by its size.
+#[derive(Debug, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    /// Either the bundle size in bytes or an error message.
+    ///
+    /// The error may come from loading the widget manifest or from bundling.
+    pub report: Outcome<u64>,
+    /// Structured diagnostics if bundling failed.
+    pub diagnostics: Vec<BundleDiagnostic>,
+    /// Structured warnings if bundling succeeded.
+    ///
+    /// These are the type errors found if type checking is enabled for the
+    /// widget.
+    pub warnings: Vec<BundleDiagnostic>,
+    /// The warnings reported if the widget is in strict mode.
+    pub strict_warnings: Vec<StrictWarning>,
+}
+
+impl ValidationReport {
+    /// Create a report of a failure with an error message only.
+    fn failure(message: String) -> Self {
+        Self {
+            report: Outcome::Err(message),
+            diagnostics: vec![],
+            warnings: vec![],
+            strict_warnings: vec![],
+        }
+    }
+}
+
+/// Validate a widget in a directory by loading its manifest and bundling it.
+///
+/// The directory need not be the widget directory of the given ID, e.g., it
+/// can be a staging directory of an update to the widget. The widget is bundled
+/// once with a fresh bundler, whose assets are written to a scratch directory
+/// and removed afterwards, so that neither the canvas nor the render worker is
+/// affected.
+pub async fn validate<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+    widget_dir: &Path,
+) -> ValidationReport {
+    let scratch_dir = match app_handle.path().app_cache_dir() {
+        Ok(dir) => dir.join("widget-validation").join(id),
+        Err(e) => return ValidationReport::failure(format!("{e:?}")),
+    };
+    let bundle = bundle_oneshot(app_handle, id, widget_dir, scratch_dir.clone()).await;
+
+    if scratch_dir.exists()
+        && let Err(e) = std::fs::remove_dir_all(&scratch_dir)
+    {
+        tracing::warn!("Failed to clean up validation of widget {id}: {e:?}");
+    }
+
+    let bundle = match bundle {
+        Ok(bundle) => bundle,
+        Err(e) => return ValidationReport::failure(format!("{e:?}")),
+    };
+    match bundle.result {
+        Ok((output, strict_warnings)) => ValidationReport {
+            report: Outcome::Ok(output.size),
+            diagnostics: vec![],
+            warnings: bundle.warnings,
+            strict_warnings,
+        },
+        Err(e) => {
+            let diagnostics = e
+                .downcast_ref::<BundleError>()
+                .map(|e| e.diagnostics.clone())
+                .unwrap_or_default();
+            // Type errors are moot if the widget does not even bundle
+            ValidationReport {
+                diagnostics,
+                ..ValidationReport::failure(format!("{e:?}"))
+            }
+        },
+    }
+}
@@ -1,14 +1,38 @@
-//! Render worker for Deskulpt widgets.
+//! Render worker pool for Deskulpt widgets.
 
-use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use anyhow::{Result, anyhow};
 use deskulpt_common::event::Event;
-use deskulpt_common::window::DeskulptWindow;
+use deskulpt_common::metrics;
+use deskulpt_common::window::{DeskulptWindow, detached_widget_label};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Runtime};
-use tokio::sync::mpsc;
+use tauri_plugin_deskulpt_settings::SettingsExt;
+use tokio::sync::{Semaphore, mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use crate::WidgetsExt;
+use crate::catalog::BundleStats;
 use crate::events::RenderEvent;
 use crate::render::bundler::Bundler;
+use crate::render::diagnostics::BundleError;
+use crate::render::type_check::{is_type_checkable, type_check_or_report};
+use crate::strict::{MAX_EVENT_PAYLOAD, StrictWarning};
+
+/// The maximum default number of widgets bundled concurrently.
+///
+/// The default concurrency is the available parallelism capped by this, since
+/// bundling is CPU-heavy and the machine is shared with the widgets themselves.
+const MAX_DEFAULT_CONCURRENCY: usize = 4;
 
 /// Tasks that the render worker can process.
 #[derive(Debug)]
@@ -16,60 +40,609 @@ pub enum RenderWorkerTask {
     /// Bundle and render a widget.
     ///
     /// The worker will use [`Bundler`] to bundle the specified widget at the
-    /// specified entry file. Upon completion, a [`RenderEvent`] will be emitted
-    /// to the canvas with the bundling result, whether success or
-    /// failure.
+    /// specified entry file, with the built-in compile-time constants and the
+    /// specified ones injected. The bundler is kept for subsequent renders of
+    /// the same widget to rebuild incrementally, unless the entry file or the
+    /// constants change or bundling fails. If type checking is enabled, the
+    /// widget is type checked alongside bundling. Upon completion, a
+    /// [`RenderEvent`] will be emitted to the canvas with the bundling result,
+    /// whether success or failure, and the type errors as warnings. The source
+    /// map of a successful bundle is kept in the manager for mapping runtime
+    /// stack traces back to original sources, and the widget is watched for
+    /// render timeout. In strict mode, the strict warnings of the widget are
+    /// updated as well.
+    ///
+    /// Different widgets are rendered concurrently, up to the configured
+    /// concurrency, while renders of the same widget are processed in order.
+    /// A render superseded by a newer one of the same widget is cancelled if
+    /// still queued, or its result is dropped if already in flight, so that
+    /// stale results never overwrite newer ones. Renders of a frozen widget
+    /// are skipped once it has been successfully bundled, until its render
+    /// state is reset, e.g., because it is unloaded.
     Render {
         /// The widget ID.
         id: String,
         /// The entry file path relative to the root of the widget.
         entry: String,
+        /// The compile-time constants defined in the widget manifest.
+        define: BTreeMap<String, Value>,
+        /// Whether the widget opts in to code splitting.
+        code_splitting: bool,
+        /// Whether to type check the widget.
+        ///
+        /// If `None`, this falls back to the application settings.
+        type_check: Option<bool>,
+        /// Whether the widget is in strict mode.
+        ///
+        /// If `None`, this falls back to the application settings.
+        strict: Option<bool>,
+        /// Whether the widget is frozen.
+        frozen: bool,
+    },
+    /// Re-render the widgets whose bundles use the `__THEME__` constant.
+    ///
+    /// This should be sent whenever the theme changes, so that the constant
+    /// does not go stale.
+    RenderThemed,
+    /// Retain the bundlers of the specified widgets only.
+    ///
+    /// Bundlers kept for all other widgets are dropped, including those
+    /// currently in use once their renders complete. This should be sent
+    /// whenever widgets are removed or unloaded, so that their bundlers do not
+    /// linger in memory.
+    Retain {
+        /// The IDs of the widgets whose bundlers should be retained.
+        ids: HashSet<String>,
     },
 }
 
+/// Messages processed by the render worker loop.
+enum RenderWorkerMessage {
+    /// A task submitted via [`RenderWorkerHandle::process`].
+    Task(RenderWorkerTask),
+    /// A render of a widget has completed.
+    Done {
+        /// The widget ID.
+        id: String,
+        /// The bundler to keep for subsequent renders, if reusable.
+        bundler: Option<CachedBundler>,
+    },
+    /// A ping submitted via [`RenderWorkerHandle::ping`].
+    Ping(oneshot::Sender<()>),
+}
+
+/// The parameters of rendering a widget.
+///
+/// See [`RenderWorkerTask::Render`] for details.
+#[derive(Debug, Clone)]
+struct RenderParams {
+    /// The entry file path relative to the root of the widget.
+    entry: String,
+    /// The compile-time constants defined in the widget manifest.
+    define: BTreeMap<String, Value>,
+    /// Whether the widget opts in to code splitting.
+    code_splitting: bool,
+    /// Whether to type check the widget.
+    type_check: Option<bool>,
+    /// Whether the widget is in strict mode.
+    strict: Option<bool>,
+    /// Whether the widget is frozen.
+    frozen: bool,
+}
+
+/// The timing breakdown of the latest render of a widget.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderTimings {
+    /// The time spent waiting for a free render slot in milliseconds.
+    pub queued_ms: u64,
+    /// The time taken to bundle in milliseconds.
+    ///
+    /// This includes creating the bundler if it cannot be reused.
+    pub bundle_ms: u64,
+    /// The time taken to type check in milliseconds.
+    ///
+    /// This is `None` if type checking is disabled for the widget. Type
+    /// checking runs alongside bundling.
+    pub type_check_ms: Option<u64>,
+    /// The time taken to emit the render event in milliseconds.
+    pub emit_ms: u64,
+    /// The total time from submitting the render to emitting the render event
+    /// in milliseconds.
+    pub total_ms: u64,
+}
+
+/// A long-lived bundler of a widget kept by the render worker.
+struct CachedBundler {
+    /// The entry file path the bundler is created with.
+    entry: String,
+    /// The full compile-time constants the bundler is created with.
+    constants: BTreeMap<String, String>,
+    /// Whether the bundler is created with code splitting.
+    code_splitting: bool,
+    /// Whether the bundler is created in strict mode.
+    strict: bool,
+    /// The bundler.
+    bundler: Bundler,
+}
+
+/// The render state of a widget kept by the render worker.
+#[derive(Default)]
+struct WidgetRenderState {
+    /// The bundler kept for the widget, if any and not in use.
+    bundler: Option<CachedBundler>,
+    /// Whether a render of the widget is in flight.
+    busy: bool,
+    /// Whether the bundler should be dropped once the in-flight render
+    /// completes.
+    discard: bool,
+    /// Whether the bundler kept for the widget uses the `__THEME__` constant.
+    themed: bool,
+    /// Whether the widget has been successfully bundled since its render state
+    /// was last reset.
+    ///
+    /// Further renders of the widget are skipped while it is frozen.
+    rendered: bool,
+    /// The parameters of the latest render, for re-rendering.
+    params: Option<RenderParams>,
+    /// The render waiting for the in-flight one to complete.
+    ///
+    /// Only the latest one is kept, since earlier ones are superseded.
+    pending: Option<RenderParams>,
+    /// The generation of the latest submitted render.
+    ///
+    /// This is bumped on every submission and shared with the in-flight
+    /// render, which drops its result if its own generation is outdated.
+    generation: Arc<AtomicU64>,
+}
+
+/// Compute the compile-time constants of a widget.
+///
+/// This includes the constants defined in the widget manifest and the built-in
+/// constants, where the latter take precedence. Values are serialized as
+/// JavaScript expressions.
+pub(super) fn constants<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+    define: &BTreeMap<String, Value>,
+) -> Result<BTreeMap<String, String>> {
+    let mut constants = define
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), serde_json::to_string(value)?)))
+        .collect::<Result<BTreeMap<_, _>>>()?;
+    constants.insert(
+        "__DESKULPT_VERSION__".to_string(),
+        serde_json::to_string(&app_handle.package_info().version.to_string())?,
+    );
+    constants.insert("__WIDGET_ID__".to_string(), serde_json::to_string(id)?);
+    constants.insert(
+        "__THEME__".to_string(),
+        serde_json::to_string(&app_handle.settings().read().theme)?,
+    );
+    Ok(constants)
+}
+
+/// Bundle and render a widget.
+///
+/// The given bundler is reused if it is compatible with the parameters. If the
+/// render is superseded by the time bundling completes, as told by the given
+/// closure, its result is dropped without side effects. Otherwise, the timing
+/// breakdown since the given submission time is recorded. This returns the
+/// bundler to keep for subsequent renders, which is `None` if bundling fails.
+/// See [`RenderWorkerTask::Render`] for details.
+async fn render<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    cached: Option<CachedBundler>,
+    id: &str,
+    params: RenderParams,
+    submitted: Instant,
+    is_superseded: impl Fn() -> bool,
+) -> Option<CachedBundler> {
+    let queued = submitted.elapsed();
+    let RenderParams {
+        entry,
+        define,
+        code_splitting,
+        type_check: type_check_option,
+        strict,
+        frozen: _,
+    } = params;
+
+    let widget_dir = app_handle.widgets().widget_dir(id);
+    let type_check_enabled = type_check_option
+        .unwrap_or_else(|| app_handle.settings().read().type_check_widgets)
+        && is_type_checkable(&widget_dir, &entry);
+    let strict = strict.unwrap_or_else(|| app_handle.settings().read().strict_widgets);
+    let type_check_task = async {
+        if !type_check_enabled {
+            return (vec![], None);
+        }
+        let start = Instant::now();
+        let diagnostics = type_check_or_report(&widget_dir, id, &entry)
+            .instrument(tracing::info_span!("type_check"))
+            .await;
+        (diagnostics, Some(start.elapsed()))
+    };
+
+    let bundle_start = Instant::now();
+    let bundle_task = async {
+        let constants = constants(app_handle, id, &define)?;
+        let mut cached = match cached {
+            Some(cached)
+                if cached.entry == entry
+                    && cached.constants == constants
+                    && cached.code_splitting == code_splitting
+                    && cached.strict == strict =>
+            {
+                cached
+            },
+            _ => {
+                let assets_dir = app_handle.widgets().assets_dir().join(id);
+                let bundler = Bundler::new(
+                    id.to_string(),
+                    widget_dir.clone(),
+                    entry.clone(),
+                    assets_dir,
+                    constants.clone(),
+                    code_splitting,
+                    strict,
+                )?;
+                CachedBundler {
+                    entry: entry.clone(),
+                    constants,
+                    code_splitting,
+                    strict,
+                    bundler,
+                }
+            },
+        };
+        let start = Instant::now();
+        let output = cached.bundler.bundle().await?;
+        anyhow::Ok((output, start.elapsed(), cached))
+    }
+    .instrument(tracing::info_span!("bundle"));
+    let bundle_task = async {
+        let result = bundle_task.await;
+        (result, bundle_start.elapsed())
+    };
+    let ((result, bundle_duration), (mut warnings, type_check_duration)) =
+        tokio::join!(bundle_task, type_check_task);
+
+    // The incremental state is still valid, so the bundler is kept for the
+    // newer render
+    if is_superseded() {
+        tracing::debug!("Dropping superseded render result of widget {id}");
+        return result.ok().map(|(_, _, cached)| cached);
+    }
+
+    // A failed bundle may leave the incremental state inconsistent, so the
+    // next render should start afresh
+    let mut kept = None;
+    let mut diagnostics = vec![];
+    let report = match result {
+        Ok((output, duration, cached)) => {
+            metrics::counter("bundle.successes").increment();
+            if strict {
+                let mut strict_warnings = cached.bundler.strict_warnings();
+                let size = output.code.len() as u64;
+                if size > MAX_EVENT_PAYLOAD {
+                    strict_warnings.push(StrictWarning::OversizedEventPayload {
+                        event: "RenderEvent".to_string(),
+                        size,
+                        limit: MAX_EVENT_PAYLOAD,
+                    });
+                }
+                if let Err(e) = app_handle
+                    .widgets()
+                    .set_strict_warnings(id, strict_warnings)
+                {
+                    tracing::error!("Failed to set strict warnings for widget {id}: {e:?}");
+                }
+            }
+            kept = Some(cached);
+            let stats = BundleStats {
+                size: output.size,
+                duration_ms: duration.as_millis() as u64,
+            };
+            if let Err(e) = app_handle.widgets().record_bundle_stats(id, stats) {
+                tracing::error!("Failed to record bundle stats for widget {id}: {e:?}");
+            }
+            app_handle.widgets().set_source_map(id, output.source_map);
+            app_handle.widgets().watch_render_timeout(id);
+            Ok(output.code)
+        },
+        Err(e) => {
+            metrics::counter("bundle.failures").increment();
+            if let Some(e) = e.downcast_ref::<BundleError>() {
+                diagnostics = e.diagnostics.clone();
+            }
+            // Type errors are moot if the widget does not even bundle
+            warnings.clear();
+            Err(e)
+        },
+    }
+    .into();
+
+    let emit_start = Instant::now();
+    let event = RenderEvent {
+        id,
+        report: &report,
+        diagnostics: &diagnostics,
+        warnings: &warnings,
+    };
+    tracing::info_span!("emit").in_scope(|| {
+        if let Err(e) = event.emit_to(app_handle, DeskulptWindow::Canvas) {
+            tracing::error!("Failed to emit RenderEvent for widget {id}: {e:?}");
+        };
+        // The widget may be detached into its own floating window
+        if let Err(e) = event.emit_to_label(app_handle, &detached_widget_label(id)) {
+            tracing::error!("Failed to emit RenderEvent for widget {id}: {e:?}");
+        };
+    });
+    app_handle.widgets().damage_widget(id);
+
+    let timings = RenderTimings {
+        queued_ms: queued.as_millis() as u64,
+        bundle_ms: bundle_duration.as_millis() as u64,
+        type_check_ms: type_check_duration.map(|d| d.as_millis() as u64),
+        emit_ms: emit_start.elapsed().as_millis() as u64,
+        total_ms: submitted.elapsed().as_millis() as u64,
+    };
+    tracing::debug!(?timings, "Rendered widget");
+    metrics::histogram("render.queued_ms").observe(timings.queued_ms as f64);
+    metrics::histogram("render.bundle_ms").observe(timings.bundle_ms as f64);
+    metrics::histogram("render.total_ms").observe(timings.total_ms as f64);
+    app_handle.widgets().record_render_timings(id, timings);
+    kept
+}
+
+/// The render worker pool.
+///
+/// This dispatches renders to concurrent jobs bounded by a semaphore, and
+/// ensures that at most one render per widget is in flight, queueing the latest
+/// further render of the same widget until it completes.
+struct RenderWorkerPool<R: Runtime> {
+    /// The Tauri app handle.
+    app_handle: AppHandle<R>,
+    /// The semaphore bounding the number of concurrent renders.
+    semaphore: Arc<Semaphore>,
+    /// The sender for reporting completed renders back to the worker loop.
+    tx: mpsc::UnboundedSender<RenderWorkerMessage>,
+    /// The render states keyed by widget ID.
+    widgets: HashMap<String, WidgetRenderState>,
+    /// The token cancelled when the render worker is replaced.
+    ///
+    /// Renders in flight outlive the render worker, so they check this to drop
+    /// their results instead of racing renders of the new render worker.
+    cancel: CancellationToken,
+}
+
+impl<R: Runtime> RenderWorkerPool<R> {
+    /// Submit a render of a widget.
+    ///
+    /// The render is started right away if the widget is idle, or queued
+    /// otherwise, replacing any render already queued. Either way, it
+    /// supersedes all earlier renders of the widget.
+    fn submit(&mut self, id: String, params: RenderParams) {
+        let state = self.widgets.entry(id.clone()).or_default();
+        if params.frozen && state.rendered {
+            tracing::debug!("Skipped render of frozen widget {id}");
+            return;
+        }
+        let generation = state.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        if state.busy {
+            if state.pending.replace(params).is_some() {
+                tracing::debug!("Cancelled superseded queued render of widget {id}");
+            }
+            return;
+        }
+
+        state.busy = true;
+        state.params = Some(params.clone());
+        let bundler = state.bundler.take();
+        let app_handle = self.app_handle.clone();
+        let semaphore = self.semaphore.clone();
+        let tx = self.tx.clone();
+        let current = state.generation.clone();
+        let cancel = self.cancel.clone();
+        let span = tracing::info_span!("render", widget = %id);
+        tauri::async_runtime::spawn(
+            async move {
+                let submitted = Instant::now();
+                let is_superseded =
+                    || cancel.is_cancelled() || current.load(Ordering::SeqCst) != generation;
+
+                // The semaphore is never closed so acquiring cannot fail
+                let _permit = semaphore.acquire_owned().await;
+                let bundler = if is_superseded() {
+                    // Superseded while waiting for a permit, so skip bundling
+                    bundler
+                } else {
+                    render(&app_handle, bundler, &id, params, submitted, is_superseded).await
+                };
+                let _ = tx.send(RenderWorkerMessage::Done { id, bundler });
+            }
+            .instrument(span),
+        );
+    }
+
+    /// Handle a completed render of a widget.
+    ///
+    /// The returned bundler is kept unless discarded meanwhile, and the next
+    /// queued render of the widget, if any, is started.
+    fn complete(&mut self, id: String, bundler: Option<CachedBundler>) {
+        let Some(state) = self.widgets.get_mut(&id) else {
+            return;
+        };
+        state.busy = false;
+        if std::mem::take(&mut state.discard) {
+            state.themed = false;
+        } else {
+            state.themed = bundler
+                .as_ref()
+                .is_some_and(|cached| cached.bundler.uses_constant("__THEME__"));
+            state.rendered |= bundler.is_some();
+            state.bundler = bundler;
+        }
+
+        match state.pending.take() {
+            Some(params) => self.submit(id, params),
+            None if state.bundler.is_none() && state.params.is_none() => {
+                self.widgets.remove(&id);
+            },
+            None => {},
+        }
+    }
+
+    /// Update the gauges of in-flight and queued renders.
+    fn update_gauges(&self) {
+        let in_flight = self.widgets.values().filter(|state| state.busy).count();
+        let queued = self
+            .widgets
+            .values()
+            .filter(|state| state.pending.is_some())
+            .count();
+        metrics::gauge("render.in_flight").set(in_flight as f64);
+        metrics::gauge("render.queued").set(queued as f64);
+    }
+
+    /// Process a task.
+    fn process(&mut self, task: RenderWorkerTask) {
+        match task {
+            RenderWorkerTask::Render {
+                id,
+                entry,
+                define,
+                code_splitting,
+                type_check,
+                strict,
+                frozen,
+            } => {
+                let params = RenderParams {
+                    entry,
+                    define,
+                    code_splitting,
+                    type_check,
+                    strict,
+                    frozen,
+                };
+                self.submit(id, params);
+            },
+            RenderWorkerTask::RenderThemed => {
+                let themed = self
+                    .widgets
+                    .iter()
+                    .filter(|(_, state)| state.themed)
+                    .filter_map(|(id, state)| Some((id.clone(), state.params.clone()?)))
+                    .collect::<Vec<_>>();
+                for (id, params) in themed {
+                    self.submit(id, params);
+                }
+            },
+            RenderWorkerTask::Retain { ids } => {
+                self.widgets.retain(|id, state| {
+                    if ids.contains(id) {
+                        return true;
+                    }
+                    // Widgets with renders in flight must stay tracked so
+                    // that their later renders are still processed in order,
+                    // but earlier renders are superseded
+                    state.generation.fetch_add(1, Ordering::SeqCst);
+                    state.discard = state.busy;
+                    state.pending = None;
+                    state.bundler = None;
+                    state.themed = false;
+                    state.rendered = false;
+                    state.params = None;
+                    state.busy
+                });
+            },
+        }
+    }
+}
+
 /// The main render worker loop.
 async fn render_worker<R: Runtime>(
     app_handle: AppHandle<R>,
-    mut rx: mpsc::UnboundedReceiver<RenderWorkerTask>,
+    tx: mpsc::UnboundedSender<RenderWorkerMessage>,
+    mut rx: mpsc::UnboundedReceiver<RenderWorkerMessage>,
+    cancel: CancellationToken,
 ) {
-    while let Some(task) = rx.recv().await {
-        match task {
-            RenderWorkerTask::Render { id, entry } => {
-                let report = async {
-                    let widget_dir = app_handle.widgets().dir().join(&id);
-                    let code = Bundler::new(widget_dir, entry)?.bundle().await?;
-                    Ok::<_, anyhow::Error>(code)
-                }
-                .await
-                .into();
+    let concurrency = app_handle
+        .settings()
+        .read()
+        .render_concurrency
+        .filter(|n| *n > 0)
+        .map(|n| n as usize)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map_or(1, NonZeroUsize::get)
+                .min(MAX_DEFAULT_CONCURRENCY)
+        });
+    tracing::debug!(concurrency, "Starting render worker pool");
 
-                let event = RenderEvent {
-                    id: &id,
-                    report: &report,
-                };
-                if let Err(e) = event.emit_to(&app_handle, DeskulptWindow::Canvas) {
-                    tracing::error!("Failed to emit RenderEvent for widget {id}: {e:?}");
-                };
+    let mut pool = RenderWorkerPool {
+        app_handle,
+        semaphore: Arc::new(Semaphore::new(concurrency)),
+        tx,
+        widgets: HashMap::new(),
+        cancel,
+    };
+    while let Some(message) = rx.recv().await {
+        match message {
+            RenderWorkerMessage::Task(task) => pool.process(task),
+            RenderWorkerMessage::Done { id, bundler } => pool.complete(id, bundler),
+            RenderWorkerMessage::Ping(tx) => {
+                let _ = tx.send(());
             },
         }
+        pool.update_gauges();
     }
 }
 
+/// A spawned render worker.
+struct SpawnedRenderWorker {
+    /// The sender for messages to the render worker.
+    tx: mpsc::UnboundedSender<RenderWorkerMessage>,
+    /// The render worker task.
+    task: JoinHandle<()>,
+    /// The token cancelling renders of the render worker in flight.
+    cancel: CancellationToken,
+}
+
+/// Spawn a render worker on Tauri's singleton async runtime.
+fn spawn<R: Runtime>(app_handle: AppHandle<R>) -> SpawnedRenderWorker {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let worker_tx = tx.clone();
+    let cancel = CancellationToken::new();
+    let worker_cancel = cancel.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        render_worker(app_handle, worker_tx, rx, worker_cancel).await;
+    });
+    SpawnedRenderWorker { tx, task, cancel }
+}
+
 /// Handle for communicating with the render worker.
-pub struct RenderWorkerHandle(mpsc::UnboundedSender<RenderWorkerTask>);
+pub struct RenderWorkerHandle {
+    /// The sender for messages to the render worker.
+    tx: Mutex<mpsc::UnboundedSender<RenderWorkerMessage>>,
+    /// The running render worker task and the token cancelling its renders.
+    task: Mutex<(JoinHandle<()>, CancellationToken)>,
+}
 
 impl RenderWorkerHandle {
     /// Create a new [`RenderWorkerHandle`] instance.
     ///
     /// This immediately spawns a dedicated render worker on Tauri's singleton
     /// async runtime that listens for incoming [`RenderWorkerTask`]s and
-    /// processes them asynchronously in order.
+    /// dispatches them to a pool of concurrent renders. The concurrency is
+    /// read from the settings at this point.
     pub fn new<R: Runtime>(app_handle: AppHandle<R>) -> Self {
-        let (tx, rx) = mpsc::unbounded_channel();
-        tauri::async_runtime::spawn(async move {
-            render_worker(app_handle, rx).await;
-        });
-        Self(tx)
+        let worker = spawn(app_handle);
+        Self {
+            tx: Mutex::new(worker.tx),
+            task: Mutex::new((worker.task, worker.cancel)),
+        }
     }
 
     /// Instruct the render worker to process a task.
@@ -78,6 +651,43 @@ impl RenderWorkerHandle {
     /// asynchronous processing and does not wait for completion. An error is
     /// returned if task submission fails, but not task processing fails.
     pub fn process(&self, task: RenderWorkerTask) -> Result<()> {
-        Ok(self.0.send(task)?)
+        self.tx
+            .lock()
+            .send(RenderWorkerMessage::Task(task))
+            .map_err(|_| anyhow!("Render worker has stopped"))
+    }
+
+    /// Whether the render worker is still running.
+    pub fn is_alive(&self) -> bool {
+        !self.tx.lock().is_closed()
+    }
+
+    /// Check whether the render worker is responsive.
+    ///
+    /// The returned receiver resolves once the render worker processes the
+    /// ping. It never resolves if the render worker is stuck, and fails if the
+    /// render worker has stopped.
+    pub fn ping(&self) -> Result<oneshot::Receiver<()>> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .lock()
+            .send(RenderWorkerMessage::Ping(tx))
+            .map_err(|_| anyhow!("Render worker has stopped"))?;
+        Ok(rx)
+    }
+
+    /// Replace the render worker with a freshly spawned one.
+    ///
+    /// The old render worker is aborted at its next suspension point. Queued
+    /// renders and kept bundlers are dropped, and renders in flight are
+    /// cancelled before the new render worker accepts tasks, so their results
+    /// are never emitted. Widgets should be rendered again afterwards.
+    pub fn restart<R: Runtime>(&self, app_handle: AppHandle<R>) {
+        let mut current = self.task.lock();
+        current.1.cancel();
+        current.0.abort();
+        let worker = spawn(app_handle);
+        *self.tx.lock() = worker.tx;
+        *current = (worker.task, worker.cancel);
     }
 }
@@ -0,0 +1,74 @@
+//! Strict mode for widget authors.
+//!
+//! In strict mode, the backend performs additional checks on a widget that are
+//! too noisy or costly to run for every widget: usage of deprecated APIs at
+//! bundle time, oversized event payloads when rendering, and network requests
+//! still in flight when the widget is unloaded. Problems are reported as
+//! [`StrictWarning`]s in the logs and in the catalog entry of the widget.
+
+use serde::Serialize;
+
+/// The maximum size in bytes of an event payload before it is considered
+/// oversized.
+///
+/// Large payloads are serialized and sent over IPC as a whole, which can stall
+/// the canvas.
+pub const MAX_EVENT_PAYLOAD: u64 = 1024 * 1024;
+
+/// Deprecated module imports and the advice for replacing them.
+pub const DEPRECATED_IMPORTS: &[(&str, &str)] = &[(
+    "@deskulpt-test/raw-apis",
+    "Import `@deskulpt-test/apis` instead, which binds the widget ID automatically",
+)];
+
+/// Deprecated globals and the advice for replacing them.
+pub const DEPRECATED_GLOBALS: &[(&str, &str)] = &[
+    (
+        "__TAURI_INTERNALS__",
+        "Use `@deskulpt-test/apis` instead of invoking Tauri internals directly",
+    ),
+    (
+        "__DESKULPT_INTERNALS__",
+        "Canvas internals are not a public API and may change without notice",
+    ),
+];
+
+/// A warning reported in strict mode.
+#[derive(Debug, Clone, PartialEq, Serialize, specta::Type)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum StrictWarning {
+    /// The widget uses a deprecated API.
+    DeprecatedApi {
+        /// The deprecated API, e.g., a module specifier or a global.
+        api: String,
+        /// The advice for replacing the deprecated API.
+        advice: String,
+        /// The file using the API, relative to the widget root.
+        file: Option<String>,
+    },
+    /// An event payload of the widget is oversized.
+    OversizedEventPayload {
+        /// The name of the event.
+        event: String,
+        /// The size of the payload in bytes.
+        size: u64,
+        /// The maximum size in bytes, see [`MAX_EVENT_PAYLOAD`].
+        limit: u64,
+    },
+    /// The widget was unloaded with network requests still in flight.
+    UnclosedRequests {
+        /// The number of requests in flight.
+        count: u64,
+    },
+}
+
+impl StrictWarning {
+    /// Log the warning for a widget.
+    pub fn log(&self, id: &str) {
+        tracing::warn!(id, warning = ?self, "Strict mode warning");
+    }
+}
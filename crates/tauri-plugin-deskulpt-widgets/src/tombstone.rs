@@ -0,0 +1,69 @@
+//! Tombstones of widgets that have gone missing.
+//!
+//! When a widget disappears from the widgets directory, e.g., because its
+//! folder is temporarily renamed or still being synced, its settings are kept
+//! as a tombstone for a retention window instead of being dropped right away.
+//! If the widget reappears within the window, its settings such as position and
+//! size are restored.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::catalog::WidgetSettings;
+
+/// The default retention window of tombstones in days.
+///
+/// This applies if not configured in the settings.
+pub const DEFAULT_RETENTION_DAYS: u64 = 7;
+
+/// Get the current time as seconds since the Unix epoch.
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The tombstone of a widget that has gone missing.
+#[derive(Debug)]
+pub struct Tombstone {
+    /// The settings of the widget when it went missing.
+    pub settings: WidgetSettings,
+    /// When the widget went missing, in seconds since the Unix epoch.
+    pub removed_at: u64,
+}
+
+/// The tombstones of widgets that have gone missing, keyed by widget ID.
+#[derive(Debug, Default)]
+pub struct Tombstones(pub BTreeMap<String, Tombstone>);
+
+impl Tombstones {
+    /// Keep the settings of a widget that has just gone missing.
+    pub fn bury(&mut self, id: String, settings: WidgetSettings) {
+        let tombstone = Tombstone {
+            settings,
+            removed_at: now_secs(),
+        };
+        self.0.insert(id, tombstone);
+    }
+
+    /// Take back the settings of a widget that has reappeared, if any.
+    pub fn revive(&mut self, id: &str) -> Option<WidgetSettings> {
+        self.0.remove(id).map(|tombstone| tombstone.settings)
+    }
+
+    /// Remove the tombstones older than the retention window.
+    ///
+    /// This returns whether any tombstone has been removed.
+    pub fn prune(&mut self, retention: Duration) -> bool {
+        let now = now_secs();
+        let len = self.0.len();
+        self.0.retain(|id, tombstone| {
+            let keep = now.saturating_sub(tombstone.removed_at) < retention.as_secs();
+            if !keep {
+                tracing::debug!(id, "Pruned settings of missing widget");
+            }
+            keep
+        });
+        self.0.len() != len
+    }
+}
@@ -0,0 +1,39 @@
+import { invoke } from "@tauri-apps/api/core";
+
+type Level = "trace" | "debug" | "info" | "warn" | "error";
+
+interface LogPayload {
+  message: string;
+  meta?: unknown;
+}
+
+function logWidget(id: string, level: Level, payload: LogPayload) {
+  return invoke<null>("plugin:deskulpt-logs|log_widget", {
+    widgetId: id,
+    level,
+    message: payload.message,
+    meta: payload.meta ?? null,
+  });
+}
+
+function trace(id: string, payload: LogPayload) {
+  return logWidget(id, "trace", payload);
+}
+
+function debug(id: string, payload: LogPayload) {
+  return logWidget(id, "debug", payload);
+}
+
+function info(id: string, payload: LogPayload) {
+  return logWidget(id, "info", payload);
+}
+
+function warn(id: string, payload: LogPayload) {
+  return logWidget(id, "warn", payload);
+}
+
+function error(id: string, payload: LogPayload) {
+  return logWidget(id, "error", payload);
+}
+
+export { trace, debug, info, warn, error };
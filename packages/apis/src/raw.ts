@@ -1,4 +1,6 @@
 import * as fs from "./fs";
+import * as log from "./log";
+import * as search from "./search";
 import * as sys from "./sys";
 
-export default { fs, sys };
+export default { fs, log, search, sys };
@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+
+interface SearchResult {
+  provider: string;
+  title: string;
+  subtitle: string | null;
+  target: string;
+  score: number;
+}
+
+function query(id: string, payload: { text: string }) {
+  return invoke<SearchResult[]>("plugin:deskulpt-core|query", {
+    id,
+    text: payload.text,
+  });
+}
+
+export { query };
@@ -7,6 +7,99 @@ import * as TauriEvent from "@tauri-apps/api/event";
 // Types
 // =============================================================================
 
+/**
+ * The canvas interaction mode.
+ */
+export type CanvasImode = 
+/**
+ * Auto mode.
+ * 
+ * Automatically switch between sink and float modes based on mouse
+ * position, so that users will feel like the widgets and the desktop are
+ * simultaneously interactable.
+ */
+"auto" | 
+/**
+ * Sink mode.
+ * 
+ * The canvas is click-through. Widgets are not interactable, except those
+ * set to be always interactive. The desktop is interactable.
+ */
+"sink" | 
+/**
+ * Float mode.
+ * 
+ * The canvas is not click-through. Widgets are interactable. The desktop
+ * is not interactable.
+ */
+"float"
+
+/**
+ * A region of the canvas to capture.
+ * 
+ * The coordinates are in canvas pixels, the same as widget positions and
+ * sizes, so that a region can be derived from widget settings directly.
+ */
+export type CaptureRegion = { 
+/**
+ * The leftmost x-coordinate in pixels.
+ */
+x: number; 
+/**
+ * The topmost y-coordinate in pixels.
+ */
+y: number; 
+/**
+ * The width in pixels.
+ */
+width: number; 
+/**
+ * The height in pixels.
+ */
+height: number }
+
+/**
+ * Diagnostics of the compositing setup, for bug triage.
+ */
+export type CompositingDiagnostics = { 
+/**
+ * The operating system.
+ */
+platform: string; 
+/**
+ * Whether hardware acceleration is requested.
+ */
+hardwareAcceleration: boolean; 
+/**
+ * Whether the canvas is transparent.
+ */
+transparentCanvas: boolean; 
+/**
+ * The WebGL renderer reported by the webview, if available.
+ */
+renderer: string | null; 
+/**
+ * The active compositing path inferred from the renderer.
+ */
+path: CompositingPath }
+
+/**
+ * The compositing path that a webview is rendered with.
+ */
+export type CompositingPath = 
+/**
+ * Rendered with GPU acceleration.
+ */
+"hardware" | 
+/**
+ * Rendered with a software rasterizer.
+ */
+"software" | 
+/**
+ * The compositing path cannot be determined.
+ */
+"unknown"
+
 /**
  * Deskulpt window enum.
  */
@@ -20,8 +113,196 @@ export type DeskulptWindow =
  */
 "canvas"
 
+/**
+ * Event for notifying the canvas of a display configuration change.
+ * 
+ * This event is emitted from the backend to the canvas when monitors are
+ * added or removed, or when the canvas is moved, resized, or rescaled, after
+ * widget positions have been re-resolved against the new monitor layout.
+ */
+export type DisplayChangedEvent = { 
+/**
+ * The number of available monitors.
+ */
+monitorCount: number; 
+/**
+ * The scale factor of the canvas.
+ */
+scaleFactor: number }
+
+/**
+ * A health report of all subsystems.
+ */
+export type HealthReport = { 
+/**
+ * The overall status, i.e., the most severe status of all subsystems.
+ */
+status: HealthStatus; 
+/**
+ * The health of the widgets subsystem.
+ */
+widgets: WidgetsHealth; 
+/**
+ * The health of the settings subsystem.
+ */
+settings: SettingsHealth; 
+/**
+ * The health of the plugins subsystem.
+ */
+plugins: PluginsHealth; 
+/**
+ * The health of the logging subsystem.
+ */
+logs: LogsHealth; 
+/**
+ * The health of the connectivity to the widgets registry.
+ */
+registry: RegistryHealth; 
+/**
+ * The health of the global mouse hook.
+ */
+mouse: MouseHealth }
+
+/**
+ * The status of a subsystem.
+ * 
+ * Statuses are ordered by severity, so that the overall status is the most
+ * severe among the subsystems.
+ */
+export type HealthStatus = 
+/**
+ * The subsystem is working as expected.
+ */
+"ok" | 
+/**
+ * The subsystem is working with reduced functionality.
+ */
+"degraded" | 
+/**
+ * The subsystem is not working.
+ */
+"down"
+
+/**
+ * A snapshot of a [`Histogram`].
+ */
+export type HistogramSnapshot = { 
+/**
+ * The number of observations.
+ */
+count: number; 
+/**
+ * The sum of observed values.
+ */
+sum: number; 
+/**
+ * The maximum observed value.
+ */
+max: number; 
+/**
+ * The estimated median.
+ */
+p50: number; 
+/**
+ * The estimated 95th percentile.
+ */
+p95: number; 
+/**
+ * The estimated 99th percentile.
+ */
+p99: number }
+
+/**
+ * Event for notifying the canvas of an interaction state change.
+ * 
+ * This event is emitted from the backend to the canvas when the canvas
+ * interaction mode changes, when layout editing starts or stops, and whenever
+ * the canvas switches between accepting and ignoring cursor events, e.g., as
+ * the cursor moves on and off widgets in auto mode.
+ */
+export type ImodeChangedEvent = { 
+/**
+ * The canvas interaction mode.
+ */
+mode: CanvasImode; 
+/**
+ * Whether the canvas is accepting cursor events.
+ */
+isInteractive: boolean; 
+/**
+ * Whether widgets are being laid out by dragging them on the canvas.
+ * 
+ * While this is the case, the canvas should keep widgets from handling
+ * cursor events, since dragging is handled by the backend.
+ */
+isEditingLayout: boolean }
+
 export type JsonValue = null | boolean | number | string | JsonValue[] | { [key in string]: JsonValue }
 
+/**
+ * The health of the logging subsystem.
+ */
+export type LogsHealth = { 
+/**
+ * The status of the subsystem.
+ */
+status: HealthStatus; 
+/**
+ * The directory where log files are stored.
+ */
+dir: string; 
+/**
+ * The number of log lines dropped so far.
+ */
+droppedLines: number; 
+/**
+ * The available disk space for log files in bytes, if known.
+ */
+availableSpace: number | null }
+
+/**
+ * A snapshot of all registered metrics.
+ */
+export type MetricsSnapshot = { 
+/**
+ * The values of counters, keyed by name.
+ */
+counters: Partial<{ [key in string]: number }>; 
+/**
+ * The values of gauges, keyed by name.
+ */
+gauges: Partial<{ [key in string]: number }>; 
+/**
+ * The snapshots of histograms, keyed by name.
+ */
+histograms: Partial<{ [key in string]: HistogramSnapshot }> }
+
+/**
+ * The health of the global mouse hook.
+ */
+export type MouseHealth = { 
+/**
+ * The status of the subsystem.
+ */
+status: HealthStatus; 
+/**
+ * The backend of the running mouse hook, if any.
+ */
+backend: MouseHookBackend | null }
+
+/**
+ * The backend of the global mouse hook.
+ */
+export type MouseHookBackend = 
+/**
+ * The native global mousemove hook of the platform.
+ */
+"native" | 
+/**
+ * Polling of the cursor position, used when no native hook is available.
+ */
+"polling"
+
 /**
  * The target to open.
  */
@@ -43,6 +324,182 @@ export type OpenTarget =
  */
 "logs"
 
+/**
+ * The health of the plugins subsystem.
+ */
+export type PluginsHealth = { 
+/**
+ * The status of the subsystem.
+ */
+status: HealthStatus; 
+/**
+ * The loaded plugins, mapping plugin names to versions.
+ */
+loaded: Partial<{ [key in string]: string }>; 
+/**
+ * The plugins required by widgets that failed to load or whose loaded
+ * versions do not satisfy the requirements.
+ */
+failed: string[]; 
+/**
+ * Whether plugin loading is skipped in safe mode.
+ */
+safeMode: boolean }
+
+/**
+ * The tabs of Deskulpt portal.
+ */
+export type PortalTab = 
+/**
+ * The widgets tab.
+ */
+"widgets" | 
+/**
+ * The settings tab.
+ */
+"settings" | 
+/**
+ * The gallery tab.
+ */
+"gallery" | 
+/**
+ * The logs tab.
+ */
+"logs" | 
+/**
+ * The about tab.
+ */
+"about" | 
+/**
+ * The recovery tab, available in safe mode only.
+ */
+"recovery"
+
+/**
+ * The health of the connectivity to the widgets registry.
+ */
+export type RegistryHealth = { 
+/**
+ * The status of the subsystem.
+ */
+status: HealthStatus; 
+/**
+ * The round-trip time to the registry in milliseconds, if reachable.
+ */
+latencyMs: number | null; 
+/**
+ * The error reaching the registry, if any.
+ */
+error: string | null }
+
+/**
+ * A search result.
+ */
+export type SearchResult = { 
+/**
+ * The name of the provider that produced the result.
+ */
+provider: string; 
+/**
+ * The title of the result.
+ */
+title: string; 
+/**
+ * Additional information about the result, e.g., its location.
+ */
+subtitle: string | null; 
+/**
+ * What the result refers to.
+ * 
+ * The meaning depends on the provider, e.g., a file path for files and a
+ * `handle/id` widget reference for the widgets registry.
+ */
+target: string; 
+/**
+ * The relevance score between 0 and 1, higher being more relevant.
+ */
+score: number }
+
+/**
+ * The health of the settings subsystem.
+ */
+export type SettingsHealth = { 
+/**
+ * The status of the subsystem.
+ */
+status: HealthStatus; 
+/**
+ * The path where settings are persisted.
+ */
+persistPath: string; 
+/**
+ * The error making the persist path unwritable, if any.
+ */
+error: string | null }
+
+/**
+ * Actions that can be bound to keyboard shortcuts.
+ */
+export type ShortcutAction = 
+/**
+ * Toggle the canvas interaction mode (imode).
+ */
+"toggleCanvasImode" | 
+/**
+ * Open Deskulpt portal.
+ */
+"openPortal" | 
+/**
+ * Undo the most recent layout change of widgets.
+ */
+"undoLayoutChange" | 
+/**
+ * Redo the most recently undone layout change of widgets.
+ */
+"redoLayoutChange" | 
+/**
+ * Refresh all widgets.
+ */
+"refreshAllWidgets" | 
+/**
+ * Toggle the visibility of all widgets by hiding or showing the canvas.
+ */
+"toggleAllWidgets" | 
+/**
+ * Switch to the next settings profile, wrapping around.
+ */
+"cycleProfile" | 
+/**
+ * Open the logs viewer in Deskulpt portal.
+ */
+"openLogsViewer" | 
+/**
+ * Temporarily switch the canvas to float mode while the shortcut is held.
+ */
+"peekCanvas"
+
+/**
+ * The result of validating a keyboard shortcut.
+ */
+export type ShortcutValidation = 
+/**
+ * The shortcut can be bound.
+ */
+{ type: "valid" } | 
+/**
+ * The shortcut cannot be parsed, with the error message.
+ */
+{ type: "invalid"; content: string } | 
+/**
+ * The shortcut is already bound to another action.
+ */
+{ type: "conflict"; content: ShortcutAction } | 
+/**
+ * The shortcut cannot be registered, e.g., because it is taken by
+ * another application, with the error message.
+ */
+{ type: "unavailable"; content: string }
+
 /**
  * Event for showing a toast notification.
  * 
@@ -59,6 +516,102 @@ export type ShowToastEvent =
  */
 { type: "error"; content: string }
 
+/**
+ * Event for switching Deskulpt portal to a tab.
+ * 
+ * This event is emitted from the backend to the portal when it is asked to
+ * show a specific tab while already open.
+ */
+export type SwitchPortalTabEvent = PortalTab
+
+/**
+ * An entry in the state timeline.
+ */
+export type TimelineEntry = { 
+/**
+ * The time of the record in milliseconds since the UNIX epoch.
+ */
+timestamp: number; 
+/**
+ * The record.
+ */
+record: TimelineRecord }
+
+/**
+ * A record of an app state change.
+ */
+export type TimelineRecord = 
+/**
+ * A full snapshot of the widget catalog.
+ */
+{ type: "catalog"; content: { 
+/**
+ * The catalog revision, increasing with each catalog change.
+ */
+revision: number; 
+/**
+ * The catalog snapshot.
+ */
+catalog: JsonValue } } | 
+/**
+ * An incremental change to the widget catalog.
+ */
+{ type: "catalogDiff"; content: { 
+/**
+ * The catalog revision, increasing with each catalog change.
+ */
+revision: number; 
+/**
+ * The catalog change.
+ */
+diff: JsonValue } } | 
+/**
+ * A change to the settings.
+ * 
+ * This maps the changed top-level settings to their new values. Removed
+ * settings are mapped to `null`.
+ */
+{ type: "settings"; content: JsonValue }
+
+/**
+ * Event for notifying the canvas of the cursor entering or leaving a widget.
+ * 
+ * This event is emitted from the backend to the canvas as the cursor moves
+ * across widgets, regardless of whether the canvas is accepting cursor events,
+ * so that widgets can show hover affordances even when click-through. Only the
+ * topmost widget under the cursor is considered hovered.
+ */
+export type WidgetHoverEvent = 
+/**
+ * The cursor entered the widget with the given ID.
+ */
+{ type: "enter"; content: string } | 
+/**
+ * The cursor left the widget with the given ID.
+ */
+{ type: "leave"; content: string }
+
+/**
+ * The health of the widgets subsystem.
+ */
+export type WidgetsHealth = { 
+/**
+ * The status of the subsystem.
+ */
+status: HealthStatus; 
+/**
+ * Whether each background worker is still running.
+ */
+workers: Partial<{ [key in string]: boolean }>; 
+/**
+ * The number of renders in flight.
+ */
+rendersInFlight: number; 
+/**
+ * The number of renders queued behind those in flight.
+ */
+rendersQueued: number }
+
 // =============================================================================
 // Events
 // =============================================================================
@@ -82,7 +635,11 @@ function makeEvent<T>(name: string) {
 }
 
 export namespace Events {
+  export const displayChanged = makeEvent<DisplayChangedEvent>("deskulpt-core://display-changed");
+  export const imodeChanged = makeEvent<ImodeChangedEvent>("deskulpt-core://imode-changed");
   export const showToast = makeEvent<ShowToastEvent>("deskulpt-core://show-toast");
+  export const switchPortalTab = makeEvent<SwitchPortalTabEvent>("deskulpt-core://switch-portal-tab");
+  export const widgetHover = makeEvent<WidgetHoverEvent>("deskulpt-core://widget-hover");
 }
 
 // =============================================================================
@@ -90,6 +647,22 @@ export namespace Events {
 // =============================================================================
 
 export namespace Commands {
+  /**
+   * Attach a detached widget back to the canvas.
+   * 
+   * This command is a wrapper of [`WindowExt::attach_widget`].
+   * 
+   * ### Errors
+   * 
+   * - The widget does not exist.
+   * - Error closing the floating window.
+   */
+  export const attachWidget = (
+    id: string,
+  ) => invoke<null>("plugin:deskulpt-core|attach_widget", {
+    id,
+  });
+
   /**
    * Call a plugin command (🚧 TODO 🚧).
    * 
@@ -116,6 +689,116 @@ export namespace Commands {
     payload,
   });
 
+  /**
+   * Capture the canvas contents to a PNG file.
+   * 
+   * This command is a wrapper of [`WindowExt::capture_canvas`].
+   * 
+   * ### Errors
+   * 
+   * - Capturing the canvas is unsupported on the platform.
+   * - The region is outside of the canvas.
+   * - Error writing the PNG file.
+   */
+  export const captureCanvas = (
+    path: string,
+    region: CaptureRegion | null,
+  ) => invoke<null>("plugin:deskulpt-core|capture_canvas", {
+    path,
+    region,
+  });
+
+  /**
+   * Detach a widget into its own floating window.
+   * 
+   * This command is a wrapper of [`WindowExt::detach_widget`].
+   * 
+   * ### Errors
+   * 
+   * - The widget does not exist.
+   * - Error creating the floating window.
+   */
+  export const detachWidget = (
+    id: string,
+  ) => invoke<null>("plugin:deskulpt-core|detach_widget", {
+    id,
+  });
+
+  /**
+   * Diagnose the compositing setup of Deskulpt windows.
+   * 
+   * This takes the WebGL renderer probed by the calling webview, if available.
+   * The diagnostics are logged for bug triage and also returned.
+   */
+  export const diagnoseCompositing = (
+    renderer: string | null,
+  ) => invoke<CompositingDiagnostics>("plugin:deskulpt-core|diagnose_compositing", {
+    renderer,
+  });
+
+  /**
+   * Dump the recorded state timeline, oldest first.
+   * 
+   * This command is a wrapper of [`TimelineExt::dump_state_timeline`].
+   * 
+   * ### Errors
+   * 
+   * - Not in dev mode, where the state timeline is not recorded.
+   * - Error reading the timeline files.
+   */
+  export const dumpStateTimeline = () => invoke<TimelineEntry[]>("plugin:deskulpt-core|dump_state_timeline");
+
+  /**
+   * Exit safe mode by restarting the app normally.
+   * 
+   * This command is a wrapper of [`SafeModeExt::exit_safe_mode`].
+   * 
+   * ### Errors
+   * 
+   * - Error resetting the startup crash count.
+   * - Error persisting settings or widgets.
+   */
+  export const exitSafeMode = () => invoke<null>("plugin:deskulpt-core|exit_safe_mode");
+
+  /**
+   * Export a diagnostic bundle for bug reports to a zip archive.
+   * 
+   * This command is a wrapper of [`DiagnosticsExt::export_diagnostics`].
+   * 
+   * ### Errors
+   * 
+   * - Error reading the logs or the widget catalog.
+   * - Error writing the archive.
+   */
+  export const exportDiagnostics = (
+    path: string,
+  ) => invoke<null>("plugin:deskulpt-core|export_diagnostics", {
+    path,
+  });
+
+  /**
+   * Get a snapshot of the runtime metrics.
+   * 
+   * This includes counters, gauges, and histograms recorded by the bundler,
+   * the render worker, plugin calls, and the mouse interaction loop.
+   */
+  export const getMetrics = () => invoke<MetricsSnapshot>("plugin:deskulpt-core|get_metrics");
+
+  /**
+   * Check the health of all subsystems.
+   * 
+   * This command is a wrapper of [`HealthExt::health`].
+   */
+  export const health = () => invoke<HealthReport>("plugin:deskulpt-core|health");
+
+  /**
+   * Check whether layout editing of widgets is supported on the platform.
+   * 
+   * This command is a wrapper of
+   * [`CanvasImodeStateExt::is_layout_editing_supported`].
+   */
+  export const isLayoutEditingSupported = () => invoke<boolean>("plugin:deskulpt-core|is_layout_editing_supported");
+
   /**
    * Open a specified target with the system's default application.
    * 
@@ -131,4 +814,45 @@ export namespace Commands {
   ) => invoke<null>("plugin:deskulpt-core|open", {
     target,
   });
+
+  /**
+   * Query search providers for results matching the text on behalf of a widget.
+   * 
+   * This command is a wrapper of [`SearchExt::search`]. The `files` provider
+   * exposes file names in user directories, so it is only queried if the
+   * widget is allowed to call `search.files` as a plugin command, see
+   * [`tauri_plugin_deskulpt_widgets::WidgetsManager::authorize_plugin_call`].
+   */
+  export const query = (
+    id: string,
+    text: string,
+  ) => invoke<SearchResult[]>("plugin:deskulpt-core|query", {
+    id,
+    text,
+  });
+
+  /**
+   * Start or stop layout editing of widgets.
+   * 
+   * This command is a wrapper of [`CanvasImodeStateExt::set_layout_editing`].
+   */
+  export const setLayoutEditing = (
+    enabled: boolean,
+  ) => invoke<null>("plugin:deskulpt-core|set_layout_editing", {
+    enabled,
+  });
+
+  /**
+   * Validate a keyboard shortcut before binding it to an action.
+   * 
+   * This command is a wrapper of [`ShortcutsExt::validate_shortcut`]. See
+   * [`ShortcutValidation`] for the possible results.
+   */
+  export const validateShortcut = (
+    accelerator: string,
+    action: ShortcutAction | null,
+  ) => invoke<ShortcutValidation>("plugin:deskulpt-core|validate_shortcut", {
+    accelerator,
+    action,
+  });
 }
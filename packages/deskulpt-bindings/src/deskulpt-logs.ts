@@ -9,19 +9,30 @@ import { invoke } from "@tauri-apps/api/core";
 /**
  * Cursor for log pagination.
  */
-export type Cursor = { 
+export type Cursor = 
 /**
- * The index of the log file in the files list.
+ * Resume from the in-memory buffer of recent log entries.
  */
-fileIdx: number; 
+{ type: "memory"; content: { 
 /**
- * The byte offset within the log file.
+ * The sequence number of the last examined entry.
  * 
- * When continuing from this cursor, reading resumes backwards from this
- * offset. An offset of zero means this file has been fully read, and the
- * reader should move to the next older file.
+ * When continuing from this cursor, reading resumes with entries
+ * older than this one.
  */
-offset: number }
+seq: number; 
+/**
+ * The timestamp of the last examined entry in RFC 3339 format.
+ * 
+ * If the buffer has no entries older than the last examined one, e.g.,
+ * because they have been evicted, reading continues from the log
+ * files with entries strictly older than this timestamp.
+ */
+timestamp: string } } | 
+/**
+ * Resume from the log files.
+ */
+{ type: "file"; content: FileCursor }
 
 /**
  * Deskulpt window enum.
@@ -57,6 +68,53 @@ message: string;
  */
 raw: JsonValue }
 
+/**
+ * Cursor for log pagination within the log files.
+ */
+export type FileCursor = { 
+/**
+ * The index of the log file in the files list.
+ */
+fileIdx: number; 
+/**
+ * The byte offset within the log file.
+ * 
+ * When continuing from this cursor, reading resumes backwards from this
+ * offset. An offset of zero means this file has been fully read, and the
+ * reader should move to the next older file.
+ */
+offset: number }
+
+/**
+ * Filters of log entries in addition to the minimum severity level.
+ * 
+ * All filters are optional, and an entry must pass all specified filters to
+ * be included.
+ */
+export type Filter = { 
+/**
+ * Only include entries whose targets are any of these or their children.
+ * 
+ * For example, `deskulpt` includes entries with targets `deskulpt` and
+ * `deskulpt::widgets`, but not `deskulpt_extra`.
+ */
+targets?: string[]; 
+/**
+ * Only include entries attributed to the widget with this ID.
+ * 
+ * An entry is attributed to a widget if it or any of its spans has the
+ * `widget` field set to the widget ID.
+ */
+widgetId?: string; 
+/**
+ * Only include entries at or after this time in RFC 3339 format.
+ */
+since?: string; 
+/**
+ * Only include entries at or before this time in RFC 3339 format.
+ */
+until?: string }
+
 export type JsonValue = null | boolean | number | string | JsonValue[] | { [key in string]: JsonValue }
 
 /**
@@ -117,7 +175,8 @@ export namespace Commands {
    * 
    * This retrieves log entries from the log files, from newest to oldest. At
    * most `limit` log entries will be returned. Only log entries with at least
-   * the severity of `min_level` will be included.
+   * the severity of `min_level` and passing `filter` will be included, see
+   * [`Filter`] for the available filters.
    * 
    * An optional `cursor` can be provided. Pass `null` to start from the latest
    * log entry. Pass a cursor returned from a previous call to continue reading
@@ -126,13 +185,43 @@ export namespace Commands {
   export const read = (
     limit: number,
     minLevel: Level,
+    filter: Filter,
     cursor: Cursor | null,
   ) => invoke<Page>("plugin:deskulpt-logs|read", {
     limit,
     minLevel,
+    filter,
     cursor,
   });
 
+  /**
+   * Read a page of log entries attributed to a widget.
+   * 
+   * Wrapper of [`crate::LogsManager::read_for_widget`].
+   */
+  export const readForWidget = (
+    widgetId: string,
+    limit: number,
+    minLevel: Level,
+    cursor: Cursor | null,
+  ) => invoke<Page>("plugin:deskulpt-logs|read_for_widget", {
+    widgetId,
+    limit,
+    minLevel,
+    cursor,
+  });
+
+  /**
+   * Clear the log files of a widget.
+   * 
+   * This returns the amount of freed space in bytes.
+   */
+  export const clearForWidget = (
+    widgetId: string,
+  ) => invoke<number>("plugin:deskulpt-logs|clear_for_widget", {
+    widgetId,
+  });
+
   /**
    * Emit a log message at the specified level.
    * 
@@ -151,4 +240,46 @@ export namespace Commands {
     message,
     meta,
   });
+
+  /**
+   * Emit a log message on behalf of a widget at the specified level.
+   * 
+   * The log entry is recorded under the `widget` target and tagged with the
+   * widget ID in the `widget` field, so that it is properly attributed to the
+   * widget instead of the canvas. Tracing targets must be static, so the ID is
+   * not part of the target itself; use the `widget_id` filter of [`read`] to
+   * isolate logs of one widget.
+   * 
+   * The `meta` parameter is the same as in [`log`].
+   */
+  export const logWidget = (
+    widgetId: string,
+    level: Level,
+    message: string,
+    meta: JsonValue,
+  ) => invoke<null>("plugin:deskulpt-logs|log_widget", {
+    widgetId,
+    level,
+    message,
+    meta,
+  });
+
+  /**
+   * Set the filter of recorded logs.
+   * 
+   * The filter takes effect immediately and is persisted in the settings. It is
+   * a comma-separated list of directives, each being either a default level
+   * (e.g., `info`) or a level for a target and its children (e.g.,
+   * `deskulpt::widgets=debug`). `None` means recording all severities.
+   * 
+   * ### Errors
+   * 
+   * - The filter is invalid.
+   * - Error updating the settings.
+   */
+  export const setLogLevel = (
+    filter: string | null,
+  ) => invoke<null>("plugin:deskulpt-logs|set_log_level", {
+    filter,
+  });
 }
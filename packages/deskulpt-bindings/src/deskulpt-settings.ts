@@ -22,8 +22,8 @@ export type CanvasImode =
 /**
  * Sink mode.
  * 
- * The canvas is click-through. Widgets are not interactable. The desktop
- * is interactable.
+ * The canvas is click-through. Widgets are not interactable, except those
+ * set to be always interactive. The desktop is interactable.
  */
 "sink" | 
 /**
@@ -47,6 +47,39 @@ export type DeskulptWindow =
  */
 "canvas"
 
+export type JsonValue = null | boolean | number | string | JsonValue[] | { [key in string]: JsonValue }
+
+/**
+ * How imported settings are combined with the current settings.
+ */
+export type MergeStrategy = 
+/**
+ * Merge the imported settings into the current settings.
+ * 
+ * Only the settings present in the imported file are applied, and the
+ * rest are kept as is. Shortcuts are merged per action.
+ */
+"merge" | 
+/**
+ * Replace the current settings with the imported settings.
+ * 
+ * Settings absent from the imported file are reset to their defaults.
+ */
+"replace"
+
+/**
+ * The available settings profiles.
+ */
+export type ProfileList = { 
+/**
+ * The active profile.
+ */
+active: string; 
+/**
+ * All available profiles, with the default profile first.
+ */
+profiles: string[] }
+
 /**
  * Full settings of the Deskulpt application.
  */
@@ -98,7 +131,35 @@ export type ShortcutAction =
 /**
  * Open Deskulpt portal.
  */
-"openPortal"
+"openPortal" | 
+/**
+ * Undo the most recent layout change of widgets.
+ */
+"undoLayoutChange" | 
+/**
+ * Redo the most recently undone layout change of widgets.
+ */
+"redoLayoutChange" | 
+/**
+ * Refresh all widgets.
+ */
+"refreshAllWidgets" | 
+/**
+ * Toggle the visibility of all widgets by hiding or showing the canvas.
+ */
+"toggleAllWidgets" | 
+/**
+ * Switch to the next settings profile, wrapping around.
+ */
+"cycleProfile" | 
+/**
+ * Open the logs viewer in Deskulpt portal.
+ */
+"openLogsViewer" | 
+/**
+ * Temporarily switch the canvas to float mode while the shortcut is held.
+ */
+"peekCanvas"
 
 /**
  * The light/dark theme of the application interface.
@@ -110,6 +171,25 @@ export type Theme = "light" | "dark"
  */
 export type UpdateEvent = Settings
 
+/**
+ * Event for notifying frontend windows of a widget configuration change.
+ * 
+ * Unlike [`UpdateEvent`], this carries only the configuration of the owning
+ * widget, so that each widget can listen for changes to its own
+ * configuration.
+ */
+export type WidgetConfigEvent = { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * The new configuration of the widget.
+ * 
+ * This is `null` if the configuration has been removed.
+ */
+config: JsonValue }
+
 // =============================================================================
 // Events
 // =============================================================================
@@ -134,6 +214,7 @@ function makeEvent<T>(name: string) {
 
 export namespace Events {
   export const update = makeEvent<UpdateEvent>("deskulpt-settings://update");
+  export const widgetConfig = makeEvent<WidgetConfigEvent>("deskulpt-settings://widget-config");
 }
 
 // =============================================================================
@@ -141,6 +222,127 @@ export namespace Events {
 // =============================================================================
 
 export namespace Commands {
+  /**
+   * Create a new settings profile as a copy of the current settings.
+   * 
+   * Wrapper of [`crate::SettingsManager::create_profile`].
+   */
+  export const createProfile = (
+    name: string,
+  ) => invoke<null>("plugin:deskulpt-settings|create_profile", {
+    name,
+  });
+
+  /**
+   * Export the current settings to a file.
+   * 
+   * Wrapper of [`crate::SettingsManager::export`].
+   */
+  export const exportSettings = (
+    path: string,
+  ) => invoke<null>("plugin:deskulpt-settings|export_settings", {
+    path,
+  });
+
+  /**
+   * Get a secret by its name.
+   * 
+   * Wrapper of [`crate::SettingsManager::secret`]. This is only allowed from
+   * the portal.
+   */
+  export const getSecret = (
+    name: string,
+  ) => invoke<string | null>("plugin:deskulpt-settings|get_secret", {
+    name,
+  });
+
+  /**
+   * Get the configuration of a widget.
+   * 
+   * Wrapper of [`crate::SettingsManager::widget_config`].
+   */
+  export const getWidgetConfig = (
+    id: string,
+  ) => invoke<JsonValue>("plugin:deskulpt-settings|get_widget_config", {
+    id,
+  });
+
+  /**
+   * Import settings from a file.
+   * 
+   * Wrapper of [`crate::SettingsManager::import`].
+   */
+  export const importSettings = (
+    path: string,
+    mergeStrategy: MergeStrategy,
+  ) => invoke<null>("plugin:deskulpt-settings|import_settings", {
+    path,
+    mergeStrategy,
+  });
+
+  /**
+   * Check whether the settings are locked.
+   * 
+   * Wrapper of [`crate::SettingsManager::is_locked`].
+   */
+  export const isLocked = () => invoke<boolean>("plugin:deskulpt-settings|is_locked");
+
+  /**
+   * List the available settings profiles.
+   * 
+   * Wrapper of [`crate::SettingsManager::list_profiles`].
+   */
+  export const listProfiles = () => invoke<ProfileList>("plugin:deskulpt-settings|list_profiles");
+
+  /**
+   * Patch the configuration of a widget.
+   * 
+   * Wrapper of [`crate::SettingsManager::patch_widget_config`].
+   */
+  export const patchWidgetConfig = (
+    id: string,
+    patch: JsonValue,
+  ) => invoke<null>("plugin:deskulpt-settings|patch_widget_config", {
+    id,
+    patch,
+  });
+
+  /**
+   * Restore the settings from a rotated backup.
+   * 
+   * Wrapper of [`crate::SettingsManager::restore_backup`].
+   */
+  export const restoreSettingsBackup = (
+    index: number,
+  ) => invoke<null>("plugin:deskulpt-settings|restore_settings_backup", {
+    index,
+  });
+
+  /**
+   * Set or remove a secret by its name.
+   * 
+   * Wrapper of [`crate::SettingsManager::set_secret`]. This is only allowed
+   * from the portal.
+   */
+  export const setSecret = (
+    name: string,
+    secret: string | null,
+  ) => invoke<null>("plugin:deskulpt-settings|set_secret", {
+    name,
+    secret,
+  });
+
+  /**
+   * Switch to another settings profile.
+   * 
+   * Wrapper of [`crate::SettingsManager::switch_profile`].
+   */
+  export const switchProfile = (
+    name: string,
+  ) => invoke<null>("plugin:deskulpt-settings|switch_profile", {
+    name,
+  });
+
   /**
    * Update the settings with a patch.
    * 
@@ -7,6 +7,164 @@ import * as TauriEvent from "@tauri-apps/api/event";
 // Types
 // =============================================================================
 
+/**
+ * How to align widgets with each other.
+ */
+export type Alignment = 
+/**
+ * Align the left edges with the leftmost one.
+ */
+"left" | 
+/**
+ * Align the top edges with the topmost one.
+ */
+"top" | 
+/**
+ * Align the right edges with the rightmost one.
+ */
+"right" | 
+/**
+ * Align the bottom edges with the bottommost one.
+ */
+"bottom" | 
+/**
+ * Distribute widgets horizontally with equal gaps between them.
+ * 
+ * The leftmost and rightmost widgets stay in place.
+ */
+"distributeHorizontally" | 
+/**
+ * Distribute widgets vertically with equal gaps between them.
+ * 
+ * The topmost and bottommost widgets stay in place.
+ */
+"distributeVertically"
+
+/**
+ * A structured diagnostic produced when bundling a widget.
+ */
+export type BundleDiagnostic = { 
+/**
+ * The diagnostic message.
+ */
+message: string; 
+/**
+ * The file that the diagnostic refers to, relative to the widget root.
+ */
+file: string | null; 
+/**
+ * The 1-based line number in the file.
+ */
+line: number | null; 
+/**
+ * The 1-based column number in the file.
+ */
+column: number | null; 
+/**
+ * The code snippet around the location, as rendered by the bundler.
+ */
+snippet: string | null }
+
+/**
+ * Event for warning that the bundle of a widget exceeds its size budget.
+ * 
+ * This is emitted after each successful bundle that is over budget, so that
+ * widget authors notice bloated dependencies early.
+ */
+export type BundleSizeEvent = { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * The size of the bundle in bytes.
+ */
+size: number; 
+/**
+ * The size budget in bytes that has been exceeded.
+ */
+budget: number }
+
+/**
+ * Statistics of the latest successful bundle of a widget.
+ */
+export type BundleStats = { 
+/**
+ * The total size of the bundled code in bytes.
+ * 
+ * With code splitting, this includes all chunks.
+ */
+size: number; 
+/**
+ * The time taken to bundle in milliseconds.
+ */
+durationMs: number }
+
+/**
+ * Hints of the canvas regions to refresh in the next frame.
+ */
+export type DamageHints = { 
+/**
+ * Whether the whole canvas should be refreshed.
+ * 
+ * If true, [`Self::regions`] should be ignored.
+ */
+full: boolean; 
+/**
+ * The damaged regions, which do not overlap each other.
+ */
+regions: DamageRect[]; 
+/**
+ * The IDs of the widgets that have changed.
+ */
+ids: string[] }
+
+/**
+ * Metrics of damage tracking for validating the savings of partial refreshes.
+ * 
+ * Metrics are accumulated since application startup.
+ */
+export type DamageMetrics = { 
+/**
+ * The number of frames with any damage.
+ */
+frames: number; 
+/**
+ * The number of frames that fell back to a full refresh.
+ */
+fullFrames: number; 
+/**
+ * The total number of pixels refreshed across frames.
+ */
+refreshedPixels: number; 
+/**
+ * The total number of pixels of the viewport across frames.
+ * 
+ * This is what would have been refreshed without damage tracking.
+ */
+viewportPixels: number }
+
+/**
+ * A rectangular region of the canvas in pixels.
+ */
+export type DamageRect = { 
+/**
+ * The leftmost x-coordinate.
+ */
+x: number; 
+/**
+ * The topmost y-coordinate.
+ */
+y: number; 
+/**
+ * The width.
+ */
+width: number; 
+/**
+ * The height.
+ */
+height: number }
+
 /**
  * Deskulpt window enum.
  */
@@ -20,6 +178,248 @@ export type DeskulptWindow =
  */
 "canvas"
 
+/**
+ * The position of a widget detached into its own floating window.
+ */
+export type DetachedPosition = { 
+/**
+ * The leftmost x-coordinate of the window in physical screen pixels.
+ */
+x: number; 
+/**
+ * The topmost y-coordinate of the window in physical screen pixels.
+ */
+y: number }
+
+/**
+ * Event for notifying frontend windows of a fine-grained catalog change.
+ * 
+ * Unlike [`UpdateEvent`] which carries the full catalog, this event only
+ * carries the affected widget entry. The full snapshot is reserved for
+ * (re)synchronizing the whole catalog.
+ */
+export type DiffEvent = 
+/**
+ * A widget has been added to the catalog.
+ */
+{ type: "widgetAdded"; content: { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * The added widget.
+ */
+widget: Widget } } | 
+/**
+ * A widget has been removed from the catalog.
+ */
+{ type: "widgetRemoved"; content: { 
+/**
+ * The ID of the widget.
+ */
+id: string } } | 
+/**
+ * A widget in the catalog has changed.
+ */
+{ type: "widgetChanged"; content: { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * The changed widget.
+ */
+widget: Widget } }
+
+/**
+ * The progress of installing a widget from the registry.
+ */
+export type InstallProgress = 
+/**
+ * Verifying the signature of the widget package.
+ */
+{ type: "verifying" } | 
+/**
+ * Resolving the widget package in the registry.
+ */
+{ type: "resolving" } | 
+/**
+ * Downloading the widget package.
+ */
+{ type: "downloading"; content: { 
+/**
+ * The number of bytes downloaded so far.
+ */
+received: number; 
+/**
+ * The total size of the widget package in bytes.
+ */
+total: number } } | 
+/**
+ * Unpacking the downloaded widget package.
+ */
+{ type: "unpacking" } | 
+/**
+ * Validating the unpacked widget.
+ */
+{ type: "validating" }
+
+/**
+ * Event for reporting the progress of installing a widget from the registry.
+ * 
+ * This is emitted for installs and upgrades started with a request ID, so
+ * that the frontend can follow the progress of its own request and cancel it
+ * if needed.
+ */
+export type InstallProgressEvent = { 
+/**
+ * The ID of the install request.
+ */
+requestId: string; 
+/**
+ * The progress of the installation.
+ */
+progress: InstallProgress }
+
+/**
+ * How a widget responds to cursor events.
+ */
+export type Interactivity = 
+/**
+ * Follow the canvas interaction mode.
+ */
+"inherit" | 
+/**
+ * Stay interactive even if the canvas is in sink mode.
+ */
+"alwaysInteractive" | 
+/**
+ * Stay click-through even if the canvas is in float mode.
+ */
+"clickThrough"
+
+/**
+ * A problem found in a widget manifest.
+ */
+export type ManifestDiagnostic = { 
+/**
+ * The severity of the problem.
+ */
+severity: ManifestSeverity; 
+/**
+ * The description of the problem.
+ */
+message: string; 
+/**
+ * The path to the offending field in the manifest, e.g.,
+ * `requiredPlugins[0]`.
+ * 
+ * This is `None` if the problem is not specific to a field, e.g., if the
+ * manifest cannot be read or is not valid JSON or TOML.
+ */
+field: string | null }
+
+/**
+ * The severity of a problem in a widget manifest.
+ */
+export type ManifestSeverity = 
+/**
+ * The widget works but likely not as intended.
+ */
+"warning" | 
+/**
+ * The widget cannot work.
+ */
+"error"
+
+/**
+ * A plugin required by a widget that is unavailable.
+ */
+export type MissingPlugin = { 
+/**
+ * The name of the plugin.
+ */
+name: string; 
+/**
+ * The requirement as declared in the widget manifest, e.g., `sys>=0.3`.
+ */
+requirement: string; 
+/**
+ * The version of the plugin that is available but does not satisfy the
+ * requirement.
+ * 
+ * This is `None` if the plugin is not available at all.
+ */
+found: string | null }
+
+/**
+ * The position of a widget relative to the monitor it is on.
+ * 
+ * Absolute coordinates break when the monitor layout changes, e.g., when a
+ * monitor is unplugged or its DPI changes. Anchoring widgets to monitors
+ * allows re-resolving their absolute coordinates in such cases.
+ */
+export type MonitorAnchor = { 
+/**
+ * The identifier of the monitor.
+ */
+monitor: string; 
+/**
+ * The x-coordinate relative to the left edge of the monitor in pixels.
+ */
+x: number; 
+/**
+ * The y-coordinate relative to the top edge of the monitor in pixels.
+ */
+y: number }
+
+/**
+ * A network request issued by a widget.
+ */
+export type NetworkRequest = { 
+/**
+ * The absolute URL of the request.
+ */
+url: string; 
+/**
+ * The HTTP method of the request.
+ */
+method: string; 
+/**
+ * The headers of the request.
+ */
+headers: ([string, string])[]; 
+/**
+ * The base64-encoded body of the request, if any.
+ */
+body?: string }
+
+/**
+ * The response to a [`NetworkRequest`].
+ */
+export type NetworkResponse = { 
+/**
+ * The final URL of the response after redirects.
+ */
+url: string; 
+/**
+ * The HTTP status code.
+ */
+status: number; 
+/**
+ * The HTTP status text.
+ */
+statusText: string; 
+/**
+ * The headers of the response.
+ */
+headers: ([string, string])[]; 
+/**
+ * The base64-encoded body of the response.
+ */
+body: string }
+
 /**
  * A result-like binary outcome.
  * 
@@ -57,7 +457,34 @@ description: string;
 /**
  * The releases of the widget, ordered from newest to oldest.
  */
-releases: RegistryEntryRelease[] }
+releases: RegistryEntryRelease[]; 
+/**
+ * The tags of the widget.
+ */
+tags: string[]; 
+/**
+ * The categories of the widget.
+ * 
+ * See [`crate::catalog::WidgetManifest::categories`].
+ */
+categories: string[]; 
+/**
+ * The keywords of the widget.
+ * 
+ * See [`crate::catalog::WidgetManifest::keywords`].
+ */
+keywords: string[]; 
+/**
+ * The number of downloads of the widget across all releases.
+ */
+downloads: number; 
+/**
+ * The ID of the registry the widget comes from.
+ * 
+ * See [`Registry::id`] for details. This is not part of the registry
+ * index but filled in when the index is fetched.
+ */
+registry?: string }
 
 /**
  * An entry for a specific release of a widget in the registry.
@@ -91,10 +518,68 @@ api: number;
  * The datetime when the index was generated, in ISO 8601 format.
  */
 generatedAt: string; 
+/**
+ * The generation of the index, if the registry supports delta updates.
+ * 
+ * This increases every time the index changes.
+ */
+generation?: number; 
+/**
+ * The URL of the delta endpoint, if the registry supports delta updates.
+ * 
+ * See [`RegistryIndexDelta`] for details.
+ */
+deltaUrl?: string; 
 /**
  * The list of widgets in the registry.
  */
-widgets: RegistryEntry[] }
+widgets: RegistryEntry[]; 
+/**
+ * The list of revoked widget packages.
+ * 
+ * Revoked packages are refused on installation, and flagged if already
+ * installed.
+ */
+revoked: RevokedPackage[] }
+
+/**
+ * A page of registry search results.
+ */
+export type RegistrySearchPage = { 
+/**
+ * The widgets on this page.
+ */
+widgets: RegistryEntry[]; 
+/**
+ * The zero-based index of this page.
+ */
+page: number; 
+/**
+ * The total number of pages.
+ */
+pageCount: number; 
+/**
+ * The total number of matching widgets across all pages.
+ */
+total: number }
+
+/**
+ * The order of registry search results.
+ */
+export type RegistrySort = 
+/**
+ * Best matches first; widgets keep their order in the index if there is
+ * no query text.
+ */
+"relevance" | 
+/**
+ * Most recently released first.
+ */
+"recent" | 
+/**
+ * Most downloaded first.
+ */
+"popular"
 
 /**
  * Preview information about a widget in the registry.
@@ -127,87 +612,399 @@ description?: string;
 /**
  * URL to the homepage of the widget.
  */
-homepage?: string }) & { 
+homepage?: string }) & { 
+/**
+ * The local ID of the widget.
+ * 
+ * See [`RegistryWidgetReference::local_id`] for details.
+ */
+id: string; 
+/**
+ * The size of the widget package in bytes.
+ */
+size: number; 
+/**
+ * The URL of the widget package in the registry.
+ */
+registryUrl: string; 
+/**
+ * The creation datetime of the widget package, in ISO 8601 format.
+ */
+created?: string; 
+/**
+ * The git repository URL of the widget source code.
+ */
+git?: string; 
+/**
+ * The README of the widget in Markdown, if fetched and available.
+ */
+readme?: string; 
+/**
+ * The screenshots of the widget as data URLs, if fetched and available.
+ */
+screenshots: string[] }
+
+/**
+ * A reference to a widget in the registry.
+ * 
+ * These information uniquely and immutably identify a widget package in the
+ * widgets registry.
+ */
+export type RegistryWidgetReference = { 
+/**
+ * The publisher handle.
+ */
+handle: string; 
+/**
+ * The widget ID.
+ * 
+ * Note that this ID is unique only within the publisher's namespace.
+ */
+id: string; 
+/**
+ * The SHA-256 digest of the widget package.
+ */
+digest: string; 
+/**
+ * The ID of the registry the widget comes from.
+ * 
+ * See [`Registry::id`] for details. If not set, the widget comes from the
+ * official registry.
+ */
+registry?: string }
+
+/**
+ * Event for reporting the rendering result of a widget to the canvas.
+ */
+export type RenderEvent = { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * Either the code string to render or a bundling error message.
+ */
+report: Outcome<string>; 
+/**
+ * Structured diagnostics if bundling failed.
+ * 
+ * Each diagnostic locates the problem in the original widget sources.
+ * This may be empty even on failure if the error is not attributable to
+ * specific locations.
+ */
+diagnostics: BundleDiagnostic[]; 
+/**
+ * Structured warnings if bundling succeeded.
+ * 
+ * These are the type errors found if type checking is enabled for the
+ * widget. They do not prevent the widget from rendering.
+ */
+warnings: BundleDiagnostic[] }
+
+/**
+ * The timing breakdown of the latest render of a widget.
+ */
+export type RenderTimings = { 
+/**
+ * The time spent waiting for a free render slot in milliseconds.
+ */
+queuedMs: number; 
+/**
+ * The time taken to bundle in milliseconds.
+ * 
+ * This includes creating the bundler if it cannot be reused.
+ */
+bundleMs: number; 
+/**
+ * The time taken to type check in milliseconds.
+ * 
+ * This is `None` if type checking is disabled for the widget. Type
+ * checking runs alongside bundling.
+ */
+typeCheckMs: number | null; 
+/**
+ * The time taken to emit the render event in milliseconds.
+ */
+emitMs: number; 
+/**
+ * The total time from submitting the render to emitting the render event
+ * in milliseconds.
+ */
+totalMs: number }
+
+/**
+ * A widget package revoked by the registry, e.g., for being malicious.
+ */
+export type RevokedPackage = { 
+/**
+ * The SHA-256 digest of the revoked widget package.
+ */
+digest: string; 
+/**
+ * The reason for the revocation.
+ */
+reason: string }
+
+/**
+ * An installed widget whose package has been revoked by the registry.
+ */
+export type RevokedWidget = { 
+/**
+ * The local ID of the widget.
+ */
+id: string; 
+/**
+ * The digest of the installed widget package.
+ */
+digest: string; 
+/**
+ * The reason for the revocation.
+ */
+reason: string }
+
+/**
+ * Event for flagging installed widgets whose packages have been revoked by
+ * the registry.
+ * 
+ * This is emitted after each check for updates, even if no widgets are
+ * revoked, so that stale flags can be cleared.
+ */
+export type RevokedWidgetsEvent = RevokedWidget[]
+
+/**
+ * A warning reported in strict mode.
+ */
+export type StrictWarning = 
+/**
+ * The widget uses a deprecated API.
+ */
+{ kind: "deprecatedApi"; 
+/**
+ * The deprecated API, e.g., a module specifier or a global.
+ */
+api: string; 
+/**
+ * The advice for replacing the deprecated API.
+ */
+advice: string; 
+/**
+ * The file using the API, relative to the widget root.
+ */
+file: string | null } | 
+/**
+ * An event payload of the widget is oversized.
+ */
+{ kind: "oversizedEventPayload"; 
+/**
+ * The name of the event.
+ */
+event: string; 
+/**
+ * The size of the payload in bytes.
+ */
+size: number; 
+/**
+ * The maximum size in bytes, see [`MAX_EVENT_PAYLOAD`].
+ */
+limit: number } | 
+/**
+ * The widget was unloaded with network requests still in flight.
+ */
+{ kind: "unclosedRequests"; 
+/**
+ * The number of requests in flight.
+ */
+count: number }
+
+/**
+ * Event for reporting to the canvas that a widget has timed out rendering.
+ * 
+ * This is emitted if the canvas does not report completion of evaluating the
+ * widget module within the configured render timeout, e.g., when the module
+ * awaits forever at the top level.
+ */
+export type TimeoutEvent = { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * The render timeout in seconds that has been exceeded.
+ */
+timeoutSeconds: number }
+
+/**
+ * Event for notifying frontend windows of a widget catalog update.
+ * 
+ * This carries the full catalog snapshot and is only emitted when the whole
+ * catalog is (re)synchronized. See [`DiffEvent`] for incremental changes.
+ */
+export type UpdateEvent = WidgetCatalog
+
+/**
+ * Event for notifying frontend windows of available upgrades of widgets
+ * installed from the registry.
+ * 
+ * This is emitted after each check for updates, even if no upgrades are
+ * available, so that stale badges can be cleared.
+ */
+export type UpgradesAvailableEvent = WidgetUpgrade[]
+
+/**
+ * The report of validating a widget.
+ * 
+ * This mirrors the [`RenderEvent`](crate::events::RenderEvent) that rendering
+ * the widget would emit, with the bundled code replaced by its size.
+ */
+export type ValidationReport = { 
+/**
+ * Either the bundle size in bytes or an error message.
+ * 
+ * The error may come from loading the widget manifest or from bundling.
+ */
+report: Outcome<number>; 
+/**
+ * Structured diagnostics if bundling failed.
+ */
+diagnostics: BundleDiagnostic[]; 
+/**
+ * Structured warnings if bundling succeeded.
+ * 
+ * These are the type errors found if type checking is enabled for the
+ * widget.
+ */
+warnings: BundleDiagnostic[]; 
+/**
+ * The warnings reported if the widget is in strict mode.
+ */
+strictWarnings: StrictWarning[] }
+
+/**
+ * A Deskulpt widget.
+ */
+export type Widget = { 
+/**
+ * The manifest of the widget or an error message loading it.
+ */
+manifest: Outcome<WidgetManifest>; 
+/**
+ * The settings of the widget.
+ */
+settings: WidgetSettings; 
+/**
+ * The statistics of the latest successful bundle of the widget.
+ * 
+ * This is `None` if the widget has not been successfully bundled since
+ * it was last loaded.
+ */
+bundleStats: BundleStats | null; 
+/**
+ * The warnings reported in strict mode.
+ * 
+ * This is empty if the widget is not in strict mode.
+ */
+strictWarnings: StrictWarning[]; 
 /**
- * The local ID of the widget.
+ * The required plugins of the widget that are unavailable.
  * 
- * See [`RegistryWidgetReference::local_id`] for details.
+ * The widget may fail at runtime when using these plugins.
  */
-id: string; 
+missingPlugins: MissingPlugin[]; 
 /**
- * The size of the widget package in bytes.
+ * The warnings found in the widget manifest.
+ * 
+ * See [`WidgetManifest::validate`]. Errors are reported in place of the
+ * manifest instead.
  */
-size: number; 
+manifestWarnings: ManifestDiagnostic[]; 
 /**
- * The URL of the widget package in the registry.
+ * The registry package the widget was installed from.
+ * 
+ * This is `None` for local widgets, including registry widgets installed
+ * before installs were recorded in the lockfile.
  */
-registryUrl: string; 
+source: RegistryWidgetReference | null; 
 /**
- * The creation datetime of the widget package, in ISO 8601 format.
+ * The permissions in effect for the widget.
+ * 
+ * Local widgets are trusted with the permissions they request, while
+ * registry widgets only get the requested permissions granted by the
+ * user. This is `None` if a local widget does not declare permissions, see
+ * [`WidgetManifest::permissions`].
  */
-created?: string; 
+permissions: WidgetPermissions | null; 
 /**
- * The git repository URL of the widget source code.
+ * The permissions requested by the widget but not granted by the user.
+ * 
+ * This is always empty for local widgets. The user should be prompted to
+ * grant these permissions.
  */
-git?: string }
+pendingPermissions: WidgetPermissions }
 
 /**
- * A reference to a widget in the registry.
+ * The catalog of Deskulpt widgets.
  * 
- * These information uniquely and immutably identify a widget package in the
- * widgets registry.
+ * Widgets are keyed by their IDs, which are the paths of the widget
+ * directories relative to the widgets directory, separated by `/`.
  */
-export type RegistryWidgetReference = { 
+export type WidgetCatalog = { [key in string]: Widget }
+
 /**
- * The publisher handle.
+ * The health report of a candidate widget directory.
  */
-handle: string; 
+export type WidgetDirReport = { 
 /**
- * The widget ID.
+ * The path of the directory relative to the widgets directory, separated
+ * by `/`.
  * 
- * Note that this ID is unique only within the publisher's namespace.
+ * This is the widget ID if the directory is recognized as a widget.
  */
 id: string; 
 /**
- * The SHA-256 digest of the widget package.
+ * The status of the directory.
  */
-digest: string }
-
+status: WidgetDirStatus; 
 /**
- * Event for reporting the rendering result of a widget to the canvas.
+ * The problems found in the widget manifest that do not prevent the
+ * directory from being recognized as a widget.
+ * 
+ * This is empty unless the directory is recognized as a widget.
  */
-export type RenderEvent = { 
+warnings: string[] }
+
 /**
- * The ID of the widget.
+ * The status of a candidate widget directory.
  */
-id: string; 
+export type WidgetDirStatus = 
 /**
- * Either the code string to render or a bundling error message.
+ * The directory is recognized as a widget.
  */
-report: Outcome<string> }
-
+{ kind: "widget" } | 
 /**
- * Event for notifying frontend windows of a widget catalog update.
+ * The directory is skipped because it has no widget manifest.
  */
-export type UpdateEvent = WidgetCatalog
-
+{ kind: "noManifest" } | 
 /**
- * A Deskulpt widget.
+ * The directory is skipped because its widget manifest marks itself as
+ * ignored.
  */
-export type Widget = { 
+{ kind: "ignored" } | 
 /**
- * The manifest of the widget or an error message loading it.
+ * The widget manifest of the directory cannot be loaded.
  */
-manifest: Outcome<WidgetManifest>; 
+{ kind: "invalidManifest"; 
 /**
- * The settings of the widget.
+ * The error message.
  */
-settings: WidgetSettings }
-
+message: string; 
 /**
- * The catalog of Deskulpt widgets.
+ * The path to the offending field in the manifest, e.g.,
+ * `requiredPlugins[0]`.
+ * 
+ * This is `None` if the manifest cannot be read or is not valid JSON
+ * or TOML.
  */
-export type WidgetCatalog = { [key in string]: Widget }
+field: string | null }
 
 /**
  * Deskulpt widget manifest.
@@ -236,7 +1033,30 @@ description?: string;
 /**
  * URL to the homepage of the widget.
  */
-homepage?: string }
+homepage?: string; 
+/**
+ * The categories of the widget, e.g., `productivity` or `system`.
+ * 
+ * These are for browsing widgets by category in the registry.
+ */
+categories: string[]; 
+/**
+ * The keywords of the widget.
+ * 
+ * These are matched when searching widgets in the registry, in addition
+ * to the name, description, and authors.
+ */
+keywords: string[]; 
+/**
+ * The permissions requested by the widget.
+ * 
+ * Registry widgets may only use the requested permissions granted by the
+ * user, see [`Widget::permissions`]. If not set, network access is
+ * restricted to [`Self::network_hosts`], while filesystem and plugin
+ * access is unrestricted for local widgets and denied for registry
+ * widgets.
+ */
+permissions?: WidgetPermissions }
 
 /**
  * An author of a Deskulpt widget.
@@ -267,6 +1087,67 @@ homepage?: string } |
  */
 string
 
+/**
+ * Network usage metrics of a widget.
+ * 
+ * Metrics are accumulated since application startup.
+ */
+export type WidgetMetrics = { 
+/**
+ * The number of requests completed.
+ */
+requests: number; 
+/**
+ * The number of requests blocked by the host allowlist.
+ */
+blockedRequests: number; 
+/**
+ * The number of request body bytes sent.
+ */
+bytesSent: number; 
+/**
+ * The number of response body bytes received.
+ */
+bytesReceived: number }
+
+/**
+ * Permissions of a widget.
+ * 
+ * Widgets request permissions in their manifests. Registry widgets may only
+ * use the requested permissions that are also granted by the user, see
+ * [`Settings::widget_grants`].
+ */
+export type WidgetPermissions = { 
+/**
+ * The hosts the widget may access over the network.
+ * 
+ * Each entry is either an exact host name or a wildcard `*.example.com`
+ * that matches the domain and all its subdomains.
+ * 
+ * This is advisory until widgets are isolated from each other: the
+ * network proxy enforces it, and the canvas blocks direct connections
+ * with its content security policy, but widgets share the canvas and can
+ * send requests in the name of other widgets.
+ */
+network?: string[]; 
+/**
+ * The paths the widget may access with the `fs` plugin.
+ * 
+ * Each entry is a path relative to the widget directory, covering the
+ * path itself and everything under it, e.g., `data`. `.` covers the whole
+ * widget directory.
+ */
+filesystem?: string[]; 
+/**
+ * The plugin commands the widget may call.
+ * 
+ * Each entry is either a plugin name covering all its commands, e.g.,
+ * `sys`, or a plugin name and a command separated by a dot, e.g.,
+ * `fs.read_file`. Searching files in user directories is covered by
+ * `search.files`.
+ */
+plugins?: string[] }
+
 /**
  * Deskulpt widget settings.
  */
@@ -279,6 +1160,15 @@ x: number;
  * The topmost y-coordinate in pixels.
  */
 y: number; 
+/**
+ * The anchor of the widget position relative to a monitor.
+ * 
+ * This is derived from [`WidgetSettings::x`] and [`WidgetSettings::y`]
+ * whenever they change, and used to re-resolve them when the monitor
+ * layout changes. It is `None` for settings persisted by older versions,
+ * in which case it will be derived once monitors are known.
+ */
+anchor: MonitorAnchor | null; 
 /**
  * The width in pixels.
  */
@@ -302,7 +1192,29 @@ zIndex: number;
 /**
  * Whether the widget should be loaded on the canvas or not.
  */
-isLoaded: boolean }
+isLoaded: boolean; 
+/**
+ * How the widget responds to cursor events.
+ * 
+ * This overrides the canvas interaction mode for the widget, e.g., a clock
+ * can stay click-through even if the canvas is in float mode.
+ */
+interactivity: Interactivity; 
+/**
+ * Whether the widget is frozen.
+ * 
+ * A frozen widget keeps its current render, skipping re-renders on
+ * changes, periodic refreshes, and theme changes, until unfrozen.
+ */
+frozen: boolean; 
+/**
+ * The position of the floating window of the widget, if detached.
+ * 
+ * A detached widget is rendered in its own always-on-top window instead
+ * of on the canvas, and [`WidgetSettings::x`] and [`WidgetSettings::y`]
+ * are kept so that it returns to its place when attached back.
+ */
+detached: DetachedPosition | null }
 
 /**
  * A patch for partial updates to [`WidgetSettings`].
@@ -335,7 +1247,36 @@ zIndex?: number;
 /**
  * If not `None`, update [`WidgetSettings::is_loaded`].
  */
-isLoaded?: boolean }
+isLoaded?: boolean; 
+/**
+ * If not `None`, update [`WidgetSettings::interactivity`].
+ */
+interactivity?: Interactivity; 
+/**
+ * If not `None`, update [`WidgetSettings::frozen`].
+ */
+frozen?: boolean }
+
+/**
+ * An available upgrade of an installed widget.
+ */
+export type WidgetUpgrade = { 
+/**
+ * The local ID of the widget.
+ */
+id: string; 
+/**
+ * The version of the installed release, if known.
+ */
+currentVersion: string | null; 
+/**
+ * The version of the latest release.
+ */
+latestVersion: string; 
+/**
+ * The reference to the latest release, to be passed to the upgrade.
+ */
+latest: RegistryWidgetReference }
 
 // =============================================================================
 // Events
@@ -360,8 +1301,14 @@ function makeEvent<T>(name: string) {
 }
 
 export namespace Events {
+  export const bundleSize = makeEvent<BundleSizeEvent>("deskulpt-widgets://bundle-size");
+  export const diff = makeEvent<DiffEvent>("deskulpt-widgets://diff");
+  export const installProgress = makeEvent<InstallProgressEvent>("deskulpt-widgets://install-progress");
   export const render = makeEvent<RenderEvent>("deskulpt-widgets://render");
+  export const revokedWidgets = makeEvent<RevokedWidgetsEvent>("deskulpt-widgets://revoked-widgets");
+  export const timeout = makeEvent<TimeoutEvent>("deskulpt-widgets://timeout");
   export const update = makeEvent<UpdateEvent>("deskulpt-widgets://update");
+  export const upgradesAvailable = makeEvent<UpgradesAvailableEvent>("deskulpt-widgets://upgrades-available");
 }
 
 // =============================================================================
@@ -369,6 +1316,58 @@ export namespace Events {
 // =============================================================================
 
 export namespace Commands {
+  /**
+   * Align widgets with each other.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::align_widgets`].
+   */
+  export const alignWidgets = (
+    ids: string[],
+    alignment: Alignment,
+  ) => invoke<null>("plugin:deskulpt-widgets|align_widgets", {
+    ids,
+    alignment,
+  });
+
+  /**
+   * Get the statistics of the latest successful bundles of widgets.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::bundle_stats`].
+   */
+  export const bundleStats = () => invoke<{ [key in string]: BundleStats }>("plugin:deskulpt-widgets|bundle_stats");
+
+  /**
+   * Cancel a pending install or upgrade from the registry.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::cancel_install`].
+   */
+  export const cancelInstall = (
+    requestId: string,
+  ) => invoke<boolean>("plugin:deskulpt-widgets|cancel_install", {
+    requestId,
+  });
+
+  /**
+   * Report the health of every candidate widget directory.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::catalog_report`].
+   */
+  export const catalogReport = () => invoke<WidgetDirReport[]>("plugin:deskulpt-widgets|catalog_report");
+
+  /**
+   * Check for upgrades of widgets installed from the registry.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::check_updates`].
+   */
+  export const checkUpdates = () => invoke<WidgetUpgrade[]>("plugin:deskulpt-widgets|check_updates");
+
+  /**
+   * Get the metrics of damage tracking.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::damage_metrics`].
+   */
+  export const damageMetrics = () => invoke<DamageMetrics>("plugin:deskulpt-widgets|damage_metrics");
+
   /**
    * Fetch the widgets registry index.
    * 
@@ -377,6 +1376,30 @@ export namespace Commands {
    */
   export const fetchRegistryIndex = () => invoke<RegistryIndex>("plugin:deskulpt-widgets|fetch_registry_index");
 
+  /**
+   * Grant permissions to a widget, or revoke them.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::grant_permissions`].
+   */
+  export const grantPermissions = (
+    id: string,
+    permissions: WidgetPermissions | null,
+  ) => invoke<null>("plugin:deskulpt-widgets|grant_permissions", {
+    id,
+    permissions,
+  });
+
+  /**
+   * Import a widget from another desktop widget platform.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::import_widget`].
+   */
+  export const importWidget = (
+    src: string,
+  ) => invoke<string>("plugin:deskulpt-widgets|import_widget", {
+    src,
+  });
+
   /**
    * Install a widget from the registry.
    * 
@@ -384,8 +1407,27 @@ export namespace Commands {
    */
   export const install = (
     widget: RegistryWidgetReference,
+    version: string | null,
+    requestId: string | null,
+    grant: WidgetPermissions | null,
   ) => invoke<null>("plugin:deskulpt-widgets|install", {
     widget,
+    version,
+    requestId,
+    grant,
+  });
+
+  /**
+   * List the releases of a widget in the registry.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::list_releases`].
+   */
+  export const listReleases = (
+    handle: string,
+    id: string,
+  ) => invoke<RegistryEntryRelease[]>("plugin:deskulpt-widgets|list_releases", {
+    handle,
+    id,
   });
 
   /**
@@ -395,10 +1437,32 @@ export namespace Commands {
    */
   export const preview = (
     widget: RegistryWidgetReference,
+    includeAssets: boolean,
   ) => invoke<RegistryWidgetPreview>("plugin:deskulpt-widgets|preview", {
     widget,
+    includeAssets,
+  });
+
+  /**
+   * Send a network request on behalf of a widget.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::proxy_fetch`].
+   */
+  export const proxyFetch = (
+    id: string,
+    request: NetworkRequest,
+  ) => invoke<NetworkResponse>("plugin:deskulpt-widgets|proxy_fetch", {
+    id,
+    request,
   });
 
+  /**
+   * Redo the most recently undone layout change of widgets.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::redo_layout_change`].
+   */
+  export const redoLayoutChange = () => invoke<boolean>("plugin:deskulpt-widgets|redo_layout_change");
+
   /**
    * Refresh a specific widget by its ID.
    * 
@@ -417,6 +1481,120 @@ export namespace Commands {
    */
   export const refreshAll = () => invoke<null>("plugin:deskulpt-widgets|refresh_all");
 
+  /**
+   * Count the widgets in each category of the widgets registry.
+   * 
+   * This command is a wrapper of
+   * [`crate::WidgetsManager::registry_categories`].
+   */
+  export const registryCategories = () => invoke<{ [key in string]: number }>("plugin:deskulpt-widgets|registry_categories");
+
+  /**
+   * Reinstall the widgets recorded in a lockfile that are missing locally.
+   * 
+   * This command is a wrapper of
+   * [`crate::WidgetsManager::reinstall_from_lockfile`].
+   */
+  export const reinstallFromLockfile = (
+    path: string | null,
+  ) => invoke<Partial<{ [key in string]: Outcome<null> }>>("plugin:deskulpt-widgets|reinstall_from_lockfile", {
+    path,
+  });
+
+  /**
+   * Get the timing breakdowns of the latest renders of widgets.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::render_timings`].
+   */
+  export const renderTimings = () => invoke<{ [key in string]: RenderTimings }>("plugin:deskulpt-widgets|render_timings");
+
+  /**
+   * Report that the canvas has finished evaluating a widget module.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::report_rendered`].
+   */
+  export const reportRendered = (
+    id: string,
+  ) => invoke<null>("plugin:deskulpt-widgets|report_rendered", {
+    id,
+  });
+
+  /**
+   * Report an abusive widget in the registry.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::report_widget`].
+   */
+  export const reportWidget = (
+    widget: RegistryWidgetReference,
+    reason: string,
+  ) => invoke<boolean>("plugin:deskulpt-widgets|report_widget", {
+    widget,
+    reason,
+  });
+
+  /**
+   * Roll back a widget installed from the registry to its previous release.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::rollback_widget`].
+   */
+  export const rollbackWidget = (
+    id: string,
+  ) => invoke<null>("plugin:deskulpt-widgets|rollback_widget", {
+    id,
+  });
+
+  /**
+   * Search the widgets registry.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::search_registry`].
+   */
+  export const searchRegistry = (
+    query: string,
+    category: string | null,
+    tags: string[],
+    sort: RegistrySort,
+    page: number,
+  ) => invoke<RegistrySearchPage>("plugin:deskulpt-widgets|search_registry", {
+    query,
+    category,
+    tags,
+    sort,
+    page,
+  });
+
+  /**
+   * Map a runtime stack trace of a widget back to its original sources.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::symbolicate`].
+   */
+  export const symbolicate = (
+    id: string,
+    stack: string,
+  ) => invoke<string>("plugin:deskulpt-widgets|symbolicate", {
+    id,
+    stack,
+  });
+
+  /**
+   * Take the canvas regions damaged since the last frame.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::take_damage_hints`].
+   */
+  export const takeDamageHints = (
+    viewportWidth: number,
+    viewportHeight: number,
+  ) => invoke<DamageHints>("plugin:deskulpt-widgets|take_damage_hints", {
+    viewportWidth,
+    viewportHeight,
+  });
+
+  /**
+   * Undo the most recent layout change of widgets.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::undo_layout_change`].
+   */
+  export const undoLayoutChange = () => invoke<boolean>("plugin:deskulpt-widgets|undo_layout_change");
+
   /**
    * Uninstall a widget from the registry.
    * 
@@ -428,6 +1606,13 @@ export namespace Commands {
     widget,
   });
 
+  /**
+   * Update all widgets with newer releases in the registry.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::update_all`].
+   */
+  export const updateAll = () => invoke<Partial<{ [key in string]: Outcome<string> }>>("plugin:deskulpt-widgets|update_all");
+
   /**
    * Update the settings of a widget with a patch.
    * 
@@ -441,6 +1626,17 @@ export namespace Commands {
     patch,
   });
 
+  /**
+   * Update a widget to its latest release in the registry.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::update_widget`].
+   */
+  export const updateWidget = (
+    id: string,
+  ) => invoke<WidgetUpgrade | null>("plugin:deskulpt-widgets|update_widget", {
+    id,
+  });
+
   /**
    * Upgrade a widget from the registry.
    * 
@@ -448,7 +1644,38 @@ export namespace Commands {
    */
   export const upgrade = (
     widget: RegistryWidgetReference,
+    requestId: string | null,
   ) => invoke<null>("plugin:deskulpt-widgets|upgrade", {
     widget,
+    requestId,
+  });
+
+  /**
+   * Validate the manifest of a widget.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::validate_manifest`].
+   */
+  export const validateManifest = (
+    id: string,
+  ) => invoke<ManifestDiagnostic[]>("plugin:deskulpt-widgets|validate_manifest", {
+    id,
+  });
+
+  /**
+   * Validate a widget without rendering it.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::validate_widget`].
+   */
+  export const validateWidget = (
+    id: string,
+  ) => invoke<ValidationReport>("plugin:deskulpt-widgets|validate_widget", {
+    id,
   });
+
+  /**
+   * Get the network usage metrics of widgets.
+   * 
+   * This command is a wrapper of [`crate::WidgetsManager::widget_metrics`].
+   */
+  export const widgetMetrics = () => invoke<{ [key in string]: WidgetMetrics }>("plugin:deskulpt-widgets|widget_metrics");
 }
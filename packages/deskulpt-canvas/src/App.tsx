@@ -1,33 +1,56 @@
 import WidgetContainer from "./components/WidgetContainer";
+import ImodeIndicator from "./components/ImodeIndicator";
 import { Toaster } from "sonner";
 import { Theme as RadixTheme } from "@radix-ui/themes";
 import { useShallow } from "zustand/shallow";
 import {
+  useBundleSizeListener,
+  useCompositingProbe,
+  useDamageHints,
+  useImodeChangedListener,
   useInitialRefresh,
+  useRenderTimeoutListener,
   useRenderWidgetListener,
   useSettingsStore,
   useShowToastListener,
   useUpdateSettingsListener,
   useUpdateWidgetCatalogListener,
+  useWidgetHoverListener,
   useWidgetsStore,
 } from "./hooks";
 
+// The ID of the widget if this is its floating window instead of the canvas
+const detachedWidget = window.__DESKULPT_INTERNALS__.detachedWidget;
+
 const App = () => {
   const theme = useSettingsStore((state) => state.theme);
   const ids = useWidgetsStore(
     useShallow((state) =>
       Object.entries(state)
-        .filter(([_, { settings }]) => settings !== undefined)
-        .map(([id]) => id),
+        .filter(([id, { settings }]) =>
+          detachedWidget === null
+            ? settings !== undefined && settings.detached === null
+            : settings !== undefined && id === detachedWidget,
+        )
+        .map(([id]) => id)
+        // Widgets with the same z-index are stacked in this order, which must
+        // match hit testing in the backend
+        .sort(),
     ),
   );
 
+  useBundleSizeListener();
+  useImodeChangedListener();
+  useRenderTimeoutListener();
   useRenderWidgetListener();
   useShowToastListener();
   useUpdateSettingsListener();
   useUpdateWidgetCatalogListener();
+  useWidgetHoverListener();
 
   useInitialRefresh();
+  useCompositingProbe();
+  useDamageHints();
 
   return (
     <RadixTheme
@@ -48,8 +71,13 @@ const App = () => {
           },
         }}
       />
+      {detachedWidget === null && <ImodeIndicator />}
       {ids.map((id) => (
-        <WidgetContainer key={id} id={id} />
+        <WidgetContainer
+          key={id}
+          id={id}
+          detached={detachedWidget !== null}
+        />
       ))}
     </RadixTheme>
   );
@@ -1,5 +1,8 @@
 import { css } from "@emotion/react";
-import { Box, Code, Dialog, ScrollArea, Text } from "@radix-ui/themes";
+import { Box, Code, Dialog, Flex, ScrollArea, Text } from "@radix-ui/themes";
+import { ReactNode, useEffect, useState } from "react";
+import { DeskulptWidgets } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
 
 const styles = {
   trigger: css({ cursor: "pointer" }),
@@ -9,9 +12,47 @@ interface ErrorDisplayProps {
   id: string;
   error: string;
   message: string;
+  /**
+   * Structured bundling diagnostics located in the original widget sources.
+   *
+   * If non-empty, these are displayed instead of the raw message.
+   */
+  diagnostics?: DeskulptWidgets.BundleDiagnostic[];
+  /**
+   * A runtime stack trace of the error.
+   *
+   * It is mapped back to the original widget sources and displayed below the
+   * message.
+   */
+  stack?: string;
+  actions?: ReactNode;
 }
 
-const ErrorDisplay = ({ id, error, message }: ErrorDisplayProps) => {
+const formatLocation = (diagnostic: DeskulptWidgets.BundleDiagnostic) =>
+  diagnostic.file === null
+    ? ""
+    : `${diagnostic.file}:${diagnostic.line ?? 0}:${diagnostic.column ?? 0}: `;
+
+const ErrorDisplay = ({
+  id,
+  error,
+  message,
+  diagnostics = [],
+  stack,
+  actions,
+}: ErrorDisplayProps) => {
+  const [mappedStack, setMappedStack] = useState(stack);
+
+  useEffect(() => {
+    setMappedStack(stack);
+    if (stack === undefined) {
+      return;
+    }
+    DeskulptWidgets.Commands.symbolicate(id, stack)
+      .then(setMappedStack)
+      .catch(logger.error);
+  }, [id, stack]);
+
   return (
     <Dialog.Root>
       <Dialog.Trigger>
@@ -31,15 +72,49 @@ const ErrorDisplay = ({ id, error, message }: ErrorDisplayProps) => {
         </Dialog.Description>
         <ScrollArea asChild>
           <Box px="3" pb="3" maxHeight="50vh">
-            <Box asChild m="0">
-              <pre>
-                <Code size="2" variant="ghost">
-                  {message}
-                </Code>
-              </pre>
-            </Box>
+            {diagnostics.length === 0 ? (
+              <Box asChild m="0">
+                <pre>
+                  <Code size="2" variant="ghost">
+                    {message}
+                  </Code>
+                </pre>
+              </Box>
+            ) : (
+              diagnostics.map((diagnostic, index) => (
+                <Box key={index} mb="3">
+                  <Text size="2" as="div">
+                    <Code variant="ghost">{formatLocation(diagnostic)}</Code>
+                    {diagnostic.message}
+                  </Text>
+                  {diagnostic.snippet !== null && (
+                    <Box asChild m="0" mt="1">
+                      <pre>
+                        <Code size="2" variant="ghost">
+                          {diagnostic.snippet}
+                        </Code>
+                      </pre>
+                    </Box>
+                  )}
+                </Box>
+              ))
+            )}
+            {mappedStack !== undefined && (
+              <Box asChild m="0" mt="3">
+                <pre>
+                  <Code size="2" variant="ghost" color="gray">
+                    {mappedStack}
+                  </Code>
+                </pre>
+              </Box>
+            )}
           </Box>
         </ScrollArea>
+        {actions !== undefined && (
+          <Flex gap="3" mt="4" justify="end">
+            {actions}
+          </Flex>
+        )}
       </Dialog.Content>
     </Dialog.Root>
   );
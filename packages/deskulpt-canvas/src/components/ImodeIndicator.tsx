@@ -0,0 +1,56 @@
+import { useEffect, useState } from "react";
+import { Box } from "@radix-ui/themes";
+import { css } from "@emotion/react";
+import { DeskulptCore } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+
+const styles = {
+  dot: css({
+    width: 6,
+    height: 6,
+    borderRadius: "50%",
+    opacity: 0.6,
+    pointerEvents: "none",
+    transition: "background-color 200ms ease-in-out",
+  }),
+};
+
+/**
+ * A subtle indicator of whether the canvas is accepting cursor events.
+ *
+ * It is hidden until the first interaction state change is reported.
+ */
+const ImodeIndicator = () => {
+  const [state, setState] = useState<DeskulptCore.ImodeChangedEvent>();
+
+  useEffect(() => {
+    const unlisten = DeskulptCore.Events.imodeChanged.listen((event) => {
+      setState(event.payload);
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(logger.error);
+    };
+  }, []);
+
+  if (state === undefined) {
+    return null;
+  }
+
+  return (
+    <Box
+      position="fixed"
+      bottom="2"
+      left="2"
+      title={`Canvas interaction mode: ${state.mode}`}
+      css={styles.dot}
+      style={{
+        backgroundColor: state.isInteractive
+          ? "var(--accent-9)"
+          : "var(--gray-8)",
+      }}
+    />
+  );
+};
+
+export default ImodeIndicator;
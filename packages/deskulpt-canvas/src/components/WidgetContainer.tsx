@@ -16,14 +16,23 @@ import { Box, Text } from "@radix-ui/themes";
 import { useWidgetsStore } from "../hooks";
 import { css } from "@emotion/react";
 import { DeskulptWidgets } from "@deskulpt/bindings";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { toggleWidgetStyles } from "../widgetStyles";
 
 const styles = {
   wrapper: css({
-    "&:hover": {
+    // Hovering is also reported by the backend when click-through
+    "&:hover, &[data-hovered]": {
       ".handle": { opacity: 1 },
       boxShadow:
         "0 0 20px var(--gray-a7), 0 0 40px var(--gray-a5), 0 0 60px var(--gray-a3), inset 0 0 20px var(--gray-a2)",
     },
+    // Dragging is handled by the backend during layout editing
+    "body[data-editing-layout] &": {
+      outline: "1px dashed var(--accent-8)",
+      cursor: "move",
+      "*": { pointerEvents: "none" },
+    },
   }),
   handle: css({
     cursor: "grab",
@@ -46,6 +55,13 @@ interface WidgetGeometry {
 
 interface WidgetContainerProps {
   id: string;
+  /**
+   * Whether the widget is in its own floating window instead of the canvas.
+   *
+   * A detached widget fills its window, which is dragged as a whole via the
+   * handle instead of moving the widget within the window.
+   */
+  detached?: boolean;
 }
 
 function computeResizedGeometry(
@@ -79,7 +95,7 @@ function computeResizedGeometry(
   return { x: newX, y: newY, width: newWidth, height: newHeight };
 }
 
-const WidgetContainer = ({ id }: WidgetContainerProps) => {
+const WidgetContainer = ({ id, detached = false }: WidgetContainerProps) => {
   const draggableRef = useRef<HTMLDivElement>(null);
   const resizeStartRef = useRef<WidgetGeometry>(null);
 
@@ -104,6 +120,11 @@ const WidgetContainer = ({ id }: WidgetContainerProps) => {
     });
   }, [settings]);
 
+  // Styles injected by the widget should not apply when it is unloaded
+  useEffect(() => {
+    toggleWidgetStyles(id, settings.isLoaded);
+  }, [id, settings.isLoaded, Widget]);
+
   const onDragStop = (_: DraggableEvent, data: DraggableData) => {
     setGeometry((prev) => prev && { ...prev, x: data.x, y: data.y });
     DeskulptWidgets.Commands.updateSettings(id, { x: data.x, y: data.y });
@@ -147,6 +168,10 @@ const WidgetContainer = ({ id }: WidgetContainerProps) => {
     DeskulptWidgets.Commands.updateSettings(id, newGeometry);
   };
 
+  const onDetachedDragStart = () => {
+    getCurrentWindow().startDragging().catch(logger.error);
+  };
+
   if (!settings.isLoaded) {
     return null;
   }
@@ -154,13 +179,15 @@ const WidgetContainer = ({ id }: WidgetContainerProps) => {
   return (
     <Draggable
       nodeRef={draggableRef}
-      position={{ x: geometry.x, y: geometry.y }}
+      position={detached ? { x: 0, y: 0 } : { x: geometry.x, y: geometry.y }}
       onStop={onDragStop}
       bounds="body"
       handle=".handle"
+      disabled={detached}
     >
       <Box
         ref={draggableRef}
+        data-widget-id={id}
         overflow="hidden"
         position="absolute"
         css={styles.wrapper}
@@ -172,6 +199,7 @@ const WidgetContainer = ({ id }: WidgetContainerProps) => {
           top="1"
           right="1"
           css={styles.handle}
+          onMouseDown={detached ? onDetachedDragStart : undefined}
           asChild
         >
           <LuGripVertical size={20} />
@@ -181,6 +209,7 @@ const WidgetContainer = ({ id }: WidgetContainerProps) => {
           onResizeStart={onResizeStart}
           onResize={onResize}
           onResizeStop={onResizeStop}
+          enable={detached ? false : undefined}
           css={styles.container}
           style={{ opacity: settings.opacity / 100 }}
         >
@@ -198,6 +227,7 @@ const WidgetContainer = ({ id }: WidgetContainerProps) => {
                 id={id}
                 error="Error in the widget component [React error boundary]"
                 message={stringify(error)}
+                stack={error instanceof Error ? error.stack : undefined}
               />
             )}
           >
@@ -1,7 +1,13 @@
+export * from "./useBundleSizeListener";
+export * from "./useCompositingProbe";
+export * from "./useDamageHints";
+export * from "./useImodeChangedListener";
 export * from "./useInitialRefresh";
+export * from "./useRenderTimeoutListener";
 export * from "./useRenderWidgetListener";
 export * from "./useSettingsStore";
 export * from "./useShowToastListener";
 export * from "./useUpdateSettingsListener";
 export * from "./useUpdateWidgetCatalogListener";
+export * from "./useWidgetHoverListener";
 export * from "./useWidgetsStore";
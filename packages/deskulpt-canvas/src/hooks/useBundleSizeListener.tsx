@@ -0,0 +1,21 @@
+import { useEffect } from "react";
+import { toast } from "sonner";
+import { DeskulptWidgets } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+
+const formatKib = (bytes: number) => `${(bytes / 1024).toFixed(1)} KiB`;
+
+export function useBundleSizeListener() {
+  useEffect(() => {
+    const unlisten = DeskulptWidgets.Events.bundleSize.listen((event) => {
+      const { id, size, budget } = event.payload;
+      void toast.warning(
+        `Bundle of widget ${id} is ${formatKib(size)}, exceeding its budget of ${formatKib(budget)}`,
+      );
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(logger.error);
+    };
+  }, []);
+}
@@ -0,0 +1,34 @@
+import { DeskulptCore } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+import { useEffect } from "react";
+
+/**
+ * Probe the WebGL renderer of the canvas webview.
+ *
+ * This returns `null` if WebGL is unavailable, which by itself often indicates
+ * a broken compositing setup.
+ */
+const probeRenderer = () => {
+  const gl = document.createElement("canvas").getContext("webgl");
+  if (gl === null) {
+    return null;
+  }
+  const info = gl.getExtension("WEBGL_debug_renderer_info");
+  const renderer = gl.getParameter(
+    info === null ? gl.RENDERER : info.UNMASKED_RENDERER_WEBGL,
+  );
+  gl.getExtension("WEBGL_lose_context")?.loseContext();
+  return typeof renderer === "string" ? renderer : null;
+};
+
+export const useCompositingProbe = () => {
+  useEffect(() => {
+    // Only the canvas is diagnosed, not floating windows of detached widgets
+    if (window.__DESKULPT_INTERNALS__.detachedWidget !== null) {
+      return;
+    }
+    DeskulptCore.Commands.diagnoseCompositing(probeRenderer()).catch(
+      logger.error,
+    );
+  }, []);
+};
@@ -0,0 +1,88 @@
+import { useEffect } from "react";
+import { DeskulptCore, DeskulptWidgets } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+
+/**
+ * How long a damaged widget stays on its own compositing layer.
+ *
+ * Widgets that update frequently are kept promoted across updates instead of
+ * being promoted and demoted repeatedly.
+ */
+const PROMOTION_MS = 2000;
+
+/**
+ * Confine canvas repaints to damaged widgets.
+ *
+ * After widget changes, the damage hints are taken from the backend once per
+ * frame. Damaged widgets are promoted to their own compositing layers, so that
+ * repainting them does not invalidate the rest of the canvas. Frames that need
+ * a full refresh are left to the webview, e.g., after the display configuration
+ * changes and widgets are laid out against the new monitor layout.
+ *
+ * Damage hints are tracked for the canvas only, so this is a no-op in floating
+ * windows of detached widgets.
+ */
+export const useDamageHints = () => {
+  useEffect(() => {
+    if (window.__DESKULPT_INTERNALS__.detachedWidget !== null) {
+      return;
+    }
+
+    const timers = new Map<string, number>();
+    let frame: number | undefined;
+
+    const promote = (id: string) => {
+      const element = document.querySelector<HTMLElement>(
+        `[data-widget-id="${CSS.escape(id)}"]`,
+      );
+      if (element === null) {
+        return;
+      }
+      element.style.willChange = "transform";
+      clearTimeout(timers.get(id));
+      timers.set(
+        id,
+        window.setTimeout(() => {
+          element.style.willChange = "";
+          timers.delete(id);
+        }, PROMOTION_MS),
+      );
+    };
+
+    const schedule = () => {
+      if (frame !== undefined) {
+        return;
+      }
+      frame = requestAnimationFrame(() => {
+        frame = undefined;
+        DeskulptWidgets.Commands.takeDamageHints(
+          window.innerWidth,
+          window.innerHeight,
+        )
+          .then(({ full, ids }) => {
+            if (!full) {
+              ids.forEach(promote);
+            }
+          })
+          .catch(logger.error);
+      });
+    };
+
+    const unlisteners = [
+      DeskulptCore.Events.displayChanged.listen(schedule),
+      DeskulptWidgets.Events.diff.listen(schedule),
+      DeskulptWidgets.Events.render.listen(schedule),
+      DeskulptWidgets.Events.update.listen(schedule),
+    ];
+
+    return () => {
+      if (frame !== undefined) {
+        cancelAnimationFrame(frame);
+      }
+      timers.forEach((timer) => clearTimeout(timer));
+      unlisteners.forEach((unlisten) =>
+        unlisten.then((f) => f()).catch(logger.error),
+      );
+    };
+  }, []);
+};
@@ -0,0 +1,25 @@
+import { useEffect } from "react";
+import { DeskulptCore } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+
+/**
+ * Mark the document body with the `data-editing-layout` attribute while
+ * layout editing is active.
+ *
+ * Dragging widgets is handled by the backend during layout editing, so widgets
+ * are styled to not receive cursor events meanwhile.
+ */
+export function useImodeChangedListener() {
+  useEffect(() => {
+    const unlisten = DeskulptCore.Events.imodeChanged.listen((event) => {
+      document.body.toggleAttribute(
+        "data-editing-layout",
+        event.payload.isEditingLayout,
+      );
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(logger.error);
+    };
+  }, []);
+}
@@ -4,6 +4,12 @@ import { useEffect } from "react";
 
 export const useInitialRefresh = () => {
   useEffect(() => {
-    DeskulptWidgets.Commands.refreshAll().catch(logger.error);
+    // The floating window of a detached widget refreshes that widget only
+    const detachedWidget = window.__DESKULPT_INTERNALS__.detachedWidget;
+    const refresh =
+      detachedWidget === null
+        ? DeskulptWidgets.Commands.refreshAll()
+        : DeskulptWidgets.Commands.refresh(detachedWidget);
+    refresh.catch(logger.error);
   }, []);
 };
@@ -0,0 +1,68 @@
+import { createElement, useEffect } from "react";
+import { Button, Dialog } from "@radix-ui/themes";
+import { useWidgetsStore } from "./useWidgetsStore";
+import { logger } from "@deskulpt/utils";
+import { DeskulptWidgets } from "@deskulpt/bindings";
+import ErrorDisplay from "../components/ErrorDisplay";
+
+export const useRenderTimeoutListener = () => {
+  useEffect(() => {
+    const unlisten = DeskulptWidgets.Events.timeout.listen((event) => {
+      const { id, timeoutSeconds } = event.payload;
+
+      const actions = [
+        createElement(
+          Dialog.Close,
+          { key: "disable" },
+          createElement(
+            Button,
+            {
+              variant: "soft",
+              color: "gray",
+              onClick: () => {
+                DeskulptWidgets.Commands.updateSettings(id, {
+                  isLoaded: false,
+                }).catch(logger.error);
+              },
+            },
+            "Disable",
+          ),
+        ),
+        createElement(
+          Dialog.Close,
+          { key: "retry" },
+          createElement(
+            Button,
+            {
+              onClick: () => {
+                DeskulptWidgets.Commands.refresh(id).catch(logger.error);
+              },
+            },
+            "Retry",
+          ),
+        ),
+      ];
+
+      useWidgetsStore.setState(
+        (state) => ({
+          ...state,
+          [id]: {
+            ...state[id],
+            component: () =>
+              createElement(ErrorDisplay, {
+                id,
+                error: "Timed out rendering the widget",
+                message: `The widget module did not finish evaluating within ${timeoutSeconds} seconds. It might be awaiting forever at the top level.`,
+                actions,
+              }),
+          },
+        }),
+        true,
+      );
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(logger.error);
+    };
+  }, []);
+};
@@ -2,15 +2,26 @@ import { createElement, useEffect } from "react";
 import { useWidgetsStore } from "./useWidgetsStore";
 import { logger, stringify } from "@deskulpt/utils";
 import { DeskulptWidgets } from "@deskulpt/bindings";
+import { convertFileSrc } from "@tauri-apps/api/core";
 import ErrorDisplay from "../components/ErrorDisplay";
+import { removeWidgetStyles } from "../widgetStyles";
 
 const BASE_URL = new URL(import.meta.url).origin;
 const RAW_APIS_URL = new URL("/gen/raw-apis.js", BASE_URL).href;
+const ASSETS_URL = convertFileSrc("", "deskulpt-asset").replace(/\/$/, "");
 
 export const useRenderWidgetListener = () => {
   useEffect(() => {
     const unlisten = DeskulptWidgets.Events.render.listen(async (event) => {
-      const { id, report } = event.payload;
+      const { id, report, diagnostics, warnings } = event.payload;
+
+      for (const warning of warnings) {
+        const location =
+          warning.file === null
+            ? ""
+            : ` at ${warning.file}:${warning.line ?? 0}:${warning.column ?? 0}`;
+        logger.warn(`Widget ${id}${location}: ${warning.message}`);
+      }
 
       if (report.type === "err") {
         useWidgetsStore.setState(
@@ -23,6 +34,7 @@ export const useRenderWidgetListener = () => {
                   id,
                   error: "Error bundling the widget",
                   message: report.content,
+                  diagnostics,
                 }),
             },
           }),
@@ -53,14 +65,24 @@ export const useRenderWidgetListener = () => {
       if (widget?.moduleBlobUrl !== undefined) {
         URL.revokeObjectURL(widget.moduleBlobUrl);
       }
-      let moduleCode = report.content
-        .replaceAll("__DESKULPT_BASE_URL__", BASE_URL)
-        .replaceAll("__DESKULPT_APIS_BLOB_URL__", apisBlobUrl);
+      // The network shim shadows the globals in the widget module; it is kept on
+      // the first line so that source map lines are not shifted. This only
+      // routes plain fetch calls through the proxy, while the canvas CSP is what
+      // blocks other ways of reaching the network directly
+      let moduleCode =
+        `const { fetch, XMLHttpRequest } = window.__DESKULPT_NETWORK__(${JSON.stringify(id)});` +
+        report.content
+          .replaceAll("__DESKULPT_BASE_URL__", BASE_URL)
+          .replaceAll("__DESKULPT_APIS_BLOB_URL__", apisBlobUrl)
+          .replaceAll("__DESKULPT_ASSETS_URL__", ASSETS_URL);
       const moduleBlob = new Blob([moduleCode], {
         type: "application/javascript",
       });
       const moduleBlobUrl = URL.createObjectURL(moduleBlob);
 
+      // Styles are injected again when evaluating the new module
+      removeWidgetStyles(id);
+
       let module: any;
       try {
         module = await import(/* @vite-ignore */ moduleBlobUrl);
@@ -79,6 +101,7 @@ export const useRenderWidgetListener = () => {
                   id,
                   error: "Error importing the widget module",
                   message: stringify(error),
+                  stack: error instanceof Error ? error.stack : undefined,
                 }),
               apisBlobUrl,
             },
@@ -86,6 +109,8 @@ export const useRenderWidgetListener = () => {
           true,
         );
         return;
+      } finally {
+        DeskulptWidgets.Commands.reportRendered(id).catch(logger.error);
       }
 
       useWidgetsStore.setState(
@@ -2,6 +2,7 @@ import { DeskulptWidgets } from "@deskulpt/bindings";
 import { useWidgetsStore } from "./useWidgetsStore";
 import { logger } from "@deskulpt/utils";
 import { useEffect } from "react";
+import { removeWidgetStyles } from "../widgetStyles";
 
 export const useUpdateWidgetCatalogListener = () => {
   useEffect(() => {
@@ -21,6 +22,7 @@ export const useUpdateWidgetCatalogListener = () => {
         if (id in event.payload) {
           continue;
         }
+        removeWidgetStyles(id);
         if (widget.apisBlobUrl !== undefined) {
           URL.revokeObjectURL(widget.apisBlobUrl);
         }
@@ -30,8 +32,44 @@ export const useUpdateWidgetCatalogListener = () => {
       }
     });
 
+    const unlistenDiff = DeskulptWidgets.Events.diff.listen((event) => {
+      const widgets = useWidgetsStore.getState();
+
+      switch (event.payload.type) {
+        case "widgetAdded":
+        case "widgetChanged": {
+          const { id, widget } = event.payload.content;
+          useWidgetsStore.setState(
+            (state) => ({
+              ...state,
+              [id]: { ...widgets[id], settings: widget.settings },
+            }),
+            true,
+          );
+          break;
+        }
+        case "widgetRemoved": {
+          const { id } = event.payload.content;
+          const widget = widgets[id];
+          removeWidgetStyles(id);
+          if (widget?.apisBlobUrl !== undefined) {
+            URL.revokeObjectURL(widget.apisBlobUrl);
+          }
+          if (widget?.moduleBlobUrl !== undefined) {
+            URL.revokeObjectURL(widget.moduleBlobUrl);
+          }
+          useWidgetsStore.setState((state) => {
+            const { [id]: _, ...rest } = state;
+            return rest;
+          }, true);
+          break;
+        }
+      }
+    });
+
     return () => {
       unlisten.then((f) => f()).catch(logger.error);
+      unlistenDiff.then((f) => f()).catch(logger.error);
     };
   }, []);
 };
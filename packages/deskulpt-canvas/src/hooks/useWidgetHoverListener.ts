@@ -0,0 +1,24 @@
+import { useEffect } from "react";
+import { DeskulptCore } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+
+/**
+ * Mark the widget hovered by the cursor with the `data-hovered` attribute.
+ *
+ * Hovering is tracked by the backend, so that the attribute is set even when
+ * the canvas is click-through and the DOM receives no cursor events.
+ */
+export function useWidgetHoverListener() {
+  useEffect(() => {
+    const unlisten = DeskulptCore.Events.widgetHover.listen((event) => {
+      const { type, content: id } = event.payload;
+      document
+        .querySelector(`[data-widget-id="${CSS.escape(id)}"]`)
+        ?.toggleAttribute("data-hovered", type === "enter");
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(logger.error);
+    };
+  }, []);
+}
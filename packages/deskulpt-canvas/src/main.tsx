@@ -7,6 +7,7 @@ import {
 } from "@deskulpt/utils";
 import { DeskulptSettings } from "@deskulpt/bindings";
 import App from "./App";
+import { createNetworkShim } from "./network";
 import "@radix-ui/themes/styles.css";
 import "./custom.css";
 
@@ -15,10 +16,14 @@ declare global {
     readonly __DESKULPT_INTERNALS__: {
       readonly apisWrapper: string;
       readonly initialSettings: DeepReadonly<DeskulptSettings.Settings>;
+      readonly detachedWidget: string | null;
     };
+    __DESKULPT_NETWORK__: typeof createNetworkShim;
   }
 }
 
+window.__DESKULPT_NETWORK__ = createNetworkShim;
+
 enforceOpenNewTab();
 setupGlobalLoggingHooks();
 
@@ -0,0 +1,225 @@
+import { DeskulptWidgets } from "@deskulpt/bindings";
+
+const NULL_BODY_STATUSES = [101, 103, 204, 205, 304];
+
+const toBase64 = (bytes: Uint8Array) => {
+  let binary = "";
+  for (const byte of bytes) {
+    binary += String.fromCharCode(byte);
+  }
+  return btoa(binary);
+};
+
+const fromBase64 = (base64: string) =>
+  Uint8Array.from(atob(base64), (c) => c.charCodeAt(0));
+
+/**
+ * Whether a request to the URL should be routed through the network proxy.
+ *
+ * Only cross-origin HTTP(S) requests are proxied. Requests to the canvas
+ * itself, blob URLs, data URLs, and widget assets are left to the webview.
+ */
+const shouldProxy = (url: URL) =>
+  (url.protocol === "http:" || url.protocol === "https:") &&
+  url.origin !== window.location.origin;
+
+/**
+ * Create the `fetch` shim for a widget.
+ */
+const createFetch = (id: string): typeof window.fetch => {
+  return async (input, init) => {
+    const request = new Request(input, init);
+    const url = new URL(request.url);
+    if (!shouldProxy(url)) {
+      return window.fetch(request);
+    }
+
+    request.signal.throwIfAborted();
+    const body =
+      request.body === null
+        ? undefined
+        : toBase64(new Uint8Array(await request.arrayBuffer()));
+    const response = await DeskulptWidgets.Commands.proxyFetch(id, {
+      url: url.href,
+      method: request.method,
+      headers: [...request.headers],
+      body,
+    });
+    request.signal.throwIfAborted();
+
+    const result = new Response(
+      NULL_BODY_STATUSES.includes(response.status)
+        ? null
+        : fromBase64(response.body),
+      {
+        status: response.status,
+        statusText: response.statusText,
+        headers: response.headers,
+      },
+    );
+    Object.defineProperty(result, "url", { value: response.url });
+    return result;
+  };
+};
+
+/**
+ * Create the `XMLHttpRequest` shim for a widget.
+ *
+ * This implements the commonly used subset of `XMLHttpRequest` on top of the
+ * `fetch` shim. Synchronous requests and upload progress are not supported.
+ */
+const createXMLHttpRequest = (fetch: typeof window.fetch) => {
+  return class XMLHttpRequestShim extends EventTarget {
+    static readonly UNSENT = 0;
+    static readonly OPENED = 1;
+    static readonly HEADERS_RECEIVED = 2;
+    static readonly LOADING = 3;
+    static readonly DONE = 4;
+
+    readyState = 0;
+    status = 0;
+    statusText = "";
+    responseURL = "";
+    responseType: XMLHttpRequestResponseType = "";
+    response: any = null;
+    responseText = "";
+    timeout = 0;
+    withCredentials = false;
+
+    onreadystatechange: ((event: Event) => void) | null = null;
+    onloadstart: ((event: Event) => void) | null = null;
+    onload: ((event: Event) => void) | null = null;
+    onerror: ((event: Event) => void) | null = null;
+    onabort: ((event: Event) => void) | null = null;
+    ontimeout: ((event: Event) => void) | null = null;
+    onloadend: ((event: Event) => void) | null = null;
+
+    #method = "GET";
+    #url = "";
+    #headers = new Headers();
+    #responseHeaders = new Headers();
+    #controller: AbortController | null = null;
+
+    open(method: string, url: string | URL, async = true) {
+      if (!async) {
+        throw new Error("Synchronous XMLHttpRequest is not supported");
+      }
+      this.#method = method;
+      this.#url = new URL(url, window.location.href).href;
+      this.#headers = new Headers();
+      this.#setReadyState(XMLHttpRequestShim.OPENED);
+    }
+
+    setRequestHeader(name: string, value: string) {
+      this.#headers.append(name, value);
+    }
+
+    getResponseHeader(name: string) {
+      return this.#responseHeaders.get(name);
+    }
+
+    getAllResponseHeaders() {
+      return [...this.#responseHeaders]
+        .map(([name, value]) => `${name}: ${value}\r\n`)
+        .join("");
+    }
+
+    overrideMimeType() {}
+
+    abort() {
+      this.#controller?.abort();
+    }
+
+    send(body?: BodyInit | null) {
+      const controller = new AbortController();
+      this.#controller = controller;
+      const timer =
+        this.timeout > 0
+          ? setTimeout(() => controller.abort("timeout"), this.timeout)
+          : undefined;
+
+      this.#dispatch("loadstart");
+      fetch(this.#url, {
+        method: this.#method,
+        headers: this.#headers,
+        body,
+        signal: controller.signal,
+      })
+        .then(async (response) => {
+          this.status = response.status;
+          this.statusText = response.statusText;
+          this.responseURL = response.url;
+          this.#responseHeaders = response.headers;
+          this.#setReadyState(XMLHttpRequestShim.HEADERS_RECEIVED);
+          this.#setReadyState(XMLHttpRequestShim.LOADING);
+
+          switch (this.responseType) {
+            case "arraybuffer":
+              this.response = await response.arrayBuffer();
+              break;
+            case "blob":
+              this.response = await response.blob();
+              break;
+            case "json":
+              this.responseText = await response.text();
+              try {
+                this.response = JSON.parse(this.responseText);
+              } catch {
+                this.response = null;
+              }
+              break;
+            default:
+              this.responseText = await response.text();
+              this.response = this.responseText;
+          }
+
+          this.#setReadyState(XMLHttpRequestShim.DONE);
+          this.#dispatch("load");
+        })
+        .catch(() => {
+          this.status = 0;
+          this.#setReadyState(XMLHttpRequestShim.DONE);
+          if (controller.signal.aborted) {
+            this.#dispatch(
+              controller.signal.reason === "timeout" ? "timeout" : "abort",
+            );
+          } else {
+            this.#dispatch("error");
+          }
+        })
+        .finally(() => {
+          clearTimeout(timer);
+          this.#dispatch("loadend");
+        });
+    }
+
+    #setReadyState(readyState: number) {
+      this.readyState = readyState;
+      this.#dispatch("readystatechange");
+    }
+
+    #dispatch(type: string) {
+      const event = new Event(type);
+      const handler = (this as any)[`on${type}`];
+      if (typeof handler === "function") {
+        handler.call(this, event);
+      }
+      this.dispatchEvent(event);
+    }
+  };
+};
+
+/**
+ * Create the network shim for a widget.
+ *
+ * The returned `fetch` and `XMLHttpRequest` route cross-origin requests
+ * through the backend network proxy, which enforces the hosts declared in the
+ * widget manifest and meters the bandwidth usage of the widget. The shim is
+ * injected into the widget module to shadow the globals, so that widget code
+ * uses it transparently. Note that this is not a security boundary, since
+ * widget code can still reach the globals via e.g. `window.fetch`.
+ */
+export const createNetworkShim = (id: string) => {
+  const fetch = createFetch(id);
+  return { fetch, XMLHttpRequest: createXMLHttpRequest(fetch) };
+};
@@ -0,0 +1,26 @@
+/**
+ * Get the `<style>` elements injected by the stylesheets of a widget.
+ */
+const queryWidgetStyles = (id: string) =>
+  document.head.querySelectorAll<HTMLStyleElement>(
+    `style[data-deskulpt-widget="${CSS.escape(id)}"]`,
+  );
+
+/**
+ * Remove the styles injected by a widget.
+ *
+ * This should be called when the widget unloads or before it re-renders, since
+ * re-evaluating the widget module injects its styles again.
+ */
+export const removeWidgetStyles = (id: string) => {
+  queryWidgetStyles(id).forEach((style) => style.remove());
+};
+
+/**
+ * Enable or disable the styles injected by a widget.
+ */
+export const toggleWidgetStyles = (id: string, enabled: boolean) => {
+  queryWidgetStyles(id).forEach((style) => {
+    style.disabled = !enabled;
+  });
+};
@@ -1,10 +1,15 @@
-import { Box, Flex, Theme as RadixTheme, Tabs } from "@radix-ui/themes";
+import { Badge, Box, Flex, Theme as RadixTheme, Tabs } from "@radix-ui/themes";
 import { Toaster } from "sonner";
+import { useState } from "react";
+import { DeskulptCore } from "@deskulpt/bindings";
 import {
   useInitialRefresh,
   useSettingsStore,
+  useSwitchPortalTabListener,
   useUpdateSettingsListener,
   useUpdateWidgetCatalogListener,
+  useUpgradesAvailableListener,
+  useWidgetsGalleryStore,
 } from "./hooks";
 import About from "./components/About";
 import Widgets from "./components/Widgets";
@@ -12,20 +17,49 @@ import Settings from "./components/Settings";
 import ThemeToggler from "./components/ThemeToggler";
 import Gallery from "./components/Gallery";
 import Logs from "./components/Logs";
-
-const tabs = [
-  { value: "widgets", label: "Widgets", content: <Widgets /> },
-  { value: "settings", label: "Settings", content: <Settings /> },
-  { value: "gallery", label: "Gallery", content: <Gallery /> },
-  { value: "logs", label: "Logs", content: <Logs /> },
-  { value: "about", label: "About", content: <About /> },
-];
+import Recovery from "./components/Recovery";
 
 const App = () => {
   const theme = useSettingsStore((state) => state.theme);
+  const numUpgrades = useWidgetsGalleryStore((state) => state.upgrades.length);
+  const [tab, setTab] = useState<DeskulptCore.PortalTab>(
+    window.__DESKULPT_INTERNALS__.initialTab ?? "widgets",
+  );
+
+  const tabs = [
+    ...(window.__DESKULPT_INTERNALS__.safeMode
+      ? [
+          {
+            value: "recovery",
+            label: "Recovery",
+            content: <Recovery setTab={setTab} />,
+          },
+        ]
+      : []),
+    { value: "widgets", label: "Widgets", content: <Widgets /> },
+    { value: "settings", label: "Settings", content: <Settings /> },
+    {
+      value: "gallery",
+      label: (
+        <Flex align="center" gap="1">
+          Gallery
+          {numUpgrades > 0 && (
+            <Badge size="1" radius="full" color="orange">
+              {numUpgrades}
+            </Badge>
+          )}
+        </Flex>
+      ),
+      content: <Gallery />,
+    },
+    { value: "logs", label: "Logs", content: <Logs /> },
+    { value: "about", label: "About", content: <About /> },
+  ];
 
   useUpdateSettingsListener();
+  useSwitchPortalTabListener(setTab);
   useUpdateWidgetCatalogListener();
+  useUpgradesAvailableListener();
 
   useInitialRefresh();
 
@@ -45,7 +79,11 @@ const App = () => {
         }}
       />
       <ThemeToggler theme={theme} />
-      <Tabs.Root defaultValue="widgets" asChild>
+      <Tabs.Root
+        value={tab}
+        onValueChange={(value) => setTab(value as DeskulptCore.PortalTab)}
+        asChild
+      >
         <Flex direction="column" gap="2" height="100%" p="2">
           <Tabs.List>
             {tabs.map((tab) => (
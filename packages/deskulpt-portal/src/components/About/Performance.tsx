@@ -0,0 +1,70 @@
+import { Heading, Table } from "@radix-ui/themes";
+import { DeskulptCore } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+import { SerializedStyles } from "@emotion/react";
+import { useEffect, useState } from "react";
+
+const REFRESH_INTERVAL_MS = 2000;
+
+const formatHistogram = (histogram?: DeskulptCore.HistogramSnapshot) => {
+  if (histogram === undefined || histogram.count === 0) {
+    return "-";
+  }
+  return `p50 ${histogram.p50} / p95 ${histogram.p95} (${histogram.count})`;
+};
+
+interface PerformanceProps {
+  tableCss: SerializedStyles;
+}
+
+const Performance = ({ tableCss }: PerformanceProps) => {
+  const [metrics, setMetrics] = useState<DeskulptCore.MetricsSnapshot>();
+
+  useEffect(() => {
+    const refresh = () =>
+      DeskulptCore.Commands.getMetrics().then(setMetrics).catch(logger.error);
+    refresh();
+    const interval = setInterval(refresh, REFRESH_INTERVAL_MS);
+    return () => clearInterval(interval);
+  }, []);
+
+  if (metrics === undefined) {
+    return null;
+  }
+
+  const { counters, gauges, histograms } = metrics;
+  const rows = [
+    ["Render (ms)", formatHistogram(histograms["render.total_ms"])],
+    ["Bundle (ms)", formatHistogram(histograms["render.bundle_ms"])],
+    [
+      "Bundles",
+      `${counters["bundle.successes"] ?? 0} ok / ${counters["bundle.failures"] ?? 0} failed`,
+    ],
+    [
+      "Renders",
+      `${gauges["render.in_flight"] ?? 0} in flight / ${gauges["render.queued"] ?? 0} queued`,
+    ],
+    ["Plugin (ms)", formatHistogram(histograms["plugin.call_ms"])],
+    ["Hit test (µs)", formatHistogram(histograms["mouse.hit_test_us"])],
+  ];
+
+  return (
+    <>
+      <Heading size="3" mt="4" mb="1">
+        Performance
+      </Heading>
+      <Table.Root size="1" css={tableCss}>
+        <Table.Body>
+          {rows.map(([label, value]) => (
+            <Table.Row key={label} align="center">
+              <Table.RowHeaderCell>{label}</Table.RowHeaderCell>
+              <Table.Cell>{value}</Table.Cell>
+            </Table.Row>
+          ))}
+        </Table.Body>
+      </Table.Root>
+    </>
+  );
+};
+
+export default Performance;
@@ -2,6 +2,7 @@ import { Avatar, Box, Flex, Heading, Table } from "@radix-ui/themes";
 import CopyLink from "../CopyLink";
 import { SiGithub } from "react-icons/si";
 import { css } from "@emotion/react";
+import Performance from "./Performance";
 
 const styles = {
   logo: css({
@@ -61,6 +62,7 @@ const AboutTab = () => {
             </Table.Row>
           </Table.Body>
         </Table.Root>
+        <Performance tableCss={styles.table} />
       </Box>
     </Flex>
   );
@@ -1,22 +1,40 @@
 import { css } from "@emotion/react";
 import { Button, Flex, Select } from "@radix-ui/themes";
 import { LuRepeat } from "react-icons/lu";
+import { useWidgetsGalleryStore } from "../../hooks";
 
 const styles = {
-  select: css({ width: "100px" }),
+  select: css({ width: "140px" }),
 };
 
 interface HeaderProps {
   refresh: () => void;
 }
 
+const ALL_CATEGORIES = "all";
+
 const Header = ({ refresh }: HeaderProps) => {
+  const categories = useWidgetsGalleryStore((state) => state.categories);
+  const category = useWidgetsGalleryStore((state) => state.category);
+  const setCategory = useWidgetsGalleryStore((state) => state.setCategory);
+
   return (
     <Flex align="center" gap="2" justify="between">
-      <Select.Root size="1" defaultValue="widgets">
+      <Select.Root
+        size="1"
+        value={category ?? ALL_CATEGORIES}
+        onValueChange={(value) =>
+          setCategory(value === ALL_CATEGORIES ? null : value)
+        }
+      >
         <Select.Trigger css={styles.select} />
         <Select.Content position="popper">
-          <Select.Item value="widgets">Widgets</Select.Item>
+          <Select.Item value={ALL_CATEGORIES}>All widgets</Select.Item>
+          {Object.entries(categories).map(([name, count]) => (
+            <Select.Item key={name} value={name}>
+              {name} ({count})
+            </Select.Item>
+          ))}
         </Select.Content>
       </Select.Root>
 
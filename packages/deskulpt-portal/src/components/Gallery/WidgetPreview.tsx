@@ -24,6 +24,14 @@ const styles = {
   dataListRoot: css({
     gap: "var(--space-2)",
   }),
+  screenshot: css({
+    maxHeight: "240px",
+    borderRadius: "var(--radius-2)",
+  }),
+  readme: css({
+    whiteSpace: "pre-wrap",
+    margin: 0,
+  }),
 };
 
 const WidgetPreview = () => {
@@ -120,6 +128,28 @@ const WidgetPreview = () => {
                     <DataList.Value>{formatBytes(preview.size)}</DataList.Value>
                   </DataList.Item>
                 </DataList.Root>
+
+                {preview.screenshots.length > 0 && (
+                  <Flex gap="2" wrap="wrap">
+                    {preview.screenshots.map((screenshot, index) => (
+                      <img
+                        key={index}
+                        src={screenshot}
+                        alt={`Screenshot ${index + 1}`}
+                        css={styles.screenshot}
+                      />
+                    ))}
+                  </Flex>
+                )}
+
+                {preview.readme !== undefined && (
+                  <>
+                    <Separator size="4" />
+                    <Text size="2" asChild>
+                      <pre css={styles.readme}>{preview.readme}</pre>
+                    </Text>
+                  </>
+                )}
               </Flex>
             </Box>
           </ScrollArea>
@@ -29,7 +29,10 @@ const WidgetSecondaryActions = ({
   const preview = async () => {
     setIsLoadingPreview(true);
     try {
-      const previewData = await DeskulptWidgets.Commands.preview(reference);
+      const previewData = await DeskulptWidgets.Commands.preview(
+        reference,
+        true,
+      );
       openPreview({ reference, version, preview: previewData });
     } catch (error) {
       logger.error(error);
@@ -48,7 +48,10 @@ const WidgetVersionPicker = () => {
     };
 
     try {
-      const previewData = await DeskulptWidgets.Commands.preview(reference);
+      const previewData = await DeskulptWidgets.Commands.preview(
+        reference,
+        true,
+      );
       openPreview({
         reference,
         version: release.version,
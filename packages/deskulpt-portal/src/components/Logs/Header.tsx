@@ -1,7 +1,14 @@
 import { DeskulptCore, DeskulptLogs } from "@deskulpt/bindings";
 import { LOGGING_LEVELS, formatBytes, logger } from "@deskulpt/utils";
 import { css } from "@emotion/react";
-import { Button, Flex, Popover, Select, Text } from "@radix-ui/themes";
+import {
+  Button,
+  Flex,
+  Popover,
+  Select,
+  Text,
+  TextField,
+} from "@radix-ui/themes";
 import { Dispatch, SetStateAction } from "react";
 import { LuFolderOpen, LuRepeat, LuTrash } from "react-icons/lu";
 import { toast } from "sonner";
@@ -14,15 +21,26 @@ const styles = {
   minLevelOption: css({
     textTransform: "capitalize",
   }),
+  widgetIdInput: css({
+    width: "160px",
+  }),
 };
 
 interface HeaderProps {
   minLevel: DeskulptLogs.Level;
   setMinLevel: Dispatch<SetStateAction<DeskulptLogs.Level>>;
+  widgetId: string;
+  setWidgetId: Dispatch<SetStateAction<string>>;
   refreshLogs: () => void;
 }
 
-const Header = ({ minLevel, setMinLevel, refreshLogs }: HeaderProps) => {
+const Header = ({
+  minLevel,
+  setMinLevel,
+  widgetId,
+  setWidgetId,
+  refreshLogs,
+}: HeaderProps) => {
   const clearLogs = () => {
     DeskulptLogs.Commands.clear()
       .then((bytes) => {
@@ -34,20 +52,33 @@ const Header = ({ minLevel, setMinLevel, refreshLogs }: HeaderProps) => {
 
   return (
     <Flex align="center" gap="2" justify="between">
-      <Select.Root
-        size="1"
-        value={minLevel}
-        onValueChange={(value) => setMinLevel(value as DeskulptLogs.Level)}
-      >
-        <Select.Trigger css={styles.minLevelSelect} />
-        <Select.Content position="popper">
-          {LOGGING_LEVELS.map((level) => (
-            <Select.Item key={level} value={level} css={styles.minLevelOption}>
-              {level}
-            </Select.Item>
-          ))}
-        </Select.Content>
-      </Select.Root>
+      <Flex align="center" gap="2">
+        <Select.Root
+          size="1"
+          value={minLevel}
+          onValueChange={(value) => setMinLevel(value as DeskulptLogs.Level)}
+        >
+          <Select.Trigger css={styles.minLevelSelect} />
+          <Select.Content position="popper">
+            {LOGGING_LEVELS.map((level) => (
+              <Select.Item
+                key={level}
+                value={level}
+                css={styles.minLevelOption}
+              >
+                {level}
+              </Select.Item>
+            ))}
+          </Select.Content>
+        </Select.Root>
+        <TextField.Root
+          size="1"
+          placeholder="Filter by widget ID"
+          value={widgetId}
+          onChange={(event) => setWidgetId(event.target.value.trim())}
+          css={styles.widgetIdInput}
+        />
+      </Flex>
 
       <Flex align="center" justify="end" gap="2">
         <Button
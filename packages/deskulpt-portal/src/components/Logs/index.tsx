@@ -10,9 +10,11 @@ import { LuLogs } from "react-icons/lu";
 const Logs = () => {
   const parentRef = useRef<HTMLDivElement>(null);
   const [minLevel, setMinLevel] = useState<DeskulptLogs.Level>("info");
+  const [widgetId, setWidgetId] = useState("");
 
   const { entries, hasMore, isFetching, fetchMore, refresh } = useLogs({
     minLevel,
+    widgetId,
     pageSize: 100,
   });
 
@@ -42,6 +44,8 @@ const Logs = () => {
       <Header
         minLevel={minLevel}
         setMinLevel={setMinLevel}
+        widgetId={widgetId}
+        setWidgetId={setWidgetId}
         refreshLogs={refresh}
       />
 
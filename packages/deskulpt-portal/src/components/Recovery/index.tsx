@@ -0,0 +1,56 @@
+import { Button, Callout, Flex, Text } from "@radix-ui/themes";
+import { DeskulptCore } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+import {
+  LuFolderOpen,
+  LuLogs,
+  LuRotateCcw,
+  LuTriangleAlert,
+} from "react-icons/lu";
+
+interface RecoveryProps {
+  setTab: (tab: DeskulptCore.PortalTab) => void;
+}
+
+const Recovery = ({ setTab }: RecoveryProps) => {
+  return (
+    <Flex height="100%" direction="column" justify="center" gap="4" px="6">
+      <Callout.Root color="amber">
+        <Callout.Icon>
+          <LuTriangleAlert />
+        </Callout.Icon>
+        <Callout.Text>
+          Deskulpt crashed repeatedly during startup and is running in safe
+          mode. Widgets are not rendered and plugins are not loaded.
+        </Callout.Text>
+      </Callout.Root>
+      <Text size="2" color="gray">
+        A broken widget or plugin is the most likely cause. Check the logs for
+        the crash, then remove or fix the offending widget in the widgets
+        directory before restarting normally.
+      </Text>
+      <Flex gap="3">
+        <Button variant="soft" onClick={() => setTab("logs")}>
+          <LuLogs /> View logs
+        </Button>
+        <Button
+          variant="soft"
+          onClick={() =>
+            DeskulptCore.Commands.open("widgets").catch(logger.error)
+          }
+        >
+          <LuFolderOpen /> Open widgets directory
+        </Button>
+        <Button
+          onClick={() =>
+            DeskulptCore.Commands.exitSafeMode().catch(logger.error)
+          }
+        >
+          <LuRotateCcw /> Restart normally
+        </Button>
+      </Flex>
+    </Flex>
+  );
+};
+
+export default Recovery;
@@ -9,7 +9,8 @@ import {
 } from "@radix-ui/themes";
 import { KeyboardEvent as ReactKeyboardEvent, useRef, useState } from "react";
 import { LuSquarePen, LuTrash } from "react-icons/lu";
-import { DeskulptSettings } from "@deskulpt/bindings";
+import { DeskulptCore, DeskulptSettings } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
 import { useSettingsStore } from "../../hooks";
 import { toast } from "sonner";
 import { INVALID_KEYCODES, KEYCODES, MODIFIERS } from "./keyboard";
@@ -39,6 +40,8 @@ const ShortcutAction = ({ action }: Props) => {
   const [value, setValue] = useState(shortcut ?? "");
   const [placeholder, setPlaceholder] = useState(INITIAL_PLACEHOLDER);
   const [isValid, setIsValid] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+  const latestRef = useRef("");
 
   const handleFocus = () => {
     setPlaceholder("Press key combination...");
@@ -50,9 +53,37 @@ const ShortcutAction = ({ action }: Props) => {
       setValue(shortcut ?? "");
       setPlaceholder(INITIAL_PLACEHOLDER);
       setIsValid(true);
+      setError(null);
     }
   };
 
+  const validate = (accelerator: string) => {
+    latestRef.current = accelerator;
+    DeskulptCore.Commands.validateShortcut(accelerator, action)
+      .then((result) => {
+        if (latestRef.current !== accelerator) {
+          // Superseded by a newer key combination
+          return;
+        }
+        switch (result.type) {
+          case "valid":
+            setError(null);
+            return;
+          case "invalid":
+            setError(`Invalid shortcut: ${result.content}`);
+            break;
+          case "conflict":
+            setError(`Already bound to action: ${result.content}`);
+            break;
+          case "unavailable":
+            setError(`Shortcut unavailable: ${result.content}`);
+            break;
+        }
+        setIsValid(false);
+      })
+      .catch(logger.error);
+  };
+
   const handleKeyDown = (event: ReactKeyboardEvent) => {
     if (event.key === "Tab" || event.repeat) {
       // Ignore key repeats for performance; ignore tab key for keyboard
@@ -92,8 +123,15 @@ const ShortcutAction = ({ action }: Props) => {
       keys.push(INVALID_KEYCODES[event.code as keyof typeof INVALID_KEYCODES]);
     }
 
-    setValue(keys.join(" + "));
-    setIsValid(localHasKey && localHasModifier);
+    const accelerator = keys.join(" + ");
+    const localIsValid = localHasKey && localHasModifier;
+    setValue(accelerator);
+    setIsValid(localIsValid);
+    setError(null);
+    latestRef.current = "";
+    if (localIsValid && accelerator !== (shortcut ?? "")) {
+      validate(accelerator);
+    }
   };
 
   const confirmAction = () => {
@@ -118,6 +156,7 @@ const ShortcutAction = ({ action }: Props) => {
     }
     setValue("");
     setIsValid(true);
+    setError(null);
   };
 
   return (
@@ -170,6 +209,11 @@ const ShortcutAction = ({ action }: Props) => {
               </Popover.Close>
             )}
           </Flex>
+          {error !== null && (
+            <Text size="1" color="red" as="div" mt="2">
+              {error}
+            </Text>
+          )}
         </Popover.Content>
       </Popover.Root>
     </Flex>
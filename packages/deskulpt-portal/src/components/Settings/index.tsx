@@ -37,6 +37,48 @@ const Settings = () => {
                   <Shortcut action="openPortal" />
                 </Table.Cell>
               </Table.Row>
+              <Table.Row align="center">
+                <Table.RowHeaderCell>Undo layout change</Table.RowHeaderCell>
+                <Table.Cell>
+                  <Shortcut action="undoLayoutChange" />
+                </Table.Cell>
+              </Table.Row>
+              <Table.Row align="center">
+                <Table.RowHeaderCell>Redo layout change</Table.RowHeaderCell>
+                <Table.Cell>
+                  <Shortcut action="redoLayoutChange" />
+                </Table.Cell>
+              </Table.Row>
+              <Table.Row align="center">
+                <Table.RowHeaderCell>Refresh all widgets</Table.RowHeaderCell>
+                <Table.Cell>
+                  <Shortcut action="refreshAllWidgets" />
+                </Table.Cell>
+              </Table.Row>
+              <Table.Row align="center">
+                <Table.RowHeaderCell>Toggle all widgets</Table.RowHeaderCell>
+                <Table.Cell>
+                  <Shortcut action="toggleAllWidgets" />
+                </Table.Cell>
+              </Table.Row>
+              <Table.Row align="center">
+                <Table.RowHeaderCell>Cycle profile</Table.RowHeaderCell>
+                <Table.Cell>
+                  <Shortcut action="cycleProfile" />
+                </Table.Cell>
+              </Table.Row>
+              <Table.Row align="center">
+                <Table.RowHeaderCell>Open logs viewer</Table.RowHeaderCell>
+                <Table.Cell>
+                  <Shortcut action="openLogsViewer" />
+                </Table.Cell>
+              </Table.Row>
+              <Table.Row align="center">
+                <Table.RowHeaderCell>Peek canvas (hold)</Table.RowHeaderCell>
+                <Table.Cell>
+                  <Shortcut action="peekCanvas" />
+                </Table.Cell>
+              </Table.Row>
             </SectionTable>
           </Flex>
         </Box>
@@ -1,9 +1,26 @@
+import { useEffect, useState } from "react";
 import { Flex, IconButton } from "@radix-ui/themes";
-import { LuFolderOpen, LuRepeat } from "react-icons/lu";
+import { LuFolderOpen, LuMove, LuRepeat } from "react-icons/lu";
 import { DeskulptCore, DeskulptWidgets } from "@deskulpt/bindings";
 import { logger } from "@deskulpt/utils";
 
 const GlobalActions = () => {
+  const [isLayoutEditingSupported, setIsLayoutEditingSupported] =
+    useState(false);
+  const [isEditingLayout, setIsEditingLayout] = useState(false);
+
+  useEffect(() => {
+    DeskulptCore.Commands.isLayoutEditingSupported()
+      .then(setIsLayoutEditingSupported)
+      .catch(logger.error);
+  }, []);
+
+  const toggleLayoutEditing = () => {
+    DeskulptCore.Commands.setLayoutEditing(!isEditingLayout)
+      .then(() => setIsEditingLayout(!isEditingLayout))
+      .catch(logger.error);
+  };
+
   return (
     <Flex gap="6" align="center" justify="center" pb="2" pr="4">
       <IconButton
@@ -16,6 +33,20 @@ const GlobalActions = () => {
       >
         <LuRepeat size={16} />
       </IconButton>
+      {isLayoutEditingSupported && (
+        <IconButton
+          title={
+            isEditingLayout
+              ? "Stop editing layout on the canvas"
+              : "Edit layout on the canvas"
+          }
+          size="1"
+          variant={isEditingLayout ? "soft" : "ghost"}
+          onClick={toggleLayoutEditing}
+        >
+          <LuMove size={16} />
+        </IconButton>
+      )}
       <IconButton
         title="Open widgets directory"
         size="1"
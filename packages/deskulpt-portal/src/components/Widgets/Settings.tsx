@@ -1,9 +1,19 @@
-import { Flex, Table } from "@radix-ui/themes";
+import { Flex, Select, Switch, Table } from "@radix-ui/themes";
 import { LuX } from "react-icons/lu";
 import { useWidgetsStore } from "../../hooks";
 import IntegerInput from "../IntegerInput";
 import { css } from "@emotion/react";
-import { DeskulptWidgets } from "@deskulpt/bindings";
+import { DeskulptCore, DeskulptWidgets } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+
+const interactivityOptions: {
+  value: DeskulptWidgets.Interactivity;
+  label: string;
+}[] = [
+  { value: "inherit", label: "Inherit" },
+  { value: "alwaysInteractive", label: "Always interactive" },
+  { value: "clickThrough", label: "Click-through" },
+];
 
 const styles = {
   table: css({
@@ -106,12 +116,60 @@ const Opacity = ({ id }: SettingsProps) => {
   );
 };
 
+const Interactivity = ({ id }: SettingsProps) => {
+  const interactivity = useWidgetsStore(
+    (state) => state[id]?.settings.interactivity,
+  );
+
+  return (
+    <Select.Root
+      size="1"
+      value={interactivity}
+      onValueChange={(value: DeskulptWidgets.Interactivity) => {
+        DeskulptWidgets.Commands.updateSettings(id, {
+          interactivity: value,
+        }).catch(logger.error);
+      }}
+    >
+      <Select.Trigger />
+      <Select.Content>
+        {interactivityOptions.map((option) => (
+          <Select.Item key={option.value} value={option.value}>
+            {option.label}
+          </Select.Item>
+        ))}
+      </Select.Content>
+    </Select.Root>
+  );
+};
+
+const Detached = ({ id }: SettingsProps) => {
+  const detached = useWidgetsStore(
+    (state) => (state[id]?.settings.detached ?? null) !== null,
+  );
+
+  return (
+    <Switch
+      size="1"
+      checked={detached}
+      onCheckedChange={(checked) => {
+        const action = checked
+          ? DeskulptCore.Commands.detachWidget(id)
+          : DeskulptCore.Commands.attachWidget(id);
+        action.catch(logger.error);
+      }}
+    />
+  );
+};
+
 X.displayName = "Settings.X";
 Y.displayName = "Settings.Y";
 Width.displayName = "Settings.Width";
 Height.displayName = "Settings.Height";
 ZIndex.displayName = "Settings.ZIndex";
 Opacity.displayName = "Settings.Opacity";
+Interactivity.displayName = "Settings.Interactivity";
+Detached.displayName = "Settings.Detached";
 
 interface SettingsProps {
   id: string;
@@ -153,6 +211,18 @@ const Settings = ({ id }: SettingsProps) => {
             <Opacity id={id} />
           </Table.Cell>
         </Table.Row>
+        <Table.Row align="center">
+          <Table.RowHeaderCell>Interactivity</Table.RowHeaderCell>
+          <Table.Cell>
+            <Interactivity id={id} />
+          </Table.Cell>
+        </Table.Row>
+        <Table.Row align="center">
+          <Table.RowHeaderCell>Floating</Table.RowHeaderCell>
+          <Table.Cell>
+            <Detached id={id} />
+          </Table.Cell>
+        </Table.Row>
       </Table.Body>
     </Table.Root>
   );
@@ -2,7 +2,9 @@ export * from "./useInitialRefresh";
 export * from "./useInstallWidget";
 export * from "./useLogs";
 export * from "./useSettingsStore";
+export * from "./useSwitchPortalTabListener";
 export * from "./useUpdateSettingsListener";
 export * from "./useUpdateWidgetCatalogListener";
+export * from "./useUpgradesAvailableListener";
 export * from "./useWidgetsGalleryStore";
 export * from "./useWidgetsStore";
@@ -5,5 +5,7 @@ import { useEffect } from "react";
 export const useInitialRefresh = () => {
   useEffect(() => {
     DeskulptWidgets.Commands.refreshAll().catch(logger.error);
+    // The result is delivered via the upgrades available event
+    DeskulptWidgets.Commands.checkUpdates().catch(logger.error);
   }, []);
 };
@@ -7,6 +7,23 @@ import { toast } from "sonner";
 
 type InstallationStatus = "installed" | "not-installed" | "upgrade-available";
 
+function confirmPermissions(
+  localId: string,
+  permissions: DeskulptWidgets.WidgetPermissions,
+) {
+  const lines = [
+    ...(permissions.network ?? []).map((host) => `Network: ${host}`),
+    ...(permissions.filesystem ?? []).map((path) => `Files: ${path}`),
+    ...(permissions.plugins ?? []).map((plugin) => `Plugin: ${plugin}`),
+  ];
+  if (lines.length === 0) {
+    return true;
+  }
+  return window.confirm(
+    `${localId} requests the following permissions:\n\n${lines.join("\n")}\n\nGrant them and install?`,
+  );
+}
+
 export function useInstallWidget(
   reference: DeskulptWidgets.RegistryWidgetReference,
   version: string,
@@ -32,7 +49,22 @@ export function useInstallWidget(
   const install = useCallback(async () => {
     useWidgetsGalleryStore.getState().addInFlightOp(localId);
     try {
-      await DeskulptWidgets.Commands.install(reference);
+      const { permissions } = await DeskulptWidgets.Commands.preview(
+        reference,
+        false,
+      );
+      if (
+        permissions !== undefined &&
+        !confirmPermissions(localId, permissions)
+      ) {
+        return;
+      }
+      await DeskulptWidgets.Commands.install(
+        reference,
+        null,
+        null,
+        permissions ?? null,
+      );
       toast.success(`Installed: ${localId}`);
     } catch (error) {
       logger.error(error);
@@ -47,6 +79,7 @@ export function useInstallWidget(
     try {
       await DeskulptWidgets.Commands.uninstall(reference);
       toast.success(`Uninstalled: ${localId}`);
+      DeskulptWidgets.Commands.checkUpdates().catch(logger.error);
     } catch (error) {
       logger.error(error);
       toast.error(`Uninstallation failed: ${localId}`);
@@ -58,8 +91,9 @@ export function useInstallWidget(
   const upgrade = useCallback(async () => {
     useWidgetsGalleryStore.getState().addInFlightOp(localId);
     try {
-      await DeskulptWidgets.Commands.upgrade(reference);
+      await DeskulptWidgets.Commands.upgrade(reference, null);
       toast.success(`Upgraded: ${localId}`);
+      DeskulptWidgets.Commands.checkUpdates().catch(logger.error);
     } catch (error) {
       logger.error(error);
       toast.error(`Upgrade failed: ${localId}`);
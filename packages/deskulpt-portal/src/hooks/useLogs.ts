@@ -3,10 +3,11 @@ import { useCallback, useEffect, useRef, useState } from "react";
 
 interface UseLogsProps {
   minLevel: string;
+  widgetId: string;
   pageSize: number;
 }
 
-export function useLogs({ minLevel, pageSize }: UseLogsProps) {
+export function useLogs({ minLevel, widgetId, pageSize }: UseLogsProps) {
   const fetchIdRef = useRef(0); // Used for preventing race conditions
 
   const [entries, setEntries] = useState<DeskulptLogs.Entry[]>([]);
@@ -30,6 +31,7 @@ export function useLogs({ minLevel, pageSize }: UseLogsProps) {
         const page = await DeskulptLogs.Commands.read(
           pageSize,
           minLevel as DeskulptLogs.Level,
+          widgetId === "" ? {} : { widgetId },
           cursor,
         );
         if (fetchId === fetchIdRef.current) {
@@ -44,7 +46,7 @@ export function useLogs({ minLevel, pageSize }: UseLogsProps) {
         }
       }
     },
-    [minLevel, pageSize],
+    [minLevel, widgetId, pageSize],
   );
 
   const fetchMore = useCallback(async () => {
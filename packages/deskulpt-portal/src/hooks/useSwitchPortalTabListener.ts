@@ -0,0 +1,17 @@
+import { DeskulptCore } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+import { useEffect } from "react";
+
+export const useSwitchPortalTabListener = (
+  setTab: (tab: DeskulptCore.PortalTab) => void,
+) => {
+  useEffect(() => {
+    const unlisten = DeskulptCore.Events.switchPortalTab.listen((event) => {
+      setTab(event.payload);
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(logger.error);
+    };
+  }, [setTab]);
+};
@@ -9,8 +9,28 @@ export const useUpdateWidgetCatalogListener = () => {
       useWidgetsStore.setState(() => event.payload, true);
     });
 
+    const unlistenDiff = DeskulptWidgets.Events.diff.listen((event) => {
+      switch (event.payload.type) {
+        case "widgetAdded":
+        case "widgetChanged": {
+          const { id, widget } = event.payload.content;
+          useWidgetsStore.setState((state) => ({ ...state, [id]: widget }), true);
+          break;
+        }
+        case "widgetRemoved": {
+          const { id } = event.payload.content;
+          useWidgetsStore.setState((state) => {
+            const { [id]: _, ...rest } = state;
+            return rest;
+          }, true);
+          break;
+        }
+      }
+    });
+
     return () => {
       unlisten.then((f) => f()).catch(logger.error);
+      unlistenDiff.then((f) => f()).catch(logger.error);
     };
   }, []);
 };
@@ -0,0 +1,18 @@
+import { DeskulptWidgets } from "@deskulpt/bindings";
+import { logger } from "@deskulpt/utils";
+import { useEffect } from "react";
+import { useWidgetsGalleryStore } from "./useWidgetsGalleryStore";
+
+export const useUpgradesAvailableListener = () => {
+  useEffect(() => {
+    const unlisten = DeskulptWidgets.Events.upgradesAvailable.listen(
+      (event) => {
+        useWidgetsGalleryStore.setState({ upgrades: event.payload });
+      },
+    );
+
+    return () => {
+      unlisten.then((f) => f()).catch(logger.error);
+    };
+  }, []);
+};
@@ -16,7 +16,11 @@ interface WidgetVersionPickerData {
 }
 
 interface WidgetsGalleryState {
+  allWidgets: DeskulptWidgets.RegistryEntry[];
   widgets: DeskulptWidgets.RegistryEntry[];
+  categories: Partial<Record<string, number>>;
+  category: string | null;
+  upgrades: DeskulptWidgets.WidgetUpgrade[];
   isFetching: boolean;
   inFlightOps: Set<string>;
 
@@ -29,6 +33,7 @@ interface WidgetsGalleryState {
 
 interface WidgetsGalleryActions {
   refresh: () => Promise<void>;
+  setCategory: (category: string | null) => void;
   addInFlightOp: (id: string) => void;
   removeInFlightOp: (id: string) => void;
 
@@ -39,20 +44,41 @@ interface WidgetsGalleryActions {
   closeVersionPicker: () => void;
 }
 
+function filterByCategory(
+  widgets: DeskulptWidgets.RegistryEntry[],
+  category: string | null,
+) {
+  if (category === null) {
+    return widgets;
+  }
+  return widgets.filter((widget) =>
+    widget.categories.some((c) => c.toLowerCase() === category),
+  );
+}
+
 export const useWidgetsGalleryStore = create<
   WidgetsGalleryState & WidgetsGalleryActions
 >((set) => ({
+  allWidgets: [],
   widgets: [],
+  categories: {},
+  category: null,
+  upgrades: [],
   isFetching: false,
   inFlightOps: new Set(),
   isPreviewOpen: false,
   isVersionPickerOpen: false,
 
   refresh: async () => {
-    set({ widgets: [], isFetching: true });
+    set({ allWidgets: [], widgets: [], isFetching: true });
     try {
       const index = await DeskulptWidgets.Commands.fetchRegistryIndex();
-      set({ widgets: index.widgets });
+      const categories = await DeskulptWidgets.Commands.registryCategories();
+      set((state) => ({
+        allWidgets: index.widgets,
+        widgets: filterByCategory(index.widgets, state.category),
+        categories,
+      }));
     } catch (error) {
       logger.error(error);
       toast.error("Failed to load widgets gallery");
@@ -61,6 +87,13 @@ export const useWidgetsGalleryStore = create<
     }
   },
 
+  setCategory: (category) => {
+    set((state) => ({
+      category,
+      widgets: filterByCategory(state.allWidgets, category),
+    }));
+  },
+
   addInFlightOp: (id: string) => {
     set((state) => {
       const newSet = new Set([...state.inFlightOps, id]);
@@ -5,7 +5,7 @@ import {
   enforceOpenNewTab,
   setupGlobalLoggingHooks,
 } from "@deskulpt/utils";
-import { DeskulptSettings } from "@deskulpt/bindings";
+import { DeskulptCore, DeskulptSettings } from "@deskulpt/bindings";
 import App from "./App";
 import "@radix-ui/themes/styles.css";
 import "./custom.css";
@@ -14,6 +14,8 @@ declare global {
   interface Window {
     readonly __DESKULPT_INTERNALS__: {
       readonly initialSettings: DeepReadonly<DeskulptSettings.Settings>;
+      readonly initialTab: DeskulptCore.PortalTab | null;
+      readonly safeMode: boolean;
     };
   }
 }
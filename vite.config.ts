@@ -1,13 +1,61 @@
-import { defineConfig } from "vite";
+import { defineConfig, type Plugin } from "vite";
 import { resolve } from "path";
 import react from "@vitejs/plugin-react";
 import { version } from "./package.json";
 
+// Origins of the Tauri IPC and of the asset protocols, with both the custom
+// scheme form and the http://<scheme>.localhost form used on Windows
+const IPC_ORIGINS = "ipc: http://ipc.localhost";
+const ASSET_ORIGINS =
+  "asset: http://asset.localhost deskulpt-asset: http://deskulpt-asset.localhost";
+
+// The content security policy of the canvas, where widget code runs; widgets
+// may only reach the network through the metered proxy over IPC, so direct
+// connections, images, media, and the like to other origins are blocked
+const CANVAS_CSP = [
+  "default-src 'self'",
+  "script-src 'self' blob:",
+  `style-src 'self' 'unsafe-inline' ${ASSET_ORIGINS}`,
+  `img-src 'self' blob: data: ${ASSET_ORIGINS}`,
+  `media-src 'self' blob: data: ${ASSET_ORIGINS}`,
+  `font-src 'self' data: ${ASSET_ORIGINS}`,
+  `connect-src 'self' blob: data: ${IPC_ORIGINS} ${ASSET_ORIGINS}`,
+  "object-src 'none'",
+  "base-uri 'self'",
+].join("; ");
+
+/**
+ * Inject the content security policy into the canvas page.
+ *
+ * This is only applied to builds, since the dev server relies on inline
+ * scripts for hot reloading.
+ */
+const canvasCsp = (): Plugin => ({
+  name: "deskulpt-canvas-csp",
+  apply: "build",
+  transformIndexHtml: {
+    order: "pre",
+    handler: (html, { path }) =>
+      path.endsWith("deskulpt-canvas/index.html")
+        ? [
+            {
+              tag: "meta",
+              attrs: {
+                "http-equiv": "Content-Security-Policy",
+                content: CANVAS_CSP,
+              },
+              injectTo: "head-prepend",
+            },
+          ]
+        : html,
+  },
+});
+
 export default defineConfig({
   define: {
     __VERSION__: JSON.stringify(version),
   },
-  plugins: [react({ jsxImportSource: "@emotion/react" })],
+  plugins: [react({ jsxImportSource: "@emotion/react" }), canvasCsp()],
   clearScreen: false,
   server: {
     port: 1420,
@@ -1,4 +1,5 @@
 mod bindings;
+mod publish;
 mod schema;
 
 use anyhow::Result;
@@ -10,9 +11,11 @@ enum Commands {
     Bindings,
     /// Generate JSON schemas.
     Schema,
+    /// Package a widget and publish it to the widgets registry.
+    Publish(publish::PublishArgs),
 }
 
-/// [XTASK] Code generation for Deskulpt.
+/// [XTASK] Code generation and tooling for Deskulpt.
 #[derive(Debug, Parser)]
 #[command(version, about, author, bin_name = "cargo xtask")]
 struct Args {
@@ -25,6 +28,7 @@ fn main() -> Result<()> {
     match args.command {
         Commands::Bindings => bindings::run()?,
         Commands::Schema => schema::run()?,
+        Commands::Publish(args) => publish::run(args)?,
     }
     Ok(())
 }
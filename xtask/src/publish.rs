@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use tauri_plugin_deskulpt_widgets::WidgetPackage;
+
+/// The environment variable holding the registry username.
+const USERNAME_ENV: &str = "DESKULPT_REGISTRY_USERNAME";
+
+/// The environment variable holding the registry password or access token.
+const PASSWORD_ENV: &str = "DESKULPT_REGISTRY_TOKEN";
+
+#[derive(Debug, Args)]
+pub struct PublishArgs {
+    /// The local widget directory to publish.
+    dir: PathBuf,
+    /// The publisher handle.
+    #[arg(long)]
+    handle: String,
+    /// The widget ID; defaults to the name of the widget directory.
+    #[arg(long)]
+    id: Option<String>,
+    /// The tag to push; defaults to the widget version, or "latest".
+    #[arg(long)]
+    tag: Option<String>,
+    /// The git repository URL of the widget source code.
+    #[arg(long)]
+    git: Option<String>,
+    /// The base of the OCI repositories of the registry.
+    #[arg(long, default_value = "ghcr.io/deskulpt-apps/widgets")]
+    oci_base: String,
+    /// Only pack the widget without pushing it.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub fn run(args: PublishArgs) -> Result<()> {
+    let id = match args.id {
+        Some(id) => id,
+        None => args
+            .dir
+            .canonicalize()?
+            .file_name()
+            .context("Cannot infer widget ID from directory")?
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let package = WidgetPackage::pack(&args.dir, args.git.as_deref()).await?;
+        let tag = args
+            .tag
+            .as_deref()
+            .or(package.version())
+            .unwrap_or("latest")
+            .to_string();
+        let reference = format!("{}/{}/{id}:{tag}", args.oci_base, args.handle);
+        println!("📦 Packed: {} ({} bytes)", reference, package.size());
+
+        if args.dry_run {
+            return Ok(());
+        }
+
+        let (Ok(username), Ok(password)) =
+            (std::env::var(USERNAME_ENV), std::env::var(PASSWORD_ENV))
+        else {
+            bail!("Set {USERNAME_ENV} and {PASSWORD_ENV} to publish");
+        };
+        let digest = package.push(&reference, &username, &password).await?;
+        println!("✅ Published: {reference}@{digest}");
+        Ok(())
+    })
+}